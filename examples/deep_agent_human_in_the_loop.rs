@@ -87,6 +87,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
         AgentInvokeResult::Interrupt { interrupt_value } => {
             println!("Interrupted for human approval.\n");
+            // Pending approvals are listed from the checkpointer, so a separate process
+            // (e.g. an approval UI) can find them later
+            for pending in checkpointer.list_pending().await? {
+                println!(
+                    "  Pending on thread {} since {} ({} tool call(s))",
+                    pending.thread_id,
+                    pending.created_at,
+                    pending.pending_actions.len()
+                );
+            }
             let payload: InterruptPayload = serde_json::from_value(interrupt_value.clone())
                 .unwrap_or_else(|_| InterruptPayload {
                     action_requests: vec![],
@@ -0,0 +1,106 @@
+//! Deep Agent event streaming: observe a run (including subagents) as it happens.
+//!
+//! Demonstrates [UnifiedAgent::stream_events]:
+//! - Token deltas from the model while it plans
+//! - Planned tool calls, tool start/end and middleware decisions
+//! - Nested subagent runs linked to the `task` tool run through `parent_run_id`
+//!
+//! Run with:
+//! ```bash
+//! cargo run --example deep_agent_stream_events
+//! ```
+
+use std::{collections::HashMap, io::Write, sync::Arc};
+
+use futures::StreamExt;
+use langchain_ai_rust::{
+    agent::{create_agent, create_deep_agent, AgentInput, AgentStreamEventKind, DeepAgentConfig},
+    langgraph::RunnableConfig,
+    prompt_args,
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    env_logger::init();
+
+    let researcher = Arc::new(create_agent(
+        "gpt-4o-mini",
+        &[],
+        Some("You are a research assistant. Answer questions concisely."),
+        None,
+    )?);
+
+    let config = DeepAgentConfig::new()
+        .with_planning(true)
+        .with_filesystem(false)
+        .with_subagent(
+            researcher,
+            "researcher",
+            "Use for factual questions and research",
+        );
+
+    let agent = create_deep_agent(
+        "gpt-4o-mini",
+        &[],
+        Some(
+            "You are a coordinator. Use the 'task' tool with subagent_id 'researcher' for factual questions.",
+        ),
+        config,
+    )?;
+
+    let run_config = RunnableConfig::default();
+    let mut events = agent.stream_events(
+        AgentInput::State(prompt_args! {"input" => "What is the tallest mountain in Europe?"}),
+        &run_config,
+    );
+
+    // Indent nested runs by their depth in the run tree
+    let mut depth: HashMap<String, usize> = HashMap::new();
+    while let Some(event) = events.next().await {
+        let level = event
+            .parent_run_id
+            .as_ref()
+            .and_then(|parent| depth.get(parent))
+            .map(|d| d + 1)
+            .unwrap_or(0);
+        depth.insert(event.run_id.clone(), level);
+        let indent = "  ".repeat(level);
+
+        match &event.kind {
+            AgentStreamEventKind::TokenDelta { delta } => {
+                print!("{}", delta);
+                std::io::stdout().flush()?;
+            }
+            AgentStreamEventKind::RunStart { .. } => {
+                println!("\n{}▶ run {} started", indent, event.name)
+            }
+            AgentStreamEventKind::ToolCallPlanned { tool, input, .. } => {
+                println!("\n{}• planned {}({})", indent, tool, input)
+            }
+            AgentStreamEventKind::ToolStart { tool, .. } => {
+                println!("{}⚙ {} running", indent, tool)
+            }
+            AgentStreamEventKind::ToolProgress { tool, update, .. } => {
+                println!("{}… {} {}", indent, tool, update)
+            }
+            AgentStreamEventKind::ToolEnd { tool, output, .. } => {
+                println!("{}✓ {} -> {}", indent, tool, output)
+            }
+            AgentStreamEventKind::MiddlewareDecision { tool, decision, .. } => {
+                println!("{}! middleware {:?} {}", indent, decision, tool)
+            }
+            AgentStreamEventKind::Interrupt { value } => {
+                println!("{}⏸ interrupted: {}", indent, value)
+            }
+            AgentStreamEventKind::Finish { output } => {
+                println!("\n{}■ {} finished: {}", indent, event.name, output)
+            }
+            AgentStreamEventKind::Error { message } => {
+                println!("{}✗ {} failed: {}", indent, event.name, message)
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
@@ -164,8 +164,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             langchain_ai_rust::langgraph::StreamEvent::Error { error } => {
                 eprintln!("  ✗ Error: {:?}", error);
             }
-            langchain_ai_rust::langgraph::StreamEvent::MessageChunk { .. }
-            | langchain_ai_rust::langgraph::StreamEvent::CustomData { .. } => {}
+            _ => {}
         }
     }
 
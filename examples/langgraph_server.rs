@@ -0,0 +1,80 @@
+#[cfg(feature = "server")]
+use langchain_ai_rust::langgraph::{
+    function_node, interrupt, server::GraphServer, InMemorySaver, LangGraphError, MessagesState,
+    StateGraph, END, START,
+};
+#[cfg(feature = "server")]
+use langchain_ai_rust::schemas::messages::Message;
+
+/// LangGraph HTTP server example
+///
+/// This example demonstrates:
+/// 1. Serving a compiled graph over HTTP with `GraphServer`
+/// 2. Interrupting a run for human approval
+/// 3. Resuming the thread with a `Command`
+///
+/// Try it with curl:
+///
+/// ```bash
+/// curl -X POST localhost:8123/threads -H 'content-type: application/json' -d '{"thread_id": "t1"}'
+/// curl -N -X POST localhost:8123/threads/t1/runs/stream -H 'content-type: application/json' \
+///      -d '{"input": {"messages": []}, "stream_mode": ["updates"]}'
+/// curl -X POST localhost:8123/threads/t1/runs/wait -H 'content-type: application/json' \
+///      -d '{"command": {"resume": {"resume": true}}}'
+/// curl localhost:8123/threads/t1/history
+/// ```
+///
+/// Note: This example requires the `server` feature.
+#[cfg(feature = "server")]
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let draft = function_node("draft", |_state: &MessagesState| async move {
+        use std::collections::HashMap;
+        let mut update = HashMap::new();
+        update.insert(
+            "messages".to_string(),
+            serde_json::to_value(vec![Message::new_ai_message("Draft reply")])?,
+        );
+        Ok(update)
+    });
+
+    let approval = function_node("approval", |_state: &MessagesState| async move {
+        use std::collections::HashMap;
+        let approved = interrupt("Send the draft reply?")
+            .await
+            .map_err(LangGraphError::InterruptError)?;
+        let mut update = HashMap::new();
+        update.insert(
+            "messages".to_string(),
+            serde_json::to_value(vec![Message::new_ai_message(format!(
+                "Approved: {}",
+                approved
+            ))])?,
+        );
+        Ok(update)
+    });
+
+    let mut graph = StateGraph::<MessagesState>::new();
+    graph.add_node("draft", draft)?;
+    graph.add_node("approval", approval)?;
+    graph.add_edge(START, "draft");
+    graph.add_edge("draft", "approval");
+    graph.add_edge("approval", END);
+
+    let server = GraphServer::new(
+        graph.compile()?,
+        std::sync::Arc::new(InMemorySaver::<MessagesState>::new()),
+    );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:8123").await?;
+    println!("Serving graph on http://127.0.0.1:8123");
+    server.serve(listener).await?;
+
+    Ok(())
+}
+
+#[cfg(not(feature = "server"))]
+fn main() {
+    eprintln!("This example requires the 'server' feature");
+    eprintln!("Run with: cargo run --example langgraph_server --features server");
+}
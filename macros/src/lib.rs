@@ -0,0 +1,348 @@
+//! Procedural macros for `langchain-ai-rust`; use them through `langchain_ai_rust::tools`.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Expr, ExprLit, FnArg, Ident, ItemFn, Lit, LitBool, LitStr, Meta, Pat, Type,
+};
+
+/// Turn an async function into a [`Tool`](../langchain_ai_rust/tools/trait.Tool.html).
+///
+/// The function takes at most one arguments value, whose type implements `Deserialize` and
+/// `JsonSchema`, and optionally a `&ToolRuntime`. It returns `Result<String, E>` where `E`
+/// converts into `ToolError`. The macro keeps the function and adds a unit struct named after
+/// it in PascalCase (`get_weather` becomes `GetWeather`) implementing `Tool`:
+///
+/// - the name is the function name and the description its doc comment;
+/// - the parameters are the JSON schema of the arguments type, with field doc comments as
+///   parameter descriptions;
+/// - invalid arguments are returned to the model as `ToolError::InvalidInputError` listing
+///   every schema violation.
+///
+/// Options: `name = "..."`, `description = "..."` and `parallel_safe = false`.
+///
+/// ```rust,ignore
+/// #[derive(Deserialize, JsonSchema)]
+/// struct WeatherArgs {
+///     /// City to get the weather for
+///     city: String,
+///     /// Unit of the temperature, celsius by default
+///     unit: Option<String>,
+/// }
+///
+/// /// Get the current weather in a city.
+/// #[tool]
+/// async fn get_weather(args: WeatherArgs) -> Result<String, ToolError> {
+///     Ok(format!("Sunny in {}", args.city))
+/// }
+///
+/// let agent = create_agent("gpt-4o-mini", &[Arc::new(GetWeather)], None, None)?;
+/// ```
+#[proc_macro_attribute]
+pub fn tool(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut options = ToolOptions::default();
+    let parser = syn::meta::parser(|meta| options.parse(meta));
+    parse_macro_input!(attr with parser);
+    let function = parse_macro_input!(item as ItemFn);
+    expand_tool(options, function)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct ToolOptions {
+    name: Option<LitStr>,
+    description: Option<LitStr>,
+    parallel_safe: Option<LitBool>,
+}
+
+impl ToolOptions {
+    fn parse(&mut self, meta: syn::meta::ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("name") {
+            self.name = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("description") {
+            self.description = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("parallel_safe") {
+            self.parallel_safe = Some(meta.value()?.parse()?);
+        } else {
+            return Err(meta.error("expected `name`, `description` or `parallel_safe`"));
+        }
+        Ok(())
+    }
+}
+
+/// What the function receives for each of its parameters
+enum Param {
+    Arguments(Box<Type>),
+    Runtime,
+}
+
+fn expand_tool(options: ToolOptions, function: ItemFn) -> syn::Result<TokenStream2> {
+    let signature = &function.sig;
+    if signature.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(
+            signature.fn_token,
+            "#[tool] requires an async fn",
+        ));
+    }
+    if !signature.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &signature.generics,
+            "#[tool] functions cannot be generic",
+        ));
+    }
+
+    let mut params = Vec::new();
+    for input in &signature.inputs {
+        let FnArg::Typed(input) = input else {
+            return Err(syn::Error::new_spanned(
+                input,
+                "#[tool] functions cannot take self",
+            ));
+        };
+        if !matches!(*input.pat, Pat::Ident(_)) {
+            return Err(syn::Error::new_spanned(
+                &input.pat,
+                "#[tool] parameters must be plain identifiers",
+            ));
+        }
+        if is_runtime(&input.ty) {
+            params.push(Param::Runtime);
+        } else if params
+            .iter()
+            .any(|param| matches!(param, Param::Arguments(_)))
+        {
+            return Err(syn::Error::new_spanned(
+                &input.ty,
+                "#[tool] functions take a single arguments struct; add fields to it instead",
+            ));
+        } else {
+            params.push(Param::Arguments(input.ty.clone()));
+        }
+    }
+
+    let function_name = &signature.ident;
+    let name = options
+        .name
+        .unwrap_or_else(|| LitStr::new(&function_name.to_string(), function_name.span()));
+    let description = match options.description {
+        Some(description) => description,
+        None => doc_comment(&function).ok_or_else(|| {
+            syn::Error::new_spanned(
+                function_name,
+                "document the tool with a doc comment or #[tool(description = \"...\")]",
+            )
+        })?,
+    };
+    let struct_name = Ident::new(&pascal_case(&function_name.to_string()), Span::call_site());
+    let visibility = &function.vis;
+    let krate = quote!(::langchain_ai_rust);
+
+    let arguments_type = params.iter().find_map(|param| match param {
+        Param::Arguments(ty) => Some(ty),
+        Param::Runtime => None,
+    });
+    let parameters = match arguments_type {
+        Some(ty) => quote!(#krate::tools::parameters_schema::<#ty>()),
+        None => quote!(#krate::__macro_support::serde_json::json!({
+            "type": "object",
+            "properties": {}
+        })),
+    };
+    let parse = match arguments_type {
+        Some(ty) => quote! {
+            let arguments = #krate::tools::parse_arguments::<#ty>(#name, input)?;
+        },
+        None => quote!(let _ = input;),
+    };
+    let call_args = params.iter().map(|param| match param {
+        Param::Arguments(_) => format_ident!("arguments"),
+        Param::Runtime => format_ident!("runtime"),
+    });
+    let call = quote! {
+        #function_name(#(#call_args),*)
+            .await
+            .map(::core::convert::Into::<::std::string::String>::into)
+            .map_err(::core::convert::Into::<#krate::error::ToolError>::into)
+    };
+
+    let needs_runtime = params.iter().any(|param| matches!(param, Param::Runtime));
+    let run = if needs_runtime {
+        quote! {
+            async fn run(
+                &self,
+                _input: #krate::__macro_support::serde_json::Value,
+            ) -> ::std::result::Result<::std::string::String, #krate::error::ToolError> {
+                ::std::result::Result::Err(#krate::error::ToolError::ConfigurationError(
+                    ::std::format!("{} requires runtime. Use run_with_runtime instead.", #name),
+                ))
+            }
+
+            async fn run_with_runtime(
+                &self,
+                input: #krate::__macro_support::serde_json::Value,
+                runtime: &#krate::tools::ToolRuntime,
+            ) -> ::std::result::Result<
+                #krate::tools::ToolResult,
+                ::std::boxed::Box<dyn ::std::error::Error>,
+            > {
+                #parse
+                ::std::result::Result::Ok(#krate::tools::ToolResult::Text(#call?))
+            }
+
+            fn requires_runtime(&self) -> bool {
+                true
+            }
+        }
+    } else {
+        quote! {
+            async fn run(
+                &self,
+                input: #krate::__macro_support::serde_json::Value,
+            ) -> ::std::result::Result<::std::string::String, #krate::error::ToolError> {
+                #parse
+                #call
+            }
+        }
+    };
+    let parallel_safe = options.parallel_safe.map(|parallel_safe| {
+        quote! {
+            fn is_parallel_safe(&self) -> bool {
+                #parallel_safe
+            }
+        }
+    });
+    let struct_doc = format!("Tool calling [`{}`].", function_name);
+
+    Ok(quote! {
+        #function
+
+        #[doc = #struct_doc]
+        #[derive(Clone, Copy, Debug, Default)]
+        #visibility struct #struct_name;
+
+        #[#krate::__macro_support::async_trait]
+        impl #krate::tools::Tool for #struct_name {
+            fn name(&self) -> ::std::string::String {
+                ::std::string::String::from(#name)
+            }
+
+            fn description(&self) -> ::std::string::String {
+                ::std::string::String::from(#description)
+            }
+
+            fn parameters(&self) -> #krate::__macro_support::serde_json::Value {
+                #parameters
+            }
+
+            async fn parse_input(&self, input: &str) -> #krate::__macro_support::serde_json::Value {
+                #krate::tools::parse_tool_input(input)
+            }
+
+            #run
+
+            #parallel_safe
+        }
+    })
+}
+
+/// Whether a parameter is `&ToolRuntime`
+fn is_runtime(ty: &Type) -> bool {
+    let Type::Reference(reference) = ty else {
+        return false;
+    };
+    let Type::Path(path) = &*reference.elem else {
+        return false;
+    };
+    path.path
+        .segments
+        .last()
+        .is_some_and(|segment| segment.ident == "ToolRuntime")
+}
+
+/// The doc comment of a function, lines joined and trimmed
+fn doc_comment(function: &ItemFn) -> Option<LitStr> {
+    let lines: Vec<String> = function
+        .attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(meta) if meta.path.is_ident("doc") => match &meta.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(doc), ..
+                }) => Some(doc.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| {
+            line.strip_prefix(' ')
+                .unwrap_or(&line)
+                .trim_end()
+                .to_string()
+        })
+        .collect();
+    let doc = lines.join("\n").trim().to_string();
+    (!doc.is_empty()).then(|| LitStr::new(&doc, Span::call_site()))
+}
+
+fn pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse::Parser;
+
+    use super::*;
+
+    #[test]
+    fn test_pascal_case() {
+        assert_eq!(pascal_case("get_weather"), "GetWeather");
+        assert_eq!(pascal_case("search"), "Search");
+        assert_eq!(pascal_case("_private_tool"), "PrivateTool");
+    }
+
+    #[test]
+    fn test_doc_comment() {
+        let function: ItemFn = syn::parse_quote! {
+            /// Get the weather.
+            ///
+            /// Uses the public API.
+            async fn get_weather() {}
+        };
+        assert_eq!(
+            doc_comment(&function).unwrap().value(),
+            "Get the weather.\n\nUses the public API."
+        );
+    }
+
+    #[test]
+    fn test_rejects_sync_fn() {
+        let function: ItemFn = syn::parse_quote! {
+            /// Sync
+            fn sync_tool() -> Result<String, ToolError> { Ok(String::new()) }
+        };
+        assert!(expand_tool(ToolOptions::default(), function).is_err());
+    }
+
+    #[test]
+    fn test_options() {
+        let mut options = ToolOptions::default();
+        let parser = syn::meta::parser(|meta| options.parse(meta));
+        parser
+            .parse2(quote!(name = "weather", parallel_safe = false))
+            .unwrap();
+        assert_eq!(options.name.unwrap().value(), "weather");
+        assert!(!options.parallel_safe.unwrap().value);
+    }
+}
@@ -0,0 +1,256 @@
+use std::pin::Pin;
+
+use async_stream::stream;
+use futures::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use super::{
+    error::A2AError,
+    types::{
+        A2AEvent, A2AMessage, AgentCard, JsonRpcRequest, JsonRpcResponse, MessageSendParams, Task,
+        TaskIdParams, TaskQueryParams, TaskState, AGENT_CARD_PATH,
+    },
+};
+
+/// Client for a remote agent speaking the A2A protocol
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let client = A2AClient::new("http://billing:9999");
+/// let card = client.get_agent_card().await?;
+/// let task = client
+///     .send_message(A2AMessage::user_text("Was I charged twice?"))
+///     .await?;
+/// ```
+#[derive(Clone)]
+pub struct A2AClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl A2AClient {
+    /// Create a client for the agent at `base_url`, which serves the agent card and the
+    /// JSON-RPC endpoint
+    pub fn new<S: Into<String>>(base_url: S) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Use a custom HTTP client, e.g. with default authentication headers
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Fetch the agent card
+    pub async fn get_agent_card(&self) -> Result<AgentCard, A2AError> {
+        let response = self
+            .client
+            .get(format!("{}{}", self.base_url, AGENT_CARD_PATH))
+            .send()
+            .await?;
+        let response = check_status(response).await?;
+        Ok(response.json().await?)
+    }
+
+    /// Send a message and wait for the resulting task (or direct message reply)
+    pub async fn send_message(&self, message: A2AMessage) -> Result<A2AEvent, A2AError> {
+        self.call(
+            "message/send",
+            MessageSendParams {
+                message,
+                configuration: None,
+            },
+        )
+        .await
+    }
+
+    /// Send a message and stream the task events as they happen
+    ///
+    /// The first event is the submitted task; the stream ends after the final event.
+    pub async fn send_message_stream(
+        &self,
+        message: A2AMessage,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<A2AEvent, A2AError>> + Send>>, A2AError> {
+        let request = JsonRpcRequest::new(
+            "message/stream",
+            serde_json::to_value(MessageSendParams {
+                message,
+                configuration: None,
+            })?,
+        );
+        let response = self
+            .client
+            .post(format!("{}/", self.base_url))
+            .header("Accept", "text/event-stream")
+            .json(&request)
+            .send()
+            .await?;
+        let response = check_status(response).await?;
+
+        let is_sse = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/event-stream"));
+        if !is_sse {
+            // Errors are answered with a plain JSON-RPC response
+            let response: JsonRpcResponse = response.json().await?;
+            let event = rpc_result::<A2AEvent>(response)?;
+            return Ok(Box::pin(futures::stream::once(async move { Ok(event) })));
+        }
+
+        let mut bytes = response.bytes_stream();
+        Ok(Box::pin(stream! {
+            let mut buffer = String::new();
+            while let Some(chunk) = bytes.next().await {
+                let chunk = match chunk {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        yield Err(A2AError::from(e));
+                        break;
+                    }
+                };
+                buffer.push_str(&String::from_utf8_lossy(&chunk).replace("\r\n", "\n"));
+
+                while let Some(end) = buffer.find("\n\n") {
+                    let block: String = buffer.drain(..end + 2).collect();
+                    let Some(data) = sse_data(&block) else {
+                        continue;
+                    };
+                    let event = serde_json::from_str::<JsonRpcResponse>(&data)
+                        .map_err(A2AError::from)
+                        .and_then(rpc_result::<A2AEvent>);
+                    let is_final = event.as_ref().map_or(true, A2AEvent::is_final);
+                    yield event;
+                    if is_final {
+                        return;
+                    }
+                }
+            }
+        }))
+    }
+
+    /// Get a task by id, with at most `history_length` history messages
+    pub async fn get_task(
+        &self,
+        task_id: &str,
+        history_length: Option<usize>,
+    ) -> Result<Task, A2AError> {
+        let event: A2AEvent = self
+            .call(
+                "tasks/get",
+                TaskQueryParams {
+                    id: task_id.to_string(),
+                    history_length,
+                },
+            )
+            .await?;
+        expect_task(event)
+    }
+
+    /// Cancel a running task
+    pub async fn cancel_task(&self, task_id: &str) -> Result<Task, A2AError> {
+        let event: A2AEvent = self
+            .call(
+                "tasks/cancel",
+                TaskIdParams {
+                    id: task_id.to_string(),
+                },
+            )
+            .await?;
+        expect_task(event)
+    }
+
+    /// Send a text message and return the agent's text answer with the conversation's
+    /// context id, which continues the conversation when passed back
+    pub async fn ask(
+        &self,
+        text: &str,
+        context_id: Option<&str>,
+    ) -> Result<(String, Option<String>), A2AError> {
+        let mut message = A2AMessage::user_text(text);
+        message.context_id = context_id.map(str::to_string);
+
+        match self.send_message(message).await? {
+            A2AEvent::Message(reply) => Ok((reply.text(), reply.context_id)),
+            A2AEvent::Task(task) if task.status.state == TaskState::Completed => {
+                Ok((task.output_text(), Some(task.context_id)))
+            }
+            A2AEvent::Task(task) => Err(A2AError::AgentError(format!(
+                "Task {} ended in state {:?}: {}",
+                task.id,
+                task.status.state,
+                task.output_text()
+            ))),
+            event => Err(A2AError::InvalidResponse(format!(
+                "Unexpected reply to message/send: {}",
+                json!(event)
+            ))),
+        }
+    }
+
+    async fn call<P: serde::Serialize, R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: P,
+    ) -> Result<R, A2AError> {
+        let request = JsonRpcRequest::new(method, serde_json::to_value(params)?);
+        let response = self
+            .client
+            .post(format!("{}/", self.base_url))
+            .json(&request)
+            .send()
+            .await?;
+        let response: JsonRpcResponse = check_status(response).await?.json().await?;
+        rpc_result(response)
+    }
+}
+
+async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, A2AError> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+    Err(A2AError::StatusError {
+        status: response.status().as_u16(),
+        body: response.text().await.unwrap_or_default(),
+    })
+}
+
+fn rpc_result<R: DeserializeOwned>(response: JsonRpcResponse) -> Result<R, A2AError> {
+    if let Some(error) = response.error {
+        return Err(A2AError::RpcError(error));
+    }
+    let result = response
+        .result
+        .ok_or_else(|| A2AError::InvalidResponse("response has no result".to_string()))?;
+    Ok(serde_json::from_value(result)?)
+}
+
+fn expect_task(event: A2AEvent) -> Result<Task, A2AError> {
+    match event {
+        A2AEvent::Task(task) => Ok(task),
+        event => Err(A2AError::InvalidResponse(format!(
+            "Expected a task, got: {}",
+            json!(event)
+        ))),
+    }
+}
+
+/// The `data` of an SSE event block, multi-line data joined with newlines
+fn sse_data(block: &str) -> Option<String> {
+    let lines: Vec<&str> = block
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(|data| data.strip_prefix(' ').unwrap_or(data))
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
@@ -0,0 +1,28 @@
+use thiserror::Error;
+
+use super::types::JsonRpcError;
+
+/// Errors returned by the A2A client and server
+#[derive(Error, Debug)]
+pub enum A2AError {
+    #[error("Request error: {0}")]
+    RequestError(#[from] reqwest::Error),
+
+    #[error("HTTP status {status}: {body}")]
+    StatusError { status: u16, body: String },
+
+    #[error("JSON-RPC error {}: {}", .0.code, .0.message)]
+    RpcError(JsonRpcError),
+
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+
+    #[error("Agent error: {0}")]
+    AgentError(String),
+
+    #[error("Serde json error: {0}")]
+    SerdeJsonError(#[from] serde_json::Error),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
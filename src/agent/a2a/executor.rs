@@ -0,0 +1,106 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use tokio::sync::mpsc::{self, UnboundedSender};
+
+use crate::{
+    agent::{AgentEventEmitter, AgentStreamEvent, AgentStreamEventKind, UnifiedAgent},
+    langgraph::{CompiledGraph, MessagesState, StreamEvent},
+    schemas::{Message, MessageType},
+};
+
+use super::error::A2AError;
+
+/// Reports progress of a running task; each update becomes a `working` status message.
+#[derive(Clone)]
+pub struct TaskUpdater {
+    sender: UnboundedSender<String>,
+}
+
+impl TaskUpdater {
+    pub(crate) fn new(sender: UnboundedSender<String>) -> Self {
+        Self { sender }
+    }
+
+    /// Send a progress message; dropped once nobody listens anymore
+    pub fn progress<S: Into<String>>(&self, text: S) {
+        let _ = self.sender.send(text.into());
+    }
+}
+
+/// Something the A2A server can run: answers the conversation of a context
+///
+/// Implemented for [`UnifiedAgent`] and for message graphs (`CompiledGraph<MessagesState>`).
+#[async_trait]
+pub trait A2AExecutor: Send + Sync {
+    /// Answer the last message of `messages`, which hold the whole conversation so far
+    async fn execute(
+        &self,
+        messages: Vec<Message>,
+        updates: &TaskUpdater,
+    ) -> Result<String, A2AError>;
+}
+
+#[async_trait]
+impl A2AExecutor for UnifiedAgent {
+    async fn execute(
+        &self,
+        messages: Vec<Message>,
+        updates: &TaskUpdater,
+    ) -> Result<String, A2AError> {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let events = AgentEventEmitter::new(sender, "agent");
+        let forward = |event: AgentStreamEvent| {
+            if let AgentStreamEventKind::ToolStart { tool, .. } = event.kind {
+                updates.progress(format!("Running tool {}", tool));
+            }
+        };
+
+        let run = self.invoke_messages_with_events(messages, &events);
+        futures::pin_mut!(run);
+        let result = loop {
+            tokio::select! {
+                biased;
+                Some(event) = receiver.recv() => forward(event),
+                result = &mut run => break result,
+            }
+        };
+        while let Ok(event) = receiver.try_recv() {
+            forward(event);
+        }
+        result.map_err(|e| A2AError::AgentError(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl A2AExecutor for CompiledGraph<MessagesState> {
+    async fn execute(
+        &self,
+        messages: Vec<Message>,
+        updates: &TaskUpdater,
+    ) -> Result<String, A2AError> {
+        let mut events = self.stream(MessagesState::with_messages(messages));
+        while let Some(event) = events.next().await {
+            match event {
+                StreamEvent::NodeEnd { node, .. } => {
+                    updates.progress(format!("Finished {}", node));
+                }
+                StreamEvent::GraphEnd { final_state } => {
+                    return Ok(final_state
+                        .messages
+                        .iter()
+                        .rev()
+                        .find(|m| matches!(m.message_type, MessageType::AIMessage))
+                        .map(|m| m.content.clone())
+                        .unwrap_or_default());
+                }
+                StreamEvent::Error { error } => {
+                    return Err(A2AError::AgentError(error.to_string()));
+                }
+                _ => {}
+            }
+        }
+        Err(A2AError::AgentError(
+            "Graph stopped without a final state".to_string(),
+        ))
+    }
+}
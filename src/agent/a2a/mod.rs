@@ -0,0 +1,43 @@
+//! Agent-to-agent (A2A) protocol server and client (requires the `a2a` feature)
+//!
+//! [`A2AServer`] exposes a [`UnifiedAgent`](crate::agent::UnifiedAgent) or a message graph
+//! (`CompiledGraph<MessagesState>`) to agents written in any language: it serves an agent card
+//! and the JSON-RPC methods `message/send`, `message/stream` (SSE), `tasks/get` and
+//! `tasks/cancel`. Each message runs as a task (`submitted` → `working` → `completed`,
+//! `failed` or `canceled`); messages with the same `contextId` continue one conversation.
+//!
+//! [`A2AClient`] talks to any A2A agent, and [`A2ARemoteAgent`] wraps one as a
+//! [`Tool`](crate::tools::Tool) or as a [`RouterAgent`](crate::agent::RouterAgent) target.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use std::sync::Arc;
+//! use langchain_ai_rust::agent::a2a::{A2AClient, A2ARemoteAgent, A2AServer, AgentCard};
+//!
+//! // Serve a local agent
+//! let listener = tokio::net::TcpListener::bind("0.0.0.0:9999").await?;
+//! let card = AgentCard::new("billing", "Answers billing questions", "http://localhost:9999/");
+//! tokio::spawn(async move { A2AServer::new(card, Arc::new(agent)).serve(listener).await });
+//!
+//! // Call a remote one
+//! let billing = A2ARemoteAgent::from_card(A2AClient::new("http://localhost:9999")).await?;
+//! let answer = billing.ask("Was I charged twice?").await?;
+//! ```
+
+mod client;
+mod error;
+mod executor;
+mod remote_agent;
+mod server;
+mod types;
+
+#[cfg(test)]
+mod tests;
+
+pub use client::*;
+pub use error::*;
+pub use executor::*;
+pub use remote_agent::*;
+pub use server::*;
+pub use types::*;
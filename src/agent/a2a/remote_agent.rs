@@ -0,0 +1,170 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::{
+    agent::multi_agent::RoutableAgent,
+    chain::ChainError,
+    error::ToolError,
+    schemas::{Message, MessageType},
+    tools::{Tool, ToolResult, ToolRuntime},
+};
+
+use super::{client::A2AClient, error::A2AError};
+
+/// A remote A2A agent usable as a [`Tool`] (like `SubagentTool`) or as a [`RouterAgent`]
+/// target
+///
+/// As a tool, the remote conversation belongs to the calling agent's state: the context id
+/// the remote agent returns is kept in a custom field of that state, so later calls from the
+/// same conversation continue it while other conversations get their own. Calls without a
+/// runtime, and router calls, start a new remote conversation every time.
+///
+/// [`RouterAgent`]: crate::agent::RouterAgent
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let billing = Arc::new(A2ARemoteAgent::from_card(A2AClient::new("http://billing:9999")).await?);
+///
+/// // As a tool of a local agent
+/// let agent = create_agent("gpt-4o-mini", &[billing.clone()], None, None)?;
+///
+/// // Or as a router target
+/// let router = RouterAgentBuilder::new()
+///     .with_keyword_router(keyword_map)
+///     .with_agent("billing".to_string(), billing)
+///     .build()?;
+/// ```
+pub struct A2ARemoteAgent {
+    client: A2AClient,
+    /// Tool name
+    name: String,
+    /// Tool description
+    description: String,
+}
+
+impl A2ARemoteAgent {
+    pub fn new<N: Into<String>, D: Into<String>>(
+        client: A2AClient,
+        name: N,
+        description: D,
+    ) -> Self {
+        Self {
+            client,
+            name: name.into(),
+            description: description.into(),
+        }
+    }
+
+    /// Create the agent with the name and description of its agent card
+    pub async fn from_card(client: A2AClient) -> Result<Self, A2AError> {
+        let card = client.get_agent_card().await?;
+        let name: String = card
+            .name
+            .trim()
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        Ok(Self::new(client, name, card.description))
+    }
+
+    pub fn client(&self) -> &A2AClient {
+        &self.client
+    }
+
+    /// Send `text` to the remote agent in a new conversation and return its answer
+    pub async fn ask(&self, text: &str) -> Result<String, A2AError> {
+        let (answer, _) = self.client.ask(text, None).await?;
+        Ok(answer)
+    }
+
+    /// Agent state field holding the remote context id of this agent
+    pub fn context_field(&self) -> String {
+        format!("a2a_context_id:{}", self.name)
+    }
+}
+
+fn input_text(input: &Value) -> String {
+    match input.get("input").unwrap_or(input) {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[async_trait]
+impl Tool for A2ARemoteAgent {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn parameters(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "input": {
+                    "type": "string",
+                    "description": format!("Message for the {} agent", self.name)
+                }
+            },
+            "required": ["input"]
+        })
+    }
+
+    async fn run(&self, input: Value) -> Result<String, ToolError> {
+        self.ask(&input_text(&input))
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("Remote agent error: {}", e)))
+    }
+
+    async fn run_with_runtime(
+        &self,
+        input: Value,
+        runtime: &ToolRuntime,
+    ) -> Result<ToolResult, Box<dyn Error>> {
+        let field = self.context_field();
+        // The state lock is released while the remote agent answers
+        let context_id = runtime
+            .state()
+            .await
+            .get_field(&field)
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let (answer, new_context_id) = self
+            .client
+            .ask(&input_text(&input), context_id.as_deref())
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("Remote agent error: {}", e)))?;
+        if let Some(new_context_id) = new_context_id {
+            runtime
+                .state()
+                .await
+                .set_field(field, Value::String(new_context_id));
+        }
+        Ok(ToolResult::Text(answer))
+    }
+
+    fn requires_runtime(&self) -> bool {
+        true
+    }
+}
+
+#[async_trait]
+impl RoutableAgent for A2ARemoteAgent {
+    /// Send the last human message in a new remote conversation
+    async fn invoke_messages(&self, messages: Vec<Message>) -> Result<String, ChainError> {
+        let input = messages
+            .iter()
+            .rev()
+            .find(|m| matches!(m.message_type, MessageType::HumanMessage))
+            .ok_or_else(|| ChainError::AgentError("No human message found".to_string()))?;
+        self.ask(&input.content)
+            .await
+            .map_err(|e| ChainError::AgentError(e.to_string()))
+    }
+}
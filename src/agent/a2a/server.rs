@@ -0,0 +1,478 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use async_stream::stream;
+use axum::{
+    extract::State as AppState,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::{get, post},
+    Json, Router,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tokio::{
+    net::TcpListener,
+    sync::{
+        mpsc::{self, UnboundedReceiver, UnboundedSender},
+        RwLock,
+    },
+    task::AbortHandle,
+};
+
+use crate::schemas::Message;
+
+use super::{
+    error::A2AError,
+    executor::{A2AExecutor, TaskUpdater},
+    types::{
+        new_id, A2AEvent, A2AMessage, AgentCard, Artifact, JsonRpcError, JsonRpcRequest,
+        JsonRpcResponse, MessageSendParams, Part, Role, Task, TaskArtifactUpdateEvent,
+        TaskIdParams, TaskQueryParams, TaskState, TaskStatus, TaskStatusUpdateEvent,
+        AGENT_CARD_PATH,
+    },
+};
+
+/// HTTP server exposing an agent over the A2A protocol
+///
+/// Serves the agent card at [`AGENT_CARD_PATH`] and a JSON-RPC endpoint at `/` with the
+/// `message/send`, `message/stream` (SSE), `tasks/get` and `tasks/cancel` methods. Every
+/// message starts a task; messages sharing a `contextId` continue the same conversation.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let listener = TcpListener::bind("0.0.0.0:9999").await?;
+/// let card = AgentCard::new("billing", "Answers billing questions", "http://billing:9999/");
+/// A2AServer::new(card, Arc::new(agent)).serve(listener).await?;
+/// ```
+pub struct A2AServer {
+    inner: Arc<ServerInner>,
+}
+
+struct ServerInner {
+    card: AgentCard,
+    executor: Arc<dyn A2AExecutor>,
+    tasks: RwLock<HashMap<String, TaskEntry>>,
+    /// Conversation of each context
+    contexts: RwLock<HashMap<String, Vec<Message>>>,
+}
+
+struct TaskEntry {
+    task: Task,
+    /// Aborts the run while the task is running
+    abort: Option<AbortHandle>,
+    /// Stream of the client that sent the message, until the final event
+    events: Option<UnboundedSender<A2AEvent>>,
+}
+
+impl A2AServer {
+    /// Serve `executor` (a [`UnifiedAgent`](crate::agent::UnifiedAgent) or a message graph)
+    /// described by `card`
+    pub fn new(card: AgentCard, executor: Arc<dyn A2AExecutor>) -> Self {
+        Self {
+            inner: Arc::new(ServerInner {
+                card,
+                executor,
+                tasks: RwLock::new(HashMap::new()),
+                contexts: RwLock::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// The card served by this server
+    pub fn card(&self) -> &AgentCard {
+        &self.inner.card
+    }
+
+    /// Build the axum router with the agent card and JSON-RPC endpoints
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route(AGENT_CARD_PATH, get(agent_card))
+            .route("/.well-known/agent.json", get(agent_card))
+            .route("/", post(json_rpc))
+            .with_state(self.inner.clone())
+    }
+
+    /// Serve the router on `listener` until the process exits
+    pub async fn serve(&self, listener: TcpListener) -> Result<(), A2AError> {
+        axum::serve(listener, self.router()).await?;
+        Ok(())
+    }
+}
+
+impl Clone for A2AServer {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl ServerInner {
+    /// Create a task for `message` and start running it
+    ///
+    /// Returns the submitted task and the receiver of its events.
+    async fn start_task(
+        self: &Arc<Self>,
+        mut message: A2AMessage,
+    ) -> Result<(Task, UnboundedReceiver<A2AEvent>), JsonRpcError> {
+        if message.role != Role::User {
+            return Err(JsonRpcError::new(
+                JsonRpcError::INVALID_PARAMS,
+                "Messages sent to an agent must have the user role",
+            ));
+        }
+
+        let context_id = message.context_id.clone().unwrap_or_else(new_id);
+        let task_id = new_id();
+        message.context_id = Some(context_id.clone());
+        message.task_id = Some(task_id.clone());
+
+        let messages = {
+            let mut contexts = self.contexts.write().await;
+            let conversation = contexts.entry(context_id.clone()).or_default();
+            conversation.push(message.to_message());
+            conversation.clone()
+        };
+
+        let task = Task {
+            id: task_id.clone(),
+            context_id: context_id.clone(),
+            status: TaskStatus::new(TaskState::Submitted),
+            artifacts: Vec::new(),
+            history: vec![message],
+        };
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.tasks.write().await.insert(
+            task_id.clone(),
+            TaskEntry {
+                task: task.clone(),
+                abort: None,
+                events: Some(sender),
+            },
+        );
+
+        let run = tokio::spawn(Arc::clone(self).run_task(task_id.clone(), messages));
+        if let Some(entry) = self.tasks.write().await.get_mut(&task_id) {
+            if !entry.task.status.state.is_terminal() {
+                entry.abort = Some(run.abort_handle());
+            }
+        }
+
+        // A panicking agent fails its task instead of leaving clients waiting
+        let server = Arc::clone(self);
+        let watched_task_id = task_id;
+        tokio::spawn(async move {
+            if let Err(e) = run.await {
+                if e.is_panic() {
+                    let message = server
+                        .agent_message(&watched_task_id, "The agent crashed".to_string())
+                        .await;
+                    let status = TaskStatus::new(TaskState::Failed).with_message(message);
+                    server.update_status(&watched_task_id, status, true).await;
+                }
+            }
+        });
+        Ok((task, receiver))
+    }
+
+    async fn run_task(self: Arc<Self>, task_id: String, messages: Vec<Message>) {
+        self.update_status(&task_id, TaskStatus::new(TaskState::Working), false)
+            .await;
+
+        let (sender, mut progress) = mpsc::unbounded_channel();
+        let executor = Arc::clone(&self.executor);
+        let execution = async move {
+            let updates = TaskUpdater::new(sender);
+            executor.execute(messages, &updates).await
+        };
+        let forward = async {
+            while let Some(text) = progress.recv().await {
+                let status = TaskStatus::new(TaskState::Working)
+                    .with_message(self.agent_message(&task_id, text).await);
+                self.update_status(&task_id, status, false).await;
+            }
+        };
+        let (result, _) = tokio::join!(execution, forward);
+
+        match result {
+            Ok(answer) => {
+                let message = self.agent_message(&task_id, answer.clone()).await;
+                if let Some(context_id) = &message.context_id {
+                    if let Some(conversation) = self.contexts.write().await.get_mut(context_id) {
+                        conversation.push(message.to_message());
+                    }
+                }
+                self.add_artifact(
+                    &task_id,
+                    Artifact {
+                        artifact_id: new_id(),
+                        name: Some("answer".to_string()),
+                        parts: vec![Part::text(answer)],
+                    },
+                )
+                .await;
+                if let Some(entry) = self.tasks.write().await.get_mut(&task_id) {
+                    entry.task.history.push(message.clone());
+                }
+                let status = TaskStatus::new(TaskState::Completed).with_message(message);
+                self.update_status(&task_id, status, true).await;
+            }
+            Err(e) => {
+                log::warn!("A2A task {} failed: {}", task_id, e);
+                let message = self.agent_message(&task_id, e.to_string()).await;
+                let status = TaskStatus::new(TaskState::Failed).with_message(message);
+                self.update_status(&task_id, status, true).await;
+            }
+        }
+    }
+
+    /// An agent message belonging to a task
+    async fn agent_message(&self, task_id: &str, text: String) -> A2AMessage {
+        let mut message = A2AMessage::agent_text(text).with_task_id(task_id);
+        if let Some(entry) = self.tasks.read().await.get(task_id) {
+            message.context_id = Some(entry.task.context_id.clone());
+        }
+        message
+    }
+
+    /// Set the status of a running task and notify its stream
+    async fn update_status(&self, task_id: &str, status: TaskStatus, is_final: bool) {
+        let mut tasks = self.tasks.write().await;
+        let Some(entry) = tasks.get_mut(task_id) else {
+            return;
+        };
+        // A canceled task keeps its status even if the run was not stopped in time
+        if entry.task.status.state.is_terminal() {
+            return;
+        }
+        entry.task.status = status.clone();
+        if is_final {
+            entry.abort = None;
+        }
+        let event = A2AEvent::StatusUpdate(TaskStatusUpdateEvent {
+            task_id: task_id.to_string(),
+            context_id: entry.task.context_id.clone(),
+            status,
+            is_final,
+        });
+        let events = if is_final {
+            entry.events.take()
+        } else {
+            entry.events.clone()
+        };
+        if let Some(events) = events {
+            let _ = events.send(event);
+        }
+    }
+
+    async fn add_artifact(&self, task_id: &str, artifact: Artifact) {
+        let mut tasks = self.tasks.write().await;
+        let Some(entry) = tasks.get_mut(task_id) else {
+            return;
+        };
+        if entry.task.status.state.is_terminal() {
+            return;
+        }
+        entry.task.artifacts.push(artifact.clone());
+        if let Some(events) = &entry.events {
+            let _ = events.send(A2AEvent::ArtifactUpdate(TaskArtifactUpdateEvent {
+                task_id: task_id.to_string(),
+                context_id: entry.task.context_id.clone(),
+                artifact,
+                append: false,
+                last_chunk: true,
+            }));
+        }
+    }
+
+    async fn task(&self, task_id: &str) -> Result<Task, JsonRpcError> {
+        self.tasks
+            .read()
+            .await
+            .get(task_id)
+            .map(|entry| entry.task.clone())
+            .ok_or_else(|| task_not_found(task_id))
+    }
+
+    async fn cancel_task(&self, task_id: &str) -> Result<Task, JsonRpcError> {
+        let mut tasks = self.tasks.write().await;
+        let entry = tasks
+            .get_mut(task_id)
+            .ok_or_else(|| task_not_found(task_id))?;
+        if entry.task.status.state.is_terminal() {
+            return Err(JsonRpcError::new(
+                JsonRpcError::TASK_NOT_CANCELABLE,
+                format!("Task cannot be canceled: {}", task_id),
+            ));
+        }
+
+        if let Some(abort) = entry.abort.take() {
+            abort.abort();
+        }
+        let status = TaskStatus::new(TaskState::Canceled);
+        entry.task.status = status.clone();
+        if let Some(events) = entry.events.take() {
+            let _ = events.send(A2AEvent::StatusUpdate(TaskStatusUpdateEvent {
+                task_id: task_id.to_string(),
+                context_id: entry.task.context_id.clone(),
+                status,
+                is_final: true,
+            }));
+        }
+        Ok(entry.task.clone())
+    }
+}
+
+fn task_not_found(task_id: &str) -> JsonRpcError {
+    JsonRpcError::new(
+        JsonRpcError::TASK_NOT_FOUND,
+        format!("Task not found: {}", task_id),
+    )
+}
+
+/// Keep only the last `history_length` messages of a task
+fn truncate_history(mut task: Task, history_length: Option<usize>) -> Task {
+    if let Some(length) = history_length {
+        let skip = task.history.len().saturating_sub(length);
+        task.history.drain(..skip);
+    }
+    task
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, JsonRpcError> {
+    serde_json::from_value(params)
+        .map_err(|e| JsonRpcError::new(JsonRpcError::INVALID_PARAMS, e.to_string()))
+}
+
+fn to_result<T: serde::Serialize>(value: &T) -> Result<Value, JsonRpcError> {
+    serde_json::to_value(value)
+        .map_err(|e| JsonRpcError::new(JsonRpcError::INTERNAL_ERROR, e.to_string()))
+}
+
+async fn agent_card(AppState(server): AppState<Arc<ServerInner>>) -> Json<AgentCard> {
+    Json(server.card.clone())
+}
+
+async fn json_rpc(AppState(server): AppState<Arc<ServerInner>>, body: String) -> Response {
+    let request: JsonRpcRequest = match serde_json::from_str::<Value>(&body) {
+        Ok(value) => match serde_json::from_value(value) {
+            Ok(request) => request,
+            Err(e) => {
+                return rpc_error(
+                    Value::Null,
+                    JsonRpcError::new(JsonRpcError::INVALID_REQUEST, e.to_string()),
+                )
+            }
+        },
+        Err(e) => {
+            return rpc_error(
+                Value::Null,
+                JsonRpcError::new(JsonRpcError::PARSE_ERROR, e.to_string()),
+            )
+        }
+    };
+    if request.jsonrpc != "2.0" {
+        return rpc_error(
+            request.id,
+            JsonRpcError::new(JsonRpcError::INVALID_REQUEST, "jsonrpc must be \"2.0\""),
+        );
+    }
+
+    let id = request.id.clone();
+    if request.method == "message/stream" {
+        return match message_stream(&server, request).await {
+            Ok(response) => response,
+            Err(error) => rpc_error(id, error),
+        };
+    }
+
+    let result = match request.method.as_str() {
+        "message/send" => message_send(&server, request.params).await,
+        "tasks/get" => match parse_params::<TaskQueryParams>(request.params) {
+            Ok(params) => server
+                .task(&params.id)
+                .await
+                .map(|task| truncate_history(task, params.history_length))
+                .and_then(|task| to_result(&A2AEvent::Task(task))),
+            Err(e) => Err(e),
+        },
+        "tasks/cancel" => match parse_params::<TaskIdParams>(request.params) {
+            Ok(params) => server
+                .cancel_task(&params.id)
+                .await
+                .and_then(|task| to_result(&A2AEvent::Task(task))),
+            Err(e) => Err(e),
+        },
+        method => Err(JsonRpcError::new(
+            JsonRpcError::METHOD_NOT_FOUND,
+            format!("Method not found: {}", method),
+        )),
+    };
+
+    match result {
+        Ok(result) => Json(JsonRpcResponse::success(id, result)).into_response(),
+        Err(error) => rpc_error(id, error),
+    }
+}
+
+async fn message_send(server: &Arc<ServerInner>, params: Value) -> Result<Value, JsonRpcError> {
+    let params: MessageSendParams = parse_params(params)?;
+    let configuration = params.configuration.unwrap_or_default();
+    let (task, mut events) = server.start_task(params.message).await?;
+
+    if configuration.blocking.unwrap_or(true) {
+        while let Some(event) = events.recv().await {
+            if event.is_final() {
+                break;
+            }
+        }
+    }
+    let task = server.task(&task.id).await?;
+    to_result(&A2AEvent::Task(truncate_history(
+        task,
+        configuration.history_length,
+    )))
+}
+
+async fn message_stream(
+    server: &Arc<ServerInner>,
+    request: JsonRpcRequest,
+) -> Result<Response, JsonRpcError> {
+    let params: MessageSendParams = parse_params(request.params)?;
+    let (task, mut events) = server.start_task(params.message).await?;
+    let id = request.id;
+
+    let events = stream! {
+        yield rpc_event(&id, &A2AEvent::Task(task));
+        while let Some(event) = events.recv().await {
+            let is_final = event.is_final();
+            yield rpc_event(&id, &event);
+            if is_final {
+                break;
+            }
+        }
+    };
+    Ok(Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response())
+}
+
+fn rpc_event(id: &Value, event: &A2AEvent) -> Result<Event, Infallible> {
+    let response = match serde_json::to_value(event) {
+        Ok(result) => JsonRpcResponse::success(id.clone(), result),
+        Err(e) => JsonRpcResponse::failure(
+            id.clone(),
+            JsonRpcError::new(JsonRpcError::INTERNAL_ERROR, e.to_string()),
+        ),
+    };
+    Ok(Event::default().data(serde_json::to_string(&response).unwrap_or_default()))
+}
+
+fn rpc_error(id: Value, error: JsonRpcError) -> Response {
+    Json(JsonRpcResponse::failure(id, error)).into_response()
+}
@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use serde_json::json;
+
+use crate::{
+    agent::{create_agent_from_llm, AgentState, RouterAgentBuilder},
+    langgraph::{function_node, messages_state_update, MessagesState, StateGraph, END, START},
+    language_models::{llm::LLM, GenerateResult, LLMError},
+    schemas::{Message, StreamData},
+    tools::{EmptyContext, InMemoryStore, Tool, ToolResult, ToolRuntime},
+};
+
+use super::*;
+
+#[derive(Clone)]
+struct FixedLLM(&'static str);
+
+#[async_trait]
+impl LLM for FixedLLM {
+    async fn generate(&self, _messages: &[Message]) -> Result<GenerateResult, LLMError> {
+        Ok(GenerateResult {
+            generation: self.0.to_string(),
+            tokens: None,
+        })
+    }
+
+    async fn stream(
+        &self,
+        _messages: &[Message],
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamData, LLMError>> + Send>>, LLMError> {
+        let answer = self.0;
+        Ok(Box::pin(futures::stream::once(async move {
+            Ok(StreamData::new(serde_json::Value::Null, None, answer))
+        })))
+    }
+}
+
+/// Never finishes, so the task stays `working` until canceled.
+struct PendingExecutor;
+
+#[async_trait]
+impl A2AExecutor for PendingExecutor {
+    async fn execute(
+        &self,
+        _messages: Vec<Message>,
+        updates: &TaskUpdater,
+    ) -> Result<String, A2AError> {
+        updates.progress("thinking");
+        futures::future::pending().await
+    }
+}
+
+/// A graph answering with the number of messages it was given.
+fn counting_graph() -> crate::langgraph::CompiledGraph<MessagesState> {
+    let mut graph = StateGraph::<MessagesState>::new();
+    graph
+        .add_node(
+            "reply",
+            function_node("reply", |state: &MessagesState| {
+                let count = state.messages.len();
+                async move {
+                    Ok(messages_state_update(vec![Message::new_ai_message(
+                        format!("seen {} messages", count),
+                    )]))
+                }
+            }),
+        )
+        .unwrap();
+    graph.add_edge(START, "reply");
+    graph.add_edge("reply", END);
+    graph.compile().unwrap()
+}
+
+async fn spawn_server(name: &str, executor: Arc<dyn A2AExecutor>) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let card = AgentCard::new(name, format!("The {} agent", name), format!("{}/", url))
+        .with_skill(AgentSkill::new("answer", "Answer", "Answers questions"));
+    let server = A2AServer::new(card, executor);
+    tokio::spawn(async move { server.serve(listener).await });
+    url
+}
+
+#[tokio::test]
+async fn test_agent_card_and_blocking_send() {
+    let agent = create_agent_from_llm(FixedLLM("Refund issued."), &[], None).unwrap();
+    let url = spawn_server("billing", Arc::new(agent)).await;
+    let client = A2AClient::new(&url);
+
+    let card = client.get_agent_card().await.unwrap();
+    assert_eq!(card.name, "billing");
+    assert_eq!(card.url, format!("{}/", url));
+    assert!(card.capabilities.streaming);
+
+    let event = client
+        .send_message(A2AMessage::user_text("I want a refund"))
+        .await
+        .unwrap();
+    let A2AEvent::Task(task) = event else {
+        panic!("expected a task, got {:?}", event);
+    };
+    assert_eq!(task.status.state, TaskState::Completed);
+    assert_eq!(task.output_text(), "Refund issued.");
+    assert_eq!(task.history.len(), 2);
+
+    let fetched = client.get_task(&task.id, Some(1)).await.unwrap();
+    assert_eq!(fetched.status.state, TaskState::Completed);
+    assert_eq!(fetched.history.len(), 1);
+    assert_eq!(fetched.history[0].role, Role::Agent);
+}
+
+#[tokio::test]
+async fn test_context_continues_conversation() {
+    let url = spawn_server("counter", Arc::new(counting_graph())).await;
+    let client = A2AClient::new(url);
+
+    let (answer, context_id) = client.ask("hello", None).await.unwrap();
+    assert_eq!(answer, "seen 1 messages");
+
+    let (answer, _) = client.ask("again", context_id.as_deref()).await.unwrap();
+    assert_eq!(answer, "seen 3 messages");
+
+    let (answer, _) = client.ask("new conversation", None).await.unwrap();
+    assert_eq!(answer, "seen 1 messages");
+}
+
+#[tokio::test]
+async fn test_stream_task_lifecycle() {
+    let url = spawn_server("counter", Arc::new(counting_graph())).await;
+    let client = A2AClient::new(url);
+
+    let events: Vec<A2AEvent> = client
+        .send_message_stream(A2AMessage::user_text("hello"))
+        .await
+        .unwrap()
+        .map(Result::unwrap)
+        .collect()
+        .await;
+
+    let kinds: Vec<String> = events
+        .iter()
+        .map(|event| match event {
+            A2AEvent::Task(task) => format!("task:{:?}", task.status.state),
+            A2AEvent::StatusUpdate(update) => format!("status:{:?}", update.status.state),
+            A2AEvent::ArtifactUpdate(update) => {
+                format!("artifact:{}", parts_text(&update.artifact.parts))
+            }
+            A2AEvent::Message(message) => format!("message:{}", message.text()),
+        })
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            "task:Submitted",
+            "status:Working",
+            "status:Working",
+            "artifact:seen 1 messages",
+            "status:Completed",
+        ]
+    );
+    assert!(events.last().unwrap().is_final());
+}
+
+#[tokio::test]
+async fn test_cancel_running_task() {
+    let url = spawn_server("slow", Arc::new(PendingExecutor)).await;
+    let client = A2AClient::new(url);
+
+    let mut events = client
+        .send_message_stream(A2AMessage::user_text("take your time"))
+        .await
+        .unwrap();
+    let Some(Ok(A2AEvent::Task(task))) = events.next().await else {
+        panic!("expected the submitted task first");
+    };
+    // Wait for the progress update so the task is running
+    while let Some(Ok(event)) = events.next().await {
+        if let A2AEvent::StatusUpdate(update) = event {
+            if update.status.message.is_some() {
+                break;
+            }
+        }
+    }
+
+    let canceled = client.cancel_task(&task.id).await.unwrap();
+    assert_eq!(canceled.status.state, TaskState::Canceled);
+    let Some(Ok(A2AEvent::StatusUpdate(update))) = events.next().await else {
+        panic!("expected the final status update");
+    };
+    assert_eq!(update.status.state, TaskState::Canceled);
+    assert!(update.is_final);
+    assert!(events.next().await.is_none());
+
+    let error = client.cancel_task(&task.id).await.unwrap_err();
+    assert!(
+        matches!(error, A2AError::RpcError(ref e) if e.code == JsonRpcError::TASK_NOT_CANCELABLE)
+    );
+    let error = client.get_task("missing", None).await.unwrap_err();
+    assert!(matches!(error, A2AError::RpcError(ref e) if e.code == JsonRpcError::TASK_NOT_FOUND));
+}
+
+fn runtime() -> ToolRuntime {
+    ToolRuntime::new(
+        Arc::new(tokio::sync::Mutex::new(AgentState::new())),
+        Arc::new(EmptyContext),
+        Arc::new(InMemoryStore::new()),
+        "call_1".to_string(),
+    )
+}
+
+async fn ask_in(remote: &A2ARemoteAgent, runtime: &ToolRuntime, text: &str) -> String {
+    match remote
+        .run_with_runtime(json!({ "input": text }), runtime)
+        .await
+        .unwrap()
+    {
+        ToolResult::Text(answer) => answer,
+        other => panic!("unexpected tool result: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_remote_agent_as_tool_and_router_target() {
+    let url = spawn_server("counter", Arc::new(counting_graph())).await;
+    let remote = Arc::new(
+        A2ARemoteAgent::from_card(A2AClient::new(url))
+            .await
+            .unwrap(),
+    );
+    assert_eq!(remote.name(), "counter");
+    assert_eq!(remote.description(), "The counter agent");
+
+    let answer = remote.run(json!({ "input": "hello" })).await.unwrap();
+    assert_eq!(answer, "seen 1 messages");
+
+    // Each calling conversation continues its own remote context
+    let alice = runtime();
+    let bob = runtime();
+    assert_eq!(ask_in(&remote, &alice, "hi").await, "seen 1 messages");
+    assert_eq!(ask_in(&remote, &alice, "again").await, "seen 3 messages");
+    assert_eq!(ask_in(&remote, &bob, "hi").await, "seen 1 messages");
+    assert!(bob
+        .state()
+        .await
+        .get_field(&remote.context_field())
+        .is_some());
+
+    let router_agent = RouterAgentBuilder::new()
+        .with_keyword_router(HashMap::from([(
+            "counter".to_string(),
+            vec!["count".to_string()],
+        )]))
+        .with_agent("counter".to_string(), remote)
+        .build()
+        .unwrap();
+    let answer = router_agent
+        .invoke_messages(vec![Message::new_human_message("count again")])
+        .await
+        .unwrap();
+    assert_eq!(answer, "seen 1 messages");
+}
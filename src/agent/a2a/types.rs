@@ -0,0 +1,492 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::schemas::Message;
+
+/// A2A protocol version implemented by the server and client
+pub const A2A_PROTOCOL_VERSION: &str = "0.3.0";
+
+/// Path of the agent card, relative to the agent's base URL
+pub const AGENT_CARD_PATH: &str = "/.well-known/agent-card.json";
+
+/// Self-description of an A2A agent, served at [`AGENT_CARD_PATH`]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentCard {
+    pub name: String,
+    pub description: String,
+    /// URL of the JSON-RPC endpoint
+    pub url: String,
+    pub version: String,
+    #[serde(default = "default_protocol_version")]
+    pub protocol_version: String,
+    #[serde(default)]
+    pub capabilities: AgentCapabilities,
+    #[serde(default = "default_modes")]
+    pub default_input_modes: Vec<String>,
+    #[serde(default = "default_modes")]
+    pub default_output_modes: Vec<String>,
+    #[serde(default)]
+    pub skills: Vec<AgentSkill>,
+}
+
+fn default_protocol_version() -> String {
+    A2A_PROTOCOL_VERSION.to_string()
+}
+
+fn default_modes() -> Vec<String> {
+    vec!["text/plain".to_string()]
+}
+
+impl AgentCard {
+    pub fn new<N: Into<String>, D: Into<String>, U: Into<String>>(
+        name: N,
+        description: D,
+        url: U,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            url: url.into(),
+            version: "1.0.0".to_string(),
+            protocol_version: default_protocol_version(),
+            capabilities: AgentCapabilities {
+                streaming: true,
+                push_notifications: false,
+            },
+            default_input_modes: default_modes(),
+            default_output_modes: default_modes(),
+            skills: Vec::new(),
+        }
+    }
+
+    pub fn with_version<S: Into<String>>(mut self, version: S) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    pub fn with_skill(mut self, skill: AgentSkill) -> Self {
+        self.skills.push(skill);
+        self
+    }
+}
+
+/// Optional protocol features an agent supports
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentCapabilities {
+    #[serde(default)]
+    pub streaming: bool,
+    #[serde(default)]
+    pub push_notifications: bool,
+}
+
+/// Something an agent can do, advertised in its card
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentSkill {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<String>,
+}
+
+impl AgentSkill {
+    pub fn new<I: Into<String>, N: Into<String>, D: Into<String>>(
+        id: I,
+        name: N,
+        description: D,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            description: description.into(),
+            tags: Vec::new(),
+            examples: Vec::new(),
+        }
+    }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn with_examples(mut self, examples: Vec<String>) -> Self {
+        self.examples = examples;
+        self
+    }
+}
+
+/// Sender of an A2A message
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Agent,
+}
+
+/// Content of an A2A message or artifact
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Part {
+    Text { text: String },
+    Data { data: Value },
+    File { file: FileContent },
+}
+
+impl Part {
+    pub fn text<S: Into<String>>(text: S) -> Self {
+        Part::Text { text: text.into() }
+    }
+}
+
+/// A file part, inline (base64 `bytes`) or by `uri`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileContent {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+}
+
+/// One turn of an A2A conversation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct A2AMessage {
+    pub role: Role,
+    pub parts: Vec<Part>,
+    pub message_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_id: Option<String>,
+}
+
+impl A2AMessage {
+    /// A user message with a single text part
+    pub fn user_text<S: Into<String>>(text: S) -> Self {
+        Self::new(Role::User, vec![Part::text(text)])
+    }
+
+    /// An agent message with a single text part
+    pub fn agent_text<S: Into<String>>(text: S) -> Self {
+        Self::new(Role::Agent, vec![Part::text(text)])
+    }
+
+    pub fn new(role: Role, parts: Vec<Part>) -> Self {
+        Self {
+            role,
+            parts,
+            message_id: new_id(),
+            task_id: None,
+            context_id: None,
+        }
+    }
+
+    /// Continue the conversation of `context_id`
+    pub fn with_context_id<S: Into<String>>(mut self, context_id: S) -> Self {
+        self.context_id = Some(context_id.into());
+        self
+    }
+
+    pub fn with_task_id<S: Into<String>>(mut self, task_id: S) -> Self {
+        self.task_id = Some(task_id.into());
+        self
+    }
+
+    /// The message content as text: text parts as is, data parts as JSON, files by name or URI
+    pub fn text(&self) -> String {
+        parts_text(&self.parts)
+    }
+
+    /// Convert to a chat message (user messages become human messages)
+    pub fn to_message(&self) -> Message {
+        match self.role {
+            Role::User => Message::new_human_message(self.text()),
+            Role::Agent => Message::new_ai_message(self.text()),
+        }
+    }
+}
+
+/// Text of a list of parts, see [`A2AMessage::text`]
+pub fn parts_text(parts: &[Part]) -> String {
+    parts
+        .iter()
+        .map(|part| match part {
+            Part::Text { text } => text.clone(),
+            Part::Data { data } => data.to_string(),
+            Part::File { file } => file
+                .name
+                .clone()
+                .or_else(|| file.uri.clone())
+                .unwrap_or_default(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Lifecycle state of a task
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum TaskState {
+    Submitted,
+    Working,
+    InputRequired,
+    Completed,
+    Canceled,
+    Failed,
+    Rejected,
+    AuthRequired,
+    Unknown,
+}
+
+impl TaskState {
+    /// Whether the task has finished and will not change anymore
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            TaskState::Completed | TaskState::Canceled | TaskState::Failed | TaskState::Rejected
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskStatus {
+    pub state: TaskState,
+    /// Message from the agent about this status, e.g. progress or the error
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<A2AMessage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+}
+
+impl TaskStatus {
+    pub fn new(state: TaskState) -> Self {
+        Self {
+            state,
+            message: None,
+            timestamp: Some(chrono::Utc::now().to_rfc3339()),
+        }
+    }
+
+    pub fn with_message(mut self, message: A2AMessage) -> Self {
+        self.message = Some(message);
+        self
+    }
+}
+
+/// An output produced by a task
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Artifact {
+    pub artifact_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub parts: Vec<Part>,
+}
+
+/// A unit of work performed by the agent for one message
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Task {
+    pub id: String,
+    pub context_id: String,
+    pub status: TaskStatus,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<Artifact>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<A2AMessage>,
+}
+
+impl Task {
+    /// Text of all artifacts, or of the status message when there are none
+    pub fn output_text(&self) -> String {
+        if self.artifacts.is_empty() {
+            return self
+                .status
+                .message
+                .as_ref()
+                .map(A2AMessage::text)
+                .unwrap_or_default();
+        }
+        self.artifacts
+            .iter()
+            .map(|artifact| parts_text(&artifact.parts))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Sent while streaming when the status of a task changes
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskStatusUpdateEvent {
+    pub task_id: String,
+    pub context_id: String,
+    pub status: TaskStatus,
+    /// Last event of the stream
+    #[serde(rename = "final", default)]
+    pub is_final: bool,
+}
+
+/// Sent while streaming when a task produces an artifact
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskArtifactUpdateEvent {
+    pub task_id: String,
+    pub context_id: String,
+    pub artifact: Artifact,
+    #[serde(default)]
+    pub append: bool,
+    #[serde(default)]
+    pub last_chunk: bool,
+}
+
+/// Result of `message/send`, and the events of `message/stream`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum A2AEvent {
+    Message(A2AMessage),
+    Task(Task),
+    StatusUpdate(TaskStatusUpdateEvent),
+    ArtifactUpdate(TaskArtifactUpdateEvent),
+}
+
+impl A2AEvent {
+    /// Whether no more events follow this one
+    pub fn is_final(&self) -> bool {
+        match self {
+            A2AEvent::Message(_) => true,
+            A2AEvent::Task(task) => task.status.state.is_terminal(),
+            A2AEvent::StatusUpdate(event) => event.is_final,
+            A2AEvent::ArtifactUpdate(_) => false,
+        }
+    }
+}
+
+/// Parameters of `message/send` and `message/stream`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageSendParams {
+    pub message: A2AMessage,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub configuration: Option<MessageSendConfiguration>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageSendConfiguration {
+    /// Wait for the task to finish before answering `message/send` (default: true)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocking: Option<bool>,
+    /// Maximum number of history messages returned with the task
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history_length: Option<usize>,
+}
+
+/// Parameters of `tasks/get`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskQueryParams {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history_length: Option<usize>,
+}
+
+/// Parameters of `tasks/cancel`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskIdParams {
+    pub id: String,
+}
+
+/// A JSON-RPC 2.0 request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcRequest {
+    pub jsonrpc: String,
+    #[serde(default)]
+    pub id: Value,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+impl JsonRpcRequest {
+    pub fn new<S: Into<String>>(method: S, params: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id: Value::String(new_id()),
+            method: method.into(),
+            params,
+        }
+    }
+}
+
+/// A JSON-RPC 2.0 response, with either a result or an error
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcResponse {
+    pub jsonrpc: String,
+    pub id: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<JsonRpcError>,
+}
+
+impl JsonRpcResponse {
+    pub fn success(id: Value, result: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    pub fn failure(id: Value, error: JsonRpcError) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(error),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl JsonRpcError {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    pub const INTERNAL_ERROR: i64 = -32603;
+    pub const TASK_NOT_FOUND: i64 = -32001;
+    pub const TASK_NOT_CANCELABLE: i64 = -32002;
+
+    pub fn new<S: Into<String>>(code: i64, message: S) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+pub(crate) fn new_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
@@ -18,5 +18,18 @@ pub trait Agent: Send + Sync {
         inputs: PromptArgs,
     ) -> Result<AgentEvent, AgentError>;
 
+    /// Plan the next step, reporting model output through `on_token` as it is generated.
+    ///
+    /// Used when an agent run is streamed. The default implementation calls [`Agent::plan`]
+    /// and reports no tokens.
+    async fn plan_streaming(
+        &self,
+        intermediate_steps: &[(AgentAction, String)],
+        inputs: PromptArgs,
+        _on_token: &(dyn for<'t> Fn(&'t str) + Send + Sync),
+    ) -> Result<AgentEvent, AgentError> {
+        self.plan(intermediate_steps, inputs).await
+    }
+
     fn get_tools(&self) -> Vec<Arc<dyn Tool>>;
 }
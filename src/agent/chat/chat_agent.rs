@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use futures::StreamExt;
 use serde_json::json;
 
 use crate::{
@@ -78,7 +79,11 @@ impl ConversationalAgent {
             thoughts.push(Message::new_ai_message(&action.log));
             let tool_response = template_jinja2!(TEMPLATE_TOOL_RESPONSE, "observation")
                 .format(prompt_args!("observation"=>observation))?;
-            thoughts.push(Message::new_human_message(&tool_response));
+            // Images and files the tool returned go with the observation
+            thoughts.push(
+                Message::new_human_message(&tool_response)
+                    .with_content_parts(action.attachments.clone()),
+            );
         }
         Ok(thoughts)
     }
@@ -99,6 +104,26 @@ impl Agent for ConversationalAgent {
         Ok(parsed_output)
     }
 
+    async fn plan_streaming(
+        &self,
+        intermediate_steps: &[(AgentAction, String)],
+        inputs: PromptArgs,
+        on_token: &(dyn for<'t> Fn(&'t str) + Send + Sync),
+    ) -> Result<AgentEvent, AgentError> {
+        let scratchpad = self.construct_scratchpad(intermediate_steps)?;
+        let mut inputs = inputs.clone();
+        inputs.insert("agent_scratchpad".to_string(), json!(scratchpad));
+        let mut stream = self.chain.stream(inputs).await?;
+        let mut output = String::new();
+        while let Some(data) = stream.next().await {
+            let data = data?;
+            on_token(&data.content);
+            output.push_str(&data.content);
+        }
+        let parsed_output = self.output_parser.parse(&output)?;
+        Ok(parsed_output)
+    }
+
     fn get_tools(&self) -> Vec<Arc<dyn Tool>> {
         self.tools.clone()
     }
@@ -64,11 +64,11 @@ impl ChatOutputParser {
                         output: action_input,
                     }))
                 } else {
-                    Ok(AgentEvent::Action(vec![AgentAction {
-                        tool: action,
-                        tool_input: action_input,
-                        log: text.to_string(),
-                    }]))
+                    Ok(AgentEvent::Action(vec![AgentAction::new(
+                        action,
+                        action_input,
+                        text.to_string(),
+                    )]))
                 }
             }
             None => {
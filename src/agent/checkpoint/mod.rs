@@ -0,0 +1,268 @@
+//! Agent checkpointer for human-in-the-loop: persist state at interrupt and resume with decisions.
+//!
+//! Backends:
+//! - [InMemoryAgentSaver]: process-local, for tests and demos
+//! - `SqliteAgentSaver` (feature `sqlite-persistence`): single-file durable storage
+//! - `PostgresAgentSaver` (feature `postgres`): shared storage for multi-instance deployments
+//!
+//! Checkpoints are stored as versioned JSON ([AgentCheckpointState::to_json] /
+//! [AgentCheckpointState::from_json]), so states written by older releases can still be resumed.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::RwLock;
+
+use crate::langgraph::PersistenceError;
+use crate::prompt::PromptArgs;
+use crate::schemas::agent::AgentAction;
+
+#[cfg(feature = "sqlite-persistence")]
+mod sqlite;
+#[cfg(feature = "sqlite-persistence")]
+pub use sqlite::*;
+
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "postgres")]
+pub use postgres::*;
+
+/// Current version of the serialized [AgentCheckpointState] format.
+///
+/// - `0`: unversioned states (steps, input_variables, pending_actions only)
+/// - `1`: adds `version`, `interrupt` and `created_at`
+pub const AGENT_CHECKPOINT_VERSION: u32 = 1;
+
+/// Serializable agent state saved at interrupt.
+///
+/// Used to resume execution after human provides decisions for pending tool calls.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AgentCheckpointState {
+    /// Format version (see [AGENT_CHECKPOINT_VERSION]).
+    #[serde(default)]
+    pub version: u32,
+    /// Steps (action, observation) so far.
+    pub steps: Vec<(AgentAction, String)>,
+    /// Last plan input (chat_history, input, etc.).
+    pub input_variables: PromptArgs,
+    /// Tool calls that were pending when we interrupted (same order as in interrupt payload).
+    pub pending_actions: Vec<AgentAction>,
+    /// Interrupt payload returned to the caller (e.g. action_requests / review_configs).
+    #[serde(default)]
+    pub interrupt: Option<Value>,
+    /// When the interrupt happened.
+    #[serde(default = "Utc::now")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl AgentCheckpointState {
+    /// Create a state at the current version.
+    pub fn new(
+        steps: Vec<(AgentAction, String)>,
+        input_variables: PromptArgs,
+        pending_actions: Vec<AgentAction>,
+    ) -> Self {
+        Self {
+            version: AGENT_CHECKPOINT_VERSION,
+            steps,
+            input_variables,
+            pending_actions,
+            interrupt: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Attach the interrupt payload returned to the caller.
+    pub fn with_interrupt(mut self, interrupt: Value) -> Self {
+        self.interrupt = Some(interrupt);
+        self
+    }
+
+    /// Whether this state is waiting for human decisions.
+    pub fn is_pending(&self) -> bool {
+        !self.pending_actions.is_empty()
+    }
+
+    /// Serialize to versioned JSON.
+    pub fn to_json(&self) -> Result<Value, PersistenceError> {
+        let mut state = self.clone();
+        state.version = AGENT_CHECKPOINT_VERSION;
+        Ok(serde_json::to_value(state)?)
+    }
+
+    /// Deserialize from JSON written by this or an older version.
+    ///
+    /// Unversioned states are upgraded; states from a newer version are rejected
+    /// rather than resumed with missing data.
+    pub fn from_json(value: Value) -> Result<Self, PersistenceError> {
+        let version = value
+            .get("version")
+            .and_then(Value::as_u64)
+            .unwrap_or_default() as u32;
+        if version > AGENT_CHECKPOINT_VERSION {
+            return Err(PersistenceError::InvalidConfig(format!(
+                "unsupported agent checkpoint version {} (latest supported is {})",
+                version, AGENT_CHECKPOINT_VERSION
+            )));
+        }
+        let mut state: Self = serde_json::from_value(value)?;
+        state.version = AGENT_CHECKPOINT_VERSION;
+        Ok(state)
+    }
+}
+
+/// A thread waiting for human decisions, as returned by [AgentCheckpointer::list_pending].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PendingApproval {
+    /// Thread to resume (pass the same thread_id in the resume config).
+    pub thread_id: String,
+    /// Tool calls awaiting decisions.
+    pub pending_actions: Vec<AgentAction>,
+    /// Interrupt payload returned when the run paused.
+    pub interrupt: Option<Value>,
+    /// When the run paused.
+    pub created_at: DateTime<Utc>,
+}
+
+impl PendingApproval {
+    /// Build from a stored state.
+    pub fn from_state(thread_id: impl Into<String>, state: &AgentCheckpointState) -> Self {
+        Self {
+            thread_id: thread_id.into(),
+            pending_actions: state.pending_actions.clone(),
+            interrupt: state.interrupt.clone(),
+            created_at: state.created_at,
+        }
+    }
+}
+
+/// Trait for persisting and loading agent checkpoint state (e.g. for HILP resume).
+#[async_trait]
+pub trait AgentCheckpointer: Send + Sync {
+    /// Save state for the given thread. Overwrites any existing state for that thread.
+    async fn put(
+        &self,
+        thread_id: &str,
+        state: &AgentCheckpointState,
+    ) -> Result<(), PersistenceError>;
+
+    /// Load state for the given thread, if any.
+    async fn get(&self, thread_id: &str) -> Result<Option<AgentCheckpointState>, PersistenceError>;
+
+    /// Remove state for the given thread (called once a resumed run no longer needs it).
+    async fn delete(&self, thread_id: &str) -> Result<(), PersistenceError>;
+
+    /// List threads waiting for human decisions, oldest first.
+    async fn list_pending(&self) -> Result<Vec<PendingApproval>, PersistenceError>;
+}
+
+/// In-memory checkpointer (one state per thread_id).
+#[derive(Default)]
+pub struct InMemoryAgentSaver {
+    state: RwLock<HashMap<String, AgentCheckpointState>>,
+}
+
+impl InMemoryAgentSaver {
+    pub fn new() -> Self {
+        Self {
+            state: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl AgentCheckpointer for InMemoryAgentSaver {
+    async fn put(
+        &self,
+        thread_id: &str,
+        state: &AgentCheckpointState,
+    ) -> Result<(), PersistenceError> {
+        let mut g = self.state.write().await;
+        g.insert(thread_id.to_string(), state.clone());
+        Ok(())
+    }
+
+    async fn get(&self, thread_id: &str) -> Result<Option<AgentCheckpointState>, PersistenceError> {
+        let g = self.state.read().await;
+        Ok(g.get(thread_id).cloned())
+    }
+
+    async fn delete(&self, thread_id: &str) -> Result<(), PersistenceError> {
+        let mut g = self.state.write().await;
+        g.remove(thread_id);
+        Ok(())
+    }
+
+    async fn list_pending(&self) -> Result<Vec<PendingApproval>, PersistenceError> {
+        let g = self.state.read().await;
+        let mut pending: Vec<PendingApproval> = g
+            .iter()
+            .filter(|(_, state)| state.is_pending())
+            .map(|(thread_id, state)| PendingApproval::from_state(thread_id, state))
+            .collect();
+        pending.sort_by_key(|p| p.created_at);
+        Ok(pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn action(tool: &str) -> AgentAction {
+        AgentAction::new(tool, "{}", "")
+    }
+
+    #[test]
+    fn test_from_json_upgrades_unversioned_state() {
+        let legacy = json!({
+            "steps": [],
+            "input_variables": { "input": "hi" },
+            "pending_actions": [action("delete_file")]
+        });
+
+        let state = AgentCheckpointState::from_json(legacy).unwrap();
+        assert_eq!(state.version, AGENT_CHECKPOINT_VERSION);
+        assert_eq!(state.pending_actions[0].tool, "delete_file");
+        assert!(state.interrupt.is_none());
+    }
+
+    #[test]
+    fn test_from_json_rejects_newer_version() {
+        let mut value = AgentCheckpointState::new(vec![], PromptArgs::new(), vec![])
+            .to_json()
+            .unwrap();
+        value["version"] = json!(AGENT_CHECKPOINT_VERSION + 1);
+
+        assert!(AgentCheckpointState::from_json(value).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_list_pending() {
+        let saver = InMemoryAgentSaver::new();
+        let pending = AgentCheckpointState::new(vec![], PromptArgs::new(), vec![action("send")])
+            .with_interrupt(json!({ "action_requests": [] }));
+        saver.put("t1", &pending).await.unwrap();
+        saver
+            .put(
+                "t2",
+                &AgentCheckpointState::new(vec![], PromptArgs::new(), vec![]),
+            )
+            .await
+            .unwrap();
+
+        let list = saver.list_pending().await.unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].thread_id, "t1");
+        assert_eq!(list[0].pending_actions[0].tool, "send");
+
+        saver.delete("t1").await.unwrap();
+        assert!(saver.get("t1").await.unwrap().is_none());
+        assert!(saver.list_pending().await.unwrap().is_empty());
+    }
+}
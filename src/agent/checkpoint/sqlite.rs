@@ -0,0 +1,187 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use tokio::sync::Mutex;
+
+use crate::langgraph::PersistenceError;
+
+use super::{AgentCheckpointState, AgentCheckpointer, PendingApproval, AGENT_CHECKPOINT_VERSION};
+
+/// SQLite-based agent checkpointer.
+///
+/// Stores one row per thread in the `agent_checkpoints` table, so pending approvals
+/// survive restarts and deploys.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let saver = Arc::new(SqliteAgentSaver::new("agent_checkpoints.db")?);
+/// let agent = create_agent(...)?.with_checkpointer(Some(saver));
+/// ```
+pub struct SqliteAgentSaver {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl SqliteAgentSaver {
+    /// Open (or create) a database file
+    pub fn new(path: &str) -> Result<Self, PersistenceError> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    /// Create a saver backed by an in-memory database
+    pub fn new_in_memory() -> Result<Self, PersistenceError> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    /// Create a saver from an existing connection, creating the table if needed
+    pub fn from_connection(connection: Connection) -> Result<Self, PersistenceError> {
+        connection.execute(
+            "CREATE TABLE IF NOT EXISTS agent_checkpoints (
+                thread_id TEXT PRIMARY KEY,
+                version INTEGER NOT NULL,
+                state TEXT NOT NULL,
+                pending INTEGER NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        connection.execute(
+            "CREATE INDEX IF NOT EXISTS idx_agent_checkpoints_pending
+             ON agent_checkpoints(pending, created_at)",
+            [],
+        )?;
+        Ok(Self {
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+}
+
+#[async_trait]
+impl AgentCheckpointer for SqliteAgentSaver {
+    async fn put(
+        &self,
+        thread_id: &str,
+        state: &AgentCheckpointState,
+    ) -> Result<(), PersistenceError> {
+        let json = state.to_json()?;
+        let conn = self.connection.lock().await;
+        conn.execute(
+            "INSERT INTO agent_checkpoints (thread_id, version, state, pending, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(thread_id) DO UPDATE SET
+                version = excluded.version,
+                state = excluded.state,
+                pending = excluded.pending,
+                created_at = excluded.created_at,
+                updated_at = excluded.updated_at",
+            params![
+                thread_id,
+                AGENT_CHECKPOINT_VERSION,
+                json.to_string(),
+                state.is_pending(),
+                state.created_at.to_rfc3339(),
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    async fn get(&self, thread_id: &str) -> Result<Option<AgentCheckpointState>, PersistenceError> {
+        let conn = self.connection.lock().await;
+        let state: Option<String> = conn
+            .query_row(
+                "SELECT state FROM agent_checkpoints WHERE thread_id = ?1",
+                params![thread_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        state
+            .map(|s| AgentCheckpointState::from_json(serde_json::from_str(&s)?))
+            .transpose()
+    }
+
+    async fn delete(&self, thread_id: &str) -> Result<(), PersistenceError> {
+        let conn = self.connection.lock().await;
+        conn.execute(
+            "DELETE FROM agent_checkpoints WHERE thread_id = ?1",
+            params![thread_id],
+        )?;
+        Ok(())
+    }
+
+    async fn list_pending(&self) -> Result<Vec<PendingApproval>, PersistenceError> {
+        let conn = self.connection.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT thread_id, state FROM agent_checkpoints
+             WHERE pending = 1 ORDER BY created_at ASC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut pending = Vec::new();
+        for row in rows {
+            let (thread_id, state) = row?;
+            let state = AgentCheckpointState::from_json(serde_json::from_str(&state)?)?;
+            pending.push(PendingApproval::from_state(thread_id, &state));
+        }
+        Ok(pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prompt::PromptArgs;
+    use crate::schemas::agent::AgentAction;
+
+    fn pending_state(tool: &str) -> AgentCheckpointState {
+        AgentCheckpointState::new(
+            vec![],
+            PromptArgs::new(),
+            vec![AgentAction::new(tool, "{}", "")],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_agent_saver_roundtrip() {
+        let path = std::env::temp_dir().join(format!(
+            "agent_checkpoints_{}.db",
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let path = path.to_str().unwrap();
+
+        {
+            let saver = SqliteAgentSaver::new(path).unwrap();
+            saver.put("t1", &pending_state("send_email")).await.unwrap();
+            saver
+                .put("t2", &pending_state("delete_file"))
+                .await
+                .unwrap();
+            saver
+                .put(
+                    "t2",
+                    &AgentCheckpointState::new(vec![], PromptArgs::new(), vec![]),
+                )
+                .await
+                .unwrap();
+        }
+
+        // Reopen: state survives a restart
+        let saver = SqliteAgentSaver::new(path).unwrap();
+        let state = saver.get("t1").await.unwrap().unwrap();
+        assert_eq!(state.pending_actions[0].tool, "send_email");
+
+        let pending = saver.list_pending().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].thread_id, "t1");
+
+        saver.delete("t1").await.unwrap();
+        assert!(saver.get("t1").await.unwrap().is_none());
+
+        let _ = std::fs::remove_file(path);
+    }
+}
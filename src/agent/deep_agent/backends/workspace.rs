@@ -20,6 +20,11 @@ impl WorkspaceBackend {
     pub fn new(workspace_root: PathBuf) -> Self {
         Self { workspace_root }
     }
+
+    /// Root directory all paths are resolved under.
+    pub fn root(&self) -> &std::path::Path {
+        &self.workspace_root
+    }
 }
 
 #[async_trait]
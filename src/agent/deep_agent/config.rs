@@ -15,7 +15,7 @@ use std::sync::Arc;
 
 use crate::agent::{AgentCheckpointer, InterruptConfig, Middleware, UnifiedAgent};
 use crate::schemas::StructuredOutputStrategy;
-use crate::tools::{FileBackend, ToolContext, ToolStore};
+use crate::tools::{FileBackend, SandboxPolicy, ToolContext, ToolStore};
 
 /// Configuration for creating a Deep Agent.
 ///
@@ -83,6 +83,8 @@ pub struct DeepAgentConfig {
     pub custom_tool_descriptions: HashMap<String, String>,
     /// When set (e.g. "/memories/"), paths under this prefix are routed to the store (long-term memory); other paths use the default backend. Requires a store (defaults to InMemoryStore).
     pub long_term_memory_prefix: Option<String>,
+    /// When set, adds the `execute` tool running commands under this policy, jailed in the policy root or else the workspace root.
+    pub execution_policy: Option<SandboxPolicy>,
 }
 
 impl std::fmt::Debug for DeepAgentConfig {
@@ -127,6 +129,7 @@ impl std::fmt::Debug for DeepAgentConfig {
                 &self.custom_tool_descriptions.len(),
             )
             .field("long_term_memory_prefix", &self.long_term_memory_prefix)
+            .field("execution_policy", &self.execution_policy)
             .finish()
     }
 }
@@ -180,6 +183,7 @@ impl Default for DeepAgentConfig {
             filesystem_system_prompt: None,
             custom_tool_descriptions: HashMap::new(),
             long_term_memory_prefix: None,
+            execution_policy: None,
         }
     }
 }
@@ -419,4 +423,10 @@ impl DeepAgentConfig {
         self.long_term_memory_prefix = Some(prefix.into());
         self
     }
+
+    /// Add the `execute` tool, running commands under this sandbox policy in the workspace root (or the policy root if set). Only the policy's allowlist runs (a few file utilities by default); shells and interpreters stay denied unless removed from `denied_commands`.
+    pub fn with_execution(mut self, policy: SandboxPolicy) -> Self {
+        self.execution_policy = Some(policy);
+        self
+    }
 }
@@ -29,8 +29,8 @@ pub use backends::{CompositeBackend, FileBackend, FileInfo, StoreBackend, Worksp
 pub mod tools;
 pub use tools::fs::FileSystemToolError;
 pub use tools::{
-    EditFileTool, GlobTool, GrepTool, LsTool, ReadFileTool, TaskTool, TodoItem, TodoStatus,
-    WriteFileTool, WriteTodosTool,
+    EditFileTool, ExecuteTool, GlobTool, GrepTool, LsTool, ReadFileTool, TaskTool, TodoItem,
+    TodoStatus, WriteFileTool, WriteTodosTool,
 };
 
 use std::fs;
@@ -50,6 +50,7 @@ use crate::tools::{InMemoryStore, SimpleContext, Tool, ToolContext, ToolStore};
 const DEFAULT_DEEP_AGENT_SYSTEM_PROMPT: &str = "You are a deep agent with planning and optional file system and task tools. \
 Use write_todos to break complex tasks into steps and track progress. \
 Use ls, read_file, write_file, edit_file, glob, and grep to work inside the workspace when available. \
+Use execute to run commands in the workspace when available. \
 Use the task tool to delegate to specialized subagents when configured.";
 
 /// Name of the built-in general-purpose subagent. Always present when the task tool is enabled.
@@ -283,6 +284,14 @@ fn build_deep_agent_parts(
         all_tools.push(maybe_wrap_tool(t, &config.custom_tool_descriptions));
     }
 
+    if let Some(ref policy) = config.execution_policy {
+        let t = Arc::new(
+            tools::ExecuteTool::new(policy.clone())
+                .maybe_workspace_root(config.workspace_root.clone()),
+        );
+        all_tools.push(maybe_wrap_tool(t, &config.custom_tool_descriptions));
+    }
+
     // Task tool is not added here; callers (create_deep_agent / create_deep_agent_from_llm)
     // add it when enable_task_tool, with general-purpose + config.subagents.
 
@@ -0,0 +1,173 @@
+//! execute tool: run a command in the workspace under a sandbox policy.
+
+use std::error::Error;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::fs::common::{workspace_root_from_context, FileSystemToolError};
+use crate::agent::deep_agent::WorkspaceBackend;
+use crate::tools::{
+    split_command_line, SandboxPolicy, SandboxedExecutor, Tool, ToolResult, ToolRuntime,
+};
+
+/// Tool that runs one command (no shell) jailed in the workspace root.
+///
+/// The root is the policy root if set, otherwise the workspace root from config or context.
+/// Returns the exit code, stdout and stderr as JSON.
+pub struct ExecuteTool {
+    policy: SandboxPolicy,
+    workspace_root: Option<PathBuf>,
+}
+
+impl ExecuteTool {
+    pub fn new(policy: SandboxPolicy) -> Self {
+        Self {
+            policy,
+            workspace_root: None,
+        }
+    }
+
+    /// Jail commands in the root of a workspace backend
+    pub fn for_workspace(backend: &WorkspaceBackend, policy: SandboxPolicy) -> Self {
+        Self::new(policy).with_workspace_root(backend.root().to_path_buf())
+    }
+
+    pub fn with_workspace_root(mut self, root: PathBuf) -> Self {
+        self.workspace_root = Some(root);
+        self
+    }
+
+    pub fn maybe_workspace_root(mut self, root: Option<PathBuf>) -> Self {
+        self.workspace_root = root;
+        self
+    }
+}
+
+#[async_trait]
+impl Tool for ExecuteTool {
+    fn name(&self) -> String {
+        "execute".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Run a single command in the workspace, e.g. `cargo test` or `grep -rn TODO src`. \
+         Commands run without a shell: pipes, redirections and variables are not supported. \
+         Returns exit_code, stdout and stderr."
+            .to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "command": {
+                    "type": "string",
+                    "description": "Command line to run; quote arguments containing spaces"
+                },
+                "cwd": {
+                    "type": "string",
+                    "description": "Working directory relative to workspace root; default '.'"
+                }
+            },
+            "required": ["command"]
+        })
+    }
+
+    async fn run(&self, _input: Value) -> Result<String, crate::error::ToolError> {
+        Err(crate::error::ToolError::ConfigurationError(
+            "execute requires runtime (workspace). Use run_with_runtime.".to_string(),
+        ))
+    }
+
+    async fn run_with_runtime(
+        &self,
+        input: Value,
+        runtime: &ToolRuntime,
+    ) -> Result<ToolResult, Box<dyn Error>> {
+        let line = input
+            .get("command")
+            .and_then(Value::as_str)
+            .or_else(|| input.as_str())
+            .ok_or("command is required")?;
+        let cwd = input.get("cwd").and_then(Value::as_str);
+        let mut words = split_command_line(line)?;
+        if words.is_empty() {
+            return Err("command is empty".into());
+        }
+        let program = words.remove(0);
+
+        let mut policy = self.policy.clone();
+        if policy.root.is_none() {
+            let root = workspace_root_from_context(self.workspace_root.as_ref(), runtime.context())
+                .ok_or(FileSystemToolError::WorkspaceNotSet)?;
+            policy.root = Some(root);
+        }
+        let output = SandboxedExecutor::new(policy)
+            .execute(&program, &words, cwd)
+            .await?;
+        Ok(ToolResult::Text(serde_json::to_string_pretty(&output)?))
+    }
+
+    fn requires_runtime(&self) -> bool {
+        true
+    }
+
+    fn is_parallel_safe(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::sync::Arc;
+
+    use tokio::sync::Mutex;
+
+    use super::*;
+    use crate::agent::AgentState;
+    use crate::tools::{InMemoryStore, SimpleContext};
+
+    fn runtime(root: &std::path::Path) -> ToolRuntime {
+        let context = SimpleContext::new()
+            .with_custom("workspace_root".to_string(), root.display().to_string());
+        ToolRuntime::new(
+            Arc::new(Mutex::new(AgentState::new())),
+            Arc::new(context),
+            Arc::new(InMemoryStore::new()),
+            "call_1".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_execute_in_workspace() {
+        let root = std::env::temp_dir().join("deep_agent_execute_test");
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::write(root.join("sub/hello.txt"), "hello").unwrap();
+
+        let tool = ExecuteTool::new(SandboxPolicy::new().with_allowed_commands(["cat", "ls"]));
+        let result = tool
+            .run_with_runtime(
+                json!({ "command": "cat hello.txt", "cwd": "sub" }),
+                &runtime(&root),
+            )
+            .await
+            .unwrap();
+        let output: Value = serde_json::from_str(result.as_str()).unwrap();
+        assert_eq!(output["exit_code"], 0);
+        assert_eq!(output["stdout"], "hello");
+
+        let denied = tool
+            .run_with_runtime(json!({ "command": "rm -rf sub" }), &runtime(&root))
+            .await;
+        assert!(denied.unwrap_err().to_string().contains("not allowed"));
+        let escaped = tool
+            .run_with_runtime(
+                json!({ "command": "cat ../../etc/passwd" }),
+                &runtime(&root),
+            )
+            .await;
+        assert!(escaped.is_err());
+    }
+}
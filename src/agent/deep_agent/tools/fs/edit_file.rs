@@ -138,4 +138,8 @@ impl Tool for EditFileTool {
     fn requires_runtime(&self) -> bool {
         true
     }
+
+    fn is_parallel_safe(&self) -> bool {
+        false
+    }
 }
@@ -107,4 +107,8 @@ impl Tool for WriteFileTool {
     fn requires_runtime(&self) -> bool {
         true
     }
+
+    fn is_parallel_safe(&self) -> bool {
+        false
+    }
 }
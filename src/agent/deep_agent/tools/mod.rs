@@ -1,9 +1,11 @@
-//! Built-in tools for Deep Agent: write_todos, file system (ls, read_file, write_file, edit_file, glob, grep), task.
+//! Built-in tools for Deep Agent: write_todos, file system (ls, read_file, write_file, edit_file, glob, grep), execute, task.
 
+mod execute;
 mod task;
 mod tool_wrapper;
 mod write_todos;
 
+pub use execute::ExecuteTool;
 pub use task::TaskTool;
 pub use tool_wrapper::ToolWithCustomDescription;
 pub use write_todos::{TodoItem, TodoStatus, WriteTodosTool};
@@ -6,7 +6,7 @@ use async_trait::async_trait;
 use serde_json::Value;
 
 use crate::error::ToolError;
-use crate::tools::{Tool, ToolResult, ToolRuntime};
+use crate::tools::{Tool, ToolResult, ToolRuntime, ToolStream};
 
 /// Wraps a tool and overrides its name and/or description for the agent (e.g. from
 /// [DeepAgentConfig::custom_tool_descriptions](crate::agent::deep_agent::DeepAgentConfig)).
@@ -71,9 +71,17 @@ impl Tool for ToolWithCustomDescription {
         self.inner.run_with_runtime(input, runtime).await
     }
 
+    fn run_stream<'a>(&'a self, input: Value, runtime: &'a ToolRuntime) -> ToolStream<'a> {
+        self.inner.run_stream(input, runtime)
+    }
+
     fn requires_runtime(&self) -> bool {
         self.inner.requires_runtime()
     }
+
+    fn is_parallel_safe(&self) -> bool {
+        self.inner.is_parallel_safe()
+    }
 }
 
 #[cfg(test)]
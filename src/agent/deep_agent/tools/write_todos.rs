@@ -155,6 +155,10 @@ impl Tool for WriteTodosTool {
     fn requires_runtime(&self) -> bool {
         true
     }
+
+    fn is_parallel_safe(&self) -> bool {
+        false
+    }
 }
 
 #[cfg(test)]
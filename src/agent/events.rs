@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::callbacks::new_run_id;
+use crate::tools::ToolUpdate;
+
+/// An event emitted while an agent run is in progress.
+///
+/// Events are produced by [`AgentExecutor::stream_events`](crate::agent::AgentExecutor::stream_events)
+/// and [`UnifiedAgent::stream_events`](crate::agent::UnifiedAgent::stream_events).
+/// Every event belongs to a run: agent events use the agent run id, tool events use the
+/// tool run id. Subagents invoked through `SubagentTool` or the deep agent `task` tool
+/// start a new agent run whose `parent_run_id` is the id of that tool run, so the
+/// events of a whole run form a tree.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AgentStreamEvent {
+    /// Id of the run (agent or tool) this event belongs to
+    pub run_id: String,
+    /// Id of the enclosing run; `None` for the top-level agent
+    pub parent_run_id: Option<String>,
+    /// Name of the agent or tool
+    pub name: String,
+    #[serde(flatten)]
+    pub kind: AgentStreamEventKind,
+}
+
+/// Payload of an [`AgentStreamEvent`]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum AgentStreamEventKind {
+    /// The agent run started
+    RunStart { input: Value },
+    /// The model is called to plan the next step
+    ModelStart { iteration: usize },
+    /// A chunk of model output (only for agents that support streaming)
+    TokenDelta { delta: String },
+    /// The model asked for a tool call
+    ToolCallPlanned {
+        tool_call_id: String,
+        tool: String,
+        input: String,
+    },
+    /// A tool started running (emitted on the tool run)
+    ToolStart {
+        tool_call_id: String,
+        tool: String,
+        input: String,
+    },
+    /// A running tool reported progress or partial output (emitted on the tool run)
+    ToolProgress {
+        tool_call_id: String,
+        tool: String,
+        update: ToolUpdate,
+    },
+    /// A tool finished successfully (emitted on the tool run); failures emit `Error`
+    ToolEnd {
+        tool_call_id: String,
+        tool: String,
+        output: String,
+    },
+    /// Middleware changed or rejected a planned tool call
+    MiddlewareDecision {
+        tool_call_id: String,
+        tool: String,
+        decision: MiddlewareDecision,
+    },
+    /// The run was interrupted for human input
+    Interrupt { value: Value },
+    /// The run finished with a final answer
+    Finish { output: String },
+    /// The run (agent or tool) failed
+    Error { message: String },
+}
+
+/// What middleware decided about a planned tool call
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MiddlewareDecision {
+    /// The tool call was modified (e.g. edited arguments)
+    Modified,
+    /// The tool call was rejected and will not run
+    Rejected,
+}
+
+/// Sends [`AgentStreamEvent`]s for one run.
+///
+/// The executor creates one emitter per agent run and a child emitter per tool run.
+/// Tools receive their emitter through [`ToolRuntime::event_emitter`](crate::tools::ToolRuntime::event_emitter),
+/// which lets nested agents report into the same stream.
+#[derive(Clone, Debug)]
+pub struct AgentEventEmitter {
+    sender: UnboundedSender<AgentStreamEvent>,
+    run_id: String,
+    parent_run_id: Option<String>,
+    name: String,
+}
+
+impl AgentEventEmitter {
+    /// Create an emitter for a top-level run
+    pub fn new(sender: UnboundedSender<AgentStreamEvent>, name: impl Into<String>) -> Self {
+        Self {
+            sender,
+            run_id: new_run_id(),
+            parent_run_id: None,
+            name: name.into(),
+        }
+    }
+
+    /// Create an emitter for a run nested inside this one
+    pub fn child(&self, name: impl Into<String>) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            run_id: new_run_id(),
+            parent_run_id: Some(self.run_id.clone()),
+            name: name.into(),
+        }
+    }
+
+    /// Id of this run
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    /// Id of the enclosing run
+    pub fn parent_run_id(&self) -> Option<&str> {
+        self.parent_run_id.as_deref()
+    }
+
+    /// Name of the agent or tool for this run
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Send an event; events are dropped once the stream consumer has gone away
+    pub fn emit(&self, kind: AgentStreamEventKind) {
+        let _ = self.sender.send(AgentStreamEvent {
+            run_id: self.run_id.clone(),
+            parent_run_id: self.parent_run_id.clone(),
+            name: self.name.clone(),
+            kind,
+        });
+    }
+}
@@ -1,6 +1,8 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, future::Future, pin::Pin, sync::Arc};
 
+use async_stream::stream;
 use async_trait::async_trait;
+use futures::{Stream, StreamExt};
 use serde_json::json;
 use tokio::sync::Mutex;
 
@@ -11,17 +13,20 @@ use super::{
     agent::Agent,
     checkpoint::{AgentCheckpointState, AgentCheckpointer},
     context_engineering::{ModelRequest, ModelResponse},
+    events::{AgentEventEmitter, AgentStreamEvent, AgentStreamEventKind, MiddlewareDecision},
     message_repair,
     middleware::Middleware,
     middleware::MiddlewareContext,
     middleware::MiddlewareError,
     runtime::{Runtime, RuntimeRequest},
-    state::AgentState,
+    state::{AgentState, Command},
     AgentError,
 };
 use crate::schemas::{LogTools, Message, MessageType};
 use crate::{
+    callbacks,
     chain::{chain_trait::Chain, ChainError},
+    langgraph::{get_stream_writer, ChannelStreamWriter},
     language_models::GenerateResult,
     memory::SimpleMemory,
     prompt::PromptArgs,
@@ -30,7 +35,11 @@ use crate::{
         memory::BaseMemory,
         StructuredOutputStrategy,
     },
-    tools::{FileBackend, Tool, ToolContext, ToolRuntime, ToolStore},
+    telemetry,
+    tools::{
+        collect_tool_stream, FileBackend, StreamWriter, Tool, ToolContext, ToolResult, ToolRuntime,
+        ToolStore, ToolUpdate,
+    },
 };
 
 /// Convert message-based input format to standard prompt args.
@@ -78,6 +87,107 @@ fn convert_messages_to_prompt_args(input_variables: PromptArgs) -> Result<Prompt
     Ok(prompt_args)
 }
 
+/// A tool call after the `before_tool_call` middleware chain has run.
+enum PlannedToolCall {
+    /// Execute the (possibly modified) action.
+    Run(AgentAction),
+    /// Middleware rejected the call; it is recorded but not executed.
+    Rejected(AgentAction),
+}
+
+/// A tool call ready to execute: its index in the batch, the tool, the action and its runtime.
+type ToolJob = (usize, Arc<dyn Tool>, AgentAction, ToolRuntime);
+
+/// Sends a tool's updates to the agent event stream and the graph's `custom` stream mode,
+/// then to the stream writer the runtime already had.
+struct ToolUpdateForwarder {
+    tool: String,
+    tool_call_id: String,
+    events: Option<AgentEventEmitter>,
+    graph: Option<Arc<ChannelStreamWriter>>,
+    inner: Option<Arc<dyn StreamWriter>>,
+}
+
+impl StreamWriter for ToolUpdateForwarder {
+    fn write(&self, message: &str) {
+        self.write_update(&ToolUpdate::message(message));
+    }
+
+    fn write_update(&self, update: &ToolUpdate) {
+        if let Some(events) = &self.events {
+            events.emit(AgentStreamEventKind::ToolProgress {
+                tool_call_id: self.tool_call_id.clone(),
+                tool: self.tool.clone(),
+                update: update.clone(),
+            });
+        }
+        if let Some(graph) = &self.graph {
+            let _ = graph.send(json!({
+                "tool": self.tool,
+                "tool_call_id": self.tool_call_id,
+                "update": update,
+            }));
+        }
+        if let Some(inner) = &self.inner {
+            inner.write_update(update);
+        }
+    }
+}
+
+/// Run a single tool call, mapping errors to their message.
+///
+/// The tool runs through [`Tool::run_stream`]; its updates, and anything it writes to the
+/// runtime's stream writer, are forwarded as `ToolProgress` events and LangGraph custom data.
+async fn run_tool(
+    (index, tool, action, mut tool_runtime): ToolJob,
+) -> (usize, Result<ToolResult, String>) {
+    if let Some(events) = tool_runtime.event_emitter() {
+        events.emit(AgentStreamEventKind::ToolStart {
+            tool_call_id: tool_runtime.tool_call_id.clone(),
+            tool: action.tool.clone(),
+            input: action.tool_input.clone(),
+        });
+    }
+
+    let graph = get_stream_writer();
+    if tool_runtime.event_emitter.is_some() || graph.is_some() {
+        tool_runtime.stream_writer = Some(Arc::new(ToolUpdateForwarder {
+            tool: action.tool.clone(),
+            tool_call_id: tool_runtime.tool_call_id.clone(),
+            events: tool_runtime.event_emitter.clone(),
+            graph,
+            inner: tool_runtime.stream_writer.take(),
+        }));
+    }
+
+    let result = telemetry::tool(
+        &action.tool,
+        &tool_runtime.tool_call_id,
+        &action.tool_input,
+        async {
+            let input = tool.parse_input(&action.tool_input).await;
+            collect_tool_stream(tool.run_stream(input, &tool_runtime), &tool_runtime)
+                .await
+                .map_err(|e| e.to_string())
+        },
+    )
+    .await;
+
+    if let Some(events) = tool_runtime.event_emitter() {
+        events.emit(match &result {
+            Ok(output) => AgentStreamEventKind::ToolEnd {
+                tool_call_id: tool_runtime.tool_call_id.clone(),
+                tool: action.tool.clone(),
+                output: output.as_str().to_string(),
+            },
+            Err(message) => AgentStreamEventKind::Error {
+                message: message.clone(),
+            },
+        });
+    }
+    (index, result)
+}
+
 pub struct AgentExecutor<A>
 where
     A: Agent,
@@ -94,6 +204,8 @@ where
     file_backend: Option<Arc<dyn FileBackend>>,
     /// Checkpointer for human-in-the-loop: save state on interrupt, load on resume.
     checkpointer: Option<Arc<dyn AgentCheckpointer>>,
+    /// Maximum number of tool calls from one turn to run concurrently (`None` = unbounded).
+    max_tool_concurrency: Option<usize>,
 }
 
 impl<A> AgentExecutor<A>
@@ -113,6 +225,7 @@ where
             middleware: Vec::new(),
             file_backend: None,
             checkpointer: None,
+            max_tool_concurrency: None,
         }
     }
 
@@ -152,6 +265,19 @@ where
         self
     }
 
+    /// Limit how many tool calls from a single model turn run concurrently.
+    ///
+    /// By default all parallel-safe calls in a batch run at once; use `1` to run
+    /// tools strictly one after another.
+    pub fn with_max_tool_concurrency(mut self, max_tool_concurrency: usize) -> Self {
+        self.max_tool_concurrency = Some(max_tool_concurrency);
+        self
+    }
+
+    /// Stop the run with an error when a tool fails, instead of showing the error to the model.
+    ///
+    /// Tool calls from one turn run as a batch, so the other calls of that batch have already
+    /// run when the error is returned; the first failing call in call order is reported.
     pub fn with_break_if_error(mut self, break_if_error: bool) -> Self {
         self.break_if_error = break_if_error;
         self
@@ -178,39 +304,6 @@ where
         }
         name_to_tool
     }
-
-    async fn handle_command(
-        &self,
-        command: crate::agent::state::Command,
-    ) -> Result<(), ChainError> {
-        let mut state = self.state.lock().await;
-        match command {
-            crate::agent::state::Command::UpdateState { fields } => {
-                for (key, value) in fields {
-                    state.set_field(key, value);
-                }
-            }
-            crate::agent::state::Command::RemoveMessages { ids } => {
-                state.messages.retain(|msg| {
-                    !ids.contains(
-                        &msg.id
-                            .as_ref()
-                            .map(|s| s.as_str())
-                            .unwrap_or("")
-                            .to_string(),
-                    )
-                });
-            }
-            crate::agent::state::Command::ClearMessages => {
-                state.messages.clear();
-            }
-            crate::agent::state::Command::ClearState => {
-                state.messages.clear();
-                state.custom_fields.clear();
-            }
-        }
-        Ok(())
-    }
 }
 
 #[async_trait]
@@ -238,6 +331,61 @@ where
         input_variables: PromptArgs,
         config: Option<&crate::langgraph::RunnableConfig>,
         resume: Option<(AgentCheckpointState, serde_json::Value)>,
+    ) -> Result<GenerateResult, ChainError> {
+        self.run_loop_with_events(input_variables, config, resume, None)
+            .await
+    }
+
+    /// Run the agent loop, reporting progress to `events` when given.
+    ///
+    /// Emits `RunStart` first and exactly one of `Finish`, `Interrupt` or `Error` last.
+    pub(crate) async fn run_loop_with_events(
+        &self,
+        input_variables: PromptArgs,
+        config: Option<&crate::langgraph::RunnableConfig>,
+        resume: Option<(AgentCheckpointState, serde_json::Value)>,
+        events: Option<&AgentEventEmitter>,
+    ) -> Result<GenerateResult, ChainError> {
+        if let Some(events) = events {
+            events.emit(AgentStreamEventKind::RunStart {
+                input: json!(input_variables),
+            });
+        }
+
+        let result = callbacks::scope_callbacks(
+            config.and_then(|config| config.callbacks.as_ref()),
+            telemetry::agent(
+                events.map(|events| events.name()).unwrap_or("agent"),
+                &input_variables,
+                // Boxed: the loop future is large and the wrappers would otherwise
+                // inline it several times on the caller's stack.
+                Box::pin(self.run_loop_inner(input_variables.clone(), config, resume, events)),
+            ),
+        )
+        .await;
+
+        if let Some(events) = events {
+            events.emit(match &result {
+                Ok(result) => AgentStreamEventKind::Finish {
+                    output: result.generation.clone(),
+                },
+                Err(ChainError::Interrupt(value)) => AgentStreamEventKind::Interrupt {
+                    value: value.clone(),
+                },
+                Err(e) => AgentStreamEventKind::Error {
+                    message: e.to_string(),
+                },
+            });
+        }
+        result
+    }
+
+    async fn run_loop_inner(
+        &self,
+        input_variables: PromptArgs,
+        config: Option<&crate::langgraph::RunnableConfig>,
+        resume: Option<(AgentCheckpointState, serde_json::Value)>,
+        events: Option<&AgentEventEmitter>,
     ) -> Result<GenerateResult, ChainError> {
         let input_variables = if input_variables.contains_key("messages") {
             convert_messages_to_prompt_args(input_variables)?
@@ -290,146 +438,23 @@ where
         loop {
             // Process resumed batch (pending actions with decisions)
             if let Some((pending_actions, _)) = resume_batch.take() {
-                let pending_for_checkpoint = pending_actions.clone();
-                middleware_context.set_custom_data(
-                    CURRENT_BATCH_ACTIONS_KEY.to_string(),
-                    serde_json::to_value(&pending_actions).unwrap_or_default(),
-                );
-                for mut action in pending_actions {
-                    let mut reject_this_tool = false;
-                    for mw in &self.middleware {
-                        let res = mw
-                            .before_tool_call_with_runtime(
-                                &action,
-                                Some(&*runtime),
-                                &mut middleware_context,
-                            )
-                            .await;
-                        match &res {
-                            Err(MiddlewareError::Interrupt(p)) => {
-                                if let (Some(cp), Some(tid)) = (
-                                    self.checkpointer.as_ref(),
-                                    config.and_then(|c| c.get_thread_id()),
-                                ) {
-                                    cp.put(
-                                        &tid,
-                                        &AgentCheckpointState {
-                                            steps: steps.clone(),
-                                            input_variables: plan_input.clone(),
-                                            pending_actions: pending_for_checkpoint.clone(),
-                                        },
-                                    );
-                                }
-                                return Err(ChainError::Interrupt(p.clone()));
-                            }
-                            Err(MiddlewareError::RejectTool) => {
-                                reject_this_tool = true;
-                                break;
-                            }
-                            Err(e) => return Err(ChainError::AgentError(e.to_string())),
-                            Ok(Some(m)) => action = m.clone(),
-                            Ok(None) => {}
-                        }
-                        if matches!(res, Ok(Some(_))) {
-                            continue;
-                        }
-                        {
-                            let res2 = mw.before_tool_call(&action, &mut middleware_context).await;
-                            match res2 {
-                                Err(MiddlewareError::Interrupt(p)) => {
-                                    if let (Some(cp), Some(tid)) = (
-                                        self.checkpointer.as_ref(),
-                                        config.and_then(|c| c.get_thread_id()),
-                                    ) {
-                                        cp.put(
-                                            &tid,
-                                            &AgentCheckpointState {
-                                                steps: steps.clone(),
-                                                input_variables: plan_input.clone(),
-                                                pending_actions: pending_for_checkpoint.clone(),
-                                            },
-                                        );
-                                    }
-                                    return Err(ChainError::Interrupt(p));
-                                }
-                                Err(MiddlewareError::RejectTool) => {
-                                    reject_this_tool = true;
-                                    break;
-                                }
-                                Err(e) => return Err(ChainError::AgentError(e.to_string())),
-                                Ok(Some(m)) => action = m,
-                                Ok(None) => {}
-                            }
-                        }
-                    }
-                    if reject_this_tool {
-                        steps.push((action, "Tool call rejected by user.".to_string()));
-                        continue;
-                    }
-                    middleware_context.increment_tool_call_count();
-                    let tool = name_to_tools
-                        .get(&action.tool.trim().replace(" ", "_"))
-                        .ok_or_else(|| {
-                            AgentError::ToolError(format!("Tool {} not found", action.tool))
-                        })
-                        .map_err(|e| ChainError::AgentError(e.to_string()))?;
-                    let tool_call_id = format!("call_{}", steps.len());
-                    let mut tool_runtime = ToolRuntime::new(
-                        Arc::clone(&self.state),
-                        Arc::clone(&self.context),
-                        Arc::clone(&self.store),
-                        tool_call_id,
-                    );
-                    if let Some(ref fb) = self.file_backend {
-                        tool_runtime = tool_runtime.with_file_backend(Arc::clone(fb));
-                    }
-                    let observation_result: Result<String, String> = if tool.requires_runtime() {
-                        let input = tool.parse_input(&action.tool_input).await;
-                        tool.run_with_runtime(input, &tool_runtime)
-                            .await
-                            .map(|result| result.into_string())
-                            .map_err(|e| e.to_string())
-                    } else {
-                        tool.call(&action.tool_input)
-                            .await
-                            .map_err(|e| e.to_string())
-                    };
-                    let mut observation = match observation_result {
-                        Ok(result) => result,
-                        Err(error_msg) => {
-                            if self.break_if_error {
-                                return Err(ChainError::AgentError(
-                                    AgentError::ToolError(error_msg).to_string(),
-                                ));
-                            }
-                            format!("The tool return the following error: {}", error_msg)
-                        }
-                    };
-                    for mw in &self.middleware {
-                        let modified = mw
-                            .after_tool_call_with_runtime(
-                                &action,
-                                &observation,
-                                Some(&*runtime),
-                                &mut middleware_context,
-                            )
-                            .await
-                            .map_err(|e| {
-                                ChainError::AgentError(format!("Middleware error: {}", e))
-                            })?;
-                        if let Some(mo) = modified {
-                            observation = mo;
-                        } else if let Some(mo) = mw
-                            .after_tool_call(&action, &observation, &mut middleware_context)
-                            .await
-                            .map_err(|e| {
-                                ChainError::AgentError(format!("Middleware error: {}", e))
-                            })?
-                        {
-                            observation = mo;
-                        }
-                    }
-                    steps.push((action, observation));
+                let handoff = telemetry::agent_iteration(middleware_context.iteration)
+                    .run(self.run_tool_batch(
+                        pending_actions,
+                        &mut steps,
+                        &plan_input,
+                        config,
+                        &runtime,
+                        &name_to_tools,
+                        &mut middleware_context,
+                        events,
+                    ))
+                    .await?;
+                if let Some(output) = handoff {
+                    return Ok(GenerateResult {
+                        generation: output,
+                        ..Default::default()
+                    });
                 }
                 continue;
             }
@@ -440,6 +465,7 @@ where
             first_normal_after_resume = false;
 
             middleware_context.increment_iteration();
+            let iteration_span = telemetry::agent_iteration(middleware_context.iteration);
 
             // Create runtime request
             let runtime_request = RuntimeRequest::new(plan_input.clone(), Arc::clone(&self.state))
@@ -510,11 +536,32 @@ where
                 }
             }
 
-            let mut agent_event = self
-                .agent
-                .plan(&steps, plan_input.clone())
-                .await
-                .map_err(|e| ChainError::AgentError(format!("Error in agent planning: {}", e)))?;
+            let mut agent_event = match events {
+                Some(events) => {
+                    events.emit(AgentStreamEventKind::ModelStart {
+                        iteration: middleware_context.iteration,
+                    });
+                    let on_token = |delta: &str| {
+                        if !delta.is_empty() {
+                            events.emit(AgentStreamEventKind::TokenDelta {
+                                delta: delta.to_string(),
+                            });
+                        }
+                    };
+                    iteration_span
+                        .run(
+                            self.agent
+                                .plan_streaming(&steps, plan_input.clone(), &on_token),
+                        )
+                        .await
+                }
+                None => {
+                    iteration_span
+                        .run(self.agent.plan(&steps, plan_input.clone()))
+                        .await
+                }
+            }
+            .map_err(|e| ChainError::AgentError(format!("Error in agent planning: {}", e)))?;
 
             // Create ModelResponse (simplified - actual response comes from agent)
             let model_response = ModelResponse::new(GenerateResult {
@@ -563,163 +610,23 @@ where
             }
             match agent_event {
                 AgentEvent::Action(actions) => {
-                    let actions_for_checkpoint = actions.clone();
-                    middleware_context.set_custom_data(
-                        CURRENT_BATCH_ACTIONS_KEY.to_string(),
-                        serde_json::to_value(&actions).unwrap_or_default(),
-                    );
-                    for mut action in actions {
-                        let mut reject_this_tool = false;
-                        for mw in &self.middleware {
-                            let res = mw
-                                .before_tool_call_with_runtime(
-                                    &action,
-                                    Some(&*runtime),
-                                    &mut middleware_context,
-                                )
-                                .await;
-                            match &res {
-                                Err(MiddlewareError::Interrupt(p)) => {
-                                    if let (Some(cp), Some(tid)) = (
-                                        self.checkpointer.as_ref(),
-                                        config.and_then(|c| c.get_thread_id()),
-                                    ) {
-                                        cp.put(
-                                            &tid,
-                                            &AgentCheckpointState {
-                                                steps: steps.clone(),
-                                                input_variables: plan_input.clone(),
-                                                pending_actions: actions_for_checkpoint.clone(),
-                                            },
-                                        );
-                                    }
-                                    return Err(ChainError::Interrupt(p.clone()));
-                                }
-                                Err(MiddlewareError::RejectTool) => {
-                                    reject_this_tool = true;
-                                    break;
-                                }
-                                Err(e) => return Err(ChainError::AgentError(e.to_string())),
-                                Ok(Some(m)) => action = m.clone(),
-                                Ok(None) => {}
-                            }
-                            if matches!(res, Ok(Some(_))) {
-                                continue;
-                            }
-                            let res2 = mw.before_tool_call(&action, &mut middleware_context).await;
-                            match res2 {
-                                Err(MiddlewareError::Interrupt(p)) => {
-                                    if let (Some(cp), Some(tid)) = (
-                                        self.checkpointer.as_ref(),
-                                        config.and_then(|c| c.get_thread_id()),
-                                    ) {
-                                        cp.put(
-                                            &tid,
-                                            &AgentCheckpointState {
-                                                steps: steps.clone(),
-                                                input_variables: plan_input.clone(),
-                                                pending_actions: actions_for_checkpoint.clone(),
-                                            },
-                                        );
-                                    }
-                                    return Err(ChainError::Interrupt(p));
-                                }
-                                Err(MiddlewareError::RejectTool) => {
-                                    reject_this_tool = true;
-                                    break;
-                                }
-                                Err(e) => return Err(ChainError::AgentError(e.to_string())),
-                                Ok(Some(m)) => action = m,
-                                Ok(None) => {}
-                            }
-                        }
-                        if reject_this_tool {
-                            steps.push((action, "Tool call rejected by user.".to_string()));
-                            continue;
-                        }
-
-                        log::debug!("Action: {:?}", action.tool_input);
-                        middleware_context.increment_tool_call_count();
-
-                        let tool = name_to_tools
-                            .get(&action.tool.trim().replace(" ", "_"))
-                            .ok_or_else(|| {
-                                AgentError::ToolError(format!("Tool {} not found", action.tool))
-                            })
-                            .map_err(|e| ChainError::AgentError(e.to_string()))?;
-
-                        // Create ToolRuntime for tools that need it
-                        let tool_call_id = format!("call_{}", steps.len());
-                        let mut tool_runtime = ToolRuntime::new(
-                            Arc::clone(&self.state),
-                            Arc::clone(&self.context),
-                            Arc::clone(&self.store),
-                            tool_call_id,
-                        );
-                        if let Some(ref fb) = self.file_backend {
-                            tool_runtime = tool_runtime.with_file_backend(Arc::clone(fb));
-                        }
-
-                        // Check if tool requires runtime
-                        let observation_result: Result<String, String> = if tool.requires_runtime()
-                        {
-                            let input = tool.parse_input(&action.tool_input).await;
-                            tool.run_with_runtime(input, &tool_runtime)
-                                .await
-                                .map(|result| result.into_string())
-                                .map_err(|e| e.to_string())
-                        } else {
-                            tool.call(&action.tool_input)
-                                .await
-                                .map_err(|e| e.to_string())
-                        };
-
-                        let mut observation = match observation_result {
-                            Ok(result) => result,
-                            Err(error_msg) => {
-                                log::info!("The tool return the following error: {}", error_msg);
-                                if self.break_if_error {
-                                    return Err(ChainError::AgentError(
-                                        AgentError::ToolError(error_msg.clone()).to_string(),
-                                    ));
-                                } else {
-                                    format!("The tool return the following error: {}", error_msg)
-                                }
-                            }
-                        };
-
-                        // Apply after_tool_call hooks (try runtime-aware version first)
-                        for mw in &self.middleware {
-                            // Try runtime-aware hook first
-                            let modified = mw
-                                .after_tool_call_with_runtime(
-                                    &action,
-                                    &observation,
-                                    Some(&*runtime),
-                                    &mut middleware_context,
-                                )
-                                .await
-                                .map_err(|e| {
-                                    ChainError::AgentError(format!("Middleware error: {}", e))
-                                })?;
-
-                            if let Some(modified_observation) = modified {
-                                observation = modified_observation;
-                            } else {
-                                // Fallback to non-runtime hook
-                                if let Some(modified_observation) = mw
-                                    .after_tool_call(&action, &observation, &mut middleware_context)
-                                    .await
-                                    .map_err(|e| {
-                                        ChainError::AgentError(format!("Middleware error: {}", e))
-                                    })?
-                                {
-                                    observation = modified_observation;
-                                }
-                            }
-                        }
-
-                        steps.push((action, observation));
+                    let handoff = iteration_span
+                        .run(self.run_tool_batch(
+                            actions,
+                            &mut steps,
+                            &plan_input,
+                            config,
+                            &runtime,
+                            &name_to_tools,
+                            &mut middleware_context,
+                            events,
+                        ))
+                        .await?;
+                    if let Some(output) = handoff {
+                        return Ok(GenerateResult {
+                            generation: output,
+                            ..Default::default()
+                        });
                     }
                 }
                 AgentEvent::Finish(mut finish) => {
@@ -774,18 +681,18 @@ where
                                                 .with_tool_calls(tools_value),
                                         );
                                     }
-                                    memory.add_message(Message::new_tool_message(
-                                        observation,
-                                        tool_id,
-                                    ));
+                                    memory.add_message(
+                                        Message::new_tool_message(observation, tool_id)
+                                            .with_content_parts(action.attachments),
+                                    );
                                 }
                                 Err(_) => {
                                     // Conversational agent: action.log is raw model text, not LogTools
                                     memory.add_message(Message::new_ai_message(&action.log));
-                                    memory.add_message(Message::new_tool_message(
-                                        observation,
-                                        &action.tool,
-                                    ));
+                                    memory.add_message(
+                                        Message::new_tool_message(observation, &action.tool)
+                                            .with_content_parts(action.attachments),
+                                    );
                                 }
                             }
                         }
@@ -826,6 +733,246 @@ where
         }
     }
 
+    /// Run one batch of tool calls emitted by the model in a single turn.
+    ///
+    /// Execution happens in three phases so that middleware stays deterministic:
+    /// 1. `before_tool_call` hooks run for every action in call order. An interrupt from any
+    ///    action checkpoints the whole batch before any tool has run.
+    /// 2. Approved tools run concurrently (bounded by `max_tool_concurrency`). Tools that are
+    ///    not [parallel safe](Tool::is_parallel_safe) run alone, in order, acting as barriers.
+    /// 3. `after_tool_call` hooks run and results are appended to `steps` in call order.
+    ///    Commands returned by tools are applied to the agent state.
+    ///
+    /// Returns the output of the first tool that handed off to another agent, which ends the run.
+    #[allow(clippy::too_many_arguments)]
+    async fn run_tool_batch(
+        &self,
+        actions: Vec<AgentAction>,
+        steps: &mut Vec<(AgentAction, String)>,
+        plan_input: &PromptArgs,
+        config: Option<&crate::langgraph::RunnableConfig>,
+        runtime: &Runtime,
+        name_to_tools: &HashMap<String, Arc<dyn Tool>>,
+        middleware_context: &mut MiddlewareContext,
+        events: Option<&AgentEventEmitter>,
+    ) -> Result<Option<String>, ChainError> {
+        middleware_context.set_custom_data(
+            CURRENT_BATCH_ACTIONS_KEY.to_string(),
+            serde_json::to_value(&actions).unwrap_or_default(),
+        );
+
+        // Phase 1: before_tool_call hooks, in call order
+        let mut planned: Vec<PlannedToolCall> = Vec::with_capacity(actions.len());
+        for (index, action) in actions.iter().cloned().enumerate() {
+            let tool_call_id = format!("call_{}", steps.len() + index);
+            if let Some(events) = events {
+                events.emit(AgentStreamEventKind::ToolCallPlanned {
+                    tool_call_id: tool_call_id.clone(),
+                    tool: action.tool.clone(),
+                    input: action.tool_input.clone(),
+                });
+            }
+            match self
+                .apply_before_tool_call(action.clone(), runtime, middleware_context)
+                .await
+            {
+                Ok(call) => {
+                    if let Some(events) = events {
+                        let decision = match &call {
+                            PlannedToolCall::Rejected(_) => Some(MiddlewareDecision::Rejected),
+                            PlannedToolCall::Run(run) => (run.tool != action.tool
+                                || run.tool_input != action.tool_input)
+                                .then_some(MiddlewareDecision::Modified),
+                        };
+                        if let Some(decision) = decision {
+                            events.emit(AgentStreamEventKind::MiddlewareDecision {
+                                tool_call_id,
+                                tool: action.tool.clone(),
+                                decision,
+                            });
+                        }
+                    }
+                    planned.push(call);
+                }
+                Err(MiddlewareError::Interrupt(payload)) => {
+                    if let (Some(cp), Some(tid)) = (
+                        self.checkpointer.as_ref(),
+                        config.and_then(|c| c.get_thread_id()),
+                    ) {
+                        let state = AgentCheckpointState::new(
+                            steps.clone(),
+                            plan_input.clone(),
+                            actions.clone(),
+                        )
+                        .with_interrupt(payload.clone());
+                        cp.put(&tid, &state).await.map_err(|e| {
+                            ChainError::OtherError(format!("Failed to save checkpoint: {}", e))
+                        })?;
+                    }
+                    return Err(ChainError::Interrupt(payload));
+                }
+                Err(e) => return Err(ChainError::AgentError(e.to_string())),
+            }
+        }
+
+        // Phase 2: execute approved tools
+        let mut jobs = Vec::new();
+        for (index, call) in planned.iter().enumerate() {
+            if let PlannedToolCall::Run(action) = call {
+                log::debug!("Action: {:?}", action.tool_input);
+                middleware_context.increment_tool_call_count();
+
+                let tool = name_to_tools
+                    .get(&action.tool.trim().replace(" ", "_"))
+                    .ok_or_else(|| AgentError::ToolError(format!("Tool {} not found", action.tool)))
+                    .map_err(|e| ChainError::AgentError(e.to_string()))?;
+
+                // Create ToolRuntime for tools that need it
+                let tool_call_id = format!("call_{}", steps.len() + index);
+                let mut tool_runtime = ToolRuntime::new(
+                    Arc::clone(&self.state),
+                    Arc::clone(&self.context),
+                    Arc::clone(&self.store),
+                    tool_call_id,
+                );
+                if let Some(ref fb) = self.file_backend {
+                    tool_runtime = tool_runtime.with_file_backend(Arc::clone(fb));
+                }
+                if let Some(events) = events {
+                    tool_runtime = tool_runtime.with_event_emitter(events.child(&action.tool));
+                }
+                jobs.push((index, Arc::clone(tool), action.clone(), tool_runtime));
+            }
+        }
+        let mut results = self.execute_tools(jobs).await;
+
+        // Phase 3: after_tool_call hooks and steps, in call order
+        let mut handoff_output = None;
+        for (index, call) in planned.into_iter().enumerate() {
+            let mut action = match call {
+                PlannedToolCall::Rejected(action) => {
+                    steps.push((action, "Tool call rejected by user.".to_string()));
+                    continue;
+                }
+                PlannedToolCall::Run(action) => action,
+            };
+
+            let observation_result = results
+                .remove(&index)
+                .unwrap_or_else(|| Err(format!("Tool {} produced no result", action.tool)));
+            let mut is_handoff = false;
+            let mut observation = match observation_result {
+                Ok(result) => {
+                    action.attachments = result.attachments().to_vec();
+                    let (text, command) = result.into_parts();
+                    if let Some(command) = command {
+                        is_handoff = matches!(command, Command::Handoff { .. });
+                        command.apply(&mut *self.state.lock().await);
+                    }
+                    text
+                }
+                Err(error_msg) => {
+                    log::info!("The tool return the following error: {}", error_msg);
+                    if self.break_if_error {
+                        return Err(ChainError::AgentError(
+                            AgentError::ToolError(error_msg).to_string(),
+                        ));
+                    }
+                    format!("The tool return the following error: {}", error_msg)
+                }
+            };
+
+            // Apply after_tool_call hooks (try runtime-aware version first)
+            for mw in &self.middleware {
+                let modified = mw
+                    .after_tool_call_with_runtime(
+                        &action,
+                        &observation,
+                        Some(runtime),
+                        middleware_context,
+                    )
+                    .await
+                    .map_err(|e| ChainError::AgentError(format!("Middleware error: {}", e)))?;
+
+                if let Some(modified_observation) = modified {
+                    observation = modified_observation;
+                } else if let Some(modified_observation) = mw
+                    .after_tool_call(&action, &observation, middleware_context)
+                    .await
+                    .map_err(|e| ChainError::AgentError(format!("Middleware error: {}", e)))?
+                {
+                    observation = modified_observation;
+                }
+            }
+
+            if is_handoff && handoff_output.is_none() {
+                handoff_output = Some(observation.clone());
+            }
+            steps.push((action, observation));
+        }
+
+        Ok(handoff_output)
+    }
+
+    /// Run the `before_tool_call` middleware chain for one action.
+    ///
+    /// Each middleware is called once, through the runtime-aware hook; its default
+    /// implementation delegates to `before_tool_call`. Calling both would make stateful
+    /// middleware such as human-in-the-loop consume two resume decisions per action.
+    async fn apply_before_tool_call(
+        &self,
+        mut action: AgentAction,
+        runtime: &Runtime,
+        middleware_context: &mut MiddlewareContext,
+    ) -> Result<PlannedToolCall, MiddlewareError> {
+        for mw in &self.middleware {
+            match mw
+                .before_tool_call_with_runtime(&action, Some(runtime), middleware_context)
+                .await
+            {
+                Err(MiddlewareError::RejectTool) => return Ok(PlannedToolCall::Rejected(action)),
+                Ok(Some(modified_action)) => action = modified_action,
+                other => {
+                    other?;
+                }
+            }
+        }
+        Ok(PlannedToolCall::Run(action))
+    }
+
+    /// Execute tool calls, returning results keyed by their index in the batch.
+    ///
+    /// Consecutive parallel-safe calls run concurrently; any other call runs on its own.
+    async fn execute_tools(
+        &self,
+        jobs: Vec<ToolJob>,
+    ) -> HashMap<usize, Result<ToolResult, String>> {
+        let limit = self.max_tool_concurrency.unwrap_or(usize::MAX).max(1);
+        let mut results = HashMap::new();
+        let mut group = Vec::new();
+        let mut jobs = jobs.into_iter().peekable();
+
+        while let Some(job) = jobs.next() {
+            let parallel = job.1.is_parallel_safe();
+            group.push(job);
+            let next_joins_group = parallel
+                && jobs
+                    .peek()
+                    .map(|next| next.1.is_parallel_safe())
+                    .unwrap_or(false);
+            if next_joins_group {
+                continue;
+            }
+
+            let calls: Vec<_> = group.drain(..).map(run_tool).collect();
+            let outputs: Vec<(usize, Result<ToolResult, String>)> =
+                futures::stream::iter(calls).buffered(limit).collect().await;
+            results.extend(outputs);
+        }
+
+        results
+    }
+
     /// Run the agent with optional config (thread_id for HILP checkpointer). Returns interrupt payload on HILP interrupt.
     pub async fn call_with_config(
         &self,
@@ -841,27 +988,138 @@ where
         config: &crate::langgraph::RunnableConfig,
         resume_decisions: serde_json::Value,
     ) -> Result<GenerateResult, ChainError> {
+        self.call_resume_with_events(config, resume_decisions, None)
+            .await
+    }
+
+    pub(crate) async fn call_resume_with_events(
+        &self,
+        config: &crate::langgraph::RunnableConfig,
+        resume_decisions: serde_json::Value,
+        events: Option<&AgentEventEmitter>,
+    ) -> Result<GenerateResult, ChainError> {
+        let (thread_id, state) = match self.load_checkpoint(config).await {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                if let Some(events) = events {
+                    events.emit(AgentStreamEventKind::Error {
+                        message: e.to_string(),
+                    });
+                }
+                return Err(e);
+            }
+        };
+        let result = self
+            .run_loop_with_events(
+                state.input_variables.clone(),
+                Some(config),
+                Some((state, resume_decisions)),
+                events,
+            )
+            .await;
+
+        // A new interrupt replaced the checkpoint, and a failed run keeps it so it can be
+        // resumed again; only a completed run settles the approval
+        if result.is_ok() {
+            if let Some(cp) = self.checkpointer.as_ref() {
+                if let Err(e) = cp.delete(&thread_id).await {
+                    log::warn!("Failed to delete checkpoint for {}: {}", thread_id, e);
+                }
+            }
+        }
+        result
+    }
+
+    /// Load the checkpoint for the config's thread_id.
+    async fn load_checkpoint(
+        &self,
+        config: &crate::langgraph::RunnableConfig,
+    ) -> Result<(String, AgentCheckpointState), ChainError> {
         let thread_id = config
             .get_thread_id()
             .ok_or_else(|| ChainError::OtherError("thread_id required for resume".to_string()))?;
-        let state = self
-            .checkpointer
-            .as_ref()
-            .and_then(|cp| cp.get(&thread_id))
+        let checkpointer = self.checkpointer.as_ref().ok_or_else(|| {
+            ChainError::OtherError("A checkpointer is required for resume".to_string())
+        })?;
+        let state = checkpointer
+            .get(&thread_id)
+            .await
+            .map_err(|e| ChainError::OtherError(format!("Failed to load checkpoint: {}", e)))?
             .ok_or_else(|| {
                 ChainError::OtherError(
                     "No checkpoint found for thread (use same thread_id as interrupt)".to_string(),
                 )
             })?;
-        self.run_loop(
-            state.input_variables.clone(),
-            Some(config),
-            Some((state, resume_decisions)),
-        )
-        .await
+        Ok((thread_id, state))
+    }
+
+    /// Stream the events of an agent run as it happens.
+    ///
+    /// Yields model, tool, middleware and lifecycle events for this agent and for any
+    /// subagents it calls (see [`AgentStreamEvent`]). The last event of the top-level run
+    /// is `Finish`, `Interrupt` or `Error`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut events = executor.stream_events(prompt_args! {"input" => "Hi"}, None);
+    /// while let Some(event) = events.next().await {
+    ///     if let AgentStreamEventKind::TokenDelta { delta } = &event.kind {
+    ///         print!("{}", delta);
+    ///     }
+    /// }
+    /// ```
+    pub fn stream_events<'a>(
+        &'a self,
+        input_variables: PromptArgs,
+        config: Option<&'a crate::langgraph::RunnableConfig>,
+    ) -> Pin<Box<dyn Stream<Item = AgentStreamEvent> + Send + 'a>> {
+        event_stream(move |events| async move {
+            self.run_loop_with_events(input_variables, config, None, Some(&events))
+                .await
+        })
+    }
+
+    /// Stream the events of a run resumed with human decisions (see [`Self::call_resume`]).
+    pub fn stream_events_resume<'a>(
+        &'a self,
+        config: &'a crate::langgraph::RunnableConfig,
+        resume_decisions: serde_json::Value,
+    ) -> Pin<Box<dyn Stream<Item = AgentStreamEvent> + Send + 'a>> {
+        event_stream(move |events| async move {
+            self.call_resume_with_events(config, resume_decisions, Some(&events))
+                .await
+        })
     }
 }
 
+/// Drive an agent run, yielding the events it emits while it is running.
+pub(crate) fn event_stream<'a, F, Fut>(
+    run: F,
+) -> Pin<Box<dyn Stream<Item = AgentStreamEvent> + Send + 'a>>
+where
+    F: FnOnce(AgentEventEmitter) -> Fut + Send + 'a,
+    Fut: Future<Output = Result<GenerateResult, ChainError>> + Send + 'a,
+{
+    Box::pin(stream! {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let execution = run(AgentEventEmitter::new(sender, "agent"));
+        futures::pin_mut!(execution);
+
+        // Forward events while the agent is running
+        loop {
+            tokio::select! {
+                biased;
+                Some(event) = receiver.recv() => yield event,
+                _ = &mut execution => break,
+            }
+        }
+        while let Ok(event) = receiver.try_recv() {
+            yield event;
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -900,4 +1158,601 @@ mod tests {
         assert!(args.contains_key("custom_key"));
         assert_eq!(args["custom_key"], json!("custom_value"));
     }
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use crate::agent::middleware::human_in_loop::HumanInTheLoopMiddleware;
+    use crate::agent::InMemoryAgentSaver;
+    use crate::error::ToolError;
+    use crate::prompt_args;
+    use crate::schemas::agent::AgentFinish;
+    use crate::tools::ToolStreamItem;
+
+    /// Tool that sleeps and records how many calls overlapped
+    struct SleepTool {
+        name: String,
+        parallel_safe: bool,
+        running: Arc<AtomicUsize>,
+        max_running: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Tool for SleepTool {
+        fn name(&self) -> String {
+            self.name.clone()
+        }
+
+        fn description(&self) -> String {
+            "Sleeps, then echoes its input".to_string()
+        }
+
+        async fn run(&self, input: serde_json::Value) -> Result<String, ToolError> {
+            let now = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_running.fetch_max(now, Ordering::SeqCst);
+            // Later calls finish first, so ordering bugs would show up
+            let delay = input.as_str().unwrap_or_default().len() as u64;
+            tokio::time::sleep(Duration::from_millis(100 - delay * 10)).await;
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            Ok(format!(
+                "{}:{}",
+                self.name,
+                input.as_str().unwrap_or_default()
+            ))
+        }
+
+        fn is_parallel_safe(&self) -> bool {
+            self.parallel_safe
+        }
+    }
+
+    /// Agent that requests one batch of tool calls, then finishes with the observations
+    struct BatchAgent {
+        actions: Vec<AgentAction>,
+        tools: Vec<Arc<dyn Tool>>,
+    }
+
+    #[async_trait]
+    impl Agent for BatchAgent {
+        async fn plan(
+            &self,
+            intermediate_steps: &[(AgentAction, String)],
+            _inputs: PromptArgs,
+        ) -> Result<AgentEvent, AgentError> {
+            if intermediate_steps.is_empty() {
+                return Ok(AgentEvent::Action(self.actions.clone()));
+            }
+            let output = intermediate_steps
+                .iter()
+                .map(|(_, observation)| observation.as_str())
+                .collect::<Vec<_>>()
+                .join(",");
+            Ok(AgentEvent::Finish(AgentFinish { output }))
+        }
+
+        fn get_tools(&self) -> Vec<Arc<dyn Tool>> {
+            self.tools.clone()
+        }
+    }
+
+    fn action(tool: &str, input: &str) -> AgentAction {
+        AgentAction::new(tool, input, "")
+    }
+
+    fn batch_executor(
+        parallel_safe: bool,
+        max_running: &Arc<AtomicUsize>,
+    ) -> AgentExecutor<BatchAgent> {
+        let tool: Arc<dyn Tool> = Arc::new(SleepTool {
+            name: "sleep".to_string(),
+            parallel_safe,
+            running: Arc::new(AtomicUsize::new(0)),
+            max_running: Arc::clone(max_running),
+        });
+        AgentExecutor::from_agent(BatchAgent {
+            actions: vec![
+                action("sleep", "a"),
+                action("sleep", "bb"),
+                action("sleep", "ccc"),
+            ],
+            tools: vec![tool],
+        })
+    }
+
+    #[tokio::test]
+    async fn test_parallel_tool_calls_keep_call_order() {
+        let max_running = Arc::new(AtomicUsize::new(0));
+        let executor = batch_executor(true, &max_running);
+
+        let output = executor
+            .invoke(prompt_args! {"input" => "go"})
+            .await
+            .unwrap();
+
+        assert_eq!(output, "sleep:a,sleep:bb,sleep:ccc");
+        assert_eq!(max_running.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_max_tool_concurrency_limits_parallel_calls() {
+        let max_running = Arc::new(AtomicUsize::new(0));
+        let executor = batch_executor(true, &max_running).with_max_tool_concurrency(2);
+
+        let output = executor
+            .invoke(prompt_args! {"input" => "go"})
+            .await
+            .unwrap();
+
+        assert_eq!(output, "sleep:a,sleep:bb,sleep:ccc");
+        assert_eq!(max_running.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_non_parallel_safe_tools_run_sequentially() {
+        let max_running = Arc::new(AtomicUsize::new(0));
+        let executor = batch_executor(false, &max_running);
+
+        let output = executor
+            .invoke(prompt_args! {"input" => "go"})
+            .await
+            .unwrap();
+
+        assert_eq!(output, "sleep:a,sleep:bb,sleep:ccc");
+        assert_eq!(max_running.load(Ordering::SeqCst), 1);
+    }
+
+    /// Middleware that interrupts before calls with the given input
+    struct InterruptOn(&'static str);
+
+    #[async_trait]
+    impl Middleware for InterruptOn {
+        async fn before_tool_call(
+            &self,
+            action: &AgentAction,
+            _context: &mut MiddlewareContext,
+        ) -> Result<Option<AgentAction>, MiddlewareError> {
+            if action.tool_input == self.0 {
+                return Err(MiddlewareError::Interrupt(json!({"input": self.0})));
+            }
+            Ok(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interrupt_in_batch_checkpoints_before_any_tool_runs() {
+        let max_running = Arc::new(AtomicUsize::new(0));
+        let checkpointer = Arc::new(InMemoryAgentSaver::new());
+        let executor = batch_executor(true, &max_running)
+            .with_middleware(vec![Arc::new(InterruptOn("bb"))])
+            .with_checkpointer(Some(checkpointer.clone()));
+        let config = crate::langgraph::RunnableConfig::with_thread_id("batch");
+
+        let result = executor
+            .call_with_config(prompt_args! {"input" => "go"}, Some(&config))
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(ChainError::Interrupt(payload)) if payload == json!({"input": "bb"})
+        ));
+        assert_eq!(max_running.load(Ordering::SeqCst), 0);
+        let state = checkpointer.get("batch").await.unwrap().unwrap();
+        let pending: Vec<_> = state
+            .pending_actions
+            .iter()
+            .map(|action| action.tool_input.as_str())
+            .collect();
+        assert_eq!(pending, vec!["a", "bb", "ccc"]);
+    }
+
+    /// Tool that always fails
+    struct FailTool;
+
+    #[async_trait]
+    impl Tool for FailTool {
+        fn name(&self) -> String {
+            "fail".to_string()
+        }
+
+        fn description(&self) -> String {
+            "Always fails".to_string()
+        }
+
+        async fn run(&self, _input: serde_json::Value) -> Result<String, ToolError> {
+            Err(ToolError::ExecutionError("boom".to_string()))
+        }
+
+        fn is_parallel_safe(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_break_if_error_reports_failure_after_batch_ran() {
+        let max_running = Arc::new(AtomicUsize::new(0));
+        let sleep: Arc<dyn Tool> = Arc::new(SleepTool {
+            name: "sleep".to_string(),
+            parallel_safe: true,
+            running: Arc::new(AtomicUsize::new(0)),
+            max_running: Arc::clone(&max_running),
+        });
+        let executor = AgentExecutor::from_agent(BatchAgent {
+            actions: vec![
+                action("sleep", "a"),
+                action("fail", ""),
+                action("sleep", "ccc"),
+            ],
+            tools: vec![sleep, Arc::new(FailTool)],
+        })
+        .with_break_if_error(true);
+
+        let err = executor
+            .invoke(prompt_args! {"input" => "go"})
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("boom"), "{err}");
+        // Both sleep calls ran alongside the failing one
+        assert_eq!(max_running.load(Ordering::SeqCst), 2);
+    }
+
+    fn approval_executor(
+        actions: Vec<AgentAction>,
+        checkpointer: Arc<dyn AgentCheckpointer>,
+    ) -> AgentExecutor<BatchAgent> {
+        let sleep: Arc<dyn Tool> = Arc::new(SleepTool {
+            name: "sleep".to_string(),
+            parallel_safe: true,
+            running: Arc::new(AtomicUsize::new(0)),
+            max_running: Arc::new(AtomicUsize::new(0)),
+        });
+        AgentExecutor::from_agent(BatchAgent {
+            actions,
+            tools: vec![sleep, Arc::new(FailTool)],
+        })
+        .with_middleware(vec![Arc::new(
+            HumanInTheLoopMiddleware::new().with_approval_required_for_tool_calls(true),
+        )])
+        .with_checkpointer(Some(checkpointer))
+    }
+
+    #[tokio::test]
+    async fn test_failed_resume_keeps_checkpoint() {
+        let checkpointer = Arc::new(InMemoryAgentSaver::new());
+        let executor = approval_executor(vec![action("fail", "")], checkpointer.clone())
+            .with_break_if_error(true);
+        let config = crate::langgraph::RunnableConfig::with_thread_id("failing");
+
+        let result = executor
+            .call_with_config(prompt_args! {"input" => "go"}, Some(&config))
+            .await;
+        assert!(matches!(result, Err(ChainError::Interrupt(_))));
+
+        let result = executor
+            .call_resume(&config, json!({"decisions": [{"type": "approve"}]}))
+            .await;
+        assert!(matches!(result, Err(ChainError::AgentError(ref e)) if e.contains("boom")));
+        assert!(checkpointer.get("failing").await.unwrap().is_some());
+    }
+
+    #[cfg(feature = "sqlite-persistence")]
+    #[tokio::test]
+    async fn test_resume_round_trip_with_sqlite_saver() {
+        let path = std::env::temp_dir().join(format!(
+            "agent_resume_{}.db",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let path = path.to_str().unwrap();
+        let actions = vec![action("sleep", "a"), action("sleep", "bb")];
+        let config = crate::langgraph::RunnableConfig::with_thread_id("sqlite");
+
+        let checkpointer = Arc::new(crate::agent::SqliteAgentSaver::new(path).unwrap());
+        let result = approval_executor(actions.clone(), checkpointer)
+            .call_with_config(prompt_args! {"input" => "go"}, Some(&config))
+            .await;
+        assert!(matches!(result, Err(ChainError::Interrupt(_))));
+
+        // Resume from a fresh executor and connection, as after a restart
+        let checkpointer = Arc::new(crate::agent::SqliteAgentSaver::new(path).unwrap());
+        assert_eq!(checkpointer.list_pending().await.unwrap().len(), 1);
+        let result = approval_executor(actions, checkpointer.clone())
+            .call_resume(
+                &config,
+                json!({"decisions": [{"type": "approve"}, {"type": "reject"}]}),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.generation, "sleep:a,Tool call rejected by user.");
+        assert!(checkpointer.get("sqlite").await.unwrap().is_none());
+        let _ = std::fs::remove_file(path);
+    }
+
+    fn event_names(events: &[AgentStreamEvent]) -> Vec<String> {
+        events
+            .iter()
+            .map(|event| {
+                serde_json::to_value(event).unwrap()["event"]
+                    .as_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_stream_events_for_tool_batch() {
+        let max_running = Arc::new(AtomicUsize::new(0));
+        let executor = batch_executor(true, &max_running);
+
+        let events: Vec<AgentStreamEvent> = executor
+            .stream_events(prompt_args! {"input" => "go"}, None)
+            .collect()
+            .await;
+
+        let agent_run_id = events[0].run_id.clone();
+        let (agent_events, tool_events): (Vec<_>, Vec<_>) = events
+            .into_iter()
+            .partition(|event| event.run_id == agent_run_id);
+        assert_eq!(
+            event_names(&agent_events),
+            vec![
+                "run_start",
+                "model_start",
+                "tool_call_planned",
+                "tool_call_planned",
+                "tool_call_planned",
+                "model_start",
+                "finish"
+            ]
+        );
+        assert!(matches!(
+            &agent_events.last().unwrap().kind,
+            AgentStreamEventKind::Finish { output } if output == "sleep:a,sleep:bb,sleep:ccc"
+        ));
+
+        assert_eq!(tool_events.len(), 6);
+        assert!(tool_events
+            .iter()
+            .all(
+                |event| event.parent_run_id.as_deref() == Some(agent_run_id.as_str())
+                    && event.name == "sleep"
+            ));
+        let ends: Vec<_> = tool_events
+            .iter()
+            .filter_map(|event| match &event.kind {
+                AgentStreamEventKind::ToolEnd { tool_call_id, .. } => Some(tool_call_id.clone()),
+                _ => None,
+            })
+            .collect();
+        // The shortest call finishes first
+        assert_eq!(ends, vec!["call_2", "call_1", "call_0"]);
+    }
+
+    /// Tool that reports progress for each page before returning
+    struct CrawlTool;
+
+    #[async_trait]
+    impl Tool for CrawlTool {
+        fn name(&self) -> String {
+            "crawl".to_string()
+        }
+
+        fn description(&self) -> String {
+            "Crawls two pages".to_string()
+        }
+
+        async fn run(&self, _input: serde_json::Value) -> Result<String, ToolError> {
+            Ok("crawled 2 pages".to_string())
+        }
+
+        fn run_stream<'a>(
+            &'a self,
+            _input: serde_json::Value,
+            _runtime: &'a ToolRuntime,
+        ) -> crate::tools::ToolStream<'a> {
+            Box::pin(stream! {
+                for page in 1..=2 {
+                    yield ToolStreamItem::Update(ToolUpdate::progress(page as f64, Some(2.0), None));
+                }
+                yield ToolStreamItem::Update(ToolUpdate::partial("page 1, page 2"));
+                yield ToolStreamItem::Output(Ok(ToolResult::Text("crawled 2 pages".to_string())));
+            })
+        }
+    }
+
+    fn crawl_executor() -> AgentExecutor<BatchAgent> {
+        AgentExecutor::from_agent(BatchAgent {
+            actions: vec![action("crawl", "docs")],
+            tools: vec![Arc::new(CrawlTool)],
+        })
+    }
+
+    #[tokio::test]
+    async fn test_stream_events_forward_tool_updates() {
+        let events: Vec<AgentStreamEvent> = crawl_executor()
+            .stream_events(prompt_args! {"input" => "go"}, None)
+            .collect()
+            .await;
+
+        let updates: Vec<_> = events
+            .iter()
+            .filter_map(|event| match &event.kind {
+                AgentStreamEventKind::ToolProgress {
+                    tool_call_id,
+                    tool,
+                    update,
+                } => {
+                    assert_eq!((tool_call_id.as_str(), tool.as_str()), ("call_0", "crawl"));
+                    Some(update.to_string())
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            updates,
+            vec!["Progress 1/2", "Progress 2/2", "page 1, page 2"]
+        );
+        assert!(matches!(
+            &events.last().unwrap().kind,
+            AgentStreamEventKind::Finish { output } if output == "crawled 2 pages"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_tool_updates_reach_graph_custom_stream() {
+        let mut custom = Vec::new();
+        let output = crate::langgraph::forward_custom_data(
+            crawl_executor().invoke(prompt_args! {"input" => "go"}),
+            |data| custom.push(data),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(output, "crawled 2 pages");
+        assert_eq!(custom.len(), 3);
+        assert_eq!(
+            custom[0],
+            json!({
+                "tool": "crawl",
+                "tool_call_id": "call_0",
+                "update": {"type": "progress", "progress": 1.0, "total": 2.0},
+            })
+        );
+    }
+
+    /// Tool that returns a screenshot alongside its text
+    struct ScreenshotTool;
+
+    #[async_trait]
+    impl Tool for ScreenshotTool {
+        fn name(&self) -> String {
+            "screenshot".to_string()
+        }
+
+        fn description(&self) -> String {
+            "Takes a screenshot".to_string()
+        }
+
+        async fn run(&self, _input: serde_json::Value) -> Result<String, ToolError> {
+            Ok("Took a screenshot".to_string())
+        }
+
+        async fn run_with_runtime(
+            &self,
+            _input: serde_json::Value,
+            _runtime: &ToolRuntime,
+        ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+            Ok(ToolResult::content(vec![
+                crate::schemas::ContentPart::text("Took a screenshot"),
+                crate::schemas::ContentPart::image(crate::schemas::ImageContent::from_base64(
+                    "image/png",
+                    "iVBORw0KGgo",
+                )),
+            ]))
+        }
+    }
+
+    /// Agent that calls the screenshot tool, then finishes with the attachments it sees
+    struct AttachmentsAgent;
+
+    #[async_trait]
+    impl Agent for AttachmentsAgent {
+        async fn plan(
+            &self,
+            intermediate_steps: &[(AgentAction, String)],
+            _inputs: PromptArgs,
+        ) -> Result<AgentEvent, AgentError> {
+            let Some((action_taken, observation)) = intermediate_steps.first() else {
+                return Ok(AgentEvent::Action(vec![action("screenshot", "")]));
+            };
+            Ok(AgentEvent::Finish(AgentFinish {
+                output: format!(
+                    "{}: {}",
+                    observation,
+                    serde_json::to_string(&action_taken.attachments).unwrap()
+                ),
+            }))
+        }
+
+        fn get_tools(&self) -> Vec<Arc<dyn Tool>> {
+            vec![Arc::new(ScreenshotTool)]
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_attachments_reach_the_agent() {
+        let output = AgentExecutor::from_agent(AttachmentsAgent)
+            .invoke(prompt_args! {"input" => "go"})
+            .await
+            .unwrap();
+
+        assert_eq!(
+            output,
+            r#"Took a screenshot: [{"type":"image","image_url":"data:image/png;base64,iVBORw0KGgo","detail":null}]"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_events_nests_subagent_runs() {
+        let max_running = Arc::new(AtomicUsize::new(0));
+        let subagent = crate::agent::UnifiedAgent::new(Box::new(BatchAgent {
+            actions: vec![action("sleep", "a")],
+            tools: vec![Arc::new(SleepTool {
+                name: "sleep".to_string(),
+                parallel_safe: true,
+                running: Arc::new(AtomicUsize::new(0)),
+                max_running,
+            })],
+        }));
+        let executor = AgentExecutor::from_agent(BatchAgent {
+            actions: vec![action("researcher", "look it up")],
+            tools: vec![Arc::new(crate::agent::SubagentTool::new(
+                Arc::new(subagent),
+                "researcher".to_string(),
+                "Researches".to_string(),
+            ))],
+        });
+
+        let events: Vec<AgentStreamEvent> = executor
+            .stream_events(prompt_args! {"input" => "go"}, None)
+            .collect()
+            .await;
+
+        let root = &events[0];
+        let tool_run = events
+            .iter()
+            .find(|event| matches!(event.kind, AgentStreamEventKind::ToolStart { .. }))
+            .unwrap();
+        assert_eq!(tool_run.name, "researcher");
+        assert_eq!(
+            tool_run.parent_run_id.as_deref(),
+            Some(root.run_id.as_str())
+        );
+
+        let sub_start = events
+            .iter()
+            .skip(1)
+            .find(|event| matches!(event.kind, AgentStreamEventKind::RunStart { .. }))
+            .unwrap();
+        assert_eq!(sub_start.name, "researcher");
+        assert_eq!(
+            sub_start.parent_run_id.as_deref(),
+            Some(tool_run.run_id.as_str())
+        );
+
+        let sub_tool_end = events
+            .iter()
+            .find(|event| {
+                event.parent_run_id.as_deref() == Some(sub_start.run_id.as_str())
+                    && matches!(event.kind, AgentStreamEventKind::ToolEnd { .. })
+            })
+            .unwrap();
+        assert_eq!(sub_tool_end.name, "sleep");
+
+        assert!(matches!(
+            &events.last().unwrap().kind,
+            AgentStreamEventKind::Finish { output } if output == "sleep:a"
+        ));
+    }
 }
@@ -171,12 +171,11 @@ impl Middleware for HumanInTheLoopMiddleware {
                     return match decision {
                         crate::agent::HitlDecision::Approve => Ok(None),
                         crate::agent::HitlDecision::Edit { edited_action } => {
-                            let modified = AgentAction {
-                                tool: edited_action.name.clone(),
-                                tool_input: serde_json::to_string(&edited_action.args)
-                                    .unwrap_or_default(),
-                                log: action.log.clone(),
-                            };
+                            let modified = AgentAction::new(
+                                edited_action.name.clone(),
+                                serde_json::to_string(&edited_action.args).unwrap_or_default(),
+                                action.log.clone(),
+                            );
                             Ok(Some(modified))
                         }
                         crate::agent::HitlDecision::Reject => Err(MiddlewareError::RejectTool),
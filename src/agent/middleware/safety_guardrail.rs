@@ -6,6 +6,7 @@ use crate::language_models::llm::LLM;
 use crate::prompt::PromptArgs;
 use crate::schemas::agent::{AgentEvent, AgentFinish};
 use crate::schemas::messages::Message;
+use crate::telemetry;
 
 /// Model-based guardrail: Use an LLM to evaluate response safety.
 ///
@@ -53,10 +54,9 @@ impl SafetyGuardrailMiddleware {
         let evaluation_prompt = format!("{}\n\nResponse: {}", self.safety_prompt, response);
 
         let messages = vec![Message::new_human_message(&evaluation_prompt)];
-        let result =
-            self.safety_model.generate(&messages).await.map_err(|e| {
-                MiddlewareError::ExecutionError(format!("Safety model error: {}", e))
-            })?;
+        let result = telemetry::generate(self.safety_model.as_ref(), &messages)
+            .await
+            .map_err(|e| MiddlewareError::ExecutionError(format!("Safety model error: {}", e)))?;
 
         let evaluation = result.generation.trim().to_uppercase();
 
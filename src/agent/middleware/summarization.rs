@@ -8,6 +8,7 @@ use crate::language_models::llm::LLM;
 use crate::prompt::PromptArgs;
 use crate::schemas::agent::AgentAction;
 use crate::schemas::Message;
+use crate::telemetry;
 
 /// Summarization middleware for managing long conversation history.
 ///
@@ -100,8 +101,7 @@ impl SummarizationMiddleware {
             Message::new_system_message(&self.summarization_prompt),
             Message::new_human_message(history_text),
         ];
-        let result = llm
-            .generate(&messages)
+        let result = telemetry::generate(llm.as_ref(), &messages)
             .await
             .map_err(|e| MiddlewareError::ExecutionError(e.to_string()))?;
         Ok(result.generation.trim().to_string())
@@ -7,6 +7,9 @@ pub use hitl::*;
 mod checkpoint;
 pub use checkpoint::*;
 
+mod events;
+pub use events::*;
+
 mod message_repair;
 
 mod executor;
@@ -50,6 +53,12 @@ pub mod deep_agent;
 #[allow(ambiguous_glob_reexports)]
 pub use deep_agent::*;
 
+mod sql_agent;
+pub use sql_agent::*;
+
+#[cfg(feature = "a2a")]
+pub mod a2a;
+
 use std::sync::Arc;
 
 use crate::{
@@ -0,0 +1,225 @@
+//! Running agents as nodes of supervisor and swarm graphs.
+//!
+//! A handoff is recorded in the conversation the same way a model would have made it: an AI
+//! message calling the `transfer_to_<agent>` tool followed by the tool's answer. Graph edges
+//! read those messages to decide where to go next, so the route survives checkpointing.
+
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+use serde_json::json;
+use tokio::sync::Mutex;
+
+use crate::{
+    agent::{
+        multi_agent::{handoffs::transfer_tool_name, MultiAgentError},
+        state::Command,
+        unified_agent::prompt_args_from_messages,
+        Agent, AgentError, AgentExecutor, AgentState,
+    },
+    chain::{Chain, ChainError},
+    prompt::PromptArgs,
+    schemas::{
+        agent::{AgentAction, AgentEvent},
+        Message, MessageType,
+    },
+    tools::Tool,
+};
+
+/// Lets several executors share one planned agent.
+struct SharedAgent(Arc<dyn Agent>);
+
+#[async_trait]
+impl Agent for SharedAgent {
+    async fn plan(
+        &self,
+        intermediate_steps: &[(AgentAction, String)],
+        inputs: PromptArgs,
+    ) -> Result<AgentEvent, AgentError> {
+        self.0.plan(intermediate_steps, inputs).await
+    }
+
+    async fn plan_streaming(
+        &self,
+        intermediate_steps: &[(AgentAction, String)],
+        inputs: PromptArgs,
+        on_token: &(dyn for<'t> Fn(&'t str) + Send + Sync),
+    ) -> Result<AgentEvent, AgentError> {
+        self.0
+            .plan_streaming(intermediate_steps, inputs, on_token)
+            .await
+    }
+
+    fn get_tools(&self) -> Vec<Arc<dyn Tool>> {
+        self.0.get_tools()
+    }
+}
+
+/// The result of one agent turn inside a graph.
+pub(crate) struct AgentTurn {
+    /// Final answer, or the transfer tool's output when the agent handed off
+    pub output: String,
+    /// Agent control was handed to, if any
+    pub handoff: Option<String>,
+}
+
+/// Run `agent` on the conversation with a fresh [`AgentState`] whose active agent is `name`.
+pub(crate) async fn run_agent_turn(
+    agent: &Arc<dyn Agent>,
+    name: &str,
+    messages: &[Message],
+    max_iterations: i32,
+) -> Result<AgentTurn, ChainError> {
+    let mut state = AgentState::with_messages(messages.to_vec());
+    state.set_active_agent(name.to_string());
+    let state = Arc::new(Mutex::new(state));
+
+    let executor = AgentExecutor::from_agent(SharedAgent(Arc::clone(agent)))
+        .with_max_iterations(max_iterations)
+        .with_state(Arc::clone(&state));
+    let output = executor
+        .invoke(prompt_args_from_messages(messages.to_vec())?)
+        .await?;
+
+    let handoff = state
+        .lock()
+        .await
+        .get_active_agent()
+        .filter(|active| active.as_str() != name)
+        .cloned();
+    Ok(AgentTurn { output, handoff })
+}
+
+/// Messages recording a handoff to `agent` through its transfer tool.
+pub(crate) fn handoff_messages(tool_name: &str, output: &str, call_id: String) -> Vec<Message> {
+    vec![
+        Message::new_ai_message("").with_tool_calls(json!([{
+            "id": call_id,
+            "type": "function",
+            "function": { "name": tool_name, "arguments": "{}" },
+        }])),
+        Message::new_tool_message(output, call_id),
+    ]
+}
+
+/// Names of the tools called by an AI message.
+fn called_tools(message: &Message) -> Vec<&str> {
+    message
+        .tool_calls
+        .as_ref()
+        .and_then(|calls| calls.as_array())
+        .map(|calls| {
+            calls
+                .iter()
+                .filter_map(|call| call["function"]["name"].as_str())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The agent handed off to by the last two messages, if they record a handoff.
+///
+/// `transfer_tools` maps transfer tool names to agent names.
+pub(crate) fn handoff_target(
+    messages: &[Message],
+    transfer_tools: &HashMap<String, String>,
+) -> Option<String> {
+    let [call, result] = messages.get(messages.len().checked_sub(2)?..)? else {
+        return None;
+    };
+    if !matches!(call.message_type, MessageType::AIMessage)
+        || !matches!(result.message_type, MessageType::ToolMessage)
+    {
+        return None;
+    }
+    called_tools(call)
+        .into_iter()
+        .find_map(|tool| transfer_tools.get(tool).cloned())
+}
+
+/// The active agent of a swarm conversation.
+///
+/// Starts at `default_agent` and replays every handoff recorded in `messages` as a
+/// [`Command::Handoff`], so the agent handed control last stays active.
+pub(crate) fn active_agent(
+    messages: &[Message],
+    transfer_tools: &HashMap<String, String>,
+    default_agent: &str,
+) -> String {
+    let mut state = AgentState::new();
+    state.set_active_agent(default_agent.to_string());
+    for message in messages
+        .iter()
+        .filter(|message| matches!(message.message_type, MessageType::AIMessage))
+    {
+        if let Some(agent) = called_tools(message)
+            .into_iter()
+            .find_map(|tool| transfer_tools.get(tool).cloned())
+        {
+            Command::Handoff { agent }.apply(&mut state);
+        }
+    }
+    state
+        .get_active_agent()
+        .cloned()
+        .unwrap_or_else(|| default_agent.to_string())
+}
+
+/// Map the transfer tool name of every agent to the agent.
+///
+/// Fails when two agents share a transfer tool name, e.g. `billing team` and `billing_team`.
+pub(crate) fn transfer_routes<'a>(
+    agents: impl IntoIterator<Item = &'a String>,
+) -> Result<HashMap<String, String>, MultiAgentError> {
+    let mut routes = HashMap::new();
+    for agent in agents {
+        let tool_name = transfer_tool_name(agent);
+        if let Some(other) = routes.insert(tool_name.clone(), agent.clone()) {
+            return Err(MultiAgentError::InvalidConfiguration(format!(
+                "Agents '{}' and '{}' both map to the transfer tool '{}'",
+                other, agent, tool_name
+            )));
+        }
+    }
+    Ok(routes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handoff_targets() {
+        let tools = HashMap::from([("transfer_to_billing".to_string(), "billing".to_string())]);
+        let mut messages = vec![Message::new_human_message("refund please")];
+        assert_eq!(handoff_target(&messages, &tools), None);
+
+        messages.extend(handoff_messages(
+            "transfer_to_billing",
+            "Successfully transferred to billing",
+            "call_1".to_string(),
+        ));
+        assert_eq!(
+            handoff_target(&messages, &tools),
+            Some("billing".to_string())
+        );
+
+        messages.push(Message::new_ai_message("Refund issued."));
+        assert_eq!(handoff_target(&messages, &tools), None);
+        assert_eq!(active_agent(&messages, &tools, "triage"), "billing");
+        assert_eq!(active_agent(&messages[..1], &tools, "triage"), "triage");
+    }
+
+    #[test]
+    fn test_transfer_routes_rejects_colliding_names() {
+        let agents = ["billing team".to_string(), "support".to_string()];
+        let routes = transfer_routes(&agents).unwrap();
+        assert_eq!(routes["transfer_to_billing_team"], "billing team");
+
+        let agents = ["billing team".to_string(), "billing_team".to_string()];
+        assert!(matches!(
+            transfer_routes(&agents),
+            Err(MultiAgentError::InvalidConfiguration(_))
+        ));
+    }
+}
@@ -123,6 +123,10 @@ impl Tool for HandoffTool {
     fn requires_runtime(&self) -> bool {
         true
     }
+
+    fn is_parallel_safe(&self) -> bool {
+        false
+    }
 }
 
 #[cfg(test)]
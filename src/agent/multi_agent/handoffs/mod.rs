@@ -1,5 +1,7 @@
 mod handoff_agent;
 mod handoff_tool;
+mod transfer_tool;
 
 pub use handoff_agent::{HandoffAgent, HandoffAgentBuilder};
 pub use handoff_tool::HandoffTool;
+pub use transfer_tool::{transfer_tool_name, TransferTool};
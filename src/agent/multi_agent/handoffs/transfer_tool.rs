@@ -0,0 +1,134 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use crate::{
+    agent::state::Command,
+    tools::{Tool, ToolResult, ToolRuntime},
+};
+
+/// Name of the tool that transfers control to `agent`, e.g. `transfer_to_billing`.
+pub fn transfer_tool_name(agent: &str) -> String {
+    let agent: String = agent
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("transfer_to_{}", agent)
+}
+
+/// A tool that hands control to one specific agent.
+///
+/// Calling it returns a [`Command::Handoff`], which sets the active agent and
+/// ends the calling agent's run. Supervisor and swarm graphs use these tools
+/// to route between agents.
+pub struct TransferTool {
+    agent: String,
+    name: String,
+    description: String,
+}
+
+impl TransferTool {
+    /// Create a tool that transfers to `agent`, described by what the agent does
+    pub fn new<S: Into<String>>(agent: S, agent_description: &str) -> Self {
+        let agent = agent.into();
+        Self {
+            name: transfer_tool_name(&agent),
+            description: format!(
+                "Transfer the conversation to the {} agent. {}",
+                agent, agent_description
+            ),
+            agent,
+        }
+    }
+
+    /// The agent this tool transfers to
+    pub fn agent(&self) -> &str {
+        &self.agent
+    }
+}
+
+#[async_trait]
+impl Tool for TransferTool {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {},
+        })
+    }
+
+    async fn run(&self, _input: Value) -> Result<String, crate::error::ToolError> {
+        Err(crate::error::ToolError::ConfigurationError(
+            "TransferTool requires runtime. Use run_with_runtime instead.".to_string(),
+        ))
+    }
+
+    async fn run_with_runtime(
+        &self,
+        _input: Value,
+        _runtime: &ToolRuntime,
+    ) -> Result<ToolResult, Box<dyn Error>> {
+        Ok(ToolResult::with_command(
+            format!("Successfully transferred to {}", self.agent),
+            Command::Handoff {
+                agent: self.agent.clone(),
+            },
+        ))
+    }
+
+    fn requires_runtime(&self) -> bool {
+        true
+    }
+
+    fn is_parallel_safe(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tokio::sync::Mutex;
+
+    use super::*;
+    use crate::{
+        agent::AgentState,
+        tools::{EmptyContext, InMemoryStore},
+    };
+
+    #[tokio::test]
+    async fn test_transfer_tool_returns_handoff_command() {
+        let tool = TransferTool::new("tech support", "Fixes crashes.");
+        assert_eq!(tool.name(), "transfer_to_tech_support");
+
+        let state = Arc::new(Mutex::new(AgentState::new()));
+        let runtime = ToolRuntime::new(
+            state.clone(),
+            Arc::new(EmptyContext),
+            Arc::new(InMemoryStore::new()),
+            "call_0".to_string(),
+        );
+        let (text, command) = tool
+            .run_with_runtime(json!({}), &runtime)
+            .await
+            .unwrap()
+            .into_parts();
+        assert_eq!(text, "Successfully transferred to tech support");
+
+        command.unwrap().apply(&mut *state.lock().await);
+        assert_eq!(
+            state.lock().await.get_active_agent().map(String::as_str),
+            Some("tech support")
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use thiserror::Error;
 
-use crate::agent::AgentError;
+use crate::{agent::AgentError, langgraph::LangGraphError};
 
 /// Multi-agent specific error types
 #[derive(Error, Debug)]
@@ -25,15 +25,31 @@ pub enum MultiAgentError {
 
     #[error("Serde json error: {0}")]
     SerdeJsonError(#[from] serde_json::Error),
+
+    #[error("Graph error: {0}")]
+    GraphError(#[from] LangGraphError),
 }
 
+mod graph_agent;
 pub mod handoffs;
 pub mod router;
 pub mod skills;
 pub mod subagents;
+pub mod supervisor;
+pub mod swarm;
+#[cfg(test)]
+pub(crate) mod test_llm;
 
 // Re-export commonly used types
-pub use handoffs::{HandoffAgent, HandoffAgentBuilder, HandoffTool};
-pub use router::{DefaultRouter, Router, RouterAgent, RouterAgentBuilder, RoutingStrategy};
+pub use handoffs::{
+    transfer_tool_name, HandoffAgent, HandoffAgentBuilder, HandoffTool, TransferTool,
+};
+pub use router::{
+    AgentResponse, ConcatAggregator, DefaultRouter, FirstSuccessAggregator, LLMSynthesisAggregator,
+    ResponseAggregator, RoutableAgent, RouteMatch, Router, RouterAgent, RouterAgentBuilder,
+    RoutingStrategy, SemanticRouter,
+};
 pub use skills::{SimpleSkill, Skill, SkillAgent, SkillAgentBuilder, SkillContext};
 pub use subagents::{SubagentInfo, SubagentTool, SubagentsBuilder};
+pub use supervisor::{create_supervisor, SupervisorBuilder, SUPERVISOR_NODE};
+pub use swarm::{create_swarm, SwarmAgent, SwarmBuilder};
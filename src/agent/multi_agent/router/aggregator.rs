@@ -0,0 +1,206 @@
+use async_trait::async_trait;
+
+use crate::{chain::ChainError, language_models::llm::LLM, schemas::Message, telemetry};
+
+/// The outcome of one agent run during parallel routing
+#[derive(Debug)]
+pub struct AgentResponse {
+    /// Name of the agent that produced the response
+    pub agent: String,
+    /// Route score the agent was selected with
+    pub score: f64,
+    /// The agent's answer, or the error it failed with
+    pub result: Result<String, ChainError>,
+}
+
+/// Combines the answers of agents that ran in parallel into one answer
+///
+/// Responses are passed in route rank order (best match first).
+#[async_trait]
+pub trait ResponseAggregator: Send + Sync {
+    async fn aggregate(
+        &self,
+        input: &str,
+        responses: Vec<AgentResponse>,
+    ) -> Result<String, ChainError>;
+}
+
+/// Split responses into successful `(agent, answer)` pairs, or return the first error when
+/// every agent failed.
+fn successes(responses: Vec<AgentResponse>) -> Result<Vec<(String, String)>, ChainError> {
+    let mut answers = Vec::new();
+    let mut first_error = None;
+    for response in responses {
+        match response.result {
+            Ok(answer) => answers.push((response.agent, answer)),
+            Err(e) => {
+                log::warn!("Agent {} failed: {}", response.agent, e);
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    match (answers.is_empty(), first_error) {
+        (true, Some(e)) => Err(e),
+        (true, None) => Err(ChainError::AgentError(
+            "No agent produced a response".to_string(),
+        )),
+        _ => Ok(answers),
+    }
+}
+
+/// Concatenates successful answers, each labeled with its agent name
+pub struct ConcatAggregator {
+    separator: String,
+}
+
+impl ConcatAggregator {
+    pub fn new() -> Self {
+        Self {
+            separator: "\n\n".to_string(),
+        }
+    }
+
+    /// Set the text placed between answers (default: a blank line)
+    pub fn with_separator<S: Into<String>>(mut self, separator: S) -> Self {
+        self.separator = separator.into();
+        self
+    }
+}
+
+impl Default for ConcatAggregator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ResponseAggregator for ConcatAggregator {
+    async fn aggregate(
+        &self,
+        _input: &str,
+        responses: Vec<AgentResponse>,
+    ) -> Result<String, ChainError> {
+        Ok(successes(responses)?
+            .into_iter()
+            .map(|(agent, answer)| format!("[{}]\n{}", agent, answer))
+            .collect::<Vec<_>>()
+            .join(&self.separator))
+    }
+}
+
+/// Returns the answer of the highest ranked agent that succeeded
+pub struct FirstSuccessAggregator;
+
+#[async_trait]
+impl ResponseAggregator for FirstSuccessAggregator {
+    async fn aggregate(
+        &self,
+        _input: &str,
+        responses: Vec<AgentResponse>,
+    ) -> Result<String, ChainError> {
+        successes(responses)?
+            .into_iter()
+            .next()
+            .map(|(_, answer)| answer)
+            .ok_or_else(|| ChainError::AgentError("No agent produced a response".to_string()))
+    }
+}
+
+/// Asks an LLM to merge the successful answers into a single response
+pub struct LLMSynthesisAggregator {
+    llm: Box<dyn LLM>,
+}
+
+impl LLMSynthesisAggregator {
+    pub fn new(llm: Box<dyn LLM>) -> Self {
+        Self { llm }
+    }
+}
+
+#[async_trait]
+impl ResponseAggregator for LLMSynthesisAggregator {
+    async fn aggregate(
+        &self,
+        input: &str,
+        responses: Vec<AgentResponse>,
+    ) -> Result<String, ChainError> {
+        let mut answers = successes(responses)?;
+        if answers.len() == 1 {
+            return Ok(answers.remove(0).1);
+        }
+
+        let answers: Vec<String> = answers
+            .iter()
+            .map(|(agent, answer)| format!("Answer from {}:\n{}", agent, answer))
+            .collect();
+        let prompt = format!(
+            "Several specialized agents answered the same user question. Combine their answers \
+            into a single, complete response to the user. Keep all relevant details, resolve \
+            overlaps, and do not mention the agents.\n\n\
+            User question: {}\n\n{}",
+            input,
+            answers.join("\n\n")
+        );
+
+        let result =
+            telemetry::generate(self.llm.as_ref(), &[Message::new_human_message(prompt)]).await?;
+        Ok(result.generation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn responses() -> Vec<AgentResponse> {
+        vec![
+            AgentResponse {
+                agent: "billing".to_string(),
+                score: 0.9,
+                result: Err(ChainError::AgentError("timeout".to_string())),
+            },
+            AgentResponse {
+                agent: "support".to_string(),
+                score: 0.7,
+                result: Ok("Reinstall the app.".to_string()),
+            },
+            AgentResponse {
+                agent: "sales".to_string(),
+                score: 0.2,
+                result: Ok("Upgrade your plan.".to_string()),
+            },
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_concat_aggregator_skips_failures() {
+        let answer = ConcatAggregator::new()
+            .aggregate("help", responses())
+            .await
+            .unwrap();
+        assert_eq!(
+            answer,
+            "[support]\nReinstall the app.\n\n[sales]\nUpgrade your plan."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_first_success_aggregator() {
+        let answer = FirstSuccessAggregator
+            .aggregate("help", responses())
+            .await
+            .unwrap();
+        assert_eq!(answer, "Reinstall the app.");
+
+        let failed = vec![AgentResponse {
+            agent: "billing".to_string(),
+            score: 1.0,
+            result: Err(ChainError::AgentError("timeout".to_string())),
+        }];
+        let error = FirstSuccessAggregator
+            .aggregate("help", failed)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("timeout"));
+    }
+}
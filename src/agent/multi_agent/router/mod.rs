@@ -1,5 +1,12 @@
+mod aggregator;
 mod router;
 mod router_agent;
+mod semantic_router;
 
-pub use router::{DefaultRouter, Router, RoutingStrategy};
-pub use router_agent::{RouterAgent, RouterAgentBuilder};
+pub use aggregator::{
+    AgentResponse, ConcatAggregator, FirstSuccessAggregator, LLMSynthesisAggregator,
+    ResponseAggregator,
+};
+pub use router::{DefaultRouter, RouteMatch, Router, RoutingStrategy};
+pub use router_agent::{RoutableAgent, RouterAgent, RouterAgentBuilder};
+pub use semantic_router::SemanticRouter;
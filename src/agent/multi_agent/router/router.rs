@@ -1,6 +1,9 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 
-use crate::{agent::AgentError, language_models::llm::LLM};
+use crate::{agent::AgentError, language_models::llm::LLM, schemas::Message, telemetry};
 
 /// Strategy for routing input to agents
 pub enum RoutingStrategy {
@@ -14,10 +17,28 @@ pub enum RoutingStrategy {
     /// Use keyword matching to route
     KeywordBased {
         /// Map of keywords to agent names
-        keyword_map: std::collections::HashMap<String, Vec<String>>,
+        keyword_map: HashMap<String, Vec<String>>,
     },
 }
 
+/// An agent selected by a router, with a relevance score between 0.0 and 1.0
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteMatch {
+    /// Name of the agent
+    pub agent: String,
+    /// How well the agent matches the input
+    pub score: f64,
+}
+
+impl RouteMatch {
+    pub fn new<S: Into<String>>(agent: S, score: f64) -> Self {
+        Self {
+            agent: agent.into(),
+            score,
+        }
+    }
+}
+
 /// Router that determines which agent should handle a given input
 #[async_trait]
 pub trait Router: Send + Sync {
@@ -26,6 +47,62 @@ pub trait Router: Send + Sync {
     /// Returns the name of the agent that should handle the input,
     /// or None if no suitable agent is found.
     async fn route(&self, input: &str) -> Result<Option<String>, AgentError>;
+
+    /// Rank every agent that could handle the input, best match first
+    ///
+    /// The default implementation returns the result of [`route`](Router::route)
+    /// as a single match with score 1.0.
+    async fn route_ranked(&self, input: &str) -> Result<Vec<RouteMatch>, AgentError> {
+        Ok(self
+            .route(input)
+            .await?
+            .map(|agent| RouteMatch::new(agent, 1.0))
+            .into_iter()
+            .collect())
+    }
+}
+
+/// Sort matches by descending score, keeping the original order for ties.
+fn sort_matches(matches: &mut [RouteMatch]) {
+    matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+}
+
+/// Parse an LLM ranking response such as `[{"agent": "billing", "score": 0.9}]`.
+///
+/// Falls back to every known agent name mentioned in the response, scored 1.0.
+fn parse_ranking(response: &str, agent_descriptions: &[(String, String)]) -> Vec<RouteMatch> {
+    let canonical = |name: &str| {
+        agent_descriptions
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(name.trim()))
+            .map(|(known, _)| known.clone())
+    };
+
+    let parsed = match (response.find('['), response.rfind(']')) {
+        (Some(start), Some(end)) if start < end => {
+            serde_json::from_str::<Vec<RouteMatch>>(&response[start..=end]).ok()
+        }
+        _ => None,
+    };
+
+    let mut matches: Vec<RouteMatch> = match parsed {
+        Some(parsed) => parsed
+            .into_iter()
+            .filter_map(|m| {
+                canonical(&m.agent).map(|agent| RouteMatch::new(agent, m.score.clamp(0.0, 1.0)))
+            })
+            .collect(),
+        None => {
+            let response = response.to_lowercase();
+            agent_descriptions
+                .iter()
+                .filter(|(name, _)| response.contains(&name.to_lowercase()))
+                .map(|(name, _)| RouteMatch::new(name.clone(), 1.0))
+                .collect()
+        }
+    };
+    sort_matches(&mut matches);
+    matches
 }
 
 /// Default router implementation
@@ -45,7 +122,7 @@ impl DefaultRouter {
     }
 
     /// Create a new router with keyword-based routing
-    pub fn with_keywords(keyword_map: std::collections::HashMap<String, Vec<String>>) -> Self {
+    pub fn with_keywords(keyword_map: HashMap<String, Vec<String>>) -> Self {
         Self {
             strategy: RoutingStrategy::KeywordBased { keyword_map },
         }
@@ -76,7 +153,10 @@ impl Router for DefaultRouter {
                     input
                 );
 
-                let result = llm.invoke(&prompt).await?;
+                let result =
+                    telemetry::generate(llm.as_ref(), &[Message::new_human_message(prompt)])
+                        .await?
+                        .generation;
                 let result = result.trim().to_lowercase();
 
                 // Find matching agent name
@@ -111,12 +191,63 @@ impl Router for DefaultRouter {
             }
         }
     }
+
+    async fn route_ranked(&self, input: &str) -> Result<Vec<RouteMatch>, AgentError> {
+        match &self.strategy {
+            RoutingStrategy::LLMBased {
+                llm,
+                agent_descriptions,
+            } => {
+                let agent_list: Vec<String> = agent_descriptions
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (name, desc))| format!("{}. {}: {}", i + 1, name, desc))
+                    .collect();
+
+                let prompt = format!(
+                    "You are a routing system. Based on the user input, determine which specialized agents should handle it.\n\n\
+                    Available agents:\n{}\n\n\
+                    User input: {}\n\n\
+                    Respond with ONLY a JSON array of every agent that is relevant, most relevant first, \
+                    e.g. [{{\"agent\": \"name\", \"score\": 0.9}}] with scores between 0 and 1. \
+                    Use the agent names exactly as listed above, or respond with [] if no agent is suitable.",
+                    agent_list.join("\n"),
+                    input
+                );
+
+                let result =
+                    telemetry::generate(llm.as_ref(), &[Message::new_human_message(prompt)])
+                        .await?
+                        .generation;
+                Ok(parse_ranking(&result, agent_descriptions))
+            }
+            RoutingStrategy::KeywordBased { keyword_map } => {
+                let input_lower = input.to_lowercase();
+                let mut matches: Vec<RouteMatch> = keyword_map
+                    .iter()
+                    .filter(|(_, keywords)| !keywords.is_empty())
+                    .filter_map(|(agent_name, keywords)| {
+                        let hits = keywords
+                            .iter()
+                            .filter(|keyword| input_lower.contains(&keyword.to_lowercase()))
+                            .count();
+                        (hits > 0).then(|| {
+                            RouteMatch::new(agent_name.clone(), hits as f64 / keywords.len() as f64)
+                        })
+                    })
+                    .collect();
+                // HashMap order is arbitrary; sort by name first so ties are deterministic
+                matches.sort_by(|a, b| a.agent.cmp(&b.agent));
+                sort_matches(&mut matches);
+                Ok(matches)
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashMap;
 
     #[test]
     fn test_keyword_router_creation() {
@@ -153,4 +284,58 @@ mod tests {
         let result = router.route("Random question").await.unwrap();
         assert_eq!(result, None);
     }
+
+    #[tokio::test]
+    async fn test_keyword_router_ranked() {
+        let mut keyword_map = HashMap::new();
+        keyword_map.insert(
+            "billing".to_string(),
+            vec!["invoice".to_string(), "refund".to_string()],
+        );
+        keyword_map.insert(
+            "support".to_string(),
+            vec![
+                "error".to_string(),
+                "crash".to_string(),
+                "login".to_string(),
+                "install".to_string(),
+            ],
+        );
+
+        let router = DefaultRouter::with_keywords(keyword_map);
+        let matches = router
+            .route_ranked("I got an error after my refund, no invoice either")
+            .await
+            .unwrap();
+        assert_eq!(
+            matches,
+            vec![
+                RouteMatch::new("billing", 1.0),
+                RouteMatch::new("support", 0.25)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_ranking() {
+        let agents = vec![
+            ("billing".to_string(), "Invoices".to_string()),
+            ("support".to_string(), "Technical help".to_string()),
+        ];
+
+        let matches = parse_ranking(
+            r#"Here you go: [{"agent": "Support", "score": 0.4}, {"agent": "billing", "score": 0.8}, {"agent": "sales", "score": 0.9}]"#,
+            &agents,
+        );
+        assert_eq!(
+            matches,
+            vec![
+                RouteMatch::new("billing", 0.8),
+                RouteMatch::new("support", 0.4)
+            ]
+        );
+
+        let matches = parse_ranking("support", &agents);
+        assert_eq!(matches, vec![RouteMatch::new("support", 1.0)]);
+    }
 }
@@ -1,26 +1,64 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use async_trait::async_trait;
+use futures::future::join_all;
+use serde_json::json;
+use tokio::sync::Mutex;
+
 use crate::{
-    agent::multi_agent::router::{DefaultRouter, Router},
-    agent::{AgentError, UnifiedAgent},
+    agent::multi_agent::router::{
+        AgentResponse, ConcatAggregator, DefaultRouter, ResponseAggregator, RouteMatch, Router,
+    },
+    agent::{AgentError, AgentState, UnifiedAgent},
     chain::ChainError,
     schemas::messages::Message,
+    telemetry,
 };
 
+/// An agent a [`RouterAgent`] can route to
+///
+/// Implemented for [`UnifiedAgent`]; remote agents (e.g. over A2A) implement it to take part
+/// in routing next to local ones.
+#[async_trait]
+pub trait RoutableAgent: Send + Sync {
+    /// Answer the conversation in `messages`
+    async fn invoke_messages(&self, messages: Vec<Message>) -> Result<String, ChainError>;
+}
+
+#[async_trait]
+impl RoutableAgent for UnifiedAgent {
+    async fn invoke_messages(&self, messages: Vec<Message>) -> Result<String, ChainError> {
+        UnifiedAgent::invoke_messages(self, messages).await
+    }
+}
+
 /// An agent wrapper that routes input to specialized agents.
 ///
 /// This implements the Router pattern where a routing step classifies
 /// input and directs it to one or more specialized agents.
+///
+/// With parallel execution enabled, every agent returned by
+/// [`Router::route_ranked`] (above the minimum score) runs concurrently and
+/// the answers are combined by a [`ResponseAggregator`] (by default a
+/// [`ConcatAggregator`]).
 pub struct RouterAgent {
     /// Map of agent names to agent instances
-    agents: HashMap<String, Arc<UnifiedAgent>>,
+    agents: HashMap<String, Arc<dyn RoutableAgent>>,
     /// The router that determines which agent to use
     router: Box<dyn Router>,
     /// Default agent to use when routing fails
-    default_agent: Option<Arc<UnifiedAgent>>,
+    default_agent: Option<Arc<dyn RoutableAgent>>,
     /// Whether to allow parallel execution of multiple agents
     allow_parallel: bool,
+    /// Combines answers when several agents run in parallel
+    aggregator: Box<dyn ResponseAggregator>,
+    /// Routes scoring below this are ignored in parallel mode
+    min_route_score: f64,
+    /// Maximum number of agents run in parallel
+    max_parallel_agents: Option<usize>,
+    /// State that routing decisions are recorded in
+    state: Option<Arc<Mutex<AgentState>>>,
 }
 
 impl RouterAgent {
@@ -31,17 +69,21 @@ impl RouterAgent {
             router,
             default_agent: None,
             allow_parallel: false,
+            aggregator: Box::new(ConcatAggregator::new()),
+            min_route_score: 0.0,
+            max_parallel_agents: None,
+            state: None,
         }
     }
 
     /// Add an agent to the router
-    pub fn with_agent(mut self, name: String, agent: Arc<UnifiedAgent>) -> Self {
+    pub fn with_agent(mut self, name: String, agent: Arc<dyn RoutableAgent>) -> Self {
         self.agents.insert(name, agent);
         self
     }
 
     /// Add multiple agents
-    pub fn with_agents(mut self, agents: Vec<(String, Arc<UnifiedAgent>)>) -> Self {
+    pub fn with_agents(mut self, agents: Vec<(String, Arc<dyn RoutableAgent>)>) -> Self {
         for (name, agent) in agents {
             self.agents.insert(name, agent);
         }
@@ -49,7 +91,7 @@ impl RouterAgent {
     }
 
     /// Set the default agent (used when routing fails)
-    pub fn with_default_agent(mut self, agent: Arc<UnifiedAgent>) -> Self {
+    pub fn with_default_agent(mut self, agent: Arc<dyn RoutableAgent>) -> Self {
         self.default_agent = Some(agent);
         self
     }
@@ -60,13 +102,45 @@ impl RouterAgent {
         self
     }
 
+    /// Set how answers are combined when several agents run in parallel
+    pub fn with_aggregator(mut self, aggregator: Box<dyn ResponseAggregator>) -> Self {
+        self.aggregator = aggregator;
+        self
+    }
+
+    /// Ignore routes scoring below `min_score` in parallel mode
+    pub fn with_min_route_score(mut self, min_score: f64) -> Self {
+        self.min_route_score = min_score;
+        self
+    }
+
+    /// Run at most `max` of the best ranked agents in parallel mode
+    pub fn with_max_parallel_agents(mut self, max: usize) -> Self {
+        self.max_parallel_agents = Some(max);
+        self
+    }
+
+    /// Record every routing decision in `state` (see [`AgentState::add_routing_history`])
+    pub fn with_state(mut self, state: Arc<Mutex<AgentState>>) -> Self {
+        self.state = Some(state);
+        self
+    }
+
     /// Get an agent by name
-    pub fn get_agent(&self, name: &str) -> Option<&Arc<UnifiedAgent>> {
+    pub fn get_agent(&self, name: &str) -> Option<&Arc<dyn RoutableAgent>> {
         self.agents.get(name)
     }
 
     /// Route and invoke the appropriate agent(s)
+    ///
+    /// The routing decision and the selected agent are reported as one `RouterAgent` run, so
+    /// callbacks see them as a single run tree.
     pub async fn invoke_messages(&self, messages: Vec<Message>) -> Result<String, ChainError> {
+        let inputs = json!({ "messages": messages });
+        telemetry::agent("RouterAgent", &inputs, self.route_and_invoke(messages)).await
+    }
+
+    async fn route_and_invoke(&self, messages: Vec<Message>) -> Result<String, ChainError> {
         // Extract the last human message
         let last_human_message = messages
             .iter()
@@ -76,6 +150,10 @@ impl RouterAgent {
 
         let input = &last_human_message.content;
 
+        if self.allow_parallel {
+            return self.invoke_parallel(input, messages.clone()).await;
+        }
+
         // Route to determine which agent(s) should handle this
         let selected_agent_name = self
             .router
@@ -88,20 +166,87 @@ impl RouterAgent {
             let agent = self.agents.get(&agent_name).ok_or_else(|| {
                 ChainError::AgentError(format!("Agent not found: {}", agent_name))
             })?;
+            self.record_routing(input, [agent_name.as_str()]).await;
 
             // Invoke the selected agent
             agent.invoke_messages(messages).await
         } else {
-            // No agent selected, use default or return error
-            if let Some(default) = &self.default_agent {
-                default.invoke_messages(messages).await
-            } else {
-                Err(ChainError::AgentError(
-                    "No suitable agent found and no default agent configured".to_string(),
-                ))
+            self.invoke_default(messages).await
+        }
+    }
+
+    /// Run every selected agent concurrently and aggregate their answers
+    async fn invoke_parallel(
+        &self,
+        input: &str,
+        messages: Vec<Message>,
+    ) -> Result<String, ChainError> {
+        let mut selected: Vec<RouteMatch> = self
+            .router
+            .route_ranked(input)
+            .await
+            .map_err(|e| ChainError::AgentError(e.to_string()))?
+            .into_iter()
+            .filter(|route| route.score >= self.min_route_score)
+            .filter(|route| {
+                let known = self.agents.contains_key(&route.agent);
+                if !known {
+                    log::warn!("Router selected unknown agent: {}", route.agent);
+                }
+                known
+            })
+            .collect();
+        if let Some(max) = self.max_parallel_agents {
+            selected.truncate(max);
+        }
+        self.record_routing(input, selected.iter().map(|route| route.agent.as_str()))
+            .await;
+
+        match selected.len() {
+            0 => self.invoke_default(messages).await,
+            1 => {
+                self.agents[&selected[0].agent]
+                    .invoke_messages(messages)
+                    .await
+            }
+            _ => {
+                let responses = join_all(selected.into_iter().map(|route| {
+                    let agent = Arc::clone(&self.agents[&route.agent]);
+                    let messages = messages.clone();
+                    async move {
+                        AgentResponse {
+                            result: agent.invoke_messages(messages).await,
+                            agent: route.agent,
+                            score: route.score,
+                        }
+                    }
+                }))
+                .await;
+                self.aggregator.aggregate(input, responses).await
             }
         }
     }
+
+    /// Add the selected agents to the routing history of the attached state
+    async fn record_routing<'a>(&self, input: &str, agents: impl IntoIterator<Item = &'a str>) {
+        if let Some(state) = &self.state {
+            let mut state = state.lock().await;
+            for agent in agents {
+                state.add_routing_history(input.to_string(), agent.to_string());
+            }
+        }
+    }
+
+    /// No agent selected, use default or return error
+    async fn invoke_default(&self, messages: Vec<Message>) -> Result<String, ChainError> {
+        if let Some(default) = &self.default_agent {
+            default.invoke_messages(messages).await
+        } else {
+            Err(ChainError::AgentError(
+                "No suitable agent found and no default agent configured".to_string(),
+            ))
+        }
+    }
 }
 
 /// Builder for creating RouterAgent
@@ -109,11 +254,19 @@ pub struct RouterAgentBuilder {
     /// Router strategy
     router: Option<Box<dyn Router>>,
     /// Agents to add
-    agents: Vec<(String, Arc<UnifiedAgent>)>,
+    agents: Vec<(String, Arc<dyn RoutableAgent>)>,
     /// Default agent
-    default_agent: Option<Arc<UnifiedAgent>>,
+    default_agent: Option<Arc<dyn RoutableAgent>>,
     /// Allow parallel execution
     allow_parallel: bool,
+    /// Aggregator for parallel answers
+    aggregator: Option<Box<dyn ResponseAggregator>>,
+    /// Minimum route score in parallel mode
+    min_route_score: Option<f64>,
+    /// Maximum number of agents run in parallel
+    max_parallel_agents: Option<usize>,
+    /// State for the routing history
+    state: Option<Arc<Mutex<AgentState>>>,
 }
 
 impl RouterAgentBuilder {
@@ -124,6 +277,10 @@ impl RouterAgentBuilder {
             agents: Vec::new(),
             default_agent: None,
             allow_parallel: false,
+            aggregator: None,
+            min_route_score: None,
+            max_parallel_agents: None,
+            state: None,
         }
     }
 
@@ -153,19 +310,19 @@ impl RouterAgentBuilder {
     }
 
     /// Add an agent
-    pub fn with_agent(mut self, name: String, agent: Arc<UnifiedAgent>) -> Self {
+    pub fn with_agent(mut self, name: String, agent: Arc<dyn RoutableAgent>) -> Self {
         self.agents.push((name, agent));
         self
     }
 
     /// Add multiple agents
-    pub fn with_agents(mut self, agents: Vec<(String, Arc<UnifiedAgent>)>) -> Self {
+    pub fn with_agents(mut self, agents: Vec<(String, Arc<dyn RoutableAgent>)>) -> Self {
         self.agents.extend(agents);
         self
     }
 
     /// Set default agent
-    pub fn with_default_agent(mut self, agent: Arc<UnifiedAgent>) -> Self {
+    pub fn with_default_agent(mut self, agent: Arc<dyn RoutableAgent>) -> Self {
         self.default_agent = Some(agent);
         self
     }
@@ -176,6 +333,30 @@ impl RouterAgentBuilder {
         self
     }
 
+    /// Set the aggregator for parallel answers
+    pub fn with_aggregator(mut self, aggregator: Box<dyn ResponseAggregator>) -> Self {
+        self.aggregator = Some(aggregator);
+        self
+    }
+
+    /// Set the minimum route score in parallel mode
+    pub fn with_min_route_score(mut self, min_score: f64) -> Self {
+        self.min_route_score = Some(min_score);
+        self
+    }
+
+    /// Set the maximum number of agents run in parallel
+    pub fn with_max_parallel_agents(mut self, max: usize) -> Self {
+        self.max_parallel_agents = Some(max);
+        self
+    }
+
+    /// Set the state routing decisions are recorded in
+    pub fn with_state(mut self, state: Arc<Mutex<AgentState>>) -> Self {
+        self.state = Some(state);
+        self
+    }
+
     /// Build the RouterAgent
     pub fn build(self) -> Result<RouterAgent, AgentError> {
         let router = self
@@ -193,6 +374,22 @@ impl RouterAgentBuilder {
             router_agent = router_agent.with_parallel_execution(true);
         }
 
+        if let Some(aggregator) = self.aggregator {
+            router_agent = router_agent.with_aggregator(aggregator);
+        }
+
+        if let Some(min_score) = self.min_route_score {
+            router_agent = router_agent.with_min_route_score(min_score);
+        }
+
+        if let Some(max) = self.max_parallel_agents {
+            router_agent = router_agent.with_max_parallel_agents(max);
+        }
+
+        if let Some(state) = self.state {
+            router_agent = router_agent.with_state(state);
+        }
+
         Ok(router_agent)
     }
 }
@@ -202,3 +399,66 @@ impl Default for RouterAgentBuilder {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::{create_agent_from_llm, multi_agent::test_llm::ScriptedLLM};
+
+    fn agent(answer: &'static str) -> Arc<UnifiedAgent> {
+        Arc::new(create_agent_from_llm(ScriptedLLM::fixed(answer), &[], None).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_parallel_execution_aggregates_answers() {
+        let state = Arc::new(Mutex::new(AgentState::new()));
+        let keyword_map = HashMap::from([
+            ("billing".to_string(), vec!["refund".to_string()]),
+            (
+                "support".to_string(),
+                vec!["crash".to_string(), "install".to_string()],
+            ),
+        ]);
+        let router_agent = RouterAgentBuilder::new()
+            .with_keyword_router(keyword_map)
+            .with_agent("billing".to_string(), agent("Refund issued."))
+            .with_agent("support".to_string(), agent("Update the app."))
+            .with_parallel_execution(true)
+            .with_state(Arc::clone(&state))
+            .build()
+            .unwrap();
+
+        let answer = router_agent
+            .invoke_messages(vec![Message::new_human_message(
+                "The app crashes and I want a refund",
+            )])
+            .await
+            .unwrap();
+        assert_eq!(
+            answer,
+            "[billing]\nRefund issued.\n\n[support]\nUpdate the app."
+        );
+        assert_eq!(
+            state.lock().await.routing_history,
+            vec![
+                (
+                    "The app crashes and I want a refund".to_string(),
+                    "billing".to_string()
+                ),
+                (
+                    "The app crashes and I want a refund".to_string(),
+                    "support".to_string()
+                ),
+            ]
+        );
+
+        let router_agent = router_agent.with_min_route_score(0.75);
+        let answer = router_agent
+            .invoke_messages(vec![Message::new_human_message(
+                "The app crashes and I want a refund",
+            )])
+            .await
+            .unwrap();
+        assert_eq!(answer, "Refund issued.");
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use crate::{
+    agent::{
+        multi_agent::router::{DefaultRouter, RouteMatch, Router},
+        AgentError,
+    },
+    language_models::llm::LLM,
+    semantic_router::{self, RouteLayer, RouteLayerBuilder},
+};
+
+/// Embedding-based router backed by a [`RouteLayer`]
+///
+/// Each route of the layer is named after an agent and holds example utterances for it.
+/// Inputs are routed to the agent with the most similar utterance, without any LLM call.
+/// When the best similarity is below the confidence threshold (or no route passes the route
+/// layer's own threshold), the fallback router decides instead.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let router = SemanticRouter::from_examples(
+///     RouteLayerBuilder::default().threshold(0.75),
+///     vec![
+///         ("billing".to_string(), vec!["I want a refund".to_string(), "Where is my invoice?".to_string()]),
+///         ("support".to_string(), vec!["The app crashes".to_string(), "I can't log in".to_string()]),
+///     ],
+/// )
+/// .await?
+/// .with_confidence_threshold(0.85)
+/// .with_llm_fallback(llm, agent_descriptions);
+///
+/// let router_agent = RouterAgentBuilder::new()
+///     .with_router(Box::new(router))
+///     .with_agents(agents)
+///     .build()?;
+/// ```
+pub struct SemanticRouter {
+    route_layer: RouteLayer,
+    /// Router used when the semantic match is not confident enough
+    fallback: Option<Box<dyn Router>>,
+    /// Minimum similarity to trust the semantic match
+    confidence_threshold: f64,
+}
+
+impl SemanticRouter {
+    /// Create a router from a route layer whose routes are named after agents
+    pub fn new(route_layer: RouteLayer) -> Self {
+        Self {
+            route_layer,
+            fallback: None,
+            confidence_threshold: 0.0,
+        }
+    }
+
+    /// Build the route layer from example utterances per agent
+    ///
+    /// `route_layer` provides the embedder, index and thresholds; one route is added per
+    /// `(agent, utterances)` pair.
+    pub async fn from_examples(
+        route_layer: RouteLayerBuilder,
+        examples: Vec<(String, Vec<String>)>,
+    ) -> Result<Self, AgentError> {
+        let route_layer = examples
+            .into_iter()
+            .fold(route_layer, |builder, (agent, utterances)| {
+                builder.add_route(semantic_router::Router::new(&agent, &utterances))
+            })
+            .build()
+            .await
+            .map_err(|e| AgentError::OtherError(e.to_string()))?;
+        Ok(Self::new(route_layer))
+    }
+
+    /// Use `fallback` when the best semantic match scores below the confidence threshold
+    pub fn with_fallback(mut self, fallback: Box<dyn Router>) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+
+    /// Fall back to LLM-based routing, see [`DefaultRouter::with_llm`]
+    pub fn with_llm_fallback(
+        self,
+        llm: Box<dyn LLM>,
+        agent_descriptions: Vec<(String, String)>,
+    ) -> Self {
+        self.with_fallback(Box::new(DefaultRouter::with_llm(llm, agent_descriptions)))
+    }
+
+    /// Set the minimum similarity for a semantic match to be used without the fallback
+    ///
+    /// Defaults to 0.0: the fallback only runs when no route passes the route layer's
+    /// threshold.
+    pub fn with_confidence_threshold(mut self, threshold: f64) -> Self {
+        self.confidence_threshold = threshold;
+        self
+    }
+
+    /// Every route passing the route layer's threshold, scored by its most similar utterance
+    async fn semantic_matches(&self, input: &str) -> Result<Vec<RouteMatch>, AgentError> {
+        let layer = &self.route_layer;
+        let query = layer
+            .embedder
+            .embed_query(input)
+            .await
+            .map_err(|e| AgentError::OtherError(e.to_string()))?;
+        let similar = layer
+            .index
+            .query(&query, layer.top_k)
+            .await
+            .map_err(|e| AgentError::OtherError(e.to_string()))?;
+
+        let mut best: HashMap<String, f64> = HashMap::new();
+        for (route, score) in similar {
+            if score >= layer.threshold {
+                let entry = best.entry(route).or_insert(score);
+                *entry = entry.max(score);
+            }
+        }
+        let mut matches: Vec<RouteMatch> = best
+            .into_iter()
+            .map(|(route, score)| RouteMatch::new(route, score.clamp(0.0, 1.0)))
+            .collect();
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.agent.cmp(&b.agent)));
+        Ok(matches)
+    }
+
+    /// Whether the best match is confident enough to skip the fallback
+    fn is_confident(&self, matches: &[RouteMatch]) -> bool {
+        matches
+            .first()
+            .is_some_and(|best| best.score >= self.confidence_threshold)
+    }
+}
+
+#[async_trait]
+impl Router for SemanticRouter {
+    async fn route(&self, input: &str) -> Result<Option<String>, AgentError> {
+        let matches = self.semantic_matches(input).await?;
+        if self.is_confident(&matches) {
+            log::debug!(
+                "Semantic route: {} ({:.3})",
+                matches[0].agent,
+                matches[0].score
+            );
+            return Ok(matches.into_iter().next().map(|best| best.agent));
+        }
+
+        match &self.fallback {
+            Some(fallback) => {
+                log::debug!("No confident semantic route, using fallback router");
+                fallback.route(input).await
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn route_ranked(&self, input: &str) -> Result<Vec<RouteMatch>, AgentError> {
+        let matches = self.semantic_matches(input).await?;
+        if self.is_confident(&matches) {
+            return Ok(matches);
+        }
+
+        match &self.fallback {
+            Some(fallback) => {
+                log::debug!("No confident semantic route, using fallback router");
+                fallback.route_ranked(input).await
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        agent::multi_agent::test_llm::ScriptedLLM,
+        embedding::{Embedder, EmbedderError},
+        semantic_router::MemoryIndex,
+    };
+
+    /// Embeds text as the presence of a few keywords.
+    struct KeywordEmbedder;
+
+    impl KeywordEmbedder {
+        fn embed(text: &str) -> Vec<f64> {
+            let text = text.to_lowercase();
+            ["refund", "invoice", "crash", "login"]
+                .iter()
+                .map(|word| if text.contains(word) { 1.0 } else { 0.0 })
+                .collect()
+        }
+    }
+
+    #[async_trait]
+    impl Embedder for KeywordEmbedder {
+        async fn embed_documents(
+            &self,
+            documents: &[String],
+        ) -> Result<Vec<Vec<f64>>, EmbedderError> {
+            Ok(documents.iter().map(|d| Self::embed(d)).collect())
+        }
+
+        async fn embed_query(&self, text: &str) -> Result<Vec<f64>, EmbedderError> {
+            Ok(Self::embed(text))
+        }
+    }
+
+    async fn router() -> SemanticRouter {
+        SemanticRouter::from_examples(
+            RouteLayerBuilder::new()
+                .embedder(KeywordEmbedder)
+                .llm(ScriptedLLM::fixed(""))
+                .index(MemoryIndex::new())
+                .threshold(0.5),
+            vec![
+                (
+                    "billing".to_string(),
+                    vec!["I want a refund".to_string(), "Send my invoice".to_string()],
+                ),
+                (
+                    "support".to_string(),
+                    vec!["The app crashes".to_string(), "Login fails".to_string()],
+                ),
+            ],
+        )
+        .await
+        .unwrap()
+        .with_llm_fallback(
+            Box::new(ScriptedLLM::fixed("support")),
+            vec![
+                ("billing".to_string(), "Payments".to_string()),
+                ("support".to_string(), "Technical help".to_string()),
+            ],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_semantic_route_skips_llm() {
+        let router = router().await;
+        assert_eq!(
+            router.route("Where is my refund?").await.unwrap(),
+            Some("billing".to_string())
+        );
+        assert_eq!(
+            router.route_ranked("Where is my refund?").await.unwrap(),
+            vec![RouteMatch::new("billing", 1.0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_low_confidence_falls_back_to_llm() {
+        let router = router().await;
+        // No keyword at all: nothing passes the route layer threshold
+        assert_eq!(
+            router.route("Hello there").await.unwrap(),
+            Some("support".to_string())
+        );
+
+        // Matches billing at ~0.71, below the confidence threshold
+        let router = router.with_confidence_threshold(0.9);
+        assert_eq!(
+            router.route("Refund the crash").await.unwrap(),
+            Some("support".to_string())
+        );
+    }
+}
@@ -73,7 +73,7 @@ impl Tool for SubagentTool {
     async fn run_with_runtime(
         &self,
         input: Value,
-        _runtime: &ToolRuntime,
+        runtime: &ToolRuntime,
     ) -> Result<ToolResult, Box<dyn Error>> {
         // Extract input string from the JSON value
         let input_str = if let Some(input_val) = input.get("input") {
@@ -91,12 +91,16 @@ impl Tool for SubagentTool {
         // Create a message from the input
         let message = Message::new_human_message(input_str);
 
-        // Invoke the subagent
-        let result = self
-            .agent
-            .invoke_messages(vec![message])
-            .await
-            .map_err(|e| format!("Subagent execution error: {}", e))?;
+        // Invoke the subagent, as a nested run when the parent run is streamed
+        let result = match runtime.event_emitter() {
+            Some(events) => {
+                self.agent
+                    .invoke_messages_with_events(vec![message], &events.child(&self.name))
+                    .await
+            }
+            None => self.agent.invoke_messages(vec![message]).await,
+        }
+        .map_err(|e| format!("Subagent execution error: {}", e))?;
 
         Ok(ToolResult::Text(result))
     }
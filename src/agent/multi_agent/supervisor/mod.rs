@@ -0,0 +1,3 @@
+mod supervisor_graph;
+
+pub use supervisor_graph::{create_supervisor, SupervisorBuilder, SUPERVISOR_NODE};
@@ -0,0 +1,262 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+
+use crate::{
+    agent::{
+        multi_agent::{
+            graph_agent::{handoff_messages, handoff_target, run_agent_turn, transfer_routes},
+            handoffs::{transfer_tool_name, TransferTool},
+            MultiAgentError,
+        },
+        Agent, ConversationalAgentBuilder, UnifiedAgent,
+    },
+    callbacks::new_run_id,
+    langgraph::{
+        messages_state_update, CheckpointerBox, CompiledGraph, LangGraphError, MessagesState, Node,
+        StateGraph, StateUpdate, StoreBox, END, START,
+    },
+    language_models::llm::LLM,
+    schemas::Message,
+    tools::Tool,
+};
+
+/// Name of the supervisor node in the graph
+pub const SUPERVISOR_NODE: &str = "supervisor";
+
+/// Create a supervisor graph builder with `llm` as the coordinator.
+///
+/// The supervisor is an agent whose only tools are `transfer_to_<agent>` handoffs. It keeps
+/// delegating to worker agents, reviewing their answers (each worker reports back to the
+/// supervisor), until it gives a final answer.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let graph = create_supervisor(llm)
+///     .with_agent("billing", "Invoices, payments and refunds", billing_agent)
+///     .with_agent("support", "Technical problems with the app", support_agent)
+///     .compile()?;
+///
+/// let state = graph
+///     .invoke(MessagesState::with_messages(vec![Message::new_human_message(
+///         "The app crashed while I was paying, was I charged twice?",
+///     )]))
+///     .await?;
+/// ```
+pub fn create_supervisor<L: Into<Box<dyn LLM>>>(llm: L) -> SupervisorBuilder {
+    SupervisorBuilder::new(llm)
+}
+
+/// Builder for a supervisor graph, see [`create_supervisor`]
+pub struct SupervisorBuilder {
+    /// LLM used by the supervisor agent
+    llm: Box<dyn LLM>,
+    /// Workers: (name, description, agent)
+    agents: Vec<(String, String, Arc<UnifiedAgent>)>,
+    /// Custom supervisor instructions
+    system_prompt: Option<String>,
+    /// Maximum iterations of each supervisor turn
+    max_iterations: i32,
+}
+
+impl SupervisorBuilder {
+    pub fn new<L: Into<Box<dyn LLM>>>(llm: L) -> Self {
+        Self {
+            llm: llm.into(),
+            agents: Vec::new(),
+            system_prompt: None,
+            max_iterations: 10,
+        }
+    }
+
+    /// Add a worker agent the supervisor can delegate to
+    pub fn with_agent<N: Into<String>, D: Into<String>>(
+        mut self,
+        name: N,
+        description: D,
+        agent: Arc<UnifiedAgent>,
+    ) -> Self {
+        self.agents.push((name.into(), description.into(), agent));
+        self
+    }
+
+    /// Replace the default supervisor instructions
+    pub fn with_system_prompt<S: Into<String>>(mut self, system_prompt: S) -> Self {
+        self.system_prompt = Some(system_prompt.into());
+        self
+    }
+
+    /// Set the maximum iterations of each supervisor turn
+    pub fn with_max_iterations(mut self, max_iterations: i32) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Build the graph without persistence
+    pub fn compile(self) -> Result<CompiledGraph<MessagesState>, MultiAgentError> {
+        self.compile_with_persistence(None, None)
+    }
+
+    /// Build the graph with a checkpointer and store
+    pub fn compile_with_persistence(
+        self,
+        checkpointer: Option<CheckpointerBox<MessagesState>>,
+        store: Option<StoreBox>,
+    ) -> Result<CompiledGraph<MessagesState>, MultiAgentError> {
+        if self.agents.is_empty() {
+            return Err(MultiAgentError::InvalidConfiguration(
+                "A supervisor needs at least one agent".to_string(),
+            ));
+        }
+
+        let routes = transfer_routes(self.agents.iter().map(|(name, _, _)| name))?;
+        let transfer_tools: Vec<Arc<dyn Tool>> = self
+            .agents
+            .iter()
+            .map(|(name, description, _)| {
+                Arc::new(TransferTool::new(name.clone(), description)) as Arc<dyn Tool>
+            })
+            .collect();
+        let system_prompt = self
+            .system_prompt
+            .unwrap_or_else(|| default_system_prompt(&self.agents));
+        let supervisor: Arc<dyn Agent> = Arc::new(
+            ConversationalAgentBuilder::new()
+                .tools(&transfer_tools)
+                .prefix(system_prompt)
+                .build(self.llm)?,
+        );
+
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph.add_node(
+            SUPERVISOR_NODE,
+            SupervisorNode {
+                agent: supervisor,
+                max_iterations: self.max_iterations,
+            },
+        )?;
+        graph.add_edge(START, SUPERVISOR_NODE);
+
+        let mut mapping = HashMap::from([(END.to_string(), END.to_string())]);
+        for (name, _, agent) in self.agents {
+            graph.add_node(name.clone(), WorkerNode { agent })?;
+            graph.add_edge(name.clone(), SUPERVISOR_NODE);
+            mapping.insert(name.clone(), name);
+        }
+
+        graph.add_conditional_edges(
+            SUPERVISOR_NODE,
+            move |state: &MessagesState| {
+                let next = handoff_target(&state.messages, &routes).unwrap_or(END.to_string());
+                async move { Ok(next) }
+            },
+            mapping,
+        );
+
+        Ok(graph.compile_with_persistence(checkpointer, store)?)
+    }
+}
+
+fn default_system_prompt(agents: &[(String, String, Arc<UnifiedAgent>)]) -> String {
+    let agent_list: Vec<String> = agents
+        .iter()
+        .map(|(name, description, _)| format!("- {}: {}", name, description))
+        .collect();
+    format!(
+        "You are a supervisor managing a team of agents:\n{}\n\n\
+        Delegate work to the agent best suited for it by calling its transfer tool, one agent \
+        at a time. Each agent reports back to you when it is done. Review its answer and \
+        delegate again if more work is needed. When the user's request is fully handled, \
+        reply with the final answer.",
+        agent_list.join("\n")
+    )
+}
+
+/// Runs the supervisor agent; a handoff is recorded as a transfer tool call.
+struct SupervisorNode {
+    agent: Arc<dyn Agent>,
+    max_iterations: i32,
+}
+
+#[async_trait]
+impl Node<MessagesState> for SupervisorNode {
+    async fn invoke(&self, state: &MessagesState) -> Result<StateUpdate, LangGraphError> {
+        let turn = run_agent_turn(
+            &self.agent,
+            SUPERVISOR_NODE,
+            &state.messages,
+            self.max_iterations,
+        )
+        .await?;
+        let messages = match turn.handoff {
+            Some(agent) => handoff_messages(
+                &transfer_tool_name(&agent),
+                &turn.output,
+                format!("call_{}", new_run_id()),
+            ),
+            None => vec![Message::new_ai_message(turn.output)],
+        };
+        Ok(messages_state_update(messages))
+    }
+}
+
+/// Runs a worker agent on the conversation and appends its answer.
+struct WorkerNode {
+    agent: Arc<UnifiedAgent>,
+}
+
+#[async_trait]
+impl Node<MessagesState> for WorkerNode {
+    async fn invoke(&self, state: &MessagesState) -> Result<StateUpdate, LangGraphError> {
+        let output = self.agent.invoke_messages(state.messages.clone()).await?;
+        Ok(messages_state_update(vec![Message::new_ai_message(output)]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        agent::{create_agent_from_llm, multi_agent::test_llm::ScriptedLLM},
+        schemas::MessageType,
+    };
+
+    #[tokio::test]
+    async fn test_supervisor_delegates_and_reviews() {
+        let billing = Arc::new(
+            create_agent_from_llm(ScriptedLLM::new(&["Refund issued."]), &[], None).unwrap(),
+        );
+        let supervisor = ScriptedLLM::new(&[
+            r#"{"action": "transfer_to_billing", "action_input": ""}"#,
+            r#"{"action": "Final Answer", "action_input": "Your refund was issued."}"#,
+        ]);
+
+        let graph = create_supervisor(supervisor)
+            .with_agent("billing", "Invoices and refunds", billing)
+            .compile()
+            .unwrap();
+        let state = graph
+            .invoke(MessagesState::with_messages(vec![
+                Message::new_human_message("I want a refund"),
+            ]))
+            .await
+            .unwrap();
+
+        let contents: Vec<&str> = state.messages.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(
+            contents,
+            vec![
+                "I want a refund",
+                "",
+                "Successfully transferred to billing",
+                "Refund issued.",
+                "Your refund was issued."
+            ]
+        );
+        assert!(matches!(
+            state.messages[2].message_type,
+            MessageType::ToolMessage
+        ));
+    }
+}
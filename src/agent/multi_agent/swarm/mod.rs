@@ -0,0 +1,3 @@
+mod swarm_graph;
+
+pub use swarm_graph::{create_swarm, SwarmAgent, SwarmBuilder};
@@ -0,0 +1,311 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+
+use crate::{
+    agent::{
+        multi_agent::{
+            graph_agent::{
+                active_agent, handoff_messages, handoff_target, run_agent_turn, transfer_routes,
+            },
+            handoffs::{transfer_tool_name, TransferTool},
+            MultiAgentError,
+        },
+        Agent, ConversationalAgentBuilder,
+    },
+    callbacks::new_run_id,
+    langgraph::{
+        messages_state_update, CheckpointerBox, CompiledGraph, LangGraphError, MessagesState, Node,
+        StateGraph, StateUpdate, StoreBox, END, START,
+    },
+    language_models::llm::LLM,
+    schemas::Message,
+    tools::Tool,
+};
+
+/// Create a swarm graph builder.
+///
+/// Every agent in a swarm can hand the conversation to its peers with `transfer_to_<agent>`
+/// tools. The agent that was handed control stays active for the following turns: each new
+/// input starts at the agent of the most recent handoff in the conversation (or the default
+/// agent), so with a checkpointer the active agent persists across turns of a thread.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let graph = create_swarm()
+///     .with_agent(
+///         SwarmAgent::new("triage", triage_llm)
+///             .with_description("Greets users and finds the right specialist"),
+///     )
+///     .with_agent(
+///         SwarmAgent::new("billing", billing_llm)
+///             .with_description("Invoices, payments and refunds")
+///             .with_tools(&[refund_tool]),
+///     )
+///     .with_default_agent("triage")
+///     .compile_with_persistence(Some(checkpointer), None)?;
+/// ```
+pub fn create_swarm() -> SwarmBuilder {
+    SwarmBuilder::new()
+}
+
+/// An agent taking part in a swarm
+pub struct SwarmAgent {
+    /// Name of the agent and of its graph node
+    name: String,
+    /// What the agent does, shown to peers in its transfer tool
+    description: String,
+    /// LLM driving the agent
+    llm: Box<dyn LLM>,
+    /// The agent's own tools
+    tools: Vec<Arc<dyn Tool>>,
+    /// Instructions for the agent
+    system_prompt: Option<String>,
+    /// Agents this agent may hand off to (`None` = every other agent)
+    handoffs: Option<Vec<String>>,
+}
+
+impl SwarmAgent {
+    pub fn new<N: Into<String>, L: Into<Box<dyn LLM>>>(name: N, llm: L) -> Self {
+        Self {
+            name: name.into(),
+            description: String::new(),
+            llm: llm.into(),
+            tools: Vec::new(),
+            system_prompt: None,
+            handoffs: None,
+        }
+    }
+
+    pub fn with_description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    pub fn with_tools(mut self, tools: &[Arc<dyn Tool>]) -> Self {
+        self.tools.extend_from_slice(tools);
+        self
+    }
+
+    pub fn with_system_prompt<S: Into<String>>(mut self, system_prompt: S) -> Self {
+        self.system_prompt = Some(system_prompt.into());
+        self
+    }
+
+    /// Restrict which agents this agent can hand off to
+    pub fn with_handoffs(mut self, agents: Vec<String>) -> Self {
+        self.handoffs = Some(agents);
+        self
+    }
+}
+
+/// Builder for a swarm graph, see [`create_swarm`]
+pub struct SwarmBuilder {
+    agents: Vec<SwarmAgent>,
+    /// Agent that handles the conversation before any handoff
+    default_agent: Option<String>,
+    /// Maximum iterations of each agent turn
+    max_iterations: i32,
+}
+
+impl SwarmBuilder {
+    pub fn new() -> Self {
+        Self {
+            agents: Vec::new(),
+            default_agent: None,
+            max_iterations: 10,
+        }
+    }
+
+    pub fn with_agent(mut self, agent: SwarmAgent) -> Self {
+        self.agents.push(agent);
+        self
+    }
+
+    /// Set the agent that starts the conversation (defaults to the first agent added)
+    pub fn with_default_agent<S: Into<String>>(mut self, name: S) -> Self {
+        self.default_agent = Some(name.into());
+        self
+    }
+
+    /// Set the maximum iterations of each agent turn
+    pub fn with_max_iterations(mut self, max_iterations: i32) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Build the graph without persistence
+    pub fn compile(self) -> Result<CompiledGraph<MessagesState>, MultiAgentError> {
+        self.compile_with_persistence(None, None)
+    }
+
+    /// Build the graph with a checkpointer and store
+    pub fn compile_with_persistence(
+        self,
+        checkpointer: Option<CheckpointerBox<MessagesState>>,
+        store: Option<StoreBox>,
+    ) -> Result<CompiledGraph<MessagesState>, MultiAgentError> {
+        let default_agent = match (&self.default_agent, self.agents.first()) {
+            (Some(name), _) => name.clone(),
+            (None, Some(agent)) => agent.name.clone(),
+            (None, None) => {
+                return Err(MultiAgentError::InvalidConfiguration(
+                    "A swarm needs at least one agent".to_string(),
+                ))
+            }
+        };
+
+        let descriptions: HashMap<String, String> = self
+            .agents
+            .iter()
+            .map(|agent| (agent.name.clone(), agent.description.clone()))
+            .collect();
+        if !descriptions.contains_key(&default_agent) {
+            return Err(MultiAgentError::AgentNotFound(default_agent));
+        }
+        let routes = Arc::new(transfer_routes(descriptions.keys())?);
+        let mapping: HashMap<String, String> = descriptions
+            .keys()
+            .map(|name| (name.clone(), name.clone()))
+            .collect();
+
+        let mut graph = StateGraph::<MessagesState>::new();
+        for agent in self.agents {
+            let targets = agent.handoffs.clone().unwrap_or_else(|| {
+                let mut names: Vec<String> = descriptions
+                    .keys()
+                    .filter(|name| **name != agent.name)
+                    .cloned()
+                    .collect();
+                names.sort();
+                names
+            });
+            let mut tools = agent.tools;
+            for target in targets {
+                let description = descriptions
+                    .get(&target)
+                    .ok_or_else(|| MultiAgentError::AgentNotFound(target.clone()))?;
+                tools.push(Arc::new(TransferTool::new(target, description)));
+            }
+
+            let mut builder = ConversationalAgentBuilder::new().tools(&tools);
+            if let Some(system_prompt) = agent.system_prompt {
+                builder = builder.prefix(system_prompt);
+            }
+            let node = SwarmNode {
+                name: agent.name.clone(),
+                agent: Arc::new(builder.build(agent.llm)?),
+                max_iterations: self.max_iterations,
+            };
+            graph.add_node(agent.name.clone(), node)?;
+
+            let routes = Arc::clone(&routes);
+            graph.add_conditional_edges(
+                agent.name,
+                move |state: &MessagesState| {
+                    let next = handoff_target(&state.messages, &routes).unwrap_or(END.to_string());
+                    async move { Ok(next) }
+                },
+                mapping
+                    .clone()
+                    .into_iter()
+                    .chain([(END.to_string(), END.to_string())])
+                    .collect(),
+            );
+        }
+
+        graph.add_conditional_edges(
+            START,
+            move |state: &MessagesState| {
+                let active = active_agent(&state.messages, &routes, &default_agent);
+                async move { Ok(active) }
+            },
+            mapping,
+        );
+
+        Ok(graph.compile_with_persistence(checkpointer, store)?)
+    }
+}
+
+impl Default for SwarmBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Runs one swarm agent; a handoff is recorded as a transfer tool call.
+struct SwarmNode {
+    name: String,
+    agent: Arc<dyn Agent>,
+    max_iterations: i32,
+}
+
+#[async_trait]
+impl Node<MessagesState> for SwarmNode {
+    async fn invoke(&self, state: &MessagesState) -> Result<StateUpdate, LangGraphError> {
+        let turn = run_agent_turn(
+            &self.agent,
+            &self.name,
+            &state.messages,
+            self.max_iterations,
+        )
+        .await?;
+        let messages = match turn.handoff {
+            Some(agent) => handoff_messages(
+                &transfer_tool_name(&agent),
+                &turn.output,
+                format!("call_{}", new_run_id()),
+            ),
+            None => vec![Message::new_ai_message(turn.output)],
+        };
+        Ok(messages_state_update(messages))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::multi_agent::test_llm::ScriptedLLM;
+
+    #[tokio::test]
+    async fn test_swarm_keeps_active_agent_across_turns() {
+        let graph = create_swarm()
+            .with_agent(
+                SwarmAgent::new(
+                    "triage",
+                    ScriptedLLM::new(&[
+                        r#"{"action": "transfer_to_billing", "action_input": ""}"#,
+                        "Triage here.",
+                    ]),
+                )
+                .with_description("Finds the right specialist"),
+            )
+            .with_agent(
+                SwarmAgent::new("billing", ScriptedLLM::new(&["Billing here."]))
+                    .with_description("Invoices and refunds"),
+            )
+            .compile()
+            .unwrap();
+
+        let mut state = graph
+            .invoke(MessagesState::with_messages(vec![
+                Message::new_human_message("I want a refund"),
+            ]))
+            .await
+            .unwrap();
+        assert_eq!(state.messages.len(), 4);
+        assert_eq!(
+            state.messages[2].content,
+            "Successfully transferred to billing"
+        );
+        assert_eq!(state.messages[3].content, "Billing here.");
+
+        state
+            .messages
+            .push(Message::new_human_message("And for last month?"));
+        let state = graph.invoke(state).await.unwrap();
+        assert_eq!(state.messages.len(), 6);
+        assert_eq!(state.messages[5].content, "Billing here.");
+    }
+}
@@ -0,0 +1,86 @@
+//! Scripted LLM shared by the multi-agent tests.
+
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use futures::Stream;
+
+use crate::{
+    language_models::{llm::LLM, GenerateResult, LLMError},
+    schemas::{Message, StreamData},
+};
+
+/// Replies with the scripted responses in order, repeating the last one.
+#[derive(Clone)]
+pub(crate) struct ScriptedLLM(Arc<Mutex<VecDeque<String>>>);
+
+impl ScriptedLLM {
+    pub(crate) fn new(responses: &[&str]) -> Self {
+        Self(Arc::new(Mutex::new(
+            responses.iter().map(|r| r.to_string()).collect(),
+        )))
+    }
+
+    /// Always answers with `response`
+    pub(crate) fn fixed(response: &str) -> Self {
+        Self::new(&[response])
+    }
+
+    fn next_response(&self) -> String {
+        let mut responses = self.0.lock().unwrap();
+        if responses.len() > 1 {
+            responses.pop_front().unwrap()
+        } else {
+            responses.front().cloned().unwrap_or_default()
+        }
+    }
+}
+
+#[async_trait]
+impl LLM for ScriptedLLM {
+    async fn generate(&self, _messages: &[Message]) -> Result<GenerateResult, LLMError> {
+        Ok(GenerateResult {
+            generation: self.next_response(),
+            tokens: None,
+        })
+    }
+
+    async fn stream(
+        &self,
+        _messages: &[Message],
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamData, LLMError>> + Send>>, LLMError> {
+        // One chunk per word, whitespace kept, so streaming consumers see several tokens
+        let response = self.next_response();
+        let chunks: Vec<Result<StreamData, LLMError>> = response
+            .split_inclusive(' ')
+            .map(|chunk| Ok(StreamData::new(serde_json::Value::Null, None, chunk)))
+            .collect();
+        Ok(Box::pin(futures::stream::iter(chunks)))
+    }
+}
+
+mod tests {
+    use futures::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scripted_llm_streams_next_response() {
+        let llm = ScriptedLLM::new(&["first answer", "second"]);
+
+        let chunks: Vec<String> = llm
+            .stream(&[])
+            .await
+            .unwrap()
+            .map(|chunk| chunk.unwrap().content)
+            .collect()
+            .await;
+        assert_eq!(chunks, vec!["first ", "answer"]);
+        assert_eq!(llm.invoke("hi").await.unwrap(), "second");
+        assert_eq!(llm.invoke("hi").await.unwrap(), "second");
+    }
+}
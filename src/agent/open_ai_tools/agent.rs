@@ -59,8 +59,11 @@ impl OpenAiToolAgent {
             }
 
             // Add a tool message for each observation. Observation is the ouput of the tool call.
-            // tool_id is the id of the tool.
-            thoughts.push(Message::new_tool_message(observation, tool_id));
+            // tool_id is the id of the tool. Images and files the tool returned are attached.
+            thoughts.push(
+                Message::new_tool_message(observation, tool_id)
+                    .with_content_parts(action.attachments.clone()),
+            );
         }
 
         Ok(thoughts)
@@ -88,11 +91,11 @@ impl Agent for OpenAiToolAgent {
                         tools: output.clone(), //We send the complete tools ouput, we will need it in
                                                //the open ai call
                     };
-                    actions.push(AgentAction {
-                        tool: tool.function.name.clone(),
-                        tool_input: tool.function.arguments.clone(),
-                        log: serde_json::to_string(&log)?, //We send this as string to minimise changes
-                    });
+                    actions.push(AgentAction::new(
+                        tool.function.name.clone(),
+                        tool.function.arguments.clone(),
+                        serde_json::to_string(&log)?, //We send this as string to minimise changes
+                    ));
                 }
                 return Ok(AgentEvent::Action(actions));
             }
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use crate::tools::{SQLDatabase, SQLDatabaseToolkit, SqlGuardrails};
+
+use super::{create_agent, detect_and_create_llm, AgentError, Middleware, UnifiedAgent};
+
+const SQL_AGENT_PROMPT: &str = "You are an agent designed to interact with a SQL database.
+Given an input question, create a syntactically correct {dialect} query to run, then look at the results of the query and return the answer.
+Unless the user specifies a specific number of examples they wish to obtain, always limit your query to at most {top_k} results.
+You can order the results by a relevant column to return the most interesting examples in the database.
+Never query for all the columns from a specific table, only ask for the relevant columns given the question.
+
+Always start by listing the tables with sql_db_list_tables, then look at the schema of the most relevant tables with sql_db_schema.
+Only use table and column names you have seen in a schema.
+Double check your query with sql_db_query_checker before running it with sql_db_query.
+If a query fails, read the error, rewrite the query and try again.
+
+Do not make any data modification statements (INSERT, UPDATE, DELETE, DROP etc.).";
+
+/// Create an agent that answers questions about a SQL database.
+///
+/// The agent gets the [`SQLDatabaseToolkit`] tools and explores the schema table by table,
+/// so it works on databases whose schema does not fit in a prompt. The query checker uses
+/// the same model. Queries go through `guardrails`: read-only by default, at most
+/// `max_rows` rows, only allowed statement types and a LIMIT added when missing.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let engine = SQLiteEngine::read_only("sqlite://analytics.db").await?;
+/// let database = SQLDatabaseBuilder::new(engine).build().await?;
+/// let agent = create_sql_agent(
+///     "gpt-4o-mini",
+///     Arc::new(database),
+///     SqlGuardrails::new().with_max_rows(20),
+///     None,
+/// )?;
+/// let answer = agent
+///     .invoke_messages(vec![Message::new_human_message("Which country has the most customers?")])
+///     .await?;
+/// ```
+pub fn create_sql_agent(
+    model: &str,
+    database: Arc<SQLDatabase>,
+    guardrails: SqlGuardrails,
+    middleware: Option<Vec<Arc<dyn Middleware>>>,
+) -> Result<UnifiedAgent, AgentError> {
+    let prompt = SQL_AGENT_PROMPT
+        .replace("{dialect}", &database.dialect().to_string())
+        .replace("{top_k}", &guardrails.max_rows.to_string());
+    let checker = Arc::from(detect_and_create_llm(model)?);
+    let tools = SQLDatabaseToolkit::new(database)
+        .with_llm(checker)
+        .with_guardrails(guardrails)
+        .tools();
+
+    create_agent(model, &tools, Some(&prompt), middleware)
+}
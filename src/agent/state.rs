@@ -117,6 +117,37 @@ pub enum Command {
     ClearMessages,
     /// Clear all state (messages and custom fields)
     ClearState,
+    /// Hand control to another agent: sets the active agent and ends the
+    /// current agent run with the tool's output
+    Handoff { agent: String },
+}
+
+impl Command {
+    /// Apply this command to the agent state.
+    pub fn apply(self, state: &mut AgentState) {
+        match self {
+            Command::UpdateState { fields } => {
+                for (key, value) in fields {
+                    state.set_field(key, value);
+                }
+            }
+            Command::RemoveMessages { ids } => {
+                state
+                    .messages
+                    .retain(|msg| !msg.id.as_ref().is_some_and(|id| ids.contains(id)));
+            }
+            Command::ClearMessages => {
+                state.messages.clear();
+            }
+            Command::ClearState => {
+                state.messages.clear();
+                state.custom_fields.clear();
+            }
+            Command::Handoff { agent } => {
+                state.set_active_agent(agent);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,7 +1,9 @@
+use std::pin::Pin;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use async_trait::async_trait;
+use futures::Stream;
 use serde_json::json;
 
 use crate::{
@@ -16,7 +18,11 @@ use crate::{
 };
 
 use super::{
-    agent::Agent, checkpoint::AgentCheckpointer, executor::AgentExecutor, state::AgentState,
+    agent::Agent,
+    checkpoint::AgentCheckpointer,
+    events::{AgentEventEmitter, AgentStreamEvent},
+    executor::AgentExecutor,
+    state::AgentState,
     AgentError, AgentInvokeResult,
 };
 use crate::agent::runtime::{Runtime, TypedContext};
@@ -45,6 +51,17 @@ impl Agent for AgentBox {
         self.0.plan(intermediate_steps, inputs).await
     }
 
+    async fn plan_streaming(
+        &self,
+        intermediate_steps: &[(AgentAction, String)],
+        inputs: PromptArgs,
+        on_token: &(dyn for<'t> Fn(&'t str) + Send + Sync),
+    ) -> Result<AgentEvent, AgentError> {
+        self.0
+            .plan_streaming(intermediate_steps, inputs, on_token)
+            .await
+    }
+
     fn get_tools(&self) -> Vec<Arc<dyn crate::tools::Tool>> {
         self.0.get_tools()
     }
@@ -84,6 +101,14 @@ impl UnifiedAgent {
         self
     }
 
+    /// Limit how many tool calls from a single model turn run concurrently.
+    pub fn with_max_tool_concurrency(mut self, max_tool_concurrency: usize) -> Self {
+        self.executor = self
+            .executor
+            .with_max_tool_concurrency(max_tool_concurrency);
+        self
+    }
+
     /// Set the context for the agent.
     pub fn with_context(mut self, context: Arc<dyn ToolContext>) -> Self {
         self.executor = self.executor.with_context(context);
@@ -158,12 +183,48 @@ impl UnifiedAgent {
                 }
             }
             AgentInput::Resume(decisions_value) => {
-                let gen = self.executor.call_resume(config, decisions_value).await?;
-                Ok(AgentInvokeResult::Complete(gen.generation))
+                match self.executor.call_resume(config, decisions_value).await {
+                    Ok(gen) => Ok(AgentInvokeResult::Complete(gen.generation)),
+                    Err(ChainError::Interrupt(payload)) => Ok(AgentInvokeResult::Interrupt {
+                        interrupt_value: payload,
+                    }),
+                    Err(e) => Err(e),
+                }
+            }
+        }
+    }
+
+    /// Stream the events of an agent run as it happens (see [AgentExecutor::stream_events]).
+    /// Use [AgentInput::Resume] to stream a run resumed after an interrupt.
+    pub fn stream_events<'a>(
+        &'a self,
+        input: AgentInput,
+        config: &'a RunnableConfig,
+    ) -> Pin<Box<dyn Stream<Item = AgentStreamEvent> + Send + 'a>> {
+        match input {
+            AgentInput::State(prompt_args) => {
+                self.executor.stream_events(prompt_args, Some(config))
+            }
+            AgentInput::Resume(decisions_value) => {
+                self.executor.stream_events_resume(config, decisions_value)
             }
         }
     }
 
+    /// Invoke the agent with messages as a nested run reporting to `events`.
+    pub(crate) async fn invoke_messages_with_events(
+        &self,
+        messages: Vec<Message>,
+        events: &AgentEventEmitter,
+    ) -> Result<String, ChainError> {
+        let input_variables = prompt_args_from_messages(messages)?;
+        let result = self
+            .executor
+            .run_loop_with_events(input_variables, None, None, Some(events))
+            .await?;
+        Ok(result.generation)
+    }
+
     /// Invoke the agent with messages.
     ///
     /// This method accepts either:
@@ -260,7 +321,7 @@ impl Chain for UnifiedAgent {
 }
 
 /// Convert messages to prompt args format.
-fn prompt_args_from_messages(messages: Vec<Message>) -> Result<PromptArgs, ChainError> {
+pub(crate) fn prompt_args_from_messages(messages: Vec<Message>) -> Result<PromptArgs, ChainError> {
     // Extract the last human message as input
     let input = messages
         .iter()
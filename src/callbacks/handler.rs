@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    language_models::GenerateResult,
+    schemas::{Document, Message},
+};
+
+/// Kind of run a callback refers to.
+///
+/// Agents, LangGraph graphs and graph nodes are reported as `Chain` runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunType {
+    Llm,
+    Chain,
+    Tool,
+    Retriever,
+}
+
+impl RunType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunType::Llm => "llm",
+            RunType::Chain => "chain",
+            RunType::Tool => "tool",
+            RunType::Retriever => "retriever",
+        }
+    }
+}
+
+/// Identity of a run, passed to every [`CallbackHandler`] method.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RunInfo {
+    /// Unique id of this run
+    pub run_id: String,
+    /// Id of the run this one is nested in; `None` for a root run
+    pub parent_run_id: Option<String>,
+    pub run_type: RunType,
+    /// Chain, model, tool or node name
+    pub name: String,
+    /// Tags from the [`CallbackManager`](super::CallbackManager)
+    pub tags: Vec<String>,
+    /// Metadata from the [`CallbackManager`](super::CallbackManager); LLM runs also carry
+    /// `ls_provider` and `ls_model_name`
+    pub metadata: HashMap<String, Value>,
+}
+
+/// Hooks called as LLMs, chains, tools and retrievers run.
+///
+/// Every method has an empty default, so handlers only implement what they need.
+/// Handlers are called inline on the task doing the work: keep them fast and hand
+/// anything slow (network, heavy I/O) off to another task.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// struct ToolLogger;
+///
+/// impl CallbackHandler for ToolLogger {
+///     fn on_tool_error(&self, run: &RunInfo, error: &str) {
+///         log::warn!("tool {} failed: {}", run.name, error);
+///     }
+/// }
+///
+/// let callbacks = CallbackManager::new().with_handler(Arc::new(ToolLogger));
+/// ```
+pub trait CallbackHandler: Send + Sync {
+    fn on_llm_start(&self, _run: &RunInfo, _messages: &[Message]) {}
+
+    /// A streamed chunk of model output
+    fn on_llm_new_token(&self, _run: &RunInfo, _token: &str) {}
+
+    fn on_llm_end(&self, _run: &RunInfo, _result: &GenerateResult) {}
+
+    fn on_llm_error(&self, _run: &RunInfo, _error: &str) {}
+
+    fn on_chain_start(&self, _run: &RunInfo, _inputs: &Value) {}
+
+    fn on_chain_end(&self, _run: &RunInfo, _outputs: &Value) {}
+
+    fn on_chain_error(&self, _run: &RunInfo, _error: &str) {}
+
+    fn on_tool_start(&self, _run: &RunInfo, _input: &str) {}
+
+    fn on_tool_end(&self, _run: &RunInfo, _output: &str) {}
+
+    fn on_tool_error(&self, _run: &RunInfo, _error: &str) {}
+
+    fn on_retriever_start(&self, _run: &RunInfo, _query: &str) {}
+
+    fn on_retriever_end(&self, _run: &RunInfo, _documents: &[Document]) {}
+
+    fn on_retriever_error(&self, _run: &RunInfo, _error: &str) {}
+}
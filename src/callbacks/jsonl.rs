@@ -0,0 +1,142 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    sync::Mutex,
+};
+
+use chrono::Utc;
+use serde_json::{json, Value};
+
+use crate::{
+    language_models::GenerateResult,
+    schemas::{Document, Message},
+};
+
+use super::{CallbackHandler, RunInfo};
+
+/// Appends one JSON object per callback to a file (JSON Lines).
+///
+/// Each line has `event` (e.g. `tool_end`), `timestamp` (RFC 3339), `run` (the [`RunInfo`])
+/// and `data` (inputs, outputs or error). Write failures are logged and otherwise ignored so
+/// tracing never breaks a run.
+pub struct JsonlCallbackHandler {
+    file: Mutex<File>,
+}
+
+impl JsonlCallbackHandler {
+    /// Open `path` for appending, creating it if needed.
+    pub fn new<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+
+    fn write(&self, event: &str, run: &RunInfo, data: Value) {
+        let line = json!({
+            "event": event,
+            "timestamp": Utc::now().to_rfc3339(),
+            "run": run,
+            "data": data,
+        });
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = writeln!(file, "{}", line) {
+            log::warn!("Failed to write callback event: {}", e);
+        }
+    }
+}
+
+impl CallbackHandler for JsonlCallbackHandler {
+    fn on_llm_start(&self, run: &RunInfo, messages: &[Message]) {
+        self.write("llm_start", run, json!({ "messages": messages }));
+    }
+
+    fn on_llm_new_token(&self, run: &RunInfo, token: &str) {
+        self.write("llm_new_token", run, json!({ "token": token }));
+    }
+
+    fn on_llm_end(&self, run: &RunInfo, result: &GenerateResult) {
+        self.write("llm_end", run, json!(result));
+    }
+
+    fn on_llm_error(&self, run: &RunInfo, error: &str) {
+        self.write("llm_error", run, json!({ "error": error }));
+    }
+
+    fn on_chain_start(&self, run: &RunInfo, inputs: &Value) {
+        self.write("chain_start", run, json!({ "inputs": inputs }));
+    }
+
+    fn on_chain_end(&self, run: &RunInfo, outputs: &Value) {
+        self.write("chain_end", run, json!({ "outputs": outputs }));
+    }
+
+    fn on_chain_error(&self, run: &RunInfo, error: &str) {
+        self.write("chain_error", run, json!({ "error": error }));
+    }
+
+    fn on_tool_start(&self, run: &RunInfo, input: &str) {
+        self.write("tool_start", run, json!({ "input": input }));
+    }
+
+    fn on_tool_end(&self, run: &RunInfo, output: &str) {
+        self.write("tool_end", run, json!({ "output": output }));
+    }
+
+    fn on_tool_error(&self, run: &RunInfo, error: &str) {
+        self.write("tool_error", run, json!({ "error": error }));
+    }
+
+    fn on_retriever_start(&self, run: &RunInfo, query: &str) {
+        self.write("retriever_start", run, json!({ "query": query }));
+    }
+
+    fn on_retriever_end(&self, run: &RunInfo, documents: &[Document]) {
+        self.write("retriever_end", run, json!({ "documents": documents }));
+    }
+
+    fn on_retriever_error(&self, run: &RunInfo, error: &str) {
+        self.write("retriever_error", run, json!({ "error": error }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::callbacks::RunType;
+
+    #[test]
+    fn test_jsonl_handler_appends_events() {
+        let path = std::env::temp_dir().join(format!(
+            "callbacks_{}.jsonl",
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let handler = JsonlCallbackHandler::new(&path).unwrap();
+        let run = RunInfo {
+            run_id: "run-1".to_string(),
+            parent_run_id: None,
+            run_type: RunType::Tool,
+            name: "search".to_string(),
+            tags: vec![],
+            metadata: HashMap::new(),
+        };
+
+        handler.on_tool_start(&run, "rust");
+        handler.on_tool_end(&run, "found");
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["event"], "tool_start");
+        assert_eq!(lines[0]["run"]["run_type"], "tool");
+        assert_eq!(lines[1]["data"]["output"], "found");
+
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,418 @@
+use std::{collections::HashMap, fmt, future::Future, sync::Arc};
+
+use serde_json::Value;
+
+use super::{CallbackHandler, RunInfo, RunType};
+
+tokio::task_local! {
+    static CURRENT: RunScope;
+}
+
+/// The callback manager in effect and the run new runs are nested in.
+#[derive(Clone)]
+struct RunScope {
+    manager: CallbackManager,
+    parent_run_id: Option<String>,
+}
+
+/// A set of [`CallbackHandler`]s plus the tags and metadata attached to every run.
+///
+/// Pass it through [`InvocationConfig::with_callbacks`](crate::language_models::InvocationConfig::with_callbacks)
+/// or [`RunnableConfig::with_callbacks`](crate::langgraph::RunnableConfig::with_callbacks), or wrap
+/// any call with [`CallbackManager::scope`]. Everything that runs inside (nested chains, agents,
+/// tools, retrievers, graph nodes, LLM calls) reports to the same handlers, and each run records
+/// the run it was started from as its `parent_run_id`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let usage = Arc::new(UsageCollector::new());
+/// let callbacks = CallbackManager::new()
+///     .with_handler(Arc::new(StdOutCallbackHandler::new()))
+///     .with_handler(usage.clone());
+///
+/// let answer = callbacks.scope(chain.invoke(prompt_args! {"input" => "hi"})).await?;
+/// println!("{:?}", usage.usage());
+/// ```
+#[derive(Clone, Default)]
+pub struct CallbackManager {
+    handlers: Vec<Arc<dyn CallbackHandler>>,
+    tags: Vec<String>,
+    metadata: HashMap<String, Value>,
+}
+
+impl CallbackManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_handler(mut self, handler: Arc<dyn CallbackHandler>) -> Self {
+        self.handlers.push(handler);
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags.extend(tags);
+        self
+    }
+
+    pub fn with_metadata(mut self, metadata: HashMap<String, Value>) -> Self {
+        self.metadata.extend(metadata);
+        self
+    }
+
+    pub fn handlers(&self) -> &[Arc<dyn CallbackHandler>] {
+        &self.handlers
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /// Run `fut` with this manager receiving callbacks for every run started inside it.
+    ///
+    /// The manager replaces any manager already in scope for the duration of `fut`. Runs
+    /// started inside are nested under the run that is current when `scope` is called, so
+    /// scoping a manager inside a traced tool keeps the tree intact.
+    pub async fn scope<F: Future>(&self, fut: F) -> F::Output {
+        let parent_run_id = CURRENT
+            .try_with(|scope| scope.parent_run_id.clone())
+            .ok()
+            .flatten();
+        CURRENT
+            .scope(
+                RunScope {
+                    manager: self.clone(),
+                    parent_run_id,
+                },
+                fut,
+            )
+            .await
+    }
+}
+
+impl fmt::Debug for CallbackManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackManager")
+            .field("handlers", &self.handlers.len())
+            .field("tags", &self.tags)
+            .field("metadata", &self.metadata)
+            .finish()
+    }
+}
+
+/// Scope `callbacks` around `fut` when given, otherwise run `fut` as is.
+pub(crate) async fn scope_callbacks<F: Future>(
+    callbacks: Option<&CallbackManager>,
+    fut: F,
+) -> F::Output {
+    match callbacks {
+        Some(callbacks) => callbacks.scope(fut).await,
+        None => fut.await,
+    }
+}
+
+/// A run started on the callback manager in scope; inert when no manager is in scope.
+pub(crate) struct CallbackRun {
+    active: Option<(CallbackManager, RunInfo)>,
+}
+
+impl CallbackRun {
+    pub(crate) fn start(run_type: RunType, name: &str) -> Self {
+        Self::start_with_metadata(run_type, name, HashMap::new())
+    }
+
+    pub(crate) fn start_with_metadata(
+        run_type: RunType,
+        name: &str,
+        metadata: HashMap<String, Value>,
+    ) -> Self {
+        let active = CURRENT
+            .try_with(|scope| {
+                if scope.manager.is_empty() {
+                    return None;
+                }
+                let mut run_metadata = scope.manager.metadata.clone();
+                run_metadata.extend(metadata);
+                Some((
+                    scope.manager.clone(),
+                    RunInfo {
+                        run_id: new_run_id(),
+                        parent_run_id: scope.parent_run_id.clone(),
+                        run_type,
+                        name: name.to_string(),
+                        tags: scope.manager.tags.clone(),
+                        metadata: run_metadata,
+                    },
+                ))
+            })
+            .ok()
+            .flatten();
+        Self { active }
+    }
+
+    /// Whether any handler will see this run (skip building payloads otherwise).
+    pub(crate) fn is_active(&self) -> bool {
+        self.active.is_some()
+    }
+
+    pub(crate) fn emit(&self, f: impl Fn(&dyn CallbackHandler, &RunInfo)) {
+        if let Some((manager, info)) = &self.active {
+            for handler in &manager.handlers {
+                f(handler.as_ref(), info);
+            }
+        }
+    }
+
+    /// Run `fut` with this run as the parent of runs started inside it.
+    pub(crate) async fn scope<F: Future>(&self, fut: F) -> F::Output {
+        match &self.active {
+            Some((manager, info)) => {
+                CURRENT
+                    .scope(
+                        RunScope {
+                            manager: manager.clone(),
+                            parent_run_id: Some(info.run_id.clone()),
+                        },
+                        fut,
+                    )
+                    .await
+            }
+            None => fut.await,
+        }
+    }
+}
+
+/// New unique run id (UUID v4 with the `uuid` feature).
+pub(crate) fn new_run_id() -> String {
+    #[cfg(feature = "uuid")]
+    {
+        uuid::Uuid::new_v4().to_string()
+    }
+    #[cfg(not(feature = "uuid"))]
+    {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        // UUID-shaped (version 4 / RFC 4122 variant bits set) so run ids are accepted by
+        // collectors that validate them; uniqueness comes from the timestamp and counter.
+        static RUN_ID_COUNTER: AtomicU64 = AtomicU64::new(0);
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as u64;
+        let count = RUN_ID_COUNTER.fetch_add(1, Ordering::SeqCst) & 0x3FFF_FFFF_FFFF_FFFF;
+        let bits = ((nanos as u128) << 64) | count as u128;
+        let bits = (bits & !(0xF << 76)) | (0x4 << 76);
+        let bits = bits | (0x2 << 62);
+        format!(
+            "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+            bits >> 96,
+            (bits >> 80) & 0xFFFF,
+            (bits >> 64) & 0xFFFF,
+            (bits >> 48) & 0xFFFF,
+            bits & 0xFFFF_FFFF_FFFF
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, sync::Mutex};
+
+    use async_trait::async_trait;
+    use futures::Stream;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        language_models::{llm::LLM, GenerateResult, LLMError, TokenUsage},
+        schemas::{Message, StreamData},
+        telemetry,
+    };
+
+    /// Records `(event, run)` pairs.
+    #[derive(Default)]
+    struct Recorder {
+        events: Mutex<Vec<(String, RunInfo)>>,
+    }
+
+    impl Recorder {
+        fn push(&self, event: &str, run: &RunInfo) {
+            self.events
+                .lock()
+                .unwrap()
+                .push((event.to_string(), run.clone()));
+        }
+
+        fn events(&self) -> Vec<(String, RunInfo)> {
+            self.events.lock().unwrap().clone()
+        }
+    }
+
+    impl CallbackHandler for Recorder {
+        fn on_llm_start(&self, run: &RunInfo, _messages: &[Message]) {
+            self.push("llm_start", run);
+        }
+        fn on_llm_end(&self, run: &RunInfo, _result: &GenerateResult) {
+            self.push("llm_end", run);
+        }
+        fn on_chain_start(&self, run: &RunInfo, _inputs: &Value) {
+            self.push("chain_start", run);
+        }
+        fn on_chain_end(&self, run: &RunInfo, _outputs: &Value) {
+            self.push("chain_end", run);
+        }
+        fn on_tool_start(&self, run: &RunInfo, _input: &str) {
+            self.push("tool_start", run);
+        }
+        fn on_tool_error(&self, run: &RunInfo, _error: &str) {
+            self.push("tool_error", run);
+        }
+    }
+
+    #[derive(Clone)]
+    struct FakeLLM;
+
+    #[async_trait]
+    impl LLM for FakeLLM {
+        fn model_name(&self) -> Option<&str> {
+            Some("fake-model")
+        }
+
+        async fn generate(&self, _messages: &[Message]) -> Result<GenerateResult, LLMError> {
+            Ok(GenerateResult {
+                generation: "ok".to_string(),
+                tokens: Some(TokenUsage::new(4, 2)),
+            })
+        }
+
+        async fn stream(
+            &self,
+            _messages: &[Message],
+        ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamData, LLMError>> + Send>>, LLMError>
+        {
+            Ok(Box::pin(futures::stream::once(async {
+                Ok(StreamData::new(
+                    serde_json::Value::Null,
+                    Some(TokenUsage::new(4, 2)),
+                    "ok",
+                ))
+            })))
+        }
+    }
+
+    /// LLM that only overrides `invoke`
+    #[derive(Clone)]
+    struct InvokeOnlyLLM;
+
+    #[async_trait]
+    impl LLM for InvokeOnlyLLM {
+        async fn generate(&self, _messages: &[Message]) -> Result<GenerateResult, LLMError> {
+            Ok(GenerateResult {
+                generation: "from generate".to_string(),
+                tokens: None,
+            })
+        }
+
+        async fn invoke(&self, prompt: &str) -> Result<String, LLMError> {
+            Ok(format!("invoked with {prompt}"))
+        }
+
+        async fn stream(
+            &self,
+            _messages: &[Message],
+        ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamData, LLMError>> + Send>>, LLMError>
+        {
+            Ok(Box::pin(futures::stream::once(async {
+                Ok(StreamData::new(
+                    serde_json::Value::Null,
+                    None,
+                    "from stream",
+                ))
+            })))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_nested_runs_track_parent_run_ids() {
+        let recorder = Arc::new(Recorder::default());
+        let usage = Arc::new(crate::callbacks::UsageCollector::new());
+        let callbacks = CallbackManager::new()
+            .with_handler(recorder.clone())
+            .with_handler(usage.clone())
+            .with_tags(vec!["test".to_string()]);
+
+        let result: Result<String, String> = callbacks
+            .scope(telemetry::chain("outer", &json!({"input": "hi"}), async {
+                let _ = telemetry::tool("search", "call_0", "{}", async {
+                    Err::<String, _>("no results".to_string())
+                })
+                .await;
+                let output = telemetry::generate(&FakeLLM, &[Message::new_human_message("hi")])
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(output.generation)
+            }))
+            .await;
+        assert_eq!(result.unwrap(), "ok");
+
+        let events = recorder.events();
+        let names: Vec<&str> = events.iter().map(|(event, _)| event.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "chain_start",
+                "tool_start",
+                "tool_error",
+                "llm_start",
+                "llm_end",
+                "chain_end"
+            ]
+        );
+
+        let chain = &events[0].1;
+        assert!(chain.parent_run_id.is_none());
+        assert_eq!(chain.tags, vec!["test".to_string()]);
+        for (_, run) in &events[1..5] {
+            assert_eq!(run.parent_run_id.as_deref(), Some(chain.run_id.as_str()));
+        }
+        assert_eq!(events[3].1.name, "fake-model");
+        assert_eq!(events[3].1.metadata["ls_provider"], json!("unknown"));
+
+        let usage = usage.usage();
+        assert_eq!(usage.get_total_usage("fake-model").unwrap().total_tokens, 6);
+    }
+
+    #[tokio::test]
+    async fn test_no_callbacks_outside_scope() {
+        let recorder = Arc::new(Recorder::default());
+        let _callbacks = CallbackManager::new().with_handler(recorder.clone());
+
+        telemetry::generate(&FakeLLM, &[Message::new_human_message("hi")])
+            .await
+            .unwrap();
+
+        assert!(recorder.events().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_invoke_with_config_uses_overridden_invoke() {
+        let recorder = Arc::new(Recorder::default());
+        let config = crate::language_models::InvocationConfig::new()
+            .with_callbacks(CallbackManager::new().with_handler(recorder.clone()));
+
+        let output = InvokeOnlyLLM
+            .invoke_with_config("hi", Some(&config))
+            .await
+            .unwrap();
+
+        assert_eq!(output, "invoked with hi");
+        let names: Vec<String> = recorder
+            .events()
+            .into_iter()
+            .map(|(event, _)| event)
+            .collect();
+        assert_eq!(names, vec!["llm_start", "llm_end"]);
+    }
+}
@@ -0,0 +1,33 @@
+//! Callback handlers for observing runs.
+//!
+//! A [`CallbackManager`] holds a set of [`CallbackHandler`]s. Attach it with
+//! [`InvocationConfig::with_callbacks`](crate::language_models::InvocationConfig::with_callbacks),
+//! [`RunnableConfig::with_callbacks`](crate::langgraph::RunnableConfig::with_callbacks) or
+//! [`CallbackManager::scope`], and every LLM call, chain, agent, tool, retriever and graph
+//! node running inside reports start/end/error events to it with run-id / parent-run-id
+//! tracking.
+//!
+//! Built-in handlers:
+//! - [`StdOutCallbackHandler`]: prints each run with its path in the run tree
+//! - [`JsonlCallbackHandler`]: appends every event to a JSON Lines file
+//! - [`UsageCollector`]: sums token usage per model
+//! - [`RunTreeExporter`]: ships run trees to a LangSmith-compatible collector
+
+mod handler;
+pub use handler::*;
+
+mod manager;
+pub use manager::CallbackManager;
+pub(crate) use manager::{new_run_id, scope_callbacks, CallbackRun};
+
+mod stdout;
+pub use stdout::*;
+
+mod jsonl;
+pub use jsonl::*;
+
+mod usage;
+pub use usage::*;
+
+mod run_tree;
+pub use run_tree::*;
@@ -0,0 +1,474 @@
+use std::{
+    collections::HashMap,
+    fs::OpenOptions,
+    io::{self, Write},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::{
+    language_models::GenerateResult,
+    schemas::{Document, Message},
+};
+
+use super::{CallbackHandler, RunInfo, RunType};
+
+const DEFAULT_ENDPOINT: &str = "https://api.smith.langchain.com";
+const DEFAULT_PROJECT: &str = "default";
+const DEFAULT_BATCH_SIZE: usize = 100;
+
+#[derive(Error, Debug)]
+pub enum RunTreeExportError {
+    #[error("Request error: {0}")]
+    RequestError(#[from] reqwest::Error),
+
+    #[error("Collector returned {status}: {body}")]
+    StatusError { status: u16, body: String },
+
+    #[error("Spool error: {0}")]
+    SpoolError(#[from] io::Error),
+
+    #[error("Serde json error: {0}")]
+    SerdeJsonError(#[from] serde_json::Error),
+}
+
+/// One finished run in LangSmith's run format.
+///
+/// `trace_id` is the id of the root run and `dotted_order` is the chain of
+/// `<start time><run id>` segments from the root, so a collector can rebuild the tree
+/// from a flat list of runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunTree {
+    pub id: String,
+    pub trace_id: String,
+    pub dotted_order: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_run_id: Option<String>,
+    pub name: String,
+    pub run_type: RunType,
+    pub start_time: DateTime<Utc>,
+    pub end_time: Option<DateTime<Utc>>,
+    pub inputs: Value,
+    pub outputs: Option<Value>,
+    pub error: Option<String>,
+    pub tags: Vec<String>,
+    pub extra: Value,
+    pub session_name: String,
+}
+
+/// Exports runs as run trees to a LangSmith-compatible collector.
+///
+/// Every run reported to the handler (chains, agents, graph nodes, LLM calls, tools,
+/// retrievers) becomes a [`RunTree`] linked to its parent, so an `AgentExecutor`,
+/// `CompiledGraph` or `RouterAgent` invocation shows up as a single trace including its
+/// nested subagents. Finished runs are buffered and posted to `{endpoint}/runs/batch` when a
+/// root run ends or the buffer reaches the batch size. If the collector cannot be reached the
+/// batch is appended to the spool file (JSON Lines) and sent ahead of the next batch.
+///
+/// Call [`flush`](Self::flush) before shutting down to make sure nothing is left in memory.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let exporter = Arc::new(
+///     RunTreeExporter::new("http://localhost:1984")
+///         .with_project_name("my-agent")
+///         .with_spool_path("runs.jsonl"),
+/// );
+/// let callbacks = CallbackManager::new().with_handler(exporter.clone());
+/// callbacks.scope(agent.invoke_messages(messages)).await?;
+/// exporter.flush().await?;
+/// ```
+#[derive(Clone)]
+pub struct RunTreeExporter {
+    client: reqwest::Client,
+    endpoint: String,
+    api_key: Option<String>,
+    project_name: String,
+    batch_size: usize,
+    spool_path: Option<PathBuf>,
+    auto_flush: bool,
+    /// Runs that have started but not finished, by run id
+    open: Arc<Mutex<HashMap<String, RunTree>>>,
+    /// Finished runs waiting to be sent
+    pending: Arc<Mutex<Vec<RunTree>>>,
+    /// Serializes flushes so the spool is never read and written concurrently
+    flush_lock: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl RunTreeExporter {
+    pub fn new<S: Into<String>>(endpoint: S) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            endpoint: endpoint.into().trim_end_matches('/').to_string(),
+            api_key: None,
+            project_name: DEFAULT_PROJECT.to_string(),
+            batch_size: DEFAULT_BATCH_SIZE,
+            spool_path: None,
+            auto_flush: true,
+            open: Arc::new(Mutex::new(HashMap::new())),
+            pending: Arc::new(Mutex::new(Vec::new())),
+            flush_lock: Arc::new(tokio::sync::Mutex::new(())),
+        }
+    }
+
+    /// Configure from `LANGSMITH_ENDPOINT`, `LANGSMITH_API_KEY` and `LANGSMITH_PROJECT`.
+    pub fn from_env() -> Self {
+        let mut exporter = Self::new(
+            std::env::var("LANGSMITH_ENDPOINT").unwrap_or_else(|_| DEFAULT_ENDPOINT.to_string()),
+        );
+        exporter.api_key = std::env::var("LANGSMITH_API_KEY").ok();
+        if let Ok(project) = std::env::var("LANGSMITH_PROJECT") {
+            exporter.project_name = project;
+        }
+        exporter
+    }
+
+    /// Sent as the `x-api-key` header.
+    pub fn with_api_key<S: Into<String>>(mut self, api_key: S) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Project (session) the runs are recorded under. Defaults to `"default"`.
+    pub fn with_project_name<S: Into<String>>(mut self, project_name: S) -> Self {
+        self.project_name = project_name.into();
+        self
+    }
+
+    /// Maximum number of runs per request. Defaults to 100.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// File that batches are appended to while the collector is unreachable.
+    pub fn with_spool_path<P: Into<PathBuf>>(mut self, spool_path: P) -> Self {
+        self.spool_path = Some(spool_path.into());
+        self
+    }
+
+    /// Whether to flush in the background when a root run ends or a batch fills up.
+    /// Defaults to `true`; when disabled, runs are only sent by [`flush`](Self::flush).
+    pub fn with_auto_flush(mut self, auto_flush: bool) -> Self {
+        self.auto_flush = auto_flush;
+        self
+    }
+
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Send all finished runs, including any spooled earlier.
+    ///
+    /// On failure the unsent runs are written to the spool file (when configured) and the
+    /// error is returned; they are retried on the next flush.
+    pub async fn flush(&self) -> Result<(), RunTreeExportError> {
+        let _guard = self.flush_lock.lock().await;
+
+        let mut runs = self.take_spool()?;
+        runs.append(&mut self.pending.lock().unwrap_or_else(|e| e.into_inner()));
+
+        let mut batches = runs.chunks(self.batch_size);
+        while let Some(batch) = batches.next() {
+            if let Err(e) = self.post_batch(batch).await {
+                let unsent: Vec<RunTree> = batch.iter().chain(batches.flatten()).cloned().collect();
+                self.spool(&unsent)?;
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn post_batch(&self, runs: &[RunTree]) -> Result<(), RunTreeExportError> {
+        let mut request = self
+            .client
+            .post(format!("{}/runs/batch", self.endpoint))
+            .json(&json!({ "post": runs, "patch": [] }));
+        if let Some(api_key) = &self.api_key {
+            request = request.header("x-api-key", api_key);
+        }
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(RunTreeExportError::StatusError {
+                status: response.status().as_u16(),
+                body: response.text().await.unwrap_or_default(),
+            });
+        }
+        Ok(())
+    }
+
+    fn spool(&self, runs: &[RunTree]) -> Result<(), RunTreeExportError> {
+        let Some(path) = &self.spool_path else {
+            log::warn!("Dropping {} runs: collector unreachable", runs.len());
+            return Ok(());
+        };
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        for run in runs {
+            writeln!(file, "{}", serde_json::to_string(run)?)?;
+        }
+        Ok(())
+    }
+
+    fn take_spool(&self) -> Result<Vec<RunTree>, RunTreeExportError> {
+        let Some(path) = &self.spool_path else {
+            return Ok(Vec::new());
+        };
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        std::fs::remove_file(path)?;
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(Into::into))
+            .collect()
+    }
+
+    fn start(&self, run: &RunInfo, inputs: Value) {
+        let start_time = Utc::now();
+        let segment = format!("{}{}", start_time.format("%Y%m%dT%H%M%S%6fZ"), run.run_id);
+        let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        // A parent outside this exporter's view (e.g. another handler's scope) starts a new trace.
+        let parent = run.parent_run_id.as_ref().and_then(|id| open.get(id));
+        let (trace_id, dotted_order, parent_run_id) = match parent {
+            Some(parent) => (
+                parent.trace_id.clone(),
+                format!("{}.{}", parent.dotted_order, segment),
+                Some(parent.id.clone()),
+            ),
+            None => (run.run_id.clone(), segment, None),
+        };
+        let tree = RunTree {
+            id: run.run_id.clone(),
+            trace_id,
+            dotted_order,
+            parent_run_id,
+            name: run.name.clone(),
+            run_type: run.run_type,
+            start_time,
+            end_time: None,
+            inputs: as_object(inputs, "input"),
+            outputs: None,
+            error: None,
+            tags: run.tags.clone(),
+            extra: json!({ "metadata": run.metadata }),
+            session_name: self.project_name.clone(),
+        };
+        open.insert(run.run_id.clone(), tree);
+    }
+
+    fn end(&self, run: &RunInfo, outputs: Option<Value>, error: Option<&str>) {
+        let Some(mut tree) = self
+            .open
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&run.run_id)
+        else {
+            return;
+        };
+        tree.end_time = Some(Utc::now());
+        tree.outputs = outputs.map(|outputs| as_object(outputs, "output"));
+        tree.error = error.map(str::to_string);
+        let is_root = tree.parent_run_id.is_none();
+
+        let pending_len = {
+            let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            pending.push(tree);
+            pending.len()
+        };
+        if self.auto_flush && (is_root || pending_len >= self.batch_size) {
+            self.flush_in_background();
+        }
+    }
+
+    fn flush_in_background(&self) {
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let exporter = self.clone();
+        handle.spawn(async move {
+            if let Err(e) = exporter.flush().await {
+                log::warn!("Failed to export runs: {}", e);
+            }
+        });
+    }
+}
+
+/// LangSmith expects `inputs` / `outputs` to be objects.
+fn as_object(value: Value, key: &str) -> Value {
+    match value {
+        Value::Object(_) => value,
+        other => json!({ key: other }),
+    }
+}
+
+impl CallbackHandler for RunTreeExporter {
+    fn on_llm_start(&self, run: &RunInfo, messages: &[Message]) {
+        self.start(run, json!({ "messages": messages }));
+    }
+
+    fn on_llm_end(&self, run: &RunInfo, result: &GenerateResult) {
+        self.end(run, Some(json!(result)), None);
+    }
+
+    fn on_llm_error(&self, run: &RunInfo, error: &str) {
+        self.end(run, None, Some(error));
+    }
+
+    fn on_chain_start(&self, run: &RunInfo, inputs: &Value) {
+        self.start(run, inputs.clone());
+    }
+
+    fn on_chain_end(&self, run: &RunInfo, outputs: &Value) {
+        self.end(run, Some(outputs.clone()), None);
+    }
+
+    fn on_chain_error(&self, run: &RunInfo, error: &str) {
+        self.end(run, None, Some(error));
+    }
+
+    fn on_tool_start(&self, run: &RunInfo, input: &str) {
+        self.start(run, json!({ "input": input }));
+    }
+
+    fn on_tool_end(&self, run: &RunInfo, output: &str) {
+        self.end(run, Some(json!({ "output": output })), None);
+    }
+
+    fn on_tool_error(&self, run: &RunInfo, error: &str) {
+        self.end(run, None, Some(error));
+    }
+
+    fn on_retriever_start(&self, run: &RunInfo, query: &str) {
+        self.start(run, json!({ "query": query }));
+    }
+
+    fn on_retriever_end(&self, run: &RunInfo, documents: &[Document]) {
+        self.end(run, Some(json!({ "documents": documents })), None);
+    }
+
+    fn on_retriever_error(&self, run: &RunInfo, error: &str) {
+        self.end(run, None, Some(error));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::{callbacks::CallbackManager, telemetry};
+
+    async fn traced_run() {
+        let _: Result<String, String> = telemetry::chain("agent", &json!({"input": "hi"}), async {
+            telemetry::tool("search", "call_0", "rust", async {
+                Ok("found".to_string())
+            })
+            .await
+        })
+        .await;
+    }
+
+    fn spool_path() -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "run_tree_spool_{}.jsonl",
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_exports_nested_runs_as_one_trace() {
+        let mut server = mockito::Server::new_async().await;
+        let bodies = Arc::new(Mutex::new(Vec::<Value>::new()));
+        let captured = bodies.clone();
+        let mock = server
+            .mock("POST", "/runs/batch")
+            .match_header("x-api-key", "secret")
+            .with_status(200)
+            .with_body_from_request(move |request| {
+                captured
+                    .lock()
+                    .unwrap()
+                    .push(serde_json::from_slice(request.body().unwrap()).unwrap());
+                b"{}".to_vec()
+            })
+            .create_async()
+            .await;
+
+        let exporter = Arc::new(
+            RunTreeExporter::new(server.url())
+                .with_api_key("secret")
+                .with_project_name("tests"),
+        );
+        CallbackManager::new()
+            .with_handler(exporter.clone())
+            .scope(traced_run())
+            .await;
+        exporter.flush().await.unwrap();
+        mock.assert_async().await;
+
+        let runs: Vec<Value> = bodies
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|body| body["post"].as_array().unwrap().clone())
+            .collect();
+        assert_eq!(runs.len(), 2);
+        let tool = runs.iter().find(|run| run["run_type"] == "tool").unwrap();
+        let chain = runs.iter().find(|run| run["run_type"] == "chain").unwrap();
+        assert_eq!(tool["parent_run_id"], chain["id"]);
+        assert_eq!(tool["trace_id"], chain["id"]);
+        assert_eq!(chain["trace_id"], chain["id"]);
+        assert!(tool["dotted_order"]
+            .as_str()
+            .unwrap()
+            .starts_with(&format!("{}.", chain["dotted_order"].as_str().unwrap())));
+        assert_eq!(tool["outputs"]["output"], "found");
+        assert_eq!(chain["session_name"], "tests");
+    }
+
+    #[tokio::test]
+    async fn test_spools_when_collector_is_down() {
+        let mut server = mockito::Server::new_async().await;
+        let spool = spool_path();
+        let exporter = Arc::new(
+            RunTreeExporter::new(server.url())
+                .with_spool_path(&spool)
+                .with_auto_flush(false),
+        );
+
+        let down = server
+            .mock("POST", "/runs/batch")
+            .with_status(503)
+            .expect_at_least(1)
+            .create_async()
+            .await;
+        CallbackManager::new()
+            .with_handler(exporter.clone())
+            .scope(traced_run())
+            .await;
+        assert!(exporter.flush().await.is_err());
+        down.assert_async().await;
+        down.remove_async().await;
+        let spooled = std::fs::read_to_string(&spool).unwrap();
+        assert_eq!(spooled.lines().count(), 2);
+
+        let up = server
+            .mock("POST", "/runs/batch")
+            .with_status(200)
+            .create_async()
+            .await;
+        exporter.flush().await.unwrap();
+        up.assert_async().await;
+        assert!(!spool.exists());
+    }
+}
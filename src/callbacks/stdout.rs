@@ -0,0 +1,116 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use serde_json::Value;
+
+use crate::{
+    language_models::GenerateResult,
+    schemas::{Document, Message},
+};
+
+use super::{CallbackHandler, RunInfo};
+
+/// Prints every run to stdout, prefixed with its path in the run tree.
+///
+/// ```text
+/// [chain/start] [chain:agent] Entering run with input: {"input":"hi"}
+/// [llm/start] [chain:agent > llm:gpt-4o-mini] Entering run with 2 messages
+/// ```
+#[derive(Default)]
+pub struct StdOutCallbackHandler {
+    /// Breadcrumb of each run that has started but not finished
+    paths: Mutex<HashMap<String, String>>,
+}
+
+impl StdOutCallbackHandler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn start(&self, run: &RunInfo, message: String) {
+        let mut paths = self.paths.lock().unwrap_or_else(|e| e.into_inner());
+        let segment = format!("{}:{}", run.run_type.as_str(), run.name);
+        let path = match run.parent_run_id.as_ref().and_then(|id| paths.get(id)) {
+            Some(parent) => format!("{} > {}", parent, segment),
+            None => segment,
+        };
+        println!("[{}/start] [{}] {}", run.run_type.as_str(), path, message);
+        paths.insert(run.run_id.clone(), path);
+    }
+
+    fn end(&self, run: &RunInfo, event: &str, message: String) {
+        let path = self
+            .paths
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&run.run_id)
+            .unwrap_or_else(|| format!("{}:{}", run.run_type.as_str(), run.name));
+        println!(
+            "[{}/{}] [{}] {}",
+            run.run_type.as_str(),
+            event,
+            path,
+            message
+        );
+    }
+}
+
+impl CallbackHandler for StdOutCallbackHandler {
+    fn on_llm_start(&self, run: &RunInfo, messages: &[Message]) {
+        self.start(
+            run,
+            format!("Entering run with {} messages", messages.len()),
+        );
+    }
+
+    fn on_llm_end(&self, run: &RunInfo, result: &GenerateResult) {
+        self.end(
+            run,
+            "end",
+            format!("Exiting run with output: {}", result.generation),
+        );
+    }
+
+    fn on_llm_error(&self, run: &RunInfo, error: &str) {
+        self.end(run, "error", format!("Run errored: {}", error));
+    }
+
+    fn on_chain_start(&self, run: &RunInfo, inputs: &Value) {
+        self.start(run, format!("Entering run with input: {}", inputs));
+    }
+
+    fn on_chain_end(&self, run: &RunInfo, outputs: &Value) {
+        self.end(run, "end", format!("Exiting run with output: {}", outputs));
+    }
+
+    fn on_chain_error(&self, run: &RunInfo, error: &str) {
+        self.end(run, "error", format!("Run errored: {}", error));
+    }
+
+    fn on_tool_start(&self, run: &RunInfo, input: &str) {
+        self.start(run, format!("Entering run with input: {}", input));
+    }
+
+    fn on_tool_end(&self, run: &RunInfo, output: &str) {
+        self.end(run, "end", format!("Exiting run with output: {}", output));
+    }
+
+    fn on_tool_error(&self, run: &RunInfo, error: &str) {
+        self.end(run, "error", format!("Run errored: {}", error));
+    }
+
+    fn on_retriever_start(&self, run: &RunInfo, query: &str) {
+        self.start(run, format!("Entering run with query: {}", query));
+    }
+
+    fn on_retriever_end(&self, run: &RunInfo, documents: &[Document]) {
+        self.end(
+            run,
+            "end",
+            format!("Exiting run with {} documents", documents.len()),
+        );
+    }
+
+    fn on_retriever_error(&self, run: &RunInfo, error: &str) {
+        self.end(run, "error", format!("Run errored: {}", error));
+    }
+}
@@ -0,0 +1,39 @@
+use std::sync::Mutex;
+
+use crate::language_models::{GenerateResult, UsageMetadata};
+
+use super::{CallbackHandler, RunInfo};
+
+/// Sums token usage reported by LLM runs, per model.
+///
+/// Usage is keyed by the run name, which is the model name when the provider reports one.
+#[derive(Default)]
+pub struct UsageCollector {
+    usage: Mutex<UsageMetadata>,
+}
+
+impl UsageCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Usage collected so far.
+    pub fn usage(&self) -> UsageMetadata {
+        self.usage.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn clear(&self) {
+        self.usage.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+impl CallbackHandler for UsageCollector {
+    fn on_llm_end(&self, run: &RunInfo, result: &GenerateResult) {
+        if let Some(tokens) = &result.tokens {
+            self.usage
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .add_usage(&run.name, tokens);
+        }
+    }
+}
@@ -11,6 +11,7 @@ use crate::{
     prompt::PromptArgs,
     prompt_args,
     schemas::{memory::BaseMemory, messages::Message, StreamData},
+    telemetry,
 };
 
 const DEFAULT_INPUT_VARIABLE: &str = "input";
@@ -60,23 +61,26 @@ impl ConversationalChain {
 #[async_trait]
 impl Chain for ConversationalChain {
     async fn call(&self, input_variables: PromptArgs) -> Result<GenerateResult, ChainError> {
-        let input_variable = &input_variables
-            .get(&self.input_key)
-            .ok_or(ChainError::MissingInputVariable(self.input_key.clone()))?;
-        let human_message = Message::new_human_message(input_variable);
-
-        let history = {
-            let memory = self.memory.lock().await;
-            memory.to_string()
-        };
-        let mut input_variables = input_variables;
-        input_variables.insert("history".to_string(), history.into());
-        let result = self.llm.call(input_variables.clone()).await?;
-
-        let mut memory = self.memory.lock().await;
-        memory.add_message(human_message);
-        memory.add_message(Message::new_ai_message(&result.generation));
-        Ok(result)
+        telemetry::chain("ConversationalChain", &input_variables, async {
+            let input_variable = &input_variables
+                .get(&self.input_key)
+                .ok_or(ChainError::MissingInputVariable(self.input_key.clone()))?;
+            let human_message = Message::new_human_message(input_variable);
+
+            let history = {
+                let memory = self.memory.lock().await;
+                memory.to_string()
+            };
+            let mut input_variables = input_variables.clone();
+            input_variables.insert("history".to_string(), history.into());
+            let result = self.llm.call(input_variables.clone()).await?;
+
+            let mut memory = self.memory.lock().await;
+            memory.add_message(human_message);
+            memory.add_message(Message::new_ai_message(&result.generation));
+            Ok(result)
+        })
+        .await
     }
 
     async fn stream(
@@ -14,6 +14,7 @@ use crate::{
     language_models::{GenerateResult, TokenUsage},
     prompt::PromptArgs,
     schemas::{BaseMemory, Message, Retriever, StreamData},
+    telemetry,
 };
 // _conversationalRetrievalQADefaultInputKey             = "question"
 // _conversationalRetrievalQADefaultSourceDocumentKey    = "source_documents"
@@ -69,7 +70,12 @@ impl ConversationalRetrieverChain {
 #[async_trait]
 impl Chain for ConversationalRetrieverChain {
     async fn call(&self, input_variables: PromptArgs) -> Result<GenerateResult, ChainError> {
-        let output = self.execute(input_variables).await?;
+        let output = telemetry::chain(
+            "ConversationalRetrieverChain",
+            &input_variables,
+            self.execute(input_variables.clone()),
+        )
+        .await?;
         let result: GenerateResult = serde_json::from_value(output[DEFAULT_RESULT_KEY].clone())?;
         Ok(result)
     }
@@ -94,11 +100,10 @@ impl Chain for ConversationalRetrieverChain {
             token_usage = Some(token);
         }
 
-        let documents = self
-            .retriever
-            .get_relevant_documents(&question)
-            .await
-            .map_err(|e| ChainError::RetrieverError(e.to_string()))?;
+        let documents =
+            telemetry::retrieve(&question, self.retriever.get_relevant_documents(&question))
+                .await
+                .map_err(|e| ChainError::RetrieverError(e.to_string()))?;
 
         let mut output = self
             .combine_documents_chain
@@ -165,11 +170,10 @@ impl Chain for ConversationalRetrieverChain {
 
         let (question, _) = self.get_question(&history, &human_message.content).await?;
 
-        let documents = self
-            .retriever
-            .get_relevant_documents(&question)
-            .await
-            .map_err(|e| ChainError::RetrieverError(e.to_string()))?;
+        let documents =
+            telemetry::retrieve(&question, self.retriever.get_relevant_documents(&question))
+                .await
+                .map_err(|e| ChainError::RetrieverError(e.to_string()))?;
 
         let stream = self
             .combine_documents_chain
@@ -9,6 +9,7 @@ use crate::{
     output_parsers::{OutputParser, SimpleParser},
     prompt::{FormatPrompter, PromptArgs},
     schemas::StreamData,
+    telemetry,
 };
 
 use super::{chain_trait::Chain, options::ChainCallOptions, ChainError};
@@ -101,20 +102,22 @@ impl Chain for LLMChain {
     }
 
     async fn call(&self, input_variables: PromptArgs) -> Result<GenerateResult, ChainError> {
-        let prompt = self.prompt.format_prompt(input_variables.clone())?;
-        log::debug!("Prompt: {:?}", prompt);
-        let mut output = self.llm.generate(&prompt.to_chat_messages()).await?;
-        output.generation = self.output_parser.parse(&output.generation).await?;
-
-        Ok(output)
+        telemetry::chain("LLMChain", &input_variables, async {
+            let prompt = self.prompt.format_prompt(input_variables.clone())?;
+            log::debug!("Prompt: {:?}", prompt);
+            let mut output =
+                telemetry::generate(self.llm.as_ref(), &prompt.to_chat_messages()).await?;
+            output.generation = self.output_parser.parse(&output.generation).await?;
+
+            Ok(output)
+        })
+        .await
     }
 
     async fn invoke(&self, input_variables: PromptArgs) -> Result<String, ChainError> {
         let prompt = self.prompt.format_prompt(input_variables.clone())?;
         log::debug!("Prompt: {:?}", prompt);
-        let output = self
-            .llm
-            .generate(&prompt.to_chat_messages())
+        let output = telemetry::generate(self.llm.as_ref(), &prompt.to_chat_messages())
             .await?
             .generation;
         Ok(output)
@@ -127,7 +130,7 @@ impl Chain for LLMChain {
     {
         let prompt = self.prompt.format_prompt(input_variables.clone())?;
         log::debug!("Prompt: {:?}", prompt);
-        let llm_stream = self.llm.stream(&prompt.to_chat_messages()).await?;
+        let llm_stream = telemetry::stream(self.llm.as_ref(), &prompt.to_chat_messages()).await?;
 
         // Map the errors from LLMError to ChainError
         let mapped_stream = llm_stream.map_err(ChainError::from);
@@ -7,6 +7,7 @@ use crate::{
     chain::{Chain, ChainError, DEFAULT_OUTPUT_KEY, DEFAULT_RESULT_KEY},
     language_models::{GenerateResult, TokenUsage},
     prompt::PromptArgs,
+    telemetry,
 };
 
 //THIS IS EXPERIMENTAL
@@ -19,7 +20,12 @@ pub struct SequentialChain {
 #[async_trait]
 impl Chain for SequentialChain {
     async fn call(&self, input_variables: PromptArgs) -> Result<GenerateResult, ChainError> {
-        let output = self.execute(input_variables).await?;
+        let output = telemetry::chain(
+            "SequentialChain",
+            &input_variables,
+            self.execute(input_variables.clone()),
+        )
+        .await?;
         let result = output
             .get(DEFAULT_RESULT_KEY)
             .ok_or_else(|| ChainError::MissingInputVariable(DEFAULT_RESULT_KEY.to_string()))?
@@ -10,6 +10,7 @@ use crate::{
     prompt::PromptArgs,
     prompt_args,
     schemas::StreamData,
+    telemetry,
     tools::SQLDatabase,
 };
 
@@ -48,7 +49,9 @@ pub struct SQLDatabaseChain {
 
 /// SQLChain let you interact with a db in human lenguage
 ///
-/// The input variable name is `query`.
+/// The input variable name is `query`. The database can be PostgreSQL, SQLite or MySQL
+/// (`postgres`, `sqlite` and `mysql` features); open it with the engine's `read_only`
+/// constructor so generated SQL cannot modify it.
 /// Example
 /// ```rust,ignore
 /// # async {
@@ -57,6 +60,7 @@ pub struct SQLDatabaseChain {
 ///
 /// let db = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
 /// let engine = PostgreSQLEngine::new(&db).await.unwrap();
+/// // or: let engine = SQLiteEngine::read_only("sqlite://analytics.db").await.unwrap();
 /// let db = SQLDatabaseBuilder::new(engine).build().await.unwrap();
 /// let chain = SQLDatabaseChainBuilder::new()
 ///     .llm(llm)
@@ -156,31 +160,34 @@ impl Chain for SQLDatabaseChain {
     }
 
     async fn call(&self, input_variables: PromptArgs) -> Result<GenerateResult, ChainError> {
-        let (llm_inputs, mut token_usage) = self.call_builder_chains(&input_variables).await?;
-        let output = self.llmchain.call(llm_inputs).await?;
-        if let Some(tokens) = output.tokens {
-            if let Some(general_result) = token_usage.as_mut() {
-                general_result.completion_tokens += tokens.completion_tokens;
-                general_result.total_tokens += tokens.total_tokens;
+        telemetry::chain("SQLDatabaseChain", &input_variables, async {
+            let (llm_inputs, mut token_usage) = self.call_builder_chains(&input_variables).await?;
+            let output = self.llmchain.call(llm_inputs).await?;
+            if let Some(tokens) = output.tokens {
+                if let Some(general_result) = token_usage.as_mut() {
+                    general_result.completion_tokens += tokens.completion_tokens;
+                    general_result.total_tokens += tokens.total_tokens;
+                }
             }
-        }
 
-        let strs: Vec<&str> = output
-            .generation
-            .split("\n\n")
-            .next()
-            .unwrap_or("")
-            .split("Answer:")
-            .collect();
-        let mut output = strs[0];
-        if strs.len() > 1 {
-            output = strs[1];
-        }
-        output = output.trim();
-        Ok(GenerateResult {
-            generation: output.to_string(),
-            tokens: token_usage,
+            let strs: Vec<&str> = output
+                .generation
+                .split("\n\n")
+                .next()
+                .unwrap_or("")
+                .split("Answer:")
+                .collect();
+            let mut output = strs[0];
+            if strs.len() > 1 {
+                output = strs[1];
+            }
+            output = output.trim();
+            Ok(GenerateResult {
+                generation: output.to_string(),
+                tokens: token_usage,
+            })
         })
+        .await
     }
 
     async fn invoke(&self, input_variables: PromptArgs) -> Result<String, ChainError> {
@@ -11,6 +11,7 @@ use crate::{
     language_models::{llm::LLM, GenerateResult},
     prompt::PromptArgs,
     schemas::{Document, StreamData},
+    telemetry,
 };
 
 const COMBINE_DOCUMENTS_DEFAULT_INPUT_KEY: &str = "input_documents";
@@ -117,24 +118,27 @@ impl StuffDocument {
 #[async_trait]
 impl Chain for StuffDocument {
     async fn call(&self, input_variables: PromptArgs) -> Result<GenerateResult, ChainError> {
-        let docs = input_variables
-            .get(&self.input_key)
-            .ok_or_else(|| ChainError::MissingInputVariable(self.input_key.clone()))?;
-
-        let documents: Vec<Document> = serde_json::from_value(docs.clone()).map_err(|e| {
-            ChainError::IncorrectInputVariable {
-                source: e,
-                expected_type: "Vec<Document>".to_string(),
-            }
-        })?;
-
-        let mut input_values = input_variables.clone();
-        input_values.insert(
-            self.document_variable_name.clone(),
-            Value::String(self.join_documents(documents)),
-        );
-
-        self.llm_chain.call(input_values).await
+        telemetry::chain("StuffDocument", &input_variables, async {
+            let docs = input_variables
+                .get(&self.input_key)
+                .ok_or_else(|| ChainError::MissingInputVariable(self.input_key.clone()))?;
+
+            let documents: Vec<Document> = serde_json::from_value(docs.clone()).map_err(|e| {
+                ChainError::IncorrectInputVariable {
+                    source: e,
+                    expected_type: "Vec<Document>".to_string(),
+                }
+            })?;
+
+            let mut input_values = input_variables.clone();
+            input_values.insert(
+                self.document_variable_name.clone(),
+                Value::String(self.join_documents(documents)),
+            );
+
+            self.llm_chain.call(input_values).await
+        })
+        .await
     }
 
     async fn stream(
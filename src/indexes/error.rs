@@ -0,0 +1,40 @@
+use thiserror::Error;
+
+use crate::{document_loaders::LoaderError, vectorstore::VectorStoreError};
+
+#[derive(Error, Debug)]
+pub enum RecordManagerError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
+    #[error("Clock error: {0}")]
+    ClockError(String),
+}
+
+#[cfg(feature = "sqlx")]
+impl From<sqlx::Error> for RecordManagerError {
+    fn from(e: sqlx::Error) -> Self {
+        RecordManagerError::DatabaseError(e.to_string())
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum IndexingError {
+    #[error(transparent)]
+    RecordManagerError(#[from] RecordManagerError),
+
+    #[error(transparent)]
+    VectorStoreError(#[from] VectorStoreError),
+
+    #[error(transparent)]
+    LoaderError(#[from] LoaderError),
+
+    #[error("Document has no source id under metadata key {0:?}")]
+    MissingSourceId(String),
+
+    #[error("Invalid index options: {0}")]
+    InvalidOptions(String),
+}
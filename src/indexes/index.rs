@@ -0,0 +1,589 @@
+use std::collections::{BTreeMap, HashSet};
+
+use futures::Stream;
+use futures_util::StreamExt;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    document_loaders::LoaderError,
+    schemas::Document,
+    vectorstore::{VecStoreOptions, VectorStore},
+};
+
+use super::{IndexingError, RecordManager};
+
+/// What [index] deletes from the vector store besides the documents it replaces
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CleanupMode {
+    /// Never delete; changed documents leave their previous version behind
+    #[default]
+    None,
+    /// After each batch, delete earlier documents from the sources seen in that batch.
+    /// Requires every document to have a source id, and a store that supports delete.
+    Incremental,
+    /// After all documents are indexed, delete every document that was not part of this run.
+    /// Requires a store that supports delete.
+    Full,
+}
+
+/// Options for [index].
+///
+/// # Usage
+/// ```rust,ignore
+/// let options = IndexOptions::new(VecStoreOptions::default())
+///     .with_cleanup(CleanupMode::Incremental)
+///     .with_source_id_key("source")
+///     .with_batch_size(100);
+/// ```
+pub struct IndexOptions<O> {
+    /// Any mode but [CleanupMode::None] needs a store that supports delete; [index] checks
+    /// this before adding anything
+    pub cleanup: CleanupMode,
+    /// Metadata key holding the document's source id, e.g. the file path it was loaded from
+    pub source_id_key: Option<String>,
+    pub batch_size: usize,
+    pub cleanup_batch_size: usize,
+    /// Re-add documents that are already indexed, e.g. after changing the embedder
+    pub force_update: bool,
+    pub store_options: O,
+}
+
+impl<O> IndexOptions<O> {
+    pub fn new(store_options: O) -> Self {
+        Self {
+            cleanup: CleanupMode::None,
+            source_id_key: None,
+            batch_size: 100,
+            cleanup_batch_size: 1000,
+            force_update: false,
+            store_options,
+        }
+    }
+
+    pub fn with_cleanup(mut self, cleanup: CleanupMode) -> Self {
+        self.cleanup = cleanup;
+        self
+    }
+
+    pub fn with_source_id_key<S: Into<String>>(mut self, source_id_key: S) -> Self {
+        self.source_id_key = Some(source_id_key.into());
+        self
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    pub fn with_cleanup_batch_size(mut self, cleanup_batch_size: usize) -> Self {
+        self.cleanup_batch_size = cleanup_batch_size;
+        self
+    }
+
+    pub fn with_force_update(mut self, force_update: bool) -> Self {
+        self.force_update = force_update;
+        self
+    }
+}
+
+impl Default for IndexOptions<VecStoreOptions<Value>> {
+    fn default() -> Self {
+        Self::new(VecStoreOptions::default())
+    }
+}
+
+/// Counts of what [index] did to the vector store
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IndexingResult {
+    pub num_added: usize,
+    pub num_updated: usize,
+    pub num_skipped: usize,
+    pub num_deleted: usize,
+}
+
+/// Index documents into a vector store, skipping the ones it already holds.
+///
+/// Every document gets an id derived from a hash of its content and metadata, so
+/// unchanged documents are recognised through `record_manager` and skipped, and a
+/// changed document is added under a new id. Depending on [IndexOptions::cleanup],
+/// the previous versions of changed or removed documents are then deleted.
+///
+/// Works with any store that replaces documents by id on add; cleanup also needs delete,
+/// which is checked up front so a store without it fails before anything is added.
+/// Use one record manager (or namespace) per vector store.
+///
+/// # Example
+/// ```rust,ignore
+/// let docs = TextLoader::new(text).load_and_split(splitter).await?;
+/// let options = IndexOptions::new(VecStoreOptions::default())
+///     .with_cleanup(CleanupMode::Incremental)
+///     .with_source_id_key("source");
+/// let result = index(docs, &record_manager, &store, &options).await?;
+/// println!("added {}, skipped {}", result.num_added, result.num_skipped);
+/// ```
+pub async fn index<VS>(
+    docs: impl Stream<Item = Result<Document, LoaderError>> + Send,
+    record_manager: &dyn RecordManager,
+    vector_store: &VS,
+    options: &IndexOptions<VS::Options>,
+) -> Result<IndexingResult, IndexingError>
+where
+    VS: VectorStore + ?Sized,
+{
+    if options.batch_size == 0 || options.cleanup_batch_size == 0 {
+        return Err(IndexingError::InvalidOptions(
+            "batch sizes must be greater than zero".into(),
+        ));
+    }
+    if options.cleanup == CleanupMode::Incremental && options.source_id_key.is_none() {
+        return Err(IndexingError::InvalidOptions(
+            "incremental cleanup requires a source id key".into(),
+        ));
+    }
+    // Deleting no ids fails only on stores without delete, which cleanup would hit halfway
+    if options.cleanup != CleanupMode::None {
+        vector_store.delete(&[], &options.store_options).await?;
+    }
+
+    let index_start = record_manager.get_time().await?;
+    let mut result = IndexingResult::default();
+    let mut batches = Box::pin(docs.chunks(options.batch_size));
+
+    while let Some(batch) = batches.next().await {
+        let mut seen = HashSet::new();
+        let mut docs = Vec::new();
+        for doc in batch {
+            let mut doc = doc?;
+            let uid = document_uid(&doc);
+            if !seen.insert(uid.clone()) {
+                result.num_skipped += 1;
+                continue;
+            }
+            doc.id = Some(uid);
+            docs.push(doc);
+        }
+
+        let source_ids = docs
+            .iter()
+            .map(|doc| {
+                options
+                    .source_id_key
+                    .as_deref()
+                    .and_then(|key| source_id(doc, key))
+            })
+            .collect::<Vec<_>>();
+        if options.cleanup == CleanupMode::Incremental && source_ids.iter().any(Option::is_none) {
+            return Err(IndexingError::MissingSourceId(
+                options.source_id_key.clone().unwrap_or_default(),
+            ));
+        }
+
+        let uids = docs
+            .iter()
+            .map(|doc| doc.id.clone().unwrap_or_default())
+            .collect::<Vec<_>>();
+        let exists = record_manager.exists(&uids).await?;
+
+        let mut to_add = Vec::new();
+        for (doc, exists) in docs.into_iter().zip(exists) {
+            match (exists, options.force_update) {
+                (false, _) => {
+                    result.num_added += 1;
+                    to_add.push(doc);
+                }
+                (true, true) => {
+                    result.num_updated += 1;
+                    to_add.push(doc);
+                }
+                (true, false) => result.num_skipped += 1,
+            }
+        }
+        if !to_add.is_empty() {
+            vector_store
+                .add_documents(&to_add, &options.store_options)
+                .await?;
+        }
+        // Also refreshes the skipped documents, so cleanup keeps them
+        record_manager
+            .update(&uids, &source_ids, Some(index_start))
+            .await?;
+
+        if options.cleanup == CleanupMode::Incremental {
+            let sources = source_ids
+                .into_iter()
+                .flatten()
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect::<Vec<_>>();
+            result.num_deleted += cleanup(
+                record_manager,
+                vector_store,
+                options,
+                index_start,
+                Some(&sources),
+            )
+            .await?;
+        }
+    }
+
+    if options.cleanup == CleanupMode::Full {
+        result.num_deleted +=
+            cleanup(record_manager, vector_store, options, index_start, None).await?;
+    }
+
+    Ok(result)
+}
+
+/// Delete the documents last seen before `before`, in batches
+async fn cleanup<VS>(
+    record_manager: &dyn RecordManager,
+    vector_store: &VS,
+    options: &IndexOptions<VS::Options>,
+    before: f64,
+    group_ids: Option<&[String]>,
+) -> Result<usize, IndexingError>
+where
+    VS: VectorStore + ?Sized,
+{
+    let mut num_deleted = 0;
+    loop {
+        let keys = record_manager
+            .list_keys(Some(before), group_ids, Some(options.cleanup_batch_size))
+            .await?;
+        if keys.is_empty() {
+            return Ok(num_deleted);
+        }
+        vector_store.delete(&keys, &options.store_options).await?;
+        record_manager.delete_keys(&keys).await?;
+        num_deleted += keys.len();
+    }
+}
+
+fn source_id(doc: &Document, key: &str) -> Option<String> {
+    match doc.metadata.get(key)? {
+        Value::Null => None,
+        Value::String(source) => Some(source.clone()),
+        source => Some(source.to_string()),
+    }
+}
+
+/// Metadata with object keys sorted at every level, so equal metadata hashes equally
+fn canonical(value: &Value) -> Value {
+    match value {
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .collect::<BTreeMap<_, _>>()
+                .into_iter()
+                .map(|(key, value)| (key.clone(), canonical(value)))
+                .collect::<Map<_, _>>(),
+        ),
+        Value::Array(values) => Value::Array(values.iter().map(canonical).collect()),
+        value => value.clone(),
+    }
+}
+
+/// Hash of a document's content and metadata, formatted as a UUID since some
+/// stores (Qdrant, Weaviate) only accept UUID ids
+pub(crate) fn document_uid(doc: &Document) -> String {
+    let metadata = canonical(&Value::Object(
+        doc.metadata
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect(),
+    ));
+    let digest = Sha256::digest(json!([doc.page_content, metadata]).to_string());
+
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    // Version 8 (custom) and the RFC 4122 variant
+    bytes[6] = (bytes[6] & 0x0f) | 0x80;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = bytes
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+#[cfg(all(test, feature = "in-memory"))]
+mod tests {
+    use std::collections::HashMap;
+
+    use async_trait::async_trait;
+    use futures::stream;
+
+    use super::*;
+    use crate::{
+        embedding::{embedder_trait::Embedder, EmbedderError},
+        indexes::InMemoryRecordManager,
+        vectorstore::{in_memory::StoreBuilder, VectorStoreError},
+    };
+
+    struct LengthEmbedder;
+
+    #[async_trait]
+    impl Embedder for LengthEmbedder {
+        async fn embed_documents(
+            &self,
+            documents: &[String],
+        ) -> Result<Vec<Vec<f64>>, EmbedderError> {
+            Ok(documents
+                .iter()
+                .map(|document| vec![document.len() as f64, 1.0])
+                .collect())
+        }
+
+        async fn embed_query(&self, text: &str) -> Result<Vec<f64>, EmbedderError> {
+            Ok(vec![text.len() as f64, 1.0])
+        }
+    }
+
+    /// Adds and searches like the in-memory store, but cannot delete
+    struct AppendOnlyStore(crate::vectorstore::in_memory::Store);
+
+    #[async_trait]
+    impl VectorStore for AppendOnlyStore {
+        type Options = VecStoreOptions<Value>;
+
+        async fn add_documents(
+            &self,
+            docs: &[Document],
+            opt: &Self::Options,
+        ) -> Result<Vec<String>, VectorStoreError> {
+            self.0.add_documents(docs, opt).await
+        }
+
+        async fn similarity_search(
+            &self,
+            query: &str,
+            limit: usize,
+            opt: &Self::Options,
+        ) -> Result<Vec<Document>, VectorStoreError> {
+            self.0.similarity_search(query, limit, opt).await
+        }
+
+        async fn delete(
+            &self,
+            _ids: &[String],
+            _opt: &Self::Options,
+        ) -> Result<(), VectorStoreError> {
+            Err(VectorStoreError::DeleteNotSupported)
+        }
+    }
+
+    fn doc(content: &str, source: &str) -> Document {
+        Document::new(content).with_metadata(HashMap::from([("source".to_string(), json!(source))]))
+    }
+
+    fn docs(
+        docs: Vec<Document>,
+    ) -> impl Stream<Item = Result<Document, LoaderError>> + Send + 'static {
+        stream::iter(docs.into_iter().map(Ok))
+    }
+
+    async fn stored_contents<VS: VectorStore<Options = VecStoreOptions<Value>>>(
+        store: &VS,
+    ) -> Vec<String> {
+        let mut contents = store
+            .similarity_search("", 100, &VecStoreOptions::default())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|doc| doc.page_content)
+            .collect::<Vec<_>>();
+        contents.sort();
+        contents
+    }
+
+    #[test]
+    fn test_document_uid() {
+        let a = Document::new("text").with_metadata(HashMap::from([
+            ("a".to_string(), json!({"x": 1, "y": 2})),
+            ("b".to_string(), json!(2)),
+        ]));
+        let b = Document::new("text").with_metadata(HashMap::from([
+            ("b".to_string(), json!(2)),
+            ("a".to_string(), json!({"y": 2, "x": 1})),
+        ]));
+        assert_eq!(document_uid(&a), document_uid(&b));
+        assert_ne!(document_uid(&a), document_uid(&Document::new("text")));
+
+        let uid = document_uid(&a);
+        assert_eq!(uid.len(), 36);
+        assert_eq!(&uid[14..15], "8");
+    }
+
+    #[tokio::test]
+    async fn test_index_skips_unchanged_documents() {
+        let store = StoreBuilder::new()
+            .embedder(LengthEmbedder)
+            .build()
+            .unwrap();
+        let record_manager = InMemoryRecordManager::new();
+        let options = IndexOptions::default();
+
+        let input = vec![
+            doc("one", "a.txt"),
+            doc("two", "a.txt"),
+            doc("one", "a.txt"),
+        ];
+        let result = index(docs(input.clone()), &record_manager, &store, &options)
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            IndexingResult {
+                num_added: 2,
+                num_skipped: 1,
+                ..Default::default()
+            }
+        );
+
+        let result = index(docs(input), &record_manager, &store, &options)
+            .await
+            .unwrap();
+        assert_eq!(result.num_added, 0);
+        assert_eq!(result.num_skipped, 3);
+        assert_eq!(stored_contents(&store).await, vec!["one", "two"]);
+
+        let options = IndexOptions::default().with_force_update(true);
+        let result = index(
+            docs(vec![doc("one", "a.txt")]),
+            &record_manager,
+            &store,
+            &options,
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.num_updated, 1);
+        assert_eq!(stored_contents(&store).await, vec!["one", "two"]);
+    }
+
+    #[tokio::test]
+    async fn test_index_incremental_cleanup() {
+        let store = StoreBuilder::new()
+            .embedder(LengthEmbedder)
+            .build()
+            .unwrap();
+        let record_manager = InMemoryRecordManager::new();
+        let options = IndexOptions::default()
+            .with_cleanup(CleanupMode::Incremental)
+            .with_source_id_key("source");
+
+        let input = vec![
+            doc("a one", "a.txt"),
+            doc("a two", "a.txt"),
+            doc("b one", "b.txt"),
+        ];
+        index(docs(input), &record_manager, &store, &options)
+            .await
+            .unwrap();
+
+        // a.txt changed; b.txt was not part of this run and is kept
+        let input = vec![doc("a one", "a.txt"), doc("a three", "a.txt")];
+        let result = index(docs(input), &record_manager, &store, &options)
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            IndexingResult {
+                num_added: 1,
+                num_skipped: 1,
+                num_deleted: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            stored_contents(&store).await,
+            vec!["a one", "a three", "b one"]
+        );
+
+        let err = index(
+            docs(vec![Document::new("no source")]),
+            &record_manager,
+            &store,
+            &options,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, IndexingError::MissingSourceId(_)));
+    }
+
+    #[tokio::test]
+    async fn test_index_full_cleanup() {
+        let store = StoreBuilder::new()
+            .embedder(LengthEmbedder)
+            .build()
+            .unwrap();
+        let record_manager = InMemoryRecordManager::new();
+        let options = IndexOptions::default()
+            .with_cleanup(CleanupMode::Full)
+            .with_batch_size(1);
+
+        let input = vec![doc("a one", "a.txt"), doc("b one", "b.txt")];
+        index(docs(input), &record_manager, &store, &options)
+            .await
+            .unwrap();
+
+        let result = index(
+            docs(vec![doc("b one", "b.txt")]),
+            &record_manager,
+            &store,
+            &options,
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.num_skipped, 1);
+        assert_eq!(result.num_deleted, 1);
+        assert_eq!(stored_contents(&store).await, vec!["b one"]);
+    }
+
+    #[tokio::test]
+    async fn test_index_cleanup_requires_delete() {
+        let store = AppendOnlyStore(
+            StoreBuilder::new()
+                .embedder(LengthEmbedder)
+                .build()
+                .unwrap(),
+        );
+        let record_manager = InMemoryRecordManager::new();
+
+        for cleanup in [CleanupMode::Incremental, CleanupMode::Full] {
+            let options = IndexOptions::default()
+                .with_cleanup(cleanup)
+                .with_source_id_key("source");
+            let err = index(
+                docs(vec![doc("a one", "a.txt")]),
+                &record_manager,
+                &store,
+                &options,
+            )
+            .await
+            .unwrap_err();
+            assert!(matches!(
+                err,
+                IndexingError::VectorStoreError(VectorStoreError::DeleteNotSupported)
+            ));
+        }
+        assert!(stored_contents(&store).await.is_empty());
+
+        let result = index(
+            docs(vec![doc("a one", "a.txt")]),
+            &record_manager,
+            &store,
+            &IndexOptions::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.num_added, 1);
+    }
+}
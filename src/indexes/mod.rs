@@ -0,0 +1,28 @@
+//! Indexing API: load documents into a vector store without duplicating them on re-runs.
+//!
+//! [index] hashes every document and keeps a record of what it wrote in a [RecordManager],
+//! so unchanged documents are skipped, and stale ones are deleted according to [CleanupMode].
+//!
+//! Record managers:
+//! - [InMemoryRecordManager]: process-local, for tests and demos
+//! - `SqliteRecordManager` (feature `sqlite`, `sqlite-vec` or `sqlite-vss`): single-file storage
+//! - `PostgresRecordManager` (any feature enabling `sqlx`, e.g. `sqlite` or `postgres`): shared
+//!   storage for several ingestion workers
+
+mod error;
+mod index;
+mod record_manager;
+
+#[cfg(any(feature = "sqlite", feature = "sqlite-vec", feature = "sqlite-vss"))]
+mod sqlite;
+#[cfg(any(feature = "sqlite", feature = "sqlite-vec", feature = "sqlite-vss"))]
+pub use sqlite::*;
+
+#[cfg(feature = "sqlx")]
+mod postgres;
+#[cfg(feature = "sqlx")]
+pub use postgres::*;
+
+pub use error::*;
+pub use index::*;
+pub use record_manager::*;
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio::sync::RwLock;
+
+use super::RecordManagerError;
+
+/// Keeps track of which documents were written to a vector store and when.
+///
+/// Each record is keyed by the document id [index](super::index) writes to the store
+/// and carries an optional group id (the document's source) and the time it was last
+/// written or seen. Times are seconds since the Unix epoch, as returned by [RecordManager::get_time].
+#[async_trait]
+pub trait RecordManager: Send + Sync {
+    /// Current time of the record manager's clock
+    async fn get_time(&self) -> Result<f64, RecordManagerError>;
+
+    /// Insert or refresh records, stamping them with the current time.
+    ///
+    /// `group_ids` must have the same length as `keys`. Fails with
+    /// [RecordManagerError::ClockError] if the current time is before `time_at_least`.
+    async fn update(
+        &self,
+        keys: &[String],
+        group_ids: &[Option<String>],
+        time_at_least: Option<f64>,
+    ) -> Result<(), RecordManagerError>;
+
+    /// Whether a record exists for each key, in the order of `keys`
+    async fn exists(&self, keys: &[String]) -> Result<Vec<bool>, RecordManagerError>;
+
+    /// Keys updated strictly before `before` and belonging to one of `group_ids`, when given
+    async fn list_keys(
+        &self,
+        before: Option<f64>,
+        group_ids: Option<&[String]>,
+        limit: Option<usize>,
+    ) -> Result<Vec<String>, RecordManagerError>;
+
+    /// Delete the records of `keys`; unknown keys are ignored
+    async fn delete_keys(&self, keys: &[String]) -> Result<(), RecordManagerError>;
+}
+
+pub(crate) fn check_lengths(
+    keys: &[String],
+    group_ids: &[Option<String>],
+) -> Result<(), RecordManagerError> {
+    if keys.len() != group_ids.len() {
+        return Err(RecordManagerError::InvalidArgument(format!(
+            "got {} keys but {} group ids",
+            keys.len(),
+            group_ids.len()
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) fn check_time(now: f64, time_at_least: Option<f64>) -> Result<(), RecordManagerError> {
+    match time_at_least {
+        Some(time_at_least) if now < time_at_least => Err(RecordManagerError::ClockError(format!(
+            "current time {now} is before {time_at_least}"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Process-local record manager, for tests and demos
+#[derive(Default)]
+pub struct InMemoryRecordManager {
+    /// key -> (group id, updated at)
+    records: RwLock<HashMap<String, (Option<String>, f64)>>,
+}
+
+impl InMemoryRecordManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RecordManager for InMemoryRecordManager {
+    async fn get_time(&self) -> Result<f64, RecordManagerError> {
+        Ok(Utc::now().timestamp_micros() as f64 / 1_000_000.0)
+    }
+
+    async fn update(
+        &self,
+        keys: &[String],
+        group_ids: &[Option<String>],
+        time_at_least: Option<f64>,
+    ) -> Result<(), RecordManagerError> {
+        check_lengths(keys, group_ids)?;
+        let now = self.get_time().await?;
+        check_time(now, time_at_least)?;
+        let mut records = self.records.write().await;
+        for (key, group_id) in keys.iter().zip(group_ids) {
+            records.insert(key.clone(), (group_id.clone(), now));
+        }
+        Ok(())
+    }
+
+    async fn exists(&self, keys: &[String]) -> Result<Vec<bool>, RecordManagerError> {
+        let records = self.records.read().await;
+        Ok(keys.iter().map(|key| records.contains_key(key)).collect())
+    }
+
+    async fn list_keys(
+        &self,
+        before: Option<f64>,
+        group_ids: Option<&[String]>,
+        limit: Option<usize>,
+    ) -> Result<Vec<String>, RecordManagerError> {
+        let records = self.records.read().await;
+        Ok(records
+            .iter()
+            .filter(|(_, (_, updated_at))| before.is_none_or(|before| *updated_at < before))
+            .filter(|(_, (group_id, _))| {
+                group_ids.is_none_or(|group_ids| {
+                    group_id
+                        .as_ref()
+                        .is_some_and(|group_id| group_ids.contains(group_id))
+                })
+            })
+            .map(|(key, _)| key.clone())
+            .take(limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+
+    async fn delete_keys(&self, keys: &[String]) -> Result<(), RecordManagerError> {
+        let mut records = self.records.write().await;
+        for key in keys {
+            records.remove(key);
+        }
+        Ok(())
+    }
+}
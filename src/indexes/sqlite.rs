@@ -0,0 +1,253 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    Pool, QueryBuilder, Row, Sqlite,
+};
+
+use super::{
+    record_manager::{check_lengths, check_time},
+    RecordManager, RecordManagerError,
+};
+
+/// SQLite-based record manager.
+///
+/// Stores records in the `upsertion_record` table, scoped by `namespace` so several
+/// vector stores or collections can share one database.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let record_manager = SqliteRecordManager::new("sqlite://records.db", "sqlite_vec/documents").await?;
+/// ```
+pub struct SqliteRecordManager {
+    pool: Pool<Sqlite>,
+    namespace: String,
+}
+
+impl SqliteRecordManager {
+    /// Open (or create) the database at `connection_url` and create the table if needed
+    pub async fn new(connection_url: &str, namespace: &str) -> Result<Self, RecordManagerError> {
+        let pool = SqlitePoolOptions::new()
+            .connect_with(SqliteConnectOptions::from_str(connection_url)?.create_if_missing(true))
+            .await?;
+        Self::from_pool(pool, namespace).await
+    }
+
+    /// Use an existing pool, creating the table if needed
+    pub async fn from_pool(
+        pool: Pool<Sqlite>,
+        namespace: &str,
+    ) -> Result<Self, RecordManagerError> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS upsertion_record (
+                key TEXT NOT NULL,
+                namespace TEXT NOT NULL,
+                group_id TEXT,
+                updated_at REAL NOT NULL,
+                PRIMARY KEY (key, namespace)
+            )",
+        )
+        .execute(&pool)
+        .await?;
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS upsertion_record_updated_at
+             ON upsertion_record(namespace, updated_at)",
+        )
+        .execute(&pool)
+        .await?;
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS upsertion_record_group_id
+             ON upsertion_record(namespace, group_id)",
+        )
+        .execute(&pool)
+        .await?;
+        Ok(Self {
+            pool,
+            namespace: namespace.to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl RecordManager for SqliteRecordManager {
+    async fn get_time(&self) -> Result<f64, RecordManagerError> {
+        let row = sqlx::query("SELECT (julianday('now') - 2440587.5) * 86400.0")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(row.try_get(0)?)
+    }
+
+    async fn update(
+        &self,
+        keys: &[String],
+        group_ids: &[Option<String>],
+        time_at_least: Option<f64>,
+    ) -> Result<(), RecordManagerError> {
+        check_lengths(keys, group_ids)?;
+        let now = self.get_time().await?;
+        check_time(now, time_at_least)?;
+
+        let mut tx = self.pool.begin().await?;
+        for (key, group_id) in keys.iter().zip(group_ids) {
+            sqlx::query(
+                "INSERT INTO upsertion_record (key, namespace, group_id, updated_at)
+                 VALUES (?, ?, ?, ?)
+                 ON CONFLICT (key, namespace) DO UPDATE SET
+                    group_id = excluded.group_id,
+                    updated_at = excluded.updated_at",
+            )
+            .bind(key)
+            .bind(&self.namespace)
+            .bind(group_id)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn exists(&self, keys: &[String]) -> Result<Vec<bool>, RecordManagerError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut query =
+            QueryBuilder::<Sqlite>::new("SELECT key FROM upsertion_record WHERE namespace = ");
+        query.push_bind(&self.namespace).push(" AND key IN (");
+        let mut separated = query.separated(", ");
+        for key in keys {
+            separated.push_bind(key);
+        }
+        separated.push_unseparated(")");
+
+        let found = query
+            .build()
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|row| row.try_get::<String, _>(0))
+            .collect::<Result<std::collections::HashSet<_>, _>>()?;
+        Ok(keys.iter().map(|key| found.contains(key)).collect())
+    }
+
+    async fn list_keys(
+        &self,
+        before: Option<f64>,
+        group_ids: Option<&[String]>,
+        limit: Option<usize>,
+    ) -> Result<Vec<String>, RecordManagerError> {
+        if group_ids.is_some_and(|group_ids| group_ids.is_empty()) {
+            return Ok(Vec::new());
+        }
+        let mut query =
+            QueryBuilder::<Sqlite>::new("SELECT key FROM upsertion_record WHERE namespace = ");
+        query.push_bind(&self.namespace);
+        if let Some(before) = before {
+            query.push(" AND updated_at < ").push_bind(before);
+        }
+        if let Some(group_ids) = group_ids {
+            query.push(" AND group_id IN (");
+            let mut separated = query.separated(", ");
+            for group_id in group_ids {
+                separated.push_bind(group_id);
+            }
+            separated.push_unseparated(")");
+        }
+        if let Some(limit) = limit {
+            query.push(" LIMIT ").push_bind(limit as i64);
+        }
+
+        let keys = query
+            .build()
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|row| row.try_get(0))
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(keys)
+    }
+
+    async fn delete_keys(&self, keys: &[String]) -> Result<(), RecordManagerError> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+        let mut query =
+            QueryBuilder::<Sqlite>::new("DELETE FROM upsertion_record WHERE namespace = ");
+        query.push_bind(&self.namespace).push(" AND key IN (");
+        let mut separated = query.separated(", ");
+        for key in keys {
+            separated.push_bind(key);
+        }
+        separated.push_unseparated(")");
+        query.build().execute(&self.pool).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn record_manager() -> SqliteRecordManager {
+        // A single connection, since every connection to `sqlite::memory:` opens its own database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        SqliteRecordManager::from_pool(pool, "test").await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_record_manager() {
+        let record_manager = record_manager().await;
+        let keys = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let groups = vec![Some("x".to_string()), Some("y".to_string()), None];
+        record_manager.update(&keys, &groups, None).await.unwrap();
+
+        let exists = record_manager
+            .exists(&["a".to_string(), "z".to_string(), "c".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(exists, vec![true, false, true]);
+
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        let time = record_manager.get_time().await.unwrap();
+        record_manager
+            .update(&["b".to_string()], &[Some("y".to_string())], Some(time))
+            .await
+            .unwrap();
+
+        let mut stale = record_manager
+            .list_keys(Some(time), None, None)
+            .await
+            .unwrap();
+        stale.sort();
+        assert_eq!(stale, vec!["a", "c"]);
+        let stale = record_manager
+            .list_keys(Some(time), Some(&["x".to_string(), "y".to_string()]), None)
+            .await
+            .unwrap();
+        assert_eq!(stale, vec!["a"]);
+        assert_eq!(
+            record_manager
+                .list_keys(None, None, Some(2))
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        record_manager.delete_keys(&stale).await.unwrap();
+        let exists = record_manager.exists(&keys).await.unwrap();
+        assert_eq!(exists, vec![false, true, true]);
+
+        let err = record_manager
+            .update(&["a".to_string()], &[None], Some(time + 3600.0))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RecordManagerError::ClockError(_)));
+    }
+}
@@ -26,8 +26,10 @@ use super::{
         chunk::StreamChunk,
         metadata::{MessageChunk, MessageMetadata},
         mode::StreamMode,
+        writer::{forward_custom_data, node_steps, NodeStep},
     },
 };
+use crate::{callbacks, telemetry};
 
 /// CompiledGraph - an executable graph ready for execution
 ///
@@ -60,6 +62,12 @@ impl<S: State + 'static> CompiledGraph<S> {
     pub(crate) fn store(&self) -> &Option<StoreBox> {
         &self.store
     }
+
+    /// Replace the checkpointer (used by the HTTP server to share one checkpointer)
+    #[cfg(feature = "server")]
+    pub(crate) fn set_checkpointer(&mut self, checkpointer: CheckpointerBox<S>) {
+        self.checkpointer = Some(checkpointer);
+    }
 }
 
 impl<S: State + 'static> CompiledGraph<S> {
@@ -176,7 +184,8 @@ impl<S: State + 'static> CompiledGraph<S> {
                 .ok_or_else(|| LangGraphError::NodeNotFound(current_node.clone()))?;
 
             // Use invoke for basic invoke method (no config/store available)
-            let update = node.invoke(&current_state).await?;
+            let update =
+                telemetry::node(&current_node, &current_state, node.invoke(&current_state)).await?;
 
             // Merge the update into the current state
             current_state = self.merge_state_update(&current_state, &update)?;
@@ -481,6 +490,9 @@ impl<S: State + 'static> CompiledGraph<S> {
                                 // Subgraph completed - use its final state
                                 final_state = sub_final_state;
                             }
+                            StreamEvent::Interrupt { .. } => {
+                                // Only produced by stream_with_config_interrupt
+                            }
                             StreamEvent::Error { error } => {
                                 yield StreamEvent::Error { error };
                                 return;
@@ -535,7 +547,7 @@ impl<S: State + 'static> CompiledGraph<S> {
                         };
 
                         // Stream LLM tokens
-                        let mut stream_result = match llm.stream(&messages).await {
+                        let mut stream_result = match telemetry::stream(llm.as_ref(), &messages).await {
                             Ok(stream) => stream,
                             Err(e) => {
                                 yield StreamEvent::Error {
@@ -589,27 +601,65 @@ impl<S: State + 'static> CompiledGraph<S> {
                     } else {
                         // Not an LLM node, use invoke_with_context
                         // Note: stream_internal doesn't have config/store, so pass None
-                        match node.invoke_with_context(&current_state, None, None).await {
-                            Ok(update) => update,
-                            Err(e) => {
+                        use futures::StreamExt;
+                        let steps = node_steps(telemetry::node(
+                            &current_node,
+                            &current_state,
+                            node.invoke_with_context(&current_state, None, None),
+                        ));
+                        futures::pin_mut!(steps);
+                        let mut result = None;
+                        while let Some(step) = steps.next().await {
+                            match step {
+                                NodeStep::Custom(data) => yield StreamEvent::CustomData {
+                                    node: current_node.clone(),
+                                    data,
+                                    path: Vec::new(),
+                                },
+                                NodeStep::Done(output) => result = Some(output),
+                            }
+                        }
+                        match result {
+                            Some(Ok(update)) => update,
+                            Some(Err(e)) => {
                                 yield StreamEvent::Error {
                                     error: std::sync::Arc::new(e),
                                 };
                                 return;
                             }
+                            None => HashMap::new(),
                         }
                     }
                 } else {
                     // No message streaming needed, use invoke_with_context
                     // Note: stream_internal doesn't have config/store, so pass None
-                    match node.invoke_with_context(&current_state, None, None).await {
-                        Ok(update) => update,
-                        Err(e) => {
+                    use futures::StreamExt;
+                    let steps = node_steps(telemetry::node(
+                        &current_node,
+                        &current_state,
+                        node.invoke_with_context(&current_state, None, None),
+                    ));
+                    futures::pin_mut!(steps);
+                    let mut result = None;
+                    while let Some(step) = steps.next().await {
+                        match step {
+                            NodeStep::Custom(data) => yield StreamEvent::CustomData {
+                                node: current_node.clone(),
+                                data,
+                                path: Vec::new(),
+                            },
+                            NodeStep::Done(output) => result = Some(output),
+                        }
+                    }
+                    match result {
+                        Some(Ok(update)) => update,
+                        Some(Err(e)) => {
                             yield StreamEvent::Error {
                                 error: std::sync::Arc::new(e),
                             };
                             return;
                         }
+                        None => HashMap::new(),
                     }
                 };
 
@@ -756,6 +806,11 @@ impl<S: State + 'static> CompiledGraph<S> {
                     state: final_state.clone(),
                 })
             }
+            (StreamEvent::Interrupt { state, .. }, StreamMode::Values) => {
+                Some(StreamChunk::Values {
+                    state: state.clone(),
+                })
+            }
 
             // Updates mode: extract update from NodeEnd
             (StreamEvent::NodeEnd { node, update, .. }, StreamMode::Updates) => {
@@ -832,6 +887,11 @@ impl<S: State + 'static> CompiledGraph<S> {
                         info = info.with_info("data".to_string(), data.clone());
                         info
                     }
+                    StreamEvent::Interrupt { interrupts, .. } => DebugInfo::new("Interrupt")
+                        .with_info(
+                            "interrupts".to_string(),
+                            serde_json::to_value(interrupts).ok()?,
+                        ),
                 };
                 Some(StreamChunk::Debug { info: debug_info })
             }
@@ -928,6 +988,106 @@ impl<S: State + 'static> CompiledGraph<S> {
         initial_state: StateOrCommand<S>,
         config: &RunnableConfig,
     ) -> Result<InvokeResult<S>, LangGraphError> {
+        let run = self.prepare_interrupt_run(initial_state, config).await?;
+
+        // Execute with interrupt context
+        let execution = set_interrupt_context(run.interrupt_ctx, async {
+            self.execute_with_interrupt_support(
+                run.state.clone(),
+                &run.checkpoint_config,
+                run.parent_config.as_ref(),
+                Some(&run.runnable_config),
+                self.store.clone(),
+                None,
+            )
+            .await
+        });
+        callbacks::scope_callbacks(
+            config.callbacks.as_ref(),
+            telemetry::chain("LangGraph", &run.state, execution),
+        )
+        .await
+    }
+
+    /// Stream the graph execution with config, supporting interrupts
+    ///
+    /// This is the streaming counterpart of `invoke_with_config_interrupt`. It accepts
+    /// either a state or a `Command` to resume from the latest checkpoint, and yields
+    /// `NodeStart`/`NodeEnd` events as nodes execute. The stream ends with exactly one of
+    /// `GraphEnd`, `Interrupt` or `Error`.
+    ///
+    /// Use `StreamEvent::to_chunk` to convert the events for a given `StreamMode`.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_state` - The initial state or Command to resume from
+    /// * `config` - The runnable configuration (thread_id, checkpoint_id, etc.)
+    pub fn stream_with_config_interrupt<'a>(
+        &'a self,
+        initial_state: StateOrCommand<S>,
+        config: &RunnableConfig,
+    ) -> Pin<Box<dyn Stream<Item = StreamEvent<S>> + Send + 'a>> {
+        let config = config.clone();
+
+        Box::pin(stream! {
+            let run = match self.prepare_interrupt_run(initial_state, &config).await {
+                Ok(run) => run,
+                Err(e) => {
+                    yield StreamEvent::Error { error: Arc::new(e) };
+                    return;
+                }
+            };
+
+            let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+            let execution = set_interrupt_context(run.interrupt_ctx, async {
+                self.execute_with_interrupt_support(
+                    run.state.clone(),
+                    &run.checkpoint_config,
+                    run.parent_config.as_ref(),
+                    Some(&run.runnable_config),
+                    self.store.clone(),
+                    Some(&sender),
+                )
+                .await
+            });
+            let execution = callbacks::scope_callbacks(
+                config.callbacks.as_ref(),
+                telemetry::chain("LangGraph", &run.state, execution),
+            );
+            futures::pin_mut!(execution);
+
+            // Forward node events while the graph is running
+            let result = loop {
+                tokio::select! {
+                    biased;
+                    Some(event) = receiver.recv() => yield event,
+                    result = &mut execution => break result,
+                }
+            };
+            while let Ok(event) = receiver.try_recv() {
+                yield event;
+            }
+
+            match result {
+                Ok(InvokeResult { state, interrupt: Some(interrupts) }) => {
+                    yield StreamEvent::Interrupt { state, interrupts };
+                }
+                Ok(InvokeResult { state, interrupt: None }) => {
+                    yield StreamEvent::GraphEnd { final_state: state };
+                }
+                Err(e) => {
+                    yield StreamEvent::Error { error: Arc::new(e) };
+                }
+            }
+        })
+    }
+
+    /// Resolve the starting state, resume values and configs for an interruptible run
+    async fn prepare_interrupt_run(
+        &self,
+        initial_state: StateOrCommand<S>,
+        config: &RunnableConfig,
+    ) -> Result<InterruptRun<S>, LangGraphError> {
         let checkpoint_config = CheckpointConfig::from_config(config)?;
         let thread_id = &checkpoint_config.thread_id;
 
@@ -1014,6 +1174,7 @@ impl<S: State + 'static> CompiledGraph<S> {
         // Create RunnableConfig from checkpoint_config for nodes
         let mut runnable_config =
             RunnableConfig::with_thread_id(checkpoint_config.thread_id.clone());
+        runnable_config.callbacks = config.callbacks.clone();
         if let Some(checkpoint_id) = &checkpoint_config.checkpoint_id {
             runnable_config.configurable.insert(
                 "checkpoint_id".to_string(),
@@ -1021,20 +1182,13 @@ impl<S: State + 'static> CompiledGraph<S> {
             );
         }
 
-        // Execute with interrupt context
-        let result = set_interrupt_context(interrupt_ctx, async {
-            self.execute_with_interrupt_support(
-                current_state,
-                &checkpoint_config,
-                parent_config.as_ref(),
-                Some(&runnable_config),
-                self.store.clone(),
-            )
-            .await
+        Ok(InterruptRun {
+            state: current_state,
+            interrupt_ctx,
+            checkpoint_config,
+            parent_config,
+            runnable_config,
         })
-        .await;
-
-        result
     }
 
     /// Execute graph with interrupt support
@@ -1047,6 +1201,7 @@ impl<S: State + 'static> CompiledGraph<S> {
         parent_config: Option<&CheckpointConfig>,
         config: Option<&RunnableConfig>,
         store: Option<StoreBox>,
+        events: Option<&tokio::sync::mpsc::UnboundedSender<StreamEvent<S>>>,
     ) -> Result<InvokeResult<S>, LangGraphError> {
         let mut current_state = initial_state;
         let mut current_node = START.to_string();
@@ -1099,16 +1254,48 @@ impl<S: State + 'static> CompiledGraph<S> {
                 .get(&current_node)
                 .ok_or_else(|| LangGraphError::NodeNotFound(current_node.clone()))?;
 
+            if let Some(events) = events {
+                let _ = events.send(StreamEvent::NodeStart {
+                    node: current_node.clone(),
+                    state: current_state.clone(),
+                    path: Vec::new(),
+                });
+            }
+
             // Execute node and handle interrupts
             // Use invoke_with_context to support config and store
-            let update_result = node
-                .invoke_with_context(&current_state, config, store.clone())
-                .await;
+            let run = telemetry::node(
+                &current_node,
+                &current_state,
+                node.invoke_with_context(&current_state, config, store.clone()),
+            );
+            let update_result = match events {
+                Some(events) => {
+                    forward_custom_data(run, |data| {
+                        let _ = events.send(StreamEvent::CustomData {
+                            node: current_node.clone(),
+                            data,
+                            path: Vec::new(),
+                        });
+                    })
+                    .await
+                }
+                None => run.await,
+            };
 
             match update_result {
                 Ok(update) => {
                     // Node executed successfully, merge state
                     current_state = self.merge_state_update(&current_state, &update)?;
+
+                    if let Some(events) = events {
+                        let _ = events.send(StreamEvent::NodeEnd {
+                            node: current_node.clone(),
+                            state: current_state.clone(),
+                            update,
+                            path: Vec::new(),
+                        });
+                    }
                 }
                 Err(LangGraphError::InterruptError(interrupt_err)) => {
                     // Interrupt occurred - save checkpoint and return
@@ -1471,6 +1658,15 @@ impl<S: State + 'static> CompiledGraph<S> {
     }
 }
 
+/// Prepared inputs for an interruptible run
+struct InterruptRun<S: State> {
+    state: S,
+    interrupt_ctx: InterruptContext,
+    checkpoint_config: CheckpointConfig,
+    parent_config: Option<CheckpointConfig>,
+    runnable_config: RunnableConfig,
+}
+
 /// Stream options for controlling streaming behavior
 #[derive(Clone, Debug, Default)]
 pub struct StreamOptions {
@@ -1500,7 +1696,10 @@ impl StreamOptions {
 }
 
 /// Stream event type - represents different types of events during graph execution
+///
+/// More variants may be added, so matches need a wildcard arm.
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum StreamEvent<S: State> {
     /// A node is about to be executed
     NodeStart {
@@ -1538,6 +1737,21 @@ pub enum StreamEvent<S: State> {
         /// Path for subgraph nodes
         path: Vec<String>,
     },
+    /// Execution was paused by `interrupt()` (only from `stream_with_config_interrupt`)
+    Interrupt {
+        /// State at the interrupt point
+        state: S,
+        interrupts: Vec<Interrupt>,
+    },
+}
+
+impl<S: State + 'static> StreamEvent<S> {
+    /// Convert this event to a `StreamChunk` for the given stream mode
+    ///
+    /// Returns `None` if the event produces nothing in that mode.
+    pub fn to_chunk(&self, mode: StreamMode) -> Option<StreamChunk<S>> {
+        CompiledGraph::<S>::convert_event_to_chunk(self, mode)
+    }
 }
 
 #[cfg(test)]
@@ -1611,4 +1825,49 @@ mod tests {
         // Should have at least NodeStart, NodeEnd, and GraphEnd events
         assert!(events.len() >= 3);
     }
+
+    #[tokio::test]
+    async fn test_stream_custom_data_from_node() {
+        let mut graph = StateGraph::<MessagesState>::new();
+
+        graph
+            .add_node(
+                "fetch",
+                function_node("fetch", |_state| async move {
+                    let writer = crate::langgraph::get_stream_writer().unwrap();
+                    writer.send(serde_json::json!({"status": "fetching"}))?;
+                    tokio::task::yield_now().await;
+                    writer.send(serde_json::json!({"status": "done"}))?;
+                    Ok(HashMap::new())
+                }),
+            )
+            .unwrap();
+
+        graph.add_edge(START, "fetch");
+        graph.add_edge("fetch", END);
+
+        let compiled = graph.compile().unwrap();
+        let chunks: Vec<_> = compiled
+            .stream_with_mode(MessagesState::new(), StreamMode::Custom)
+            .collect()
+            .await;
+
+        let data: Vec<_> = chunks
+            .into_iter()
+            .map(|chunk| match chunk {
+                StreamChunk::Custom { node, data } => {
+                    assert_eq!(node, "fetch");
+                    data
+                }
+                other => panic!("unexpected chunk: {:?}", other.mode()),
+            })
+            .collect();
+        assert_eq!(
+            data,
+            vec![
+                serde_json::json!({"status": "fetching"}),
+                serde_json::json!({"status": "done"})
+            ]
+        );
+    }
 }
@@ -6,6 +6,7 @@ use crate::langgraph::{
     persistence::{config::RunnableConfig, store::StoreBox},
     state::{State, StateUpdate},
 };
+use crate::telemetry;
 
 /// Execute multiple nodes in parallel
 ///
@@ -34,7 +35,12 @@ pub async fn execute_nodes_parallel<S: State>(
             async move {
                 let node =
                     node_opt.ok_or_else(|| LangGraphError::NodeNotFound(node_name.clone()))?;
-                let update = node.invoke_with_context(&state, config, store).await?;
+                let update = telemetry::node(
+                    &node_name,
+                    &state,
+                    node.invoke_with_context(&state, config, store),
+                )
+                .await?;
                 Ok::<(String, StateUpdate), LangGraphError>((node_name, update))
             }
         })
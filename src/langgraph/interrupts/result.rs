@@ -1,3 +1,4 @@
+use serde::Serialize;
 use serde_json::Value;
 
 use crate::langgraph::state::State;
@@ -8,7 +9,7 @@ use super::types::Interrupt;
 ///
 /// When an interrupt occurs, the result includes the `__interrupt__` field
 /// containing information about the interrupt.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct InvokeResult<S: State> {
     /// The final state (or state at interrupt point)
     pub state: S,
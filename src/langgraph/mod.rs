@@ -6,6 +6,8 @@ mod graph;
 mod interrupts;
 mod node;
 mod persistence;
+#[cfg(feature = "server")]
+pub mod server;
 mod state;
 mod streaming;
 pub mod task;
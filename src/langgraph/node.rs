@@ -5,7 +5,7 @@ use async_trait::async_trait;
 
 use crate::{
     agent::Agent, chain::Chain, language_models::llm::LLM, prompt::PromptArgs,
-    schemas::messages::Message,
+    schemas::messages::Message, telemetry,
 };
 
 use super::{
@@ -330,7 +330,7 @@ impl<S: State> Node<S> for LLMNode {
         };
 
         // Generate response
-        let result = self.llm.generate(&messages).await?;
+        let result = telemetry::generate(self.llm.as_ref(), &messages).await?;
 
         // Create state update with new AI message
         let ai_message = Message::new_ai_message(&result.generation);
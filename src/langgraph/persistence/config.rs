@@ -3,6 +3,8 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::callbacks::CallbackManager;
+
 /// Configuration for graph execution with persistence
 ///
 /// Similar to Python's RunnableConfig, this contains configurable
@@ -11,6 +13,9 @@ use serde_json::Value;
 pub struct RunnableConfig {
     /// Configurable parameters (thread_id, checkpoint_id, etc.)
     pub configurable: HashMap<String, Value>,
+    /// Callback handlers notified about the run and everything nested in it
+    #[serde(skip)]
+    pub callbacks: Option<CallbackManager>,
 }
 
 impl RunnableConfig {
@@ -38,6 +43,12 @@ impl RunnableConfig {
         config
     }
 
+    /// Attach callback handlers to runs using this config
+    pub fn with_callbacks(mut self, callbacks: CallbackManager) -> Self {
+        self.callbacks = Some(callbacks);
+        self
+    }
+
     /// Get thread_id from config
     pub fn get_thread_id(&self) -> Option<String> {
         self.configurable
@@ -0,0 +1,349 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use async_stream::stream;
+use axum::{
+    extract::{Path, Query, State as AppState},
+    response::sse::{Event, KeepAlive, Sse},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::Utc;
+use futures::{Stream, StreamExt};
+use tokio::{net::TcpListener, sync::RwLock};
+
+use crate::langgraph::{
+    compiled::{CompiledGraph, StreamEvent},
+    interrupts::{Interrupt, StateOrCommand},
+    persistence::{CheckpointConfig, CheckpointerBox, RunnableConfig, StateSnapshot},
+    state::State,
+    streaming::StreamMode,
+};
+
+use super::{
+    error::ServerError,
+    types::{
+        CreateThreadRequest, HistoryQuery, RunMetadata, RunRequest, RunResponse, StateQuery,
+        ThreadInfo, UpdateStateRequest,
+    },
+};
+
+/// HTTP server exposing a `CompiledGraph` with thread, run and state endpoints
+///
+/// The checkpointer is shared with the graph, so runs, interrupts and state
+/// queries all see the same checkpoints.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let server = GraphServer::new(graph, Arc::new(InMemorySaver::new()));
+/// let app = server.router(); // mount into an existing axum app, or:
+/// server.serve(TcpListener::bind("127.0.0.1:8123").await?).await?;
+/// ```
+pub struct GraphServer<S: State + 'static> {
+    inner: Arc<ServerInner<S>>,
+}
+
+struct ServerInner<S: State + 'static> {
+    graph: CompiledGraph<S>,
+    checkpointer: CheckpointerBox<S>,
+    threads: RwLock<HashMap<String, ThreadInfo>>,
+}
+
+impl<S: State + 'static> GraphServer<S> {
+    /// Create a server for `graph`, persisting checkpoints with `checkpointer`
+    ///
+    /// Any checkpointer the graph was compiled with is replaced.
+    pub fn new(mut graph: CompiledGraph<S>, checkpointer: CheckpointerBox<S>) -> Self {
+        graph.set_checkpointer(checkpointer.clone());
+        Self {
+            inner: Arc::new(ServerInner {
+                graph,
+                checkpointer,
+                threads: RwLock::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// Build the axum router with all endpoints
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/threads", post(create_thread::<S>))
+            .route("/threads/{thread_id}", get(get_thread::<S>))
+            .route("/threads/{thread_id}/runs/wait", post(run_wait::<S>))
+            .route("/threads/{thread_id}/runs/stream", post(run_stream::<S>))
+            .route(
+                "/threads/{thread_id}/state",
+                get(get_state::<S>).post(update_state::<S>),
+            )
+            .route("/threads/{thread_id}/history", get(get_history::<S>))
+            .with_state(self.inner.clone())
+    }
+
+    /// Serve the router on `listener` until the process exits
+    pub async fn serve(&self, listener: TcpListener) -> Result<(), ServerError> {
+        axum::serve(listener, self.router()).await?;
+        Ok(())
+    }
+}
+
+impl<S: State + 'static> Clone for GraphServer<S> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<S: State + 'static> ServerInner<S> {
+    /// Look up a thread, falling back to the checkpointer for threads
+    /// created before the server started
+    async fn thread(&self, thread_id: &str) -> Result<ThreadInfo, ServerError> {
+        if let Some(thread) = self.threads.read().await.get(thread_id) {
+            return Ok(thread.clone());
+        }
+
+        let history = self.checkpointer.list(thread_id, Some(1)).await?;
+        let snapshot = history
+            .first()
+            .ok_or_else(|| ServerError::ThreadNotFound(thread_id.to_string()))?;
+
+        let thread = ThreadInfo {
+            thread_id: thread_id.to_string(),
+            created_at: snapshot.created_at,
+            metadata: HashMap::new(),
+        };
+        self.threads
+            .write()
+            .await
+            .insert(thread_id.to_string(), thread.clone());
+        Ok(thread)
+    }
+
+    /// Build the runnable config and graph input for a run request
+    fn run_input(
+        thread_id: &str,
+        request: RunRequest<S>,
+    ) -> Result<(StateOrCommand<S>, RunnableConfig), ServerError> {
+        let mut config = RunnableConfig::with_thread_id(thread_id);
+        let input = match (request.input, request.command) {
+            (Some(input), None) => {
+                if let Some(checkpoint_id) = request.checkpoint_id {
+                    config.configurable.insert(
+                        "checkpoint_id".to_string(),
+                        serde_json::json!(checkpoint_id),
+                    );
+                }
+                StateOrCommand::State(input)
+            }
+            (None, Some(command)) => StateOrCommand::Command(command),
+            _ => {
+                return Err(ServerError::BadRequest(
+                    "exactly one of `input` or `command` is required".to_string(),
+                ))
+            }
+        };
+        Ok((input, config))
+    }
+
+    /// Persist the final state of a completed run
+    ///
+    /// Interrupted runs are checkpointed by the graph itself.
+    async fn save_final_state(
+        &self,
+        thread_id: &str,
+        run_id: &str,
+        state: &S,
+    ) -> Result<(), ServerError> {
+        let mut metadata = HashMap::new();
+        metadata.insert("source".to_string(), serde_json::json!("run"));
+        metadata.insert("run_id".to_string(), serde_json::json!(run_id));
+
+        let mut snapshot = StateSnapshot::with_metadata(
+            state.clone(),
+            Vec::new(),
+            CheckpointConfig::new(thread_id),
+            metadata,
+        );
+        if let Some(parent) = self.checkpointer.get(thread_id, None).await? {
+            snapshot.parent_config = Some(parent.config);
+        }
+
+        self.checkpointer.put(thread_id, &snapshot).await?;
+        Ok(())
+    }
+}
+
+fn new_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+async fn create_thread<S: State + 'static>(
+    AppState(server): AppState<Arc<ServerInner<S>>>,
+    body: Option<Json<CreateThreadRequest>>,
+) -> Result<Json<ThreadInfo>, ServerError> {
+    let request = body.map(|Json(request)| request).unwrap_or_default();
+    let thread = ThreadInfo {
+        thread_id: request.thread_id.unwrap_or_else(new_id),
+        created_at: Utc::now(),
+        metadata: request.metadata,
+    };
+
+    let mut threads = server.threads.write().await;
+    if threads.contains_key(&thread.thread_id) {
+        return Err(ServerError::BadRequest(format!(
+            "thread already exists: {}",
+            thread.thread_id
+        )));
+    }
+    threads.insert(thread.thread_id.clone(), thread.clone());
+    Ok(Json(thread))
+}
+
+async fn get_thread<S: State + 'static>(
+    AppState(server): AppState<Arc<ServerInner<S>>>,
+    Path(thread_id): Path<String>,
+) -> Result<Json<ThreadInfo>, ServerError> {
+    Ok(Json(server.thread(&thread_id).await?))
+}
+
+async fn run_wait<S: State + 'static>(
+    AppState(server): AppState<Arc<ServerInner<S>>>,
+    Path(thread_id): Path<String>,
+    Json(request): Json<RunRequest<S>>,
+) -> Result<Json<RunResponse<S>>, ServerError> {
+    server.thread(&thread_id).await?;
+    let (input, config) = ServerInner::run_input(&thread_id, request)?;
+    let run_id = new_id();
+
+    let result = server
+        .graph
+        .invoke_with_config_interrupt(input, &config)
+        .await?;
+    if !result.has_interrupt() {
+        server
+            .save_final_state(&thread_id, &run_id, &result.state)
+            .await?;
+    }
+
+    Ok(Json(RunResponse {
+        run_id,
+        thread_id,
+        values: result.state,
+        interrupts: result.interrupt.unwrap_or_default(),
+    }))
+}
+
+async fn run_stream<S: State + 'static>(
+    AppState(server): AppState<Arc<ServerInner<S>>>,
+    Path(thread_id): Path<String>,
+    Json(request): Json<RunRequest<S>>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ServerError> {
+    server.thread(&thread_id).await?;
+    let modes = if request.stream_mode.is_empty() {
+        vec![StreamMode::Values]
+    } else {
+        request.stream_mode.clone()
+    };
+    let (input, config) = ServerInner::run_input(&thread_id, request)?;
+    let run_id = new_id();
+
+    let events = stream! {
+        yield json_event("metadata", &RunMetadata {
+            run_id: run_id.clone(),
+            thread_id: thread_id.clone(),
+        });
+
+        let mut graph_events = server.graph.stream_with_config_interrupt(input, &config);
+        while let Some(event) = graph_events.next().await {
+            for mode in &modes {
+                if let Some(chunk) = event.to_chunk(*mode) {
+                    yield json_event(mode.as_str(), &chunk);
+                }
+            }
+
+            match event {
+                StreamEvent::GraphEnd { final_state } => {
+                    if let Err(e) = server.save_final_state(&thread_id, &run_id, &final_state).await {
+                        yield error_event(&e.to_string());
+                    }
+                }
+                StreamEvent::Interrupt { interrupts, .. } => {
+                    yield json_event::<Vec<Interrupt>>("interrupt", &interrupts);
+                }
+                StreamEvent::Error { error } => {
+                    yield error_event(&error.to_string());
+                }
+                _ => {}
+            }
+        }
+
+        yield Ok(Event::default().event("end"));
+    };
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+async fn get_state<S: State + 'static>(
+    AppState(server): AppState<Arc<ServerInner<S>>>,
+    Path(thread_id): Path<String>,
+    Query(query): Query<StateQuery>,
+) -> Result<Json<StateSnapshot<S>>, ServerError> {
+    server.thread(&thread_id).await?;
+    let snapshot = server
+        .checkpointer
+        .get(&thread_id, query.checkpoint_id.as_deref())
+        .await?
+        .ok_or_else(|| ServerError::StateNotFound(thread_id.clone()))?;
+    Ok(Json(snapshot))
+}
+
+async fn update_state<S: State + 'static>(
+    AppState(server): AppState<Arc<ServerInner<S>>>,
+    Path(thread_id): Path<String>,
+    Json(request): Json<UpdateStateRequest>,
+) -> Result<Json<StateSnapshot<S>>, ServerError> {
+    server.thread(&thread_id).await?;
+    let config = match &request.checkpoint_id {
+        Some(checkpoint_id) => RunnableConfig::with_checkpoint(&thread_id, checkpoint_id),
+        None => RunnableConfig::with_thread_id(&thread_id),
+    };
+    if server
+        .checkpointer
+        .get(&thread_id, request.checkpoint_id.as_deref())
+        .await?
+        .is_none()
+    {
+        return Err(ServerError::StateNotFound(thread_id));
+    }
+
+    let snapshot = server
+        .graph
+        .update_state(&config, &request.values, request.as_node.as_deref())
+        .await?;
+    Ok(Json(snapshot))
+}
+
+async fn get_history<S: State + 'static>(
+    AppState(server): AppState<Arc<ServerInner<S>>>,
+    Path(thread_id): Path<String>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<Vec<StateSnapshot<S>>>, ServerError> {
+    server.thread(&thread_id).await?;
+    let history = server.checkpointer.list(&thread_id, query.limit).await?;
+    Ok(Json(history))
+}
+
+fn json_event<T: serde::Serialize>(name: &str, data: &T) -> Result<Event, Infallible> {
+    match Event::default().event(name).json_data(data) {
+        Ok(event) => Ok(event),
+        Err(e) => error_event(&e.to_string()),
+    }
+}
+
+fn error_event(message: &str) -> Result<Event, Infallible> {
+    Ok(Event::default()
+        .event("error")
+        .data(serde_json::json!({ "error": message }).to_string()))
+}
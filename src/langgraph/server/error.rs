@@ -0,0 +1,50 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use thiserror::Error;
+
+use crate::langgraph::{error::LangGraphError, persistence::PersistenceError};
+
+/// Errors returned by the graph server
+#[derive(Error, Debug)]
+pub enum ServerError {
+    #[error("Thread not found: {0}")]
+    ThreadNotFound(String),
+
+    #[error("No state found for thread: {0}")]
+    StateNotFound(String),
+
+    #[error("Bad request: {0}")]
+    BadRequest(String),
+
+    #[error("Graph error: {0}")]
+    Graph(#[from] LangGraphError),
+
+    #[error("Persistence error: {0}")]
+    Persistence(#[from] PersistenceError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl ServerError {
+    /// HTTP status code for this error
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Self::ThreadNotFound(_) | Self::StateNotFound(_) => StatusCode::NOT_FOUND,
+            Self::BadRequest(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::Graph(_) | Self::Persistence(_) | Self::Io(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
+
+impl IntoResponse for ServerError {
+    fn into_response(self) -> Response {
+        let body = Json(serde_json::json!({ "error": self.to_string() }));
+        (self.status_code(), body).into_response()
+    }
+}
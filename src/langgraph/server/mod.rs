@@ -0,0 +1,40 @@
+//! HTTP server for compiled graphs (requires the `server` feature)
+//!
+//! Exposes a `CompiledGraph` over LangGraph-Platform-style REST endpoints:
+//!
+//! | Method | Path | Description |
+//! |--------|------|-------------|
+//! | `POST` | `/threads` | Create a thread |
+//! | `GET` | `/threads/{thread_id}` | Get thread info |
+//! | `POST` | `/threads/{thread_id}/runs/wait` | Run (or resume) and wait for the result |
+//! | `POST` | `/threads/{thread_id}/runs/stream` | Run (or resume) and stream chunks over SSE |
+//! | `GET` | `/threads/{thread_id}/state` | Get the latest (or a given) state snapshot |
+//! | `POST` | `/threads/{thread_id}/state` | Update the thread state |
+//! | `GET` | `/threads/{thread_id}/history` | List state snapshots |
+//!
+//! Run requests carry either an `input` state or a `command` (e.g. `Command::resume`)
+//! to resume an interrupted thread. Responses are JSON-encoded `StreamChunk` and
+//! `StateSnapshot` values.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use std::sync::Arc;
+//! use langchain_ai_rust::langgraph::{server::GraphServer, InMemorySaver, MessagesState};
+//!
+//! let graph = build_graph().compile()?;
+//! let server = GraphServer::new(graph, Arc::new(InMemorySaver::<MessagesState>::new()));
+//! let listener = tokio::net::TcpListener::bind("0.0.0.0:8123").await?;
+//! server.serve(listener).await?;
+//! ```
+
+mod app;
+mod error;
+mod types;
+
+#[cfg(test)]
+mod tests;
+
+pub use app::*;
+pub use error::*;
+pub use types::*;
@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::langgraph::{
+    error::LangGraphError,
+    function_node,
+    interrupts::{interrupt, Command},
+    persistence::{InMemorySaver, StateSnapshot},
+    state::MessagesState,
+    streaming::StreamMode,
+    StateGraph, END, START,
+};
+use crate::schemas::messages::Message;
+
+use super::{GraphServer, RunRequest, RunResponse, ThreadInfo};
+
+fn ai_update(content: String) -> Result<HashMap<String, Value>, LangGraphError> {
+    let mut update = HashMap::new();
+    update.insert(
+        "messages".to_string(),
+        serde_json::to_value(vec![Message::new_ai_message(content)])?,
+    );
+    Ok(update)
+}
+
+fn approval_graph() -> crate::langgraph::CompiledGraph<MessagesState> {
+    let mut graph = StateGraph::<MessagesState>::new();
+    graph
+        .add_node(
+            "draft",
+            function_node("draft", |_state: &MessagesState| async move {
+                ai_update("draft".to_string())
+            }),
+        )
+        .unwrap();
+    graph
+        .add_node(
+            "approval",
+            function_node("approval", |_state: &MessagesState| async move {
+                let approved = interrupt("Approve?")
+                    .await
+                    .map_err(LangGraphError::InterruptError)?;
+                ai_update(format!("approved: {}", approved))
+            }),
+        )
+        .unwrap();
+    graph.add_edge(START, "draft");
+    graph.add_edge("draft", "approval");
+    graph.add_edge("approval", END);
+    graph.compile().unwrap()
+}
+
+async fn spawn_server() -> String {
+    let server = GraphServer::new(approval_graph(), Arc::new(InMemorySaver::new()));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { server.serve(listener).await });
+    format!("http://{}", addr)
+}
+
+async fn create_thread(client: &reqwest::Client, base: &str) -> String {
+    let thread: ThreadInfo = client
+        .post(format!("{}/threads", base))
+        .json(&json!({ "metadata": { "user": "test" } }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(thread.metadata.get("user"), Some(&json!("test")));
+    thread.thread_id
+}
+
+/// Parse an SSE body into (event, data) pairs
+fn parse_sse(body: &str) -> Vec<(String, Value)> {
+    body.split("\n\n")
+        .filter_map(|block| {
+            let mut event = None;
+            let mut data = String::new();
+            for line in block.lines() {
+                if let Some(name) = line.strip_prefix("event:") {
+                    event = Some(name.trim().to_string());
+                } else if let Some(chunk) = line.strip_prefix("data:") {
+                    data.push_str(chunk.trim());
+                }
+            }
+            event.map(|e| (e, serde_json::from_str(&data).unwrap_or(Value::Null)))
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_run_wait_interrupt_and_resume() {
+    let base = spawn_server().await;
+    let client = reqwest::Client::new();
+    let thread_id = create_thread(&client, &base).await;
+
+    let response: RunResponse<MessagesState> = client
+        .post(format!("{}/threads/{}/runs/wait", base, thread_id))
+        .json(&RunRequest::with_input(MessagesState::new()))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(response.interrupts.len(), 1);
+    assert_eq!(response.interrupts[0].value, json!("Approve?"));
+
+    let state: StateSnapshot<MessagesState> = client
+        .get(format!("{}/threads/{}/state", base, thread_id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(state.next, vec!["approval".to_string()]);
+
+    let resumed: RunResponse<MessagesState> = client
+        .post(format!("{}/threads/{}/runs/wait", base, thread_id))
+        .json(&RunRequest::<MessagesState>::with_command(Command::resume(
+            true,
+        )))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(resumed.interrupts.is_empty());
+    assert_eq!(
+        resumed.values.messages.last().unwrap().content,
+        "approved: true"
+    );
+
+    let history: Vec<StateSnapshot<MessagesState>> = client
+        .get(format!("{}/threads/{}/history", base, thread_id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 2);
+    assert!(history[1].next.is_empty());
+}
+
+#[tokio::test]
+async fn test_run_stream_modes() {
+    let base = spawn_server().await;
+    let client = reqwest::Client::new();
+    let thread_id = create_thread(&client, &base).await;
+
+    let request = RunRequest::with_input(MessagesState::new())
+        .with_stream_mode(vec![StreamMode::Updates, StreamMode::Values]);
+    let body = client
+        .post(format!("{}/threads/{}/runs/stream", base, thread_id))
+        .json(&request)
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    let events = parse_sse(&body);
+    let names: Vec<&str> = events.iter().map(|(e, _)| e.as_str()).collect();
+
+    assert_eq!(names.first(), Some(&"metadata"));
+    assert_eq!(names.last(), Some(&"end"));
+    assert!(names.contains(&"interrupt"));
+
+    let (_, update) = events.iter().find(|(e, _)| e == "updates").unwrap();
+    assert_eq!(update["mode"], json!("updates"));
+    assert_eq!(update["node"], json!("draft"));
+}
+
+#[tokio::test]
+async fn test_update_state_and_errors() {
+    let base = spawn_server().await;
+    let client = reqwest::Client::new();
+
+    let missing = client
+        .get(format!("{}/threads/missing/state", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(missing.status(), reqwest::StatusCode::NOT_FOUND);
+
+    let thread_id = create_thread(&client, &base).await;
+    let invalid = client
+        .post(format!("{}/threads/{}/runs/wait", base, thread_id))
+        .json(&json!({}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(invalid.status(), reqwest::StatusCode::UNPROCESSABLE_ENTITY);
+
+    client
+        .post(format!("{}/threads/{}/runs/wait", base, thread_id))
+        .json(&RunRequest::with_input(MessagesState::new()))
+        .send()
+        .await
+        .unwrap();
+
+    let updated: StateSnapshot<MessagesState> = client
+        .post(format!("{}/threads/{}/state", base, thread_id))
+        .json(&json!({
+            "values": { "messages": [Message::new_human_message("edited")] },
+            "as_node": "draft"
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(updated.metadata.get("as_node"), Some(&json!("draft")));
+    assert!(updated
+        .values
+        .messages
+        .iter()
+        .any(|m| m.content == "edited"));
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::langgraph::{
+    interrupts::{Command, Interrupt},
+    state::{State, StateUpdate},
+    streaming::StreamMode,
+};
+
+/// A conversation thread known to the server
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ThreadInfo {
+    pub thread_id: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub metadata: HashMap<String, Value>,
+}
+
+/// Body of `POST /threads`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CreateThreadRequest {
+    /// Thread id to use; generated when omitted
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    #[serde(default)]
+    pub metadata: HashMap<String, Value>,
+}
+
+/// Body of `POST /threads/{thread_id}/runs/wait` and `/runs/stream`
+///
+/// Exactly one of `input` or `command` must be set.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "S: Serialize + serde::de::DeserializeOwned")]
+pub struct RunRequest<S: State> {
+    /// Initial state for a new run
+    #[serde(default)]
+    pub input: Option<S>,
+    /// Command to resume an interrupted run
+    #[serde(default)]
+    pub command: Option<Command>,
+    /// Checkpoint to start from (time travel); only used with `input`
+    #[serde(default)]
+    pub checkpoint_id: Option<String>,
+    /// Stream modes for `/runs/stream` (defaults to `values`)
+    #[serde(default)]
+    pub stream_mode: Vec<StreamMode>,
+}
+
+impl<S: State> RunRequest<S> {
+    /// Create a run request with an input state
+    pub fn with_input(input: S) -> Self {
+        Self {
+            input: Some(input),
+            command: None,
+            checkpoint_id: None,
+            stream_mode: Vec::new(),
+        }
+    }
+
+    /// Create a run request that resumes with a command
+    pub fn with_command(command: Command) -> Self {
+        Self {
+            input: None,
+            command: Some(command),
+            checkpoint_id: None,
+            stream_mode: Vec::new(),
+        }
+    }
+
+    /// Set the stream modes
+    pub fn with_stream_mode(mut self, stream_mode: Vec<StreamMode>) -> Self {
+        self.stream_mode = stream_mode;
+        self
+    }
+}
+
+/// Response of `POST /threads/{thread_id}/runs/wait`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "S: Serialize + serde::de::DeserializeOwned")]
+pub struct RunResponse<S: State> {
+    pub run_id: String,
+    pub thread_id: String,
+    /// Final state, or the state at the interrupt point
+    pub values: S,
+    /// Pending interrupts (empty when the run completed)
+    #[serde(default)]
+    pub interrupts: Vec<Interrupt>,
+}
+
+/// Body of `POST /threads/{thread_id}/state`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct UpdateStateRequest {
+    pub values: StateUpdate,
+    #[serde(default)]
+    pub as_node: Option<String>,
+    /// Checkpoint to update; defaults to the latest
+    #[serde(default)]
+    pub checkpoint_id: Option<String>,
+}
+
+/// Query of `GET /threads/{thread_id}/state`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct StateQuery {
+    #[serde(default)]
+    pub checkpoint_id: Option<String>,
+}
+
+/// Query of `GET /threads/{thread_id}/history`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HistoryQuery {
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// Metadata sent as the first SSE event of a streamed run
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RunMetadata {
+    pub run_id: String,
+    pub thread_id: String,
+}
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::langgraph::state::{State, StateUpdate};
@@ -7,7 +8,13 @@ use super::metadata::{DebugInfo, MessageChunk};
 /// Stream chunk - represents a single item in a stream
 ///
 /// Different stream modes produce different types of chunks.
-#[derive(Clone, Debug)]
+/// Serialized as JSON tagged with its `mode`, e.g. `{"mode": "updates", "node": ..., "update": ...}`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(
+    tag = "mode",
+    rename_all = "lowercase",
+    bound = "S: Serialize + serde::de::DeserializeOwned"
+)]
 pub enum StreamChunk<S: State> {
     /// Full state value (values mode)
     Values { state: S },
@@ -92,7 +92,7 @@ impl DebugInfo {
 ///
 /// Represents a single token or message segment from an LLM
 /// along with its metadata.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct MessageChunk {
     /// The stream data (token content)
     pub chunk: StreamData,
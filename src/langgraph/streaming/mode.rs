@@ -1,7 +1,10 @@
+use serde::{Deserialize, Serialize};
+
 /// Stream mode for LangGraph streaming
 ///
 /// Determines what type of data is streamed during graph execution.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum StreamMode {
     /// Stream the full state value after each step
     Values,
@@ -68,4 +71,12 @@ mod tests {
         assert_eq!(StreamMode::Custom.as_str(), "custom");
         assert_eq!(StreamMode::Debug.as_str(), "debug");
     }
+
+    #[test]
+    fn test_stream_mode_serde() {
+        let json = serde_json::to_value(StreamMode::Updates).unwrap();
+        assert_eq!(json, serde_json::json!("updates"));
+        let mode: StreamMode = serde_json::from_value(serde_json::json!("messages")).unwrap();
+        assert_eq!(mode, StreamMode::Messages);
+    }
 }
@@ -1,6 +1,9 @@
+use std::future::Future;
 use std::sync::Arc;
 
+use async_stream::stream;
 use async_trait::async_trait;
+use futures::Stream;
 use serde_json::Value;
 use tokio::sync::mpsc;
 
@@ -28,15 +31,93 @@ impl ChannelStreamWriter {
     pub fn new(sender: mpsc::UnboundedSender<Value>) -> Self {
         Self { sender }
     }
+
+    /// Send custom data without awaiting; usable from sync code such as tool stream writers
+    pub fn send(&self, data: Value) -> Result<(), LangGraphError> {
+        self.sender
+            .send(data)
+            .map_err(|_| LangGraphError::StreamingError("Failed to send custom data".to_string()))
+    }
 }
 
 #[async_trait]
 impl StreamWriter for ChannelStreamWriter {
     async fn write(&self, data: Value) -> Result<(), LangGraphError> {
-        self.sender.send(data).map_err(|_| {
-            LangGraphError::StreamingError("Failed to send custom data".to_string())
-        })?;
-        Ok(())
+        self.send(data)
+    }
+}
+
+tokio::task_local! {
+    static CURRENT_STREAM_WRITER: Arc<ChannelStreamWriter>;
+}
+
+/// Stream writer of the node currently running, for `custom` stream mode
+///
+/// Returns `Some` inside a node executed by a streaming graph run (and anything the
+/// node awaits on the same task, such as an agent's tools); `None` otherwise.
+///
+/// ```rust,ignore
+/// if let Some(writer) = get_stream_writer() {
+///     let _ = writer.send(json!({"status": "fetching"}));
+/// }
+/// ```
+pub fn get_stream_writer() -> Option<Arc<ChannelStreamWriter>> {
+    CURRENT_STREAM_WRITER.try_with(Arc::clone).ok()
+}
+
+/// Run `future` with `writer` as the current stream writer
+pub(crate) async fn with_stream_writer<F: Future>(
+    writer: Arc<ChannelStreamWriter>,
+    future: F,
+) -> F::Output {
+    CURRENT_STREAM_WRITER.scope(writer, future).await
+}
+
+/// Run `future` with a fresh stream writer, passing everything written to `forward`
+pub(crate) async fn forward_custom_data<F: Future>(
+    future: F,
+    mut forward: impl FnMut(Value),
+) -> F::Output {
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let future = with_stream_writer(Arc::new(ChannelStreamWriter::new(sender)), future);
+    futures::pin_mut!(future);
+    let output = loop {
+        tokio::select! {
+            biased;
+            Some(data) = receiver.recv() => forward(data),
+            output = &mut future => break output,
+        }
+    };
+    while let Ok(data) = receiver.try_recv() {
+        forward(data);
+    }
+    output
+}
+
+/// Item of [node_steps]: data the node wrote, then its output
+pub(crate) enum NodeStep<T> {
+    Custom(Value),
+    Done(T),
+}
+
+/// Run `future` with a fresh stream writer, yielding the data written as it arrives
+/// and, last, the future's output
+pub(crate) fn node_steps<F: Future>(future: F) -> impl Stream<Item = NodeStep<F::Output>> {
+    stream! {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let future = with_stream_writer(Arc::new(ChannelStreamWriter::new(sender)), future);
+        futures::pin_mut!(future);
+        let output = loop {
+            tokio::select! {
+                biased;
+                Some(data) = receiver.recv() => yield NodeStep::Custom(data),
+                output = &mut future => break output,
+            }
+        };
+        while let Ok(data) = receiver.try_recv() {
+            yield NodeStep::Custom(data);
+        }
+        yield NodeStep::Done(output);
     }
 }
 
@@ -66,6 +147,33 @@ mod tests {
         assert_eq!(received, data);
     }
 
+    #[tokio::test]
+    async fn test_get_stream_writer_in_scope() {
+        assert!(get_stream_writer().is_none());
+
+        let mut received = Vec::new();
+        let output = forward_custom_data(
+            async {
+                let writer = get_stream_writer().unwrap();
+                writer.send(serde_json::json!({"step": 1})).unwrap();
+                sleep(Duration::from_millis(1)).await;
+                writer.send(serde_json::json!({"step": 2})).unwrap();
+                "done"
+            },
+            |data| received.push(data),
+        )
+        .await;
+
+        assert_eq!(output, "done");
+        assert_eq!(
+            received,
+            vec![
+                serde_json::json!({"step": 1}),
+                serde_json::json!({"step": 2})
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_stream_writer_multiple_writes() {
         let (writer, mut receiver) = create_stream_writer();
@@ -112,6 +112,14 @@ impl LLM for ConfigurableModel {
     fn messages_to_string(&self, messages: &[Message]) -> String {
         self.model.messages_to_string(messages)
     }
+
+    fn provider_name(&self) -> &str {
+        self.model.provider_name()
+    }
+
+    fn model_name(&self) -> Option<&str> {
+        self.model.model_name()
+    }
 }
 
 impl LLMClone for ConfigurableModel {
@@ -1,6 +1,8 @@
 use serde_json::Value;
 use std::collections::HashMap;
 
+use crate::callbacks::CallbackManager;
+
 /// Configuration for model invocations at runtime.
 ///
 /// This allows you to pass additional configuration when invoking a model,
@@ -24,6 +26,7 @@ pub struct InvocationConfig {
     pub metadata: HashMap<String, Value>,
     pub max_concurrency: Option<usize>,
     pub recursion_limit: Option<usize>,
+    pub callbacks: Option<CallbackManager>,
 }
 
 impl InvocationConfig {
@@ -66,6 +69,20 @@ impl InvocationConfig {
         self
     }
 
+    pub fn with_callbacks(mut self, callbacks: CallbackManager) -> Self {
+        self.callbacks = Some(callbacks);
+        self
+    }
+
+    /// The callback manager for this invocation, with the config's tags and metadata attached.
+    pub fn callback_manager(&self) -> Option<CallbackManager> {
+        self.callbacks.clone().map(|callbacks| {
+            callbacks
+                .with_tags(self.tags.clone())
+                .with_metadata(self.metadata.clone())
+        })
+    }
+
     pub fn merge(&mut self, other: InvocationConfig) {
         // Run name is not inherited - each invocation has its own
         // Tags are merged
@@ -74,13 +91,16 @@ impl InvocationConfig {
         for (k, v) in other.metadata {
             self.metadata.insert(k, v);
         }
-        // max_concurrency and recursion_limit prefer incoming if set
+        // max_concurrency, recursion_limit and callbacks prefer incoming if set
         if other.max_concurrency.is_some() {
             self.max_concurrency = other.max_concurrency;
         }
         if other.recursion_limit.is_some() {
             self.recursion_limit = other.recursion_limit;
         }
+        if other.callbacks.is_some() {
+            self.callbacks = other.callbacks;
+        }
     }
 }
 
@@ -3,7 +3,11 @@ use std::pin::Pin;
 use async_trait::async_trait;
 use futures::Stream;
 
-use crate::schemas::{Message, StreamData};
+use crate::{
+    callbacks::scope_callbacks,
+    schemas::{Message, StreamData},
+    telemetry,
+};
 
 use super::{invocation_config::InvocationConfig, options::CallOptions, GenerateResult, LLMError};
 
@@ -25,6 +29,16 @@ pub trait LLM: Sync + Send + LLMClone {
     fn add_options(&mut self, _options: CallOptions) {
         // No action taken
     }
+    /// Provider identifier reported as `gen_ai.system` in traces (e.g. `openai`, `anthropic`).
+    fn provider_name(&self) -> &str {
+        "unknown"
+    }
+
+    /// Model requested from the provider, reported as `gen_ai.request.model` in traces.
+    fn model_name(&self) -> Option<&str> {
+        None
+    }
+
     //This is usefull when using non chat models
     fn messages_to_string(&self, messages: &[Message]) -> String {
         messages
@@ -37,26 +51,42 @@ pub trait LLM: Sync + Send + LLMClone {
     /// Invoke the model with a prompt and optional invocation config.
     ///
     /// This is a convenience method that combines `invoke()` with invocation config.
-    /// The default implementation ignores the config and calls `invoke()`.
+    /// The default implementation reports the call to the config's callbacks and
+    /// otherwise behaves like `invoke()`, including an overridden one.
     /// Individual model implementations can override this to use the config.
     async fn invoke_with_config(
         &self,
         prompt: &str,
-        _config: Option<&InvocationConfig>,
+        config: Option<&InvocationConfig>,
     ) -> Result<String, LLMError> {
-        self.invoke(prompt).await
+        let callbacks = config.and_then(InvocationConfig::callback_manager);
+        let messages = [Message::new_human_message(prompt)];
+        let invoke = async {
+            self.invoke(prompt).await.map(|generation| GenerateResult {
+                generation,
+                tokens: None,
+            })
+        };
+        scope_callbacks(
+            callbacks.as_ref(),
+            telemetry::llm_call(self, &messages, invoke),
+        )
+        .await
+        .map(|res| res.generation)
     }
 
     /// Generate a response with optional invocation config.
     ///
-    /// The default implementation ignores the config and calls `generate()`.
+    /// The default implementation reports the call to the config's callbacks and
+    /// otherwise behaves like `generate()`.
     /// Individual model implementations can override this to use the config.
     async fn generate_with_config(
         &self,
         messages: &[Message],
-        _config: Option<&InvocationConfig>,
+        config: Option<&InvocationConfig>,
     ) -> Result<GenerateResult, LLMError> {
-        self.generate(messages).await
+        let callbacks = config.and_then(InvocationConfig::callback_manager);
+        scope_callbacks(callbacks.as_ref(), telemetry::generate(self, messages)).await
     }
 
     /// Batch process multiple prompts.
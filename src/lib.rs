@@ -41,8 +41,13 @@
 //!
 //! See the [repository](https://github.com/fanjia1024/langchain-rust) and [examples](https://github.com/fanjia1024/langchain-rust/tree/main/examples) for more.
 
+// Lets `#[tool]`, which refers to `::langchain_ai_rust`, be used inside this crate
+extern crate self as langchain_ai_rust;
+
 /// Agents: conversational and unified agents, tools, executor, middleware, Deep Agent.
 pub mod agent;
+/// Callbacks: handlers for LLM, chain, tool and retriever runs with run-tree tracking.
+pub mod callbacks;
 /// Chains: LLM, conversational, sequential, QA, SQL, RAG chains and options.
 pub mod chain;
 /// Document loaders: PDF, HTML, CSV, Git, S3, and more (feature-gated).
@@ -51,6 +56,8 @@ pub mod document_loaders;
 pub mod embedding;
 /// Unified error types and utilities.
 pub mod error;
+/// Indexing: record managers and incremental, deduplicated ingestion into vector stores.
+pub mod indexes;
 /// LangGraph: state graphs, streaming, persistence, subgraphs.
 pub mod langgraph;
 /// Common LLM/embedding traits and config.
@@ -71,6 +78,8 @@ pub mod retrievers;
 pub mod schemas;
 /// Semantic routing and routing layers.
 pub mod semantic_router;
+/// Tracing spans with OpenTelemetry GenAI attributes (feature `tracing`).
+pub mod telemetry;
 /// Text splitters and code splitters (tree-sitter when enabled).
 pub mod text_splitter;
 /// Tools: command, search, Wolfram, long-term memory, etc.
@@ -121,3 +130,10 @@ pub type EmbeddingF32 = Vec<f32>;
 
 /// Type alias for document list
 pub type Documents = Vec<crate::schemas::Document>;
+
+/// Re-exports used by code generated with `#[tool]`; not a public API.
+#[doc(hidden)]
+pub mod __macro_support {
+    pub use async_trait::async_trait;
+    pub use serde_json;
+}
@@ -132,6 +132,14 @@ impl Bedrock {
 
 #[async_trait]
 impl LLM for Bedrock {
+    fn provider_name(&self) -> &str {
+        "aws.bedrock"
+    }
+
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
     async fn generate(&self, messages: &[Message]) -> Result<GenerateResult, LLMError> {
         match &self.options.streaming_func {
             Some(func) => {
@@ -147,6 +147,14 @@ impl Claude {
 
 #[async_trait]
 impl LLM for Claude {
+    fn provider_name(&self) -> &str {
+        "anthropic"
+    }
+
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
     async fn generate(&self, messages: &[Message]) -> Result<GenerateResult, LLMError> {
         match &self.options.streaming_func {
             Some(func) => {
@@ -288,4 +296,34 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    async fn test_tool_message_with_image_uses_content_blocks() {
+        use crate::schemas::{ContentPart, ImageContent};
+
+        let payload = Claude::new().build_payload(
+            &[Message::new_tool_message_with_content(
+                vec![
+                    ContentPart::text("Took a screenshot"),
+                    ContentPart::image(ImageContent::from_base64("image/png", "iVBORw0KGgo")),
+                ],
+                "call_1",
+            )],
+            false,
+        );
+
+        assert_eq!(
+            serde_json::to_value(&payload.messages).unwrap(),
+            serde_json::json!([{
+                "role": "user",
+                "content": [
+                    { "type": "text", "text": "Took a screenshot" },
+                    {
+                        "type": "image",
+                        "source": { "type": "base64", "media_type": "image/png", "data": "iVBORw0KGgo" }
+                    }
+                ]
+            }])
+        );
+    }
 }
@@ -1,26 +1,95 @@
 use serde::{Deserialize, Serialize};
 
-use crate::schemas::{Message, MessageType};
+use crate::schemas::{parse_data_url, Message, MessageType};
 
 #[derive(Serialize, Deserialize)]
 pub(crate) struct ClaudeMessage {
     pub role: String,
-    pub content: String,
+    pub content: ClaudeContent,
 }
+
+/// Plain text, or content blocks when a message carries images or files
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum ClaudeContent {
+    Text(String),
+    Blocks(Vec<ContentBlock>),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ContentBlock {
+    Text { text: String },
+    Image { source: BlockSource },
+    Document { source: BlockSource },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum BlockSource {
+    Base64 { media_type: String, data: String },
+    Url { url: String },
+}
+
+impl BlockSource {
+    fn from_uri(uri: &str) -> Self {
+        match parse_data_url(uri) {
+            Some((media_type, data)) => Self::Base64 {
+                media_type: media_type.to_string(),
+                data: data.to_string(),
+            },
+            None => Self::Url {
+                url: uri.to_string(),
+            },
+        }
+    }
+}
+
 impl ClaudeMessage {
     pub fn new<S: Into<String>>(role: S, content: S) -> Self {
         Self {
             role: role.into(),
-            content: content.into(),
+            content: ClaudeContent::Text(content.into()),
         }
     }
 
     pub fn from_message(message: &Message) -> Self {
-        match message.message_type {
-            MessageType::SystemMessage => Self::new("system", &message.content),
-            MessageType::AIMessage => Self::new("assistant", &message.content),
-            MessageType::HumanMessage => Self::new("user", &message.content),
-            MessageType::ToolMessage => Self::new("tool", &message.content),
+        let role = match message.message_type {
+            MessageType::SystemMessage => "system",
+            MessageType::AIMessage => "assistant",
+            // Tool results go back as user turns, with any images and files they returned
+            MessageType::HumanMessage | MessageType::ToolMessage => "user",
+        };
+        if !message.has_attachments() {
+            return Self::new(role, &message.content);
+        }
+
+        let mut blocks = Vec::new();
+        if !message.content.is_empty() {
+            blocks.push(ContentBlock::Text {
+                text: message.content.clone(),
+            });
+        }
+        for image in message.images.iter().flatten() {
+            blocks.push(ContentBlock::Image {
+                source: BlockSource::from_uri(&image.image_url),
+            });
+        }
+        for file in message.files.iter().flatten() {
+            // Claude reads PDFs as documents; other files are referenced in text
+            blocks.push(if file.mime_type == "application/pdf" {
+                ContentBlock::Document {
+                    source: BlockSource::from_uri(&file.uri),
+                }
+            } else {
+                ContentBlock::Text {
+                    text: file.describe(),
+                }
+            });
+        }
+        Self {
+            role: role.to_string(),
+            content: ClaudeContent::Blocks(blocks),
         }
     }
 }
@@ -214,6 +214,14 @@ impl Deepseek {
 
 #[async_trait]
 impl LLM for Deepseek {
+    fn provider_name(&self) -> &str {
+        "deepseek"
+    }
+
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
     async fn generate(&self, messages: &[Message]) -> Result<GenerateResult, LLMError> {
         match &self.options.streaming_func {
             Some(func) => {
@@ -385,6 +393,7 @@ mod tests {
             message_type: MessageType::HumanMessage,
             id: Some("test_id".to_string()),
             images: None,
+            files: None,
             tool_calls: None,
         }];
 
@@ -401,6 +410,7 @@ mod tests {
             message_type: MessageType::HumanMessage,
             id: Some("test_id".to_string()),
             images: None,
+            files: None,
             tool_calls: None,
         }];
 
@@ -417,6 +427,7 @@ mod tests {
             message_type: MessageType::HumanMessage,
             id: Some("test_id".to_string()),
             images: None,
+            files: None,
             tool_calls: None,
         }];
 
@@ -196,6 +196,14 @@ impl Gemini {
 
 #[async_trait]
 impl LLM for Gemini {
+    fn provider_name(&self) -> &str {
+        "gcp.gemini"
+    }
+
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
     async fn generate(&self, messages: &[Message]) -> Result<GenerateResult, LLMError> {
         match &self.options.streaming_func {
             Some(func) => {
@@ -353,4 +361,41 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    async fn test_tool_message_with_attachments_uses_media_parts() {
+        use crate::schemas::{ContentPart, FileContent, ImageContent};
+
+        let payload = Gemini::new().build_payload(
+            &[Message::new_tool_message_with_content(
+                vec![
+                    ContentPart::text("Chart and report"),
+                    ContentPart::image(ImageContent::from_base64("image/png", "iVBORw0KGgo")),
+                    ContentPart::file(FileContent::new(
+                        "https://example.com/report.pdf",
+                        "application/pdf",
+                    )),
+                ],
+                "call_1",
+            )],
+            false,
+        );
+
+        assert_eq!(
+            serde_json::to_value(&payload.contents).unwrap(),
+            serde_json::json!([{
+                "role": "user",
+                "parts": [
+                    { "text": "Chart and report" },
+                    { "inlineData": { "mimeType": "image/png", "data": "iVBORw0KGgo" } },
+                    {
+                        "fileData": {
+                            "mimeType": "application/pdf",
+                            "fileUri": "https://example.com/report.pdf"
+                        }
+                    }
+                ]
+            }])
+        );
+    }
 }
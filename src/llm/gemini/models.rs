@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::schemas::{Message, MessageType};
+use crate::schemas::{parse_data_url, Message, MessageType};
 
 /// Google Gemini model options
 pub enum GeminiModel {
@@ -43,18 +43,84 @@ pub(crate) struct GeminiMessage {
     pub parts: Vec<Part>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub(crate) struct Part {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub text: String,
+    #[serde(
+        rename = "inlineData",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub inline_data: Option<InlineData>,
+    #[serde(rename = "fileData", default, skip_serializing_if = "Option::is_none")]
+    pub file_data: Option<FileData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct InlineData {
+    pub mime_type: String,
+    pub data: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FileData {
+    pub mime_type: String,
+    pub file_uri: String,
+}
+
+impl Part {
+    fn text<S: Into<String>>(text: S) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Inline data for base64 data URLs, a file reference otherwise
+    fn media(uri: &str, mime_type: &str) -> Self {
+        match parse_data_url(uri) {
+            Some((mime_type, data)) => Self {
+                inline_data: Some(InlineData {
+                    mime_type: mime_type.to_string(),
+                    data: data.to_string(),
+                }),
+                ..Default::default()
+            },
+            None => Self {
+                file_data: Some(FileData {
+                    mime_type: mime_type.to_string(),
+                    file_uri: uri.to_string(),
+                }),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Image media type guessed from the URL extension
+fn image_mime_type(url: &str) -> &'static str {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    match path
+        .rsplit('.')
+        .next()
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("png") => "image/png",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        _ => "image/jpeg",
+    }
 }
 
 impl GeminiMessage {
     pub fn new<S: Into<String>>(role: S, content: S) -> Self {
         Self {
             role: role.into(),
-            parts: vec![Part {
-                text: content.into(),
-            }],
+            parts: vec![Part::text(content)],
         }
     }
 
@@ -65,7 +131,23 @@ impl GeminiMessage {
             MessageType::HumanMessage => "user",
             MessageType::ToolMessage => "user",
         };
-        Self::new(role, &message.content)
+        let mut gemini_message = Self::new(role, &message.content);
+        if message.has_attachments() {
+            // Images and files (e.g. returned by tools) follow the text as media parts
+            gemini_message.parts.retain(|part| !part.text.is_empty());
+            for image in message.images.iter().flatten() {
+                gemini_message.parts.push(Part::media(
+                    &image.image_url,
+                    image_mime_type(&image.image_url),
+                ));
+            }
+            for file in message.files.iter().flatten() {
+                gemini_message
+                    .parts
+                    .push(Part::media(&file.uri, &file.mime_type));
+            }
+        }
+        gemini_message
     }
 }
 
@@ -171,6 +171,14 @@ impl HuggingFace {
 
 #[async_trait]
 impl LLM for HuggingFace {
+    fn provider_name(&self) -> &str {
+        "huggingface"
+    }
+
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
     async fn generate(&self, messages: &[Message]) -> Result<GenerateResult, LLMError> {
         match &self.options.streaming_func {
             Some(func) => {
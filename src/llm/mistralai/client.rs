@@ -173,6 +173,14 @@ impl MistralAI {
 
 #[async_trait]
 impl LLM for MistralAI {
+    fn provider_name(&self) -> &str {
+        "mistral_ai"
+    }
+
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
     async fn generate(&self, messages: &[Message]) -> Result<GenerateResult, LLMError> {
         match &self.options.streaming_func {
             Some(func) => {
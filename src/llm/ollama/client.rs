@@ -96,6 +96,14 @@ impl Default for Ollama {
 
 #[async_trait]
 impl LLM for Ollama {
+    fn provider_name(&self) -> &str {
+        "ollama"
+    }
+
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
     async fn generate(&self, messages: &[Message]) -> Result<GenerateResult, LLMError> {
         let request = self.generate_request(messages);
         let result = self.client.send_chat_messages(request).await?;
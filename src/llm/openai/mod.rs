@@ -8,10 +8,10 @@ use async_openai::{
     types::{
         ChatChoiceStream, ChatCompletionMessageToolCall, ChatCompletionRequestAssistantMessageArgs,
         ChatCompletionRequestMessage, ChatCompletionRequestMessageContentPartImageArgs,
-        ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestToolMessageArgs,
-        ChatCompletionRequestUserMessageArgs, ChatCompletionRequestUserMessageContent,
-        ChatCompletionRequestUserMessageContentPart, ChatCompletionStreamOptions,
-        CreateChatCompletionRequest, CreateChatCompletionRequestArgs,
+        ChatCompletionRequestMessageContentPartTextArgs, ChatCompletionRequestSystemMessageArgs,
+        ChatCompletionRequestToolMessageArgs, ChatCompletionRequestUserMessageArgs,
+        ChatCompletionRequestUserMessageContent, ChatCompletionRequestUserMessageContentPart,
+        ChatCompletionStreamOptions, CreateChatCompletionRequest, CreateChatCompletionRequestArgs,
     },
     Client,
 };
@@ -94,6 +94,14 @@ impl Default for OpenAI<OpenAIConfig> {
 
 #[async_trait]
 impl<C: Config + Send + Sync + 'static> LLM for OpenAI<C> {
+    fn provider_name(&self) -> &str {
+        "openai"
+    }
+
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
     async fn generate(&self, prompt: &[Message]) -> Result<GenerateResult, LLMError> {
         let client = Client::with_config(self.config.clone());
         let request = self.generate_request(prompt, self.options.streaming_func.is_some())?;
@@ -211,13 +219,56 @@ impl<C: Config + Send + Sync + 'static> LLM for OpenAI<C> {
     }
 }
 
+/// Message text followed by references to its files, which chat completions cannot take
+fn text_with_files(message: &Message) -> String {
+    let mut text = message.content.clone();
+    for file in message.files.iter().flatten() {
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(&file.describe());
+    }
+    text
+}
+
+fn image_parts(
+    message: &Message,
+) -> Result<Vec<ChatCompletionRequestUserMessageContentPart>, OpenAIError> {
+    message
+        .images
+        .iter()
+        .flatten()
+        .map(|image| {
+            Ok(ChatCompletionRequestMessageContentPartImageArgs::default()
+                .image_url(image.image_url.clone())
+                .build()?
+                .into())
+        })
+        .collect()
+}
+
+fn tool_images_message(
+    parts: Vec<ChatCompletionRequestUserMessageContentPart>,
+) -> Result<ChatCompletionRequestMessage, OpenAIError> {
+    Ok(ChatCompletionRequestUserMessageArgs::default()
+        .content(parts)
+        .build()?
+        .into())
+}
+
 impl<C: Config> OpenAI<C> {
     fn to_openai_messages(
         &self,
         messages: &[Message],
     ) -> Result<Vec<ChatCompletionRequestMessage>, LLMError> {
         let mut openai_messages: Vec<ChatCompletionRequestMessage> = Vec::new();
+        // Tool messages only take text, so images returned by tools follow the run of
+        // tool messages as a user message
+        let mut tool_images: Vec<ChatCompletionRequestUserMessageContentPart> = Vec::new();
         for m in messages {
+            if m.message_type != MessageType::ToolMessage && !tool_images.is_empty() {
+                openai_messages.push(tool_images_message(std::mem::take(&mut tool_images))?);
+            }
             match m.message_type {
                 MessageType::AIMessage => openai_messages.push(match &m.tool_calls {
                     Some(value) => {
@@ -235,24 +286,21 @@ impl<C: Config> OpenAI<C> {
                         .into(),
                 }),
                 MessageType::HumanMessage => {
-                    let content: ChatCompletionRequestUserMessageContent = match m.images.clone() {
-                        Some(images) => {
-                            let content: Result<
-                                Vec<ChatCompletionRequestUserMessageContentPart>,
-                                OpenAIError,
-                            > = images
-                                .into_iter()
-                                .map(|image| {
-                                    Ok(ChatCompletionRequestMessageContentPartImageArgs::default()
-                                        .image_url(image.image_url)
-                                        .build()?
-                                        .into())
-                                })
-                                .collect();
-
-                            content?.into()
+                    let content: ChatCompletionRequestUserMessageContent = if m.has_attachments() {
+                        let mut parts = Vec::new();
+                        let text = text_with_files(m);
+                        if !text.is_empty() {
+                            parts.push(
+                                ChatCompletionRequestMessageContentPartTextArgs::default()
+                                    .text(text)
+                                    .build()?
+                                    .into(),
+                            );
                         }
-                        None => m.content.clone().into(),
+                        parts.extend(image_parts(m)?);
+                        parts.into()
+                    } else {
+                        m.content.clone().into()
                     };
 
                     openai_messages.push(
@@ -269,16 +317,30 @@ impl<C: Config> OpenAI<C> {
                         .into(),
                 ),
                 MessageType::ToolMessage => {
+                    let tool_call_id = m.id.clone().unwrap_or_default();
+                    let images = image_parts(m)?;
+                    if !images.is_empty() {
+                        tool_images.push(
+                            ChatCompletionRequestMessageContentPartTextArgs::default()
+                                .text(format!("Images returned by tool call {}:", tool_call_id))
+                                .build()?
+                                .into(),
+                        );
+                        tool_images.extend(images);
+                    }
                     openai_messages.push(
                         ChatCompletionRequestToolMessageArgs::default()
-                            .content(m.content.clone())
-                            .tool_call_id(m.id.clone().unwrap_or_default())
+                            .content(text_with_files(m))
+                            .tool_call_id(tool_call_id)
                             .build()?
                             .into(),
                     );
                 }
             }
         }
+        if !tool_images.is_empty() {
+            openai_messages.push(tool_images_message(tool_images)?);
+        }
         Ok(openai_messages)
     }
 
@@ -506,4 +568,40 @@ mod tests {
         let response = open_ai.generate(&messages).await.unwrap();
         println!("Response: {:?}", response);
     }
+
+    #[test]
+    async fn test_tool_images_follow_tool_messages() {
+        use crate::schemas::{ContentPart, ImageContent};
+
+        let open_ai = OpenAI::new(OpenAIConfig::default());
+        let tool_calls = json!([
+            { "id": "call_1", "type": "function", "function": { "name": "screenshot", "arguments": "{}" } },
+            { "id": "call_2", "type": "function", "function": { "name": "clock", "arguments": "{}" } }
+        ]);
+        let messages = vec![
+            Message::new_ai_message("").with_tool_calls(tool_calls),
+            Message::new_tool_message_with_content(
+                vec![
+                    ContentPart::text("Took a screenshot"),
+                    ContentPart::image(ImageContent::from_base64("image/png", "iVBORw0KGgo")),
+                ],
+                "call_1",
+            ),
+            Message::new_tool_message("12:00", "call_2"),
+        ];
+
+        let request = serde_json::to_value(open_ai.to_openai_messages(&messages).unwrap()).unwrap();
+        let roles: Vec<_> = request
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|message| message["role"].as_str().unwrap())
+            .collect();
+        assert_eq!(roles, vec!["assistant", "tool", "tool", "user"]);
+        assert_eq!(request[1]["content"], "Took a screenshot");
+        assert_eq!(
+            request[3]["content"][1]["image_url"]["url"],
+            "data:image/png;base64,iVBORw0KGgo"
+        );
+    }
 }
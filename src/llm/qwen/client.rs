@@ -418,6 +418,14 @@ impl Qwen {
 
 #[async_trait]
 impl LLM for Qwen {
+    fn provider_name(&self) -> &str {
+        "qwen"
+    }
+
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
     async fn generate(&self, messages: &[Message]) -> Result<GenerateResult, LLMError> {
         match &self.options.streaming_func {
             Some(func) => {
@@ -6,6 +6,7 @@ use serde_json::Value;
 
 use crate::{
     schemas::{Document, Retriever},
+    telemetry,
     tools::{Tool, ToolResult, ToolRuntime},
 };
 
@@ -96,7 +97,8 @@ impl Tool for RetrieverTool {
         };
 
         // Retrieve documents
-        let documents = self.retriever.get_relevant_documents(&query).await?;
+        let documents =
+            telemetry::retrieve(&query, self.retriever.get_relevant_documents(&query)).await?;
 
         // Limit the number of documents
         let limited_docs: Vec<&Document> = documents.iter().take(self.max_docs).collect();
@@ -9,6 +9,7 @@ use crate::{
     memory::SimpleMemory,
     prompt::PromptArgs,
     schemas::{BaseMemory, Document, Retriever},
+    telemetry,
 };
 
 // Wrapper to convert Arc<dyn Retriever> to Box<dyn Retriever>
@@ -136,11 +137,12 @@ impl HybridRAG {
         // Step 2 & 3: Retrieval with Validation (with retries)
         let mut retrieval_attempts = 0;
         loop {
-            documents = self
-                .retriever
-                .get_relevant_documents(&current_query)
-                .await
-                .map_err(|e| RAGError::RetrieverError(e.to_string()))?;
+            documents = telemetry::retrieve(
+                &current_query,
+                self.retriever.get_relevant_documents(&current_query),
+            )
+            .await
+            .map_err(|e| RAGError::RetrieverError(e.to_string()))?;
 
             // Validate retrieval if enabled
             if self.config.enable_retrieval_validation {
@@ -7,6 +7,7 @@ use async_trait::async_trait;
 use crate::error::RetrieverError;
 use crate::language_models::llm::LLM;
 use crate::schemas::{Document, Retriever};
+use crate::telemetry;
 
 /// Configuration for Multi Query retriever
 #[derive(Debug, Clone)]
@@ -74,7 +75,7 @@ impl MultiQueryRetriever {
         let messages = vec![crate::schemas::messages::Message::new_human_message(
             &prompt,
         )];
-        let result = self.llm.generate(&messages).await?;
+        let result = telemetry::generate(self.llm.as_ref(), &messages).await?;
 
         // Parse the generated queries (split by newlines)
         let queries: Vec<String> = result
@@ -6,6 +6,7 @@ use async_trait::async_trait;
 use crate::error::RetrieverError;
 use crate::language_models::llm::LLM;
 use crate::schemas::{Document, Retriever};
+use crate::telemetry;
 
 /// Configuration for RePhrase Query retriever
 #[derive(Debug, Clone)]
@@ -65,7 +66,7 @@ impl RePhraseQueryRetriever {
         let messages = vec![crate::schemas::messages::Message::new_human_message(
             &prompt,
         )];
-        let result = self.llm.generate(&messages).await?;
+        let result = telemetry::generate(self.llm.as_ref(), &messages).await?;
 
         Ok(result.generation.trim().to_string())
     }
@@ -3,17 +3,38 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 
+use super::ContentPart;
+
 pub enum ToolInput {
     //Will implement this in the future
     StrInput(String),
     DictInput(HashMap<String, String>),
 }
 
+/// A tool call an agent decided on
+///
+/// Build actions with [`AgentAction::new`]; more fields may be added.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct AgentAction {
     pub tool: String,
     pub tool_input: String, //this should be ToolInput in the future
     pub log: String,
+    /// Images and files the tool returned, set by the executor once the tool ran.
+    /// Agents show them to the model next to the step's observation.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<ContentPart>,
+}
+
+impl AgentAction {
+    pub fn new<S: Into<String>>(tool: S, tool_input: S, log: S) -> Self {
+        Self {
+            tool: tool.into(),
+            tool_input: tool_input.into(),
+            log: log.into(),
+            attachments: Vec::new(),
+        }
+    }
 }
 
 ///Log tools is a struct used by the openai-like agents
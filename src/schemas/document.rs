@@ -7,6 +7,8 @@ use serde_json::Value;
 /// The `page_content` field is a string that contains the content of the document.
 /// The `metadata` field is a `HashMap` where the keys represent metadata properties and the values represent property values.
 /// The `score` field represents a relevance score for the document and is a floating point number.
+/// The optional `id` field identifies the document in a vector store; adding a document with an id
+/// replaces the stored document with the same id.
 ///
 /// # Usage
 /// ```rust,ignore
@@ -16,22 +18,26 @@ use serde_json::Value;
 ///       metadata.insert("author".to_string(), json!("John Doe"));
 ///       metadata
 ///   })
-///    .with_score(0.75);
+///    .with_score(0.75)
+///    .with_id("doc-1");
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Document {
     pub page_content: String,
     pub metadata: HashMap<String, Value>,
     pub score: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
 }
 
 impl Document {
-    /// Constructs a new `Document` with provided `page_content`, an empty `metadata` map and a `score` of 0.
+    /// Constructs a new `Document` with provided `page_content`, an empty `metadata` map, a `score` of 0 and no `id`.
     pub fn new<S: Into<String>>(page_content: S) -> Self {
         Document {
             page_content: page_content.into(),
             metadata: HashMap::new(),
             score: 0.0,
+            id: None,
         }
     }
 
@@ -46,15 +52,22 @@ impl Document {
         self.score = score;
         self
     }
+
+    /// Sets the `id` of the `Document` to the provided string.
+    pub fn with_id<S: Into<String>>(mut self, id: S) -> Self {
+        self.id = Some(id.into());
+        self
+    }
 }
 
 impl Default for Document {
-    /// Provides a default `Document` with an empty `page_content`, an empty `metadata` map and a `score` of 0.
+    /// Provides a default `Document` with an empty `page_content`, an empty `metadata` map, a `score` of 0 and no `id`.
     fn default() -> Self {
         Document {
             page_content: "".to_string(),
             metadata: HashMap::new(),
             score: 0.0,
+            id: None,
         }
     }
 }
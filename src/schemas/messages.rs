@@ -41,12 +41,104 @@ impl MessageType {
 }
 
 /// Struct `ImageContent` represents an image provided to an LLM.
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+///
+/// `image_url` is either a URL or a base64 data URL (`data:image/png;base64,...`).
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
 pub struct ImageContent {
     pub image_url: String,
     pub detail: Option<String>,
 }
 
+impl ImageContent {
+    /// Image from base64-encoded bytes, e.g. a screenshot or a rendered chart
+    pub fn from_base64(mime_type: &str, data: impl AsRef<str>) -> Self {
+        data_url(mime_type, data.as_ref()).into()
+    }
+
+    /// Media type and base64 data, if the image is a data URL
+    pub fn base64_data(&self) -> Option<(&str, &str)> {
+        parse_data_url(&self.image_url)
+    }
+}
+
+/// A file given to an LLM by reference, e.g. a PDF report a tool produced.
+///
+/// `uri` is a URL, a provider file URI or a base64 data URL.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct FileContent {
+    pub uri: String,
+    pub mime_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl FileContent {
+    pub fn new<S: Into<String>>(uri: S, mime_type: S) -> Self {
+        Self {
+            uri: uri.into(),
+            mime_type: mime_type.into(),
+            name: None,
+        }
+    }
+
+    /// File from base64-encoded bytes
+    pub fn from_base64(mime_type: &str, data: impl AsRef<str>) -> Self {
+        Self::new(data_url(mime_type, data.as_ref()), mime_type.to_string())
+    }
+
+    pub fn with_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Base64 data, if the file is a data URL
+    pub fn base64_data(&self) -> Option<&str> {
+        parse_data_url(&self.uri).map(|(_, data)| data)
+    }
+
+    /// Text standing in for the file where a provider cannot take files
+    pub fn describe(&self) -> String {
+        let name = self.name.as_deref().unwrap_or("file");
+        match self.base64_data() {
+            Some(_) => format!("[{}: {}, inline data omitted]", name, self.mime_type),
+            None => format!("[{}: {}, {}]", name, self.mime_type, self.uri),
+        }
+    }
+}
+
+/// A part of multimodal content, e.g. what a tool returns to the model
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentPart {
+    Text { text: String },
+    Image(ImageContent),
+    File(FileContent),
+}
+
+impl ContentPart {
+    pub fn text<S: Into<String>>(text: S) -> Self {
+        Self::Text { text: text.into() }
+    }
+
+    pub fn image<T: Into<ImageContent>>(image: T) -> Self {
+        Self::Image(image.into())
+    }
+
+    pub fn file(file: FileContent) -> Self {
+        Self::File(file)
+    }
+}
+
+fn data_url(mime_type: &str, data: &str) -> String {
+    format!("data:{};base64,{}", mime_type, data)
+}
+
+/// Split a base64 data URL into its media type and data
+pub fn parse_data_url(url: &str) -> Option<(&str, &str)> {
+    let (mime_type, data) = url.strip_prefix("data:")?.split_once(',')?;
+    Some((mime_type.strip_suffix(";base64")?, data))
+}
+
 impl<S: AsRef<str>> From<S> for ImageContent {
     fn from(image_url: S) -> Self {
         ImageContent {
@@ -64,16 +156,30 @@ impl<S: AsRef<str>> From<S> for ImageContent {
 /// let system_message = Message::new_system_message("System Alert");
 /// let ai_message = Message::new_ai_message("AI Response");
 /// ```
+///
+/// Build messages with the constructors; more fields may be added.
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[non_exhaustive]
 pub struct Message {
     pub content: String,
     pub message_type: MessageType,
     pub id: Option<String>,
     pub tool_calls: Option<Value>,
     pub images: Option<Vec<ImageContent>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub files: Option<Vec<FileContent>>,
 }
 
 impl Message {
+    /// Message of any type, e.g. one read back from storage
+    pub fn new<T: std::fmt::Display>(message_type: MessageType, content: T) -> Self {
+        Message {
+            content: content.to_string(),
+            message_type,
+            ..Default::default()
+        }
+    }
+
     // Function to create a new Human message with a generic type that implements Display
     pub fn new_human_message<T: std::fmt::Display>(content: T) -> Self {
         Message {
@@ -82,6 +188,7 @@ impl Message {
             id: None,
             tool_calls: None,
             images: None,
+            files: None,
         }
     }
 
@@ -92,6 +199,7 @@ impl Message {
             id: None,
             tool_calls: None,
             images: Some(images.into_iter().map(|i| i.into()).collect()),
+            files: None,
         }
     }
 
@@ -103,6 +211,7 @@ impl Message {
             id: None,
             tool_calls: None,
             images: None,
+            files: None,
         }
     }
 
@@ -114,6 +223,7 @@ impl Message {
             id: None,
             tool_calls: None,
             images: None,
+            files: None,
         }
     }
 
@@ -125,7 +235,39 @@ impl Message {
             id: Some(id.into()),
             tool_calls: None,
             images: None,
+            files: None,
+        }
+    }
+
+    /// Tool message with multimodal content: text parts become the content, images and
+    /// files are attached
+    pub fn new_tool_message_with_content<S: Into<String>>(parts: Vec<ContentPart>, id: S) -> Self {
+        Self::new_tool_message("", id).with_content_parts(parts)
+    }
+
+    /// Add content parts: text is appended on a new line, images and files are attached
+    pub fn with_content_parts(mut self, parts: Vec<ContentPart>) -> Self {
+        for part in parts {
+            match part {
+                ContentPart::Text { text } => {
+                    if !self.content.is_empty() {
+                        self.content.push('\n');
+                    }
+                    self.content.push_str(&text);
+                }
+                ContentPart::Image(image) => self.images.get_or_insert_with(Vec::new).push(image),
+                ContentPart::File(file) => self.files.get_or_insert_with(Vec::new).push(file),
+            }
         }
+        self
+    }
+
+    /// Whether the message carries images or files besides its text
+    pub fn has_attachments(&self) -> bool {
+        self.images
+            .as_ref()
+            .is_some_and(|images| !images.is_empty())
+            || self.files.as_ref().is_some_and(|files| !files.is_empty())
     }
 
     /// Sets the tool calls for the OpenAI-like API call.
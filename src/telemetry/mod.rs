@@ -0,0 +1,633 @@
+//! Run instrumentation: tracing spans and [callbacks](crate::callbacks).
+//!
+//! LLM calls, chains, tools, retrievers, LangGraph nodes and agent runs go through the
+//! helpers in this module, which report them to the [`CallbackManager`](crate::callbacks::CallbackManager)
+//! in scope and, with the `tracing` feature enabled, open [`tracing`](https://docs.rs/tracing)
+//! spans following the OpenTelemetry GenAI semantic conventions. Install a
+//! `tracing-opentelemetry` layer in your service and these spans show up in Jaeger (or any
+//! OTLP backend) nested under your own request spans.
+//!
+//! Recorded span attributes:
+//! - LLM spans (`chat {model}`): `gen_ai.operation.name`, `gen_ai.system`,
+//!   `gen_ai.request.model`, `gen_ai.usage.input_tokens`, `gen_ai.usage.output_tokens`
+//! - Tool spans (`execute_tool {name}`): `gen_ai.tool.name`, `gen_ai.tool.call.id`
+//! - Agent spans (`invoke_agent {name}`): `gen_ai.agent.name`, plus one
+//!   `agent_iteration` child span per planning step
+//! - Chain, retriever and node spans: `langchain.chain.name`, `langchain.retriever.documents`,
+//!   `langgraph.node.name`
+//! - Every span: `latency_ms`, and `otel.status_code` / `error.type` on failure
+//!
+//! Prompts, completions, tool arguments/results and retriever queries can contain user
+//! data, so they are only recorded on spans after [`set_capture_content`] is turned on.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use opentelemetry::trace::TracerProvider;
+//! use tracing_subscriber::prelude::*;
+//!
+//! let provider = opentelemetry_sdk::trace::SdkTracerProvider::builder()
+//!     .with_batch_exporter(opentelemetry_otlp::SpanExporter::builder().with_tonic().build()?)
+//!     .build();
+//! tracing_subscriber::registry()
+//!     .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("my-service")))
+//!     .init();
+//!
+//! // Opt in to recording prompts and completions
+//! langchain_ai_rust::telemetry::set_capture_content(true);
+//! ```
+
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Instant,
+};
+
+use futures::{Stream, StreamExt};
+use serde::Serialize;
+use serde_json::{json, Value};
+#[cfg(feature = "tracing")]
+use tracing::{field::Empty, Instrument, Span};
+
+use crate::{
+    callbacks::{CallbackRun, RunType},
+    language_models::{llm::LLM, GenerateResult, LLMError, TokenUsage},
+    schemas::{Document, Message, StreamData},
+};
+
+static CAPTURE_CONTENT: AtomicBool = AtomicBool::new(false);
+
+/// Record prompts, completions, tool arguments/results and retriever queries on spans.
+///
+/// Off by default. Callback handlers always receive content.
+pub fn set_capture_content(enabled: bool) {
+    CAPTURE_CONTENT.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether message content is recorded on spans (see [`set_capture_content`]).
+pub fn capture_content() -> bool {
+    CAPTURE_CONTENT.load(Ordering::Relaxed)
+}
+
+/// Stand-in for `tracing::Span` when the `tracing` feature is off.
+#[cfg(not(feature = "tracing"))]
+#[derive(Clone)]
+struct Span;
+
+#[cfg(not(feature = "tracing"))]
+impl Span {
+    fn record<V>(&self, _field: &str, _value: V) -> &Self {
+        self
+    }
+}
+
+/// `tracing::info_span!` with the `tracing` feature, a no-op [`Span`] without it.
+macro_rules! span {
+    ($($args:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        {
+            tracing::info_span!($($args)*)
+        }
+        #[cfg(not(feature = "tracing"))]
+        {
+            Span
+        }
+    }};
+}
+
+/// Call [`LLM::generate`] as an LLM run inside a `chat` span.
+pub(crate) async fn generate<L: LLM + ?Sized>(
+    llm: &L,
+    messages: &[Message],
+) -> Result<GenerateResult, LLMError> {
+    llm_call(llm, messages, llm.generate(messages)).await
+}
+
+/// Report `fut`, a call to `llm` with `messages`, as an LLM run inside a `chat` span.
+pub(crate) async fn llm_call<L, F>(
+    llm: &L,
+    messages: &[Message],
+    fut: F,
+) -> Result<GenerateResult, LLMError>
+where
+    L: LLM + ?Sized,
+    F: Future<Output = Result<GenerateResult, LLMError>>,
+{
+    let span = chat_span(llm, messages);
+    let run = llm_run(llm);
+    run.emit(|handler, info| handler.on_llm_start(info, messages));
+
+    let result = instrument(&span, fut).await;
+    match &result {
+        Ok(output) => {
+            if let Some(tokens) = &output.tokens {
+                record_usage(&span, tokens);
+            }
+            if capture_content() {
+                span.record("gen_ai.output.messages", output.generation.as_str());
+            }
+            run.emit(|handler, info| handler.on_llm_end(info, output));
+        }
+        Err(e) => {
+            let error = e.to_string();
+            run.emit(|handler, info| handler.on_llm_error(info, &error));
+        }
+    }
+    result
+}
+
+/// Call [`LLM::stream`] as an LLM run inside a `chat` span that stays open until the
+/// stream is drained. Each chunk is reported through `on_llm_new_token`.
+pub(crate) async fn stream<L: LLM + ?Sized>(
+    llm: &L,
+    messages: &[Message],
+) -> Result<Pin<Box<dyn Stream<Item = Result<StreamData, LLMError>> + Send>>, LLMError> {
+    let span = chat_span(llm, messages);
+    let run = llm_run(llm);
+    run.emit(|handler, info| handler.on_llm_start(info, messages));
+
+    let start = Instant::now();
+    let mut inner = match in_span(&span, llm.stream(messages)).await {
+        Ok(inner) => inner,
+        Err(e) => {
+            record_error(&span, &e);
+            let error = e.to_string();
+            run.emit(|handler, info| handler.on_llm_error(info, &error));
+            return Err(e);
+        }
+    };
+
+    Ok(Box::pin(async_stream::stream! {
+        let mut output = GenerateResult::default();
+        let mut failed = false;
+        while let Some(item) = in_span(&span, inner.next()).await {
+            match &item {
+                Ok(data) => {
+                    if let Some(tokens) = &data.tokens {
+                        record_usage(&span, tokens);
+                        output.tokens = Some(tokens.clone());
+                    }
+                    if !data.content.is_empty() {
+                        run.emit(|handler, info| handler.on_llm_new_token(info, &data.content));
+                    }
+                    output.generation.push_str(&data.content);
+                }
+                Err(e) => {
+                    failed = true;
+                    record_error(&span, e);
+                    let error = e.to_string();
+                    run.emit(|handler, info| handler.on_llm_error(info, &error));
+                }
+            }
+            yield item;
+        }
+        if capture_content() {
+            span.record("gen_ai.output.messages", output.generation.as_str());
+        }
+        span.record("latency_ms", start.elapsed().as_millis() as u64);
+        if !failed {
+            run.emit(|handler, info| handler.on_llm_end(info, &output));
+        }
+    }))
+}
+
+/// Run a chain as a chain run inside a `chain` span.
+pub(crate) async fn chain<T, E, F>(name: &str, inputs: &impl Serialize, fut: F) -> Result<T, E>
+where
+    T: Serialize,
+    E: Display,
+    F: Future<Output = Result<T, E>>,
+{
+    let span = span!(
+        "chain",
+        otel.name = %format!("chain {}", name),
+        langchain.chain.name = name,
+        latency_ms = Empty,
+        otel.status_code = Empty,
+        error.type = Empty,
+    );
+    observe_chain(span, name, inputs, fut).await
+}
+
+/// Run a LangGraph node as a chain run inside a `node` span.
+pub(crate) async fn node<T, E, F>(name: &str, state: &impl Serialize, fut: F) -> Result<T, E>
+where
+    T: Serialize,
+    E: Display,
+    F: Future<Output = Result<T, E>>,
+{
+    let span = span!(
+        "node",
+        otel.name = %format!("node {}", name),
+        langgraph.node.name = name,
+        latency_ms = Empty,
+        otel.status_code = Empty,
+        error.type = Empty,
+    );
+    observe_chain(span, name, state, fut).await
+}
+
+/// Run an agent loop as a chain run inside an `invoke_agent` span.
+pub(crate) async fn agent<T, E, F>(name: &str, inputs: &impl Serialize, fut: F) -> Result<T, E>
+where
+    T: Serialize,
+    E: Display,
+    F: Future<Output = Result<T, E>>,
+{
+    let span = span!(
+        "invoke_agent",
+        otel.name = %format!("invoke_agent {}", name),
+        gen_ai.operation.name = "invoke_agent",
+        gen_ai.agent.name = name,
+        latency_ms = Empty,
+        otel.status_code = Empty,
+        error.type = Empty,
+    );
+    observe_chain(span, name, inputs, fut).await
+}
+
+/// Run a tool call as a tool run inside an `execute_tool` span.
+pub(crate) async fn tool<T, F>(
+    name: &str,
+    tool_call_id: &str,
+    input: &str,
+    fut: F,
+) -> Result<T, String>
+where
+    T: AsRef<str>,
+    F: Future<Output = Result<T, String>>,
+{
+    #[cfg(not(feature = "tracing"))]
+    let _ = tool_call_id;
+    let span = span!(
+        "execute_tool",
+        otel.name = %format!("execute_tool {}", name),
+        gen_ai.operation.name = "execute_tool",
+        gen_ai.tool.name = name,
+        gen_ai.tool.call.id = tool_call_id,
+        gen_ai.tool.call.arguments = Empty,
+        gen_ai.tool.call.result = Empty,
+        latency_ms = Empty,
+        otel.status_code = Empty,
+        error.type = Empty,
+    );
+    if capture_content() {
+        span.record("gen_ai.tool.call.arguments", input);
+    }
+    let run = CallbackRun::start(RunType::Tool, name);
+    run.emit(|handler, info| handler.on_tool_start(info, input));
+
+    let result = instrument(&span, run.scope(fut)).await;
+    match &result {
+        Ok(output) => {
+            if capture_content() {
+                span.record("gen_ai.tool.call.result", output.as_ref());
+            }
+            run.emit(|handler, info| handler.on_tool_end(info, output.as_ref()));
+        }
+        Err(error) => run.emit(|handler, info| handler.on_tool_error(info, error)),
+    }
+    result
+}
+
+/// Run a retriever lookup as a retriever run inside a `retrieve` span.
+pub(crate) async fn retrieve<E, F>(query: &str, fut: F) -> Result<Vec<Document>, E>
+where
+    E: Display,
+    F: Future<Output = Result<Vec<Document>, E>>,
+{
+    let span = span!(
+        "retrieve",
+        langchain.retriever.query = Empty,
+        langchain.retriever.documents = Empty,
+        latency_ms = Empty,
+        otel.status_code = Empty,
+        error.type = Empty,
+    );
+    if capture_content() {
+        span.record("langchain.retriever.query", query);
+    }
+    let run = CallbackRun::start(RunType::Retriever, "retriever");
+    run.emit(|handler, info| handler.on_retriever_start(info, query));
+
+    let result = instrument(&span, fut).await;
+    match &result {
+        Ok(documents) => {
+            span.record("langchain.retriever.documents", documents.len() as u64);
+            run.emit(|handler, info| handler.on_retriever_end(info, documents));
+        }
+        Err(e) => {
+            let error = e.to_string();
+            run.emit(|handler, info| handler.on_retriever_error(info, &error));
+        }
+    }
+    result
+}
+
+/// Open an `agent_iteration` span for one planning step of an agent.
+///
+/// The model call and the tool calls of that step run inside it via [`ActiveSpan::run`].
+pub(crate) fn agent_iteration(iteration: usize) -> ActiveSpan {
+    #[cfg(not(feature = "tracing"))]
+    let _ = iteration;
+    ActiveSpan {
+        span: span!(
+            "agent_iteration",
+            langchain.agent.iteration = iteration as u64,
+            latency_ms = Empty,
+            otel.status_code = Empty,
+            error.type = Empty,
+        ),
+        start: Instant::now(),
+    }
+}
+
+/// A span that several futures run inside; its latency is recorded when dropped.
+pub(crate) struct ActiveSpan {
+    span: Span,
+    start: Instant,
+}
+
+impl ActiveSpan {
+    /// Await `fut` inside this span, marking the span failed if it errors.
+    pub(crate) async fn run<T, E, F>(&self, fut: F) -> Result<T, E>
+    where
+        E: Display,
+        F: Future<Output = Result<T, E>>,
+    {
+        let result = in_span(&self.span, fut).await;
+        if let Err(e) = &result {
+            record_error(&self.span, e);
+        }
+        result
+    }
+}
+
+impl Drop for ActiveSpan {
+    fn drop(&mut self) {
+        self.span
+            .record("latency_ms", self.start.elapsed().as_millis() as u64);
+    }
+}
+
+/// Report a chain-like run (chain, node, agent) and trace it inside `span`.
+async fn observe_chain<T, E, F>(
+    span: Span,
+    name: &str,
+    inputs: &impl Serialize,
+    fut: F,
+) -> Result<T, E>
+where
+    T: Serialize,
+    E: Display,
+    F: Future<Output = Result<T, E>>,
+{
+    let run = CallbackRun::start(RunType::Chain, name);
+    if run.is_active() {
+        let inputs = json!(inputs);
+        run.emit(|handler, info| handler.on_chain_start(info, &inputs));
+    }
+
+    let result = instrument(&span, run.scope(fut)).await;
+    if run.is_active() {
+        match &result {
+            Ok(outputs) => {
+                let outputs = json!(outputs);
+                run.emit(|handler, info| handler.on_chain_end(info, &outputs));
+            }
+            Err(e) => {
+                let error = e.to_string();
+                run.emit(|handler, info| handler.on_chain_error(info, &error));
+            }
+        }
+    }
+    result
+}
+
+fn llm_run<L: LLM + ?Sized>(llm: &L) -> CallbackRun {
+    let provider = llm.provider_name();
+    let mut metadata = HashMap::from([("ls_provider".to_string(), Value::from(provider))]);
+    if let Some(model) = llm.model_name() {
+        metadata.insert("ls_model_name".to_string(), Value::from(model));
+    }
+    CallbackRun::start_with_metadata(RunType::Llm, llm.model_name().unwrap_or(provider), metadata)
+}
+
+fn chat_span<L: LLM + ?Sized>(llm: &L, messages: &[Message]) -> Span {
+    let model = llm.model_name().unwrap_or("unknown");
+    #[cfg(not(feature = "tracing"))]
+    let _ = model;
+    let span = span!(
+        "chat",
+        otel.name = %format!("chat {}", model),
+        otel.kind = "client",
+        gen_ai.operation.name = "chat",
+        gen_ai.system = llm.provider_name(),
+        gen_ai.request.model = model,
+        gen_ai.usage.input_tokens = Empty,
+        gen_ai.usage.output_tokens = Empty,
+        gen_ai.input.messages = Empty,
+        gen_ai.output.messages = Empty,
+        latency_ms = Empty,
+        otel.status_code = Empty,
+        error.type = Empty,
+    );
+    if capture_content() {
+        if let Ok(json) = serde_json::to_string(messages) {
+            span.record("gen_ai.input.messages", json.as_str());
+        }
+    }
+    span
+}
+
+/// Await `fut` inside `span`, recording latency and failure status.
+async fn instrument<T, E, F>(span: &Span, fut: F) -> Result<T, E>
+where
+    E: Display,
+    F: Future<Output = Result<T, E>>,
+{
+    let start = Instant::now();
+    let result = in_span(span, fut).await;
+    span.record("latency_ms", start.elapsed().as_millis() as u64);
+    if let Err(e) = &result {
+        record_error(span, e);
+    }
+    result
+}
+
+async fn in_span<F: Future>(span: &Span, fut: F) -> F::Output {
+    #[cfg(feature = "tracing")]
+    {
+        fut.instrument(span.clone()).await
+    }
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = span;
+        fut.await
+    }
+}
+
+fn record_usage(span: &Span, tokens: &TokenUsage) {
+    span.record("gen_ai.usage.input_tokens", tokens.prompt_tokens);
+    span.record("gen_ai.usage.output_tokens", tokens.completion_tokens);
+}
+
+fn record_error(span: &Span, error: &dyn Display) {
+    span.record("otel.status_code", "ERROR");
+    span.record("error.type", error.to_string().as_str());
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use std::{
+        collections::HashMap,
+        fmt,
+        sync::{Arc, Mutex},
+    };
+
+    use async_trait::async_trait;
+    use tracing::{
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+        subscriber::Interest,
+        Event, Metadata, Subscriber,
+    };
+
+    use super::*;
+    use crate::language_models::TokenUsage;
+
+    /// Collects span names and fields, keyed by span name.
+    #[derive(Clone, Default)]
+    struct Recorder {
+        spans: Arc<Mutex<Vec<(String, HashMap<String, String>)>>>,
+    }
+
+    struct FieldVisitor<'a>(&'a mut HashMap<String, String>);
+
+    impl Visit for FieldVisitor<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            self.0
+                .insert(field.name().to_string(), format!("{:?}", value));
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name().to_string(), value.to_string());
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn register_callsite(&self, _: &'static Metadata<'static>) -> Interest {
+            Interest::always()
+        }
+
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+            let mut fields = HashMap::new();
+            attrs.record(&mut FieldVisitor(&mut fields));
+            let mut spans = self.spans.lock().unwrap();
+            spans.push((attrs.metadata().name().to_string(), fields));
+            Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, id: &Id, values: &Record<'_>) {
+            let mut spans = self.spans.lock().unwrap();
+            let (_, fields) = &mut spans[id.into_u64() as usize - 1];
+            values.record(&mut FieldVisitor(fields));
+        }
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, _: &Event<'_>) {}
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    impl Recorder {
+        fn span(&self, name: &str) -> HashMap<String, String> {
+            self.spans
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, fields)| fields.clone())
+                .unwrap_or_else(|| panic!("no span named {}", name))
+        }
+    }
+
+    #[derive(Clone)]
+    struct FakeLLM;
+
+    #[async_trait]
+    impl LLM for FakeLLM {
+        fn provider_name(&self) -> &str {
+            "openai"
+        }
+
+        fn model_name(&self) -> Option<&str> {
+            Some("gpt-4o-mini")
+        }
+
+        async fn generate(&self, _messages: &[Message]) -> Result<GenerateResult, LLMError> {
+            Ok(GenerateResult {
+                generation: "hello".to_string(),
+                tokens: Some(TokenUsage::new(12, 3)),
+            })
+        }
+
+        async fn stream(
+            &self,
+            _messages: &[Message],
+        ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamData, LLMError>> + Send>>, LLMError>
+        {
+            Ok(Box::pin(futures::stream::once(async {
+                Ok(StreamData::new(
+                    serde_json::Value::Null,
+                    Some(TokenUsage::new(12, 3)),
+                    "hello",
+                ))
+            })))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_records_genai_attributes() {
+        let recorder = Recorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+
+        generate(&FakeLLM, &[Message::new_human_message("hi")])
+            .await
+            .unwrap();
+
+        let chat = recorder.span("chat");
+        assert_eq!(chat["gen_ai.system"], "openai");
+        assert_eq!(chat["gen_ai.request.model"], "gpt-4o-mini");
+        assert_eq!(chat["gen_ai.usage.input_tokens"], "12");
+        assert_eq!(chat["gen_ai.usage.output_tokens"], "3");
+        assert!(chat.contains_key("latency_ms"));
+        // Content capture is opt-in
+        assert!(!chat.contains_key("gen_ai.input.messages"));
+    }
+
+    #[tokio::test]
+    async fn test_tool_failure_sets_error_status() {
+        let recorder = Recorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+
+        let result = tool("search", "call_0", "{}", async {
+            Err::<String, _>("boom".to_string())
+        })
+        .await;
+
+        assert!(result.is_err());
+        let span = recorder.span("execute_tool");
+        assert_eq!(span["gen_ai.tool.name"], "search");
+        assert_eq!(span["gen_ai.tool.call.id"], "call_0");
+        assert_eq!(span["otel.status_code"], "ERROR");
+        assert_eq!(span["error.type"], "boom");
+    }
+}
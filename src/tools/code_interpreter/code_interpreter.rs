@@ -0,0 +1,471 @@
+//! Code interpreter tool: run model-written Rhai scripts in an embedded sandbox.
+//!
+//! Scripts have no access to the host: no processes, network or environment. Files are only
+//! reachable through a [`FileBackend`], when enabled. Runaway scripts are stopped by an
+//! operation count, a wall-clock timeout and size limits on strings, arrays and maps.
+
+use std::{
+    cell::RefCell,
+    error::Error,
+    rc::Rc,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use rhai::{Array, Dynamic, Engine, EvalAltResult};
+use serde_json::{json, Value};
+use tokio::runtime::Handle;
+
+use crate::agent::WorkspaceBackend;
+use crate::error::ToolError;
+use crate::tools::{FileBackend, Tool, ToolResult, ToolRuntime};
+
+/// What scripts may do with files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FileAccess {
+    /// No file functions
+    #[default]
+    None,
+    /// `read_file` and `list_files`
+    ReadOnly,
+    /// `read_file`, `list_files` and `write_file`
+    ReadWrite,
+}
+
+/// Runs [Rhai](https://rhai.rs) scripts and returns what they print plus their last value
+///
+/// Files come from the configured backend, else the runtime's file backend, else the
+/// workspace root in the context, so a Deep Agent's filesystem is visible to scripts.
+pub struct CodeInterpreter {
+    timeout: Duration,
+    max_operations: u64,
+    max_string_size: usize,
+    max_collection_size: usize,
+    max_call_levels: usize,
+    max_output_bytes: usize,
+    file_access: FileAccess,
+    file_backend: Option<Arc<dyn FileBackend>>,
+}
+
+impl Default for CodeInterpreter {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(5),
+            max_operations: 10_000_000,
+            max_string_size: 1024 * 1024,
+            max_collection_size: 100_000,
+            max_call_levels: 64,
+            max_output_bytes: 16 * 1024,
+            file_access: FileAccess::None,
+            file_backend: None,
+        }
+    }
+}
+
+impl CodeInterpreter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wall-clock time a script may run (default 5s)
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Operations a script may run (default 10 million)
+    pub fn with_max_operations(mut self, max_operations: u64) -> Self {
+        self.max_operations = max_operations;
+        self
+    }
+
+    /// Bounds memory: the largest string in bytes (default 1 MiB) and the most elements in
+    /// an array or map (default 100 000)
+    pub fn with_memory_limits(
+        mut self,
+        max_string_size: usize,
+        max_collection_size: usize,
+    ) -> Self {
+        self.max_string_size = max_string_size;
+        self.max_collection_size = max_collection_size;
+        self
+    }
+
+    /// Printed output kept (default 16 KiB)
+    pub fn with_max_output_bytes(mut self, max_output_bytes: usize) -> Self {
+        self.max_output_bytes = max_output_bytes;
+        self
+    }
+
+    pub fn with_file_access(mut self, file_access: FileAccess) -> Self {
+        self.file_access = file_access;
+        self
+    }
+
+    /// Backend for file functions, instead of the agent's filesystem
+    pub fn with_file_backend(mut self, backend: Arc<dyn FileBackend>) -> Self {
+        self.file_backend = Some(backend);
+        self
+    }
+
+    fn backend(&self, runtime: Option<&ToolRuntime>) -> Option<Arc<dyn FileBackend>> {
+        if self.file_access == FileAccess::None {
+            return None;
+        }
+        self.file_backend
+            .clone()
+            .or_else(|| runtime.and_then(|runtime| runtime.file_backend().cloned()))
+            .or_else(|| {
+                let root = runtime?.context().get("workspace_root")?;
+                Some(Arc::new(WorkspaceBackend::new(root.into())) as Arc<dyn FileBackend>)
+            })
+    }
+
+    fn engine(
+        &self,
+        output: Rc<RefCell<Output>>,
+        backend: Option<Arc<dyn FileBackend>>,
+        handle: Handle,
+    ) -> Engine {
+        let mut engine = Engine::new();
+        engine
+            .set_max_operations(self.max_operations)
+            .set_max_string_size(self.max_string_size)
+            .set_max_array_size(self.max_collection_size)
+            .set_max_map_size(self.max_collection_size)
+            .set_max_call_levels(self.max_call_levels)
+            // The default resolver loads scripts from anywhere on disk with `import`
+            .set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new())
+            .disable_symbol("import")
+            .disable_symbol("eval");
+
+        let started = Instant::now();
+        let timeout = self.timeout;
+        engine.on_progress(move |operations| {
+            (operations % 1024 == 0 && started.elapsed() > timeout)
+                .then(|| Dynamic::from(format!("timed out after {:?}", timeout)))
+        });
+        let print_output = Rc::clone(&output);
+        engine.on_print(move |text| print_output.borrow_mut().push_line(text));
+        engine.on_debug(move |text, _, _| output.borrow_mut().push_line(text));
+
+        if let Some(backend) = backend {
+            let (reader, lister) = (Arc::clone(&backend), Arc::clone(&backend));
+            let (read_handle, list_handle) = (handle.clone(), handle.clone());
+            engine.register_fn(
+                "read_file",
+                move |path: &str| -> Result<String, Box<EvalAltResult>> {
+                    let numbered = read_handle.block_on(reader.read(path, 0, 0))?;
+                    Ok(strip_line_numbers(&numbered))
+                },
+            );
+            engine.register_fn(
+                "list_files",
+                move |path: &str| -> Result<Array, Box<EvalAltResult>> {
+                    let entries = list_handle.block_on(lister.ls_info(path))?;
+                    Ok(entries
+                        .into_iter()
+                        .map(|entry| Dynamic::from(entry.path))
+                        .collect())
+                },
+            );
+            if self.file_access == FileAccess::ReadWrite {
+                engine.register_fn(
+                    "write_file",
+                    move |path: &str, content: &str| -> Result<(), Box<EvalAltResult>> {
+                        let result = handle.block_on(backend.write(path, content))?;
+                        match result.error {
+                            Some(error) => Err(error.into()),
+                            None => Ok(()),
+                        }
+                    },
+                );
+            }
+        }
+        engine
+    }
+
+    async fn execute(
+        &self,
+        input: Value,
+        runtime: Option<&ToolRuntime>,
+    ) -> Result<String, ToolError> {
+        let script = input
+            .get("code")
+            .and_then(Value::as_str)
+            .or_else(|| input.as_str())
+            .ok_or_else(|| ToolError::InvalidInputError("code must be a string".into()))?
+            .to_string();
+        let backend = self.backend(runtime);
+        let handle = Handle::current();
+        let max_output_bytes = self.max_output_bytes;
+
+        // The engine is not Send, so it lives and dies on a blocking thread
+        let engine_config = self.clone_config();
+        tokio::task::spawn_blocking(move || {
+            let output = Rc::new(RefCell::new(Output::new(max_output_bytes)));
+            let engine = engine_config.engine(Rc::clone(&output), backend, handle);
+            let result = engine
+                .eval::<Dynamic>(&script)
+                .map_err(|error| match *error {
+                    EvalAltResult::ErrorTerminated(reason, _) => {
+                        format!("Script stopped: {}", reason)
+                    }
+                    error => error.to_string(),
+                });
+            let printed = output.borrow().render();
+            match result {
+                Ok(value) if value.is_unit() => Ok(if printed.is_empty() {
+                    "(no output)".to_string()
+                } else {
+                    printed
+                }),
+                Ok(value) => Ok(format!("{}Result: {}", printed, value)),
+                Err(error) if printed.is_empty() => Err(ToolError::ExecutionError(error)),
+                Err(error) => Err(ToolError::ExecutionError(format!(
+                    "{}\nOutput before the error:\n{}",
+                    error, printed
+                ))),
+            }
+        })
+        .await
+        .map_err(|e| ToolError::InternalError(e.to_string()))?
+    }
+
+    /// Limits and access mode without the backend, which is resolved per call
+    fn clone_config(&self) -> Self {
+        Self {
+            file_backend: None,
+            ..*self
+        }
+    }
+}
+
+/// Printed lines, capped
+struct Output {
+    text: String,
+    limit: usize,
+    truncated: bool,
+}
+
+impl Output {
+    fn new(limit: usize) -> Self {
+        Self {
+            text: String::new(),
+            limit,
+            truncated: false,
+        }
+    }
+
+    fn push_line(&mut self, line: &str) {
+        if self.text.len() + line.len() + 1 > self.limit {
+            self.truncated = true;
+            return;
+        }
+        self.text.push_str(line);
+        self.text.push('\n');
+    }
+
+    fn render(&self) -> String {
+        if self.truncated {
+            format!("{}... (output truncated)\n", self.text)
+        } else {
+            self.text.clone()
+        }
+    }
+}
+
+/// File backends return `cat -n` style lines
+fn strip_line_numbers(numbered: &str) -> String {
+    numbered
+        .lines()
+        .map(|line| line.split_once('\t').map_or(line, |(_, text)| text))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[async_trait]
+impl Tool for CodeInterpreter {
+    fn name(&self) -> String {
+        "code_interpreter".to_string()
+    }
+
+    fn description(&self) -> String {
+        let mut description = "Runs a Rhai script and returns what it prints and the value of its \
+            last expression. Use it for arithmetic, date math, tables and data transformations. \
+            Rhai looks like Rust without types: `let total = 0; for x in [1, 2, 3] { total += x; } \
+            print(`total: ${total}`); total * 2`. Available: integers, floats, strings, arrays \
+            (`push`, `sort`, `map`, `filter`, `reduce`), object maps (`#{ a: 1 }`), `parse_int`, \
+            `parse_float`, `timestamp()`."
+            .to_string();
+        match self.file_access {
+            FileAccess::None => {}
+            FileAccess::ReadOnly => {
+                description.push_str(" Files: `read_file(path)` and `list_files(dir)` (read-only).")
+            }
+            FileAccess::ReadWrite => description.push_str(
+                " Files: `read_file(path)`, `list_files(dir)` and `write_file(path, content)`.",
+            ),
+        }
+        description
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "code": {
+                    "type": "string",
+                    "description": "The Rhai script to run"
+                }
+            },
+            "required": ["code"]
+        })
+    }
+
+    async fn parse_input(&self, input: &str) -> Value {
+        crate::tools::parse_tool_input(input)
+    }
+
+    async fn run(&self, input: Value) -> Result<String, ToolError> {
+        self.execute(input, None).await
+    }
+
+    async fn run_with_runtime(
+        &self,
+        input: Value,
+        runtime: &ToolRuntime,
+    ) -> Result<ToolResult, Box<dyn Error>> {
+        Ok(ToolResult::Text(self.execute(input, Some(runtime)).await?))
+    }
+
+    fn requires_runtime(&self) -> bool {
+        self.file_access != FileAccess::None
+    }
+
+    fn is_parallel_safe(&self) -> bool {
+        self.file_access != FileAccess::ReadWrite
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::Mutex;
+
+    use super::*;
+    use crate::agent::AgentState;
+    use crate::tools::{InMemoryStore, SimpleContext};
+
+    fn runtime(root: &std::path::Path) -> ToolRuntime {
+        let context = SimpleContext::new()
+            .with_custom("workspace_root".to_string(), root.display().to_string());
+        ToolRuntime::new(
+            Arc::new(Mutex::new(AgentState::new())),
+            Arc::new(context),
+            Arc::new(InMemoryStore::new()),
+            "call_1".to_string(),
+        )
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_runs_script() {
+        let interpreter = CodeInterpreter::new();
+        let output = interpreter
+            .run(json!({ "code": "let total = 0; for x in 1..=4 { print(x); total += x; } total" }))
+            .await
+            .unwrap();
+        assert_eq!(output, "1\n2\n3\n4\nResult: 10");
+
+        let error = interpreter.run(json!("let x = ;")).await.unwrap_err();
+        assert!(matches!(error, ToolError::ExecutionError(_)));
+        let error = interpreter
+            .run(json!(r#"print("started"); open_socket()"#))
+            .await
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Output before the error:\nstarted"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_import_is_rejected() {
+        let module = std::env::temp_dir().join(format!(
+            "code_interpreter_module_{}.rhai",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        std::fs::write(&module, "export const SECRET = 42;").unwrap();
+        let script = format!(
+            r#"import "{}" as m; m::SECRET"#,
+            module.with_extension("").display()
+        );
+
+        let error = CodeInterpreter::new().run(json!(script)).await.unwrap_err();
+        assert!(!error.to_string().contains("42"));
+        let _ = std::fs::remove_file(&module);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_limits() {
+        let interpreter = CodeInterpreter::new().with_timeout(Duration::from_millis(200));
+        let started = Instant::now();
+        let error = interpreter.run(json!("loop {}")).await.unwrap_err();
+        assert!(error.to_string().contains("timed out"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let interpreter = CodeInterpreter::new().with_memory_limits(1024, 100);
+        assert!(interpreter
+            .run(json!(r#"let s = "x"; loop { s += s; }"#))
+            .await
+            .is_err());
+        assert!(interpreter
+            .run(json!("let a = []; for i in 0..1000 { a.push(i); }"))
+            .await
+            .is_err());
+
+        let interpreter = CodeInterpreter::new().with_max_output_bytes(10);
+        let output = interpreter
+            .run(json!("for i in 0..100 { print(i); }"))
+            .await
+            .unwrap();
+        assert!(output.ends_with("... (output truncated)\n"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_file_access() {
+        let root = std::env::temp_dir().join("code_interpreter_test");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("data.csv"), "a,1\nb,2\n").unwrap();
+        let runtime = runtime(&root);
+        let sum = r#"
+            let total = 0;
+            for line in read_file("data.csv").split("\n") { total += parse_int(line.split(",")[1]); }
+            total
+        "#;
+
+        let none = CodeInterpreter::new();
+        assert!(none.run_with_runtime(json!(sum), &runtime).await.is_err());
+
+        let read_only = CodeInterpreter::new().with_file_access(FileAccess::ReadOnly);
+        let output = read_only
+            .run_with_runtime(json!(sum), &runtime)
+            .await
+            .unwrap();
+        assert_eq!(output.as_str(), "Result: 3");
+        let write = r#"write_file("out.txt", "hello")"#;
+        assert!(read_only
+            .run_with_runtime(json!(write), &runtime)
+            .await
+            .is_err());
+
+        let read_write = CodeInterpreter::new().with_file_access(FileAccess::ReadWrite);
+        read_write
+            .run_with_runtime(json!(write), &runtime)
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(root.join("out.txt")).unwrap(),
+            "hello"
+        );
+    }
+}
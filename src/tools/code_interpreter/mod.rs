@@ -0,0 +1,3 @@
+mod code_interpreter;
+
+pub use code_interpreter::*;
@@ -4,8 +4,11 @@ use serde_json::{json, Value};
 
 use crate::tools::Tool;
 
+use super::sandbox::{SandboxPolicy, SandboxedExecutor};
+
 pub struct CommandExecutor {
     platform: String,
+    sandbox: Option<SandboxedExecutor>,
 }
 
 impl CommandExecutor {
@@ -17,8 +20,26 @@ impl CommandExecutor {
     pub fn new<S: Into<String>>(platform: S) -> Self {
         Self {
             platform: platform.into(),
+            sandbox: None,
         }
     }
+
+    /// Run commands under a sandbox policy instead of unrestricted
+    ///
+    /// Each command then returns its exit code, stdout and stderr as JSON, and commands
+    /// violating the policy fail with a permission error.
+    /// # Example
+    /// ```rust,ignore
+    /// let tool = CommandExecutor::new("linux").with_sandbox(
+    ///     SandboxPolicy::new()
+    ///         .with_root("/srv/workspace")
+    ///         .with_allowed_commands(["ls", "cat", "grep"]),
+    /// );
+    /// ```
+    pub fn with_sandbox(mut self, policy: SandboxPolicy) -> Self {
+        self.sandbox = Some(SandboxedExecutor::new(policy));
+        self
+    }
 }
 
 impl Default for CommandExecutor {
@@ -128,6 +149,21 @@ impl Tool for CommandExecutor {
     async fn run(&self, input: Value) -> Result<String, crate::error::ToolError> {
         let commands: Vec<CommandInput> = serde_json::from_value(input)
             .map_err(|e| crate::error::ToolError::ParsingError(e.to_string()))?;
+        if let Some(sandbox) = &self.sandbox {
+            let mut results = Vec::new();
+            for command in commands {
+                let output = sandbox.execute(&command.cmd, &command.args, None).await?;
+                let success = output.success();
+                results.push(json!({ "cmd": command.cmd, "args": command.args, "output": output }));
+                // Later commands usually depend on the earlier ones
+                if !success {
+                    break;
+                }
+            }
+            return serde_json::to_string_pretty(&results)
+                .map_err(|e| crate::error::ToolError::InternalError(e.to_string()));
+        }
+
         let mut result = String::new();
 
         for command in commands {
@@ -154,6 +190,10 @@ impl Tool for CommandExecutor {
 
         Ok(result)
     }
+
+    fn is_parallel_safe(&self) -> bool {
+        false
+    }
 }
 
 #[cfg(test)]
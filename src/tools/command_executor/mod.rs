@@ -1,2 +1,5 @@
 mod command_executor;
 pub use command_executor::*;
+
+mod sandbox;
+pub use sandbox::*;
@@ -0,0 +1,799 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Component, Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::error::ToolError;
+
+/// Binaries allowed by default: file inspection and simple file management
+///
+/// None of them runs another program. Replace the list with
+/// [`SandboxPolicy::with_allowed_commands`] to allow build tools such as `cargo`.
+pub const DEFAULT_ALLOWED_COMMANDS: &[&str] = &[
+    "ls", "cat", "head", "tail", "wc", "grep", "diff", "sort", "uniq", "cut", "tr", "echo",
+    "printenv", "pwd", "stat", "mkdir", "touch", "cp", "mv",
+];
+
+/// Binaries denied by default: privilege escalation, host administration, and shells,
+/// interpreters and wrappers that would run arbitrary commands past the policy
+///
+/// Version suffixes are ignored, so `python3.12` is denied as `python`.
+pub const DEFAULT_DENIED_COMMANDS: &[&str] = &[
+    "sudo", "su", "doas", "pkexec", "chroot", "mount", "umount", "shutdown", "reboot", "halt",
+    "poweroff", "mkfs", "dd", "nsenter", "unshare", "sh", "bash", "zsh", "dash", "ksh", "fish",
+    "csh", "tcsh", "busybox", "env", "xargs", "nohup", "nice", "timeout", "setsid", "stdbuf",
+    "find", "awk", "gawk", "python", "perl", "node", "deno", "ruby", "php", "lua",
+];
+
+/// Environment variables passed through to commands by default
+pub const DEFAULT_INHERITED_ENV: &[&str] = &["PATH", "LANG", "LC_ALL", "TZ"];
+
+#[derive(Error, Debug)]
+pub enum SandboxError {
+    #[error("Command not allowed: {0}")]
+    CommandNotAllowed(String),
+
+    #[error("Path escapes the sandbox root: {0}")]
+    PathEscapesRoot(String),
+
+    #[error("Invalid command: {0}")]
+    InvalidCommand(String),
+
+    #[error("Isolation unavailable: {0}")]
+    IsolationUnavailable(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl From<SandboxError> for ToolError {
+    fn from(error: SandboxError) -> Self {
+        match error {
+            SandboxError::CommandNotAllowed(_) | SandboxError::PathEscapesRoot(_) => {
+                ToolError::PermissionError(error.to_string())
+            }
+            SandboxError::InvalidCommand(_) => ToolError::InvalidInputError(error.to_string()),
+            _ => ToolError::ExecutionError(error.to_string()),
+        }
+    }
+}
+
+/// OS-level isolation of sandboxed commands
+///
+/// There is no seccomp filter: isolated commands can still make every system call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Isolation {
+    /// Only the policy checks and resource limits
+    None,
+    /// On Linux, run in new user, network, IPC and UTS namespaces when the kernel allows
+    /// unprivileged namespaces; otherwise run without them. The default on Linux.
+    BestEffort,
+    /// Like `BestEffort`, but refuse to run when namespaces are unavailable
+    Required,
+}
+
+impl Default for Isolation {
+    fn default() -> Self {
+        if cfg!(target_os = "linux") {
+            Isolation::BestEffort
+        } else {
+            Isolation::None
+        }
+    }
+}
+
+/// Rules and limits for [`SandboxedExecutor`]
+///
+/// Commands run directly, without a shell, in a working directory jailed under `root`, with
+/// a scrubbed environment, a null stdin and capped output. Only allowlisted binaries run,
+/// and the denylist wins over the allowlist. Path arguments are checked against the root
+/// too, but only heuristically (absolute paths and `..` in the arguments themselves), so an
+/// allowed binary that runs other programs or reads paths from files escapes the jail; use
+/// [`Isolation`] and OS permissions for hard guarantees.
+#[derive(Debug, Clone)]
+pub struct SandboxPolicy {
+    /// Working directory jail; the Deep Agent uses its workspace root when unset
+    pub root: Option<PathBuf>,
+    /// Only these binaries may run; [`DEFAULT_ALLOWED_COMMANDS`] by default
+    pub allowed_commands: HashSet<String>,
+    /// Binaries that may never run, even when allowed
+    pub denied_commands: HashSet<String>,
+    /// Wall-clock limit; the command and its children are killed when it is exceeded
+    pub timeout: Duration,
+    /// CPU time limit (Unix)
+    pub cpu_time_limit: Option<Duration>,
+    /// Address space limit in bytes (Unix)
+    pub memory_limit_bytes: Option<u64>,
+    /// Limit of captured bytes for each of stdout and stderr
+    pub max_output_bytes: usize,
+    /// Variables copied from the current environment; everything else is removed
+    pub inherited_env: Vec<String>,
+    /// Variables set for every command
+    pub env: HashMap<String, String>,
+    pub isolation: Isolation,
+}
+
+impl Default for SandboxPolicy {
+    fn default() -> Self {
+        Self {
+            root: None,
+            allowed_commands: DEFAULT_ALLOWED_COMMANDS
+                .iter()
+                .map(|c| c.to_string())
+                .collect(),
+            denied_commands: DEFAULT_DENIED_COMMANDS
+                .iter()
+                .map(|c| c.to_string())
+                .collect(),
+            timeout: Duration::from_secs(30),
+            cpu_time_limit: None,
+            memory_limit_bytes: None,
+            max_output_bytes: 64 * 1024,
+            inherited_env: DEFAULT_INHERITED_ENV
+                .iter()
+                .map(|v| v.to_string())
+                .collect(),
+            env: HashMap::new(),
+            isolation: Isolation::default(),
+        }
+    }
+}
+
+impl SandboxPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_root<P: Into<PathBuf>>(mut self, root: P) -> Self {
+        self.root = Some(root.into());
+        self
+    }
+
+    /// Only allow these binaries, matched by file name, instead of [`DEFAULT_ALLOWED_COMMANDS`]
+    pub fn with_allowed_commands<I, S>(mut self, commands: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_commands = commands.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Deny these binaries in addition to [`DEFAULT_DENIED_COMMANDS`]
+    pub fn with_denied_commands<I, S>(mut self, commands: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.denied_commands
+            .extend(commands.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_cpu_time_limit(mut self, limit: Duration) -> Self {
+        self.cpu_time_limit = Some(limit);
+        self
+    }
+
+    pub fn with_memory_limit_bytes(mut self, limit: u64) -> Self {
+        self.memory_limit_bytes = Some(limit);
+        self
+    }
+
+    pub fn with_max_output_bytes(mut self, max_output_bytes: usize) -> Self {
+        self.max_output_bytes = max_output_bytes;
+        self
+    }
+
+    /// Copy a variable from the current environment into every command's environment
+    pub fn with_inherited_env<S: Into<String>>(mut self, name: S) -> Self {
+        self.inherited_env.push(name.into());
+        self
+    }
+
+    pub fn with_env<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.env.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn with_isolation(mut self, isolation: Isolation) -> Self {
+        self.isolation = isolation;
+        self
+    }
+}
+
+/// Result of a sandboxed command
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CommandOutput {
+    /// Exit code; `None` when the command was killed by a signal or the timeout
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    /// Whether the wall-clock timeout killed the command
+    pub timed_out: bool,
+    /// Whether stdout or stderr exceeded the output cap
+    pub truncated: bool,
+}
+
+impl CommandOutput {
+    pub fn success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// Runs commands under a [`SandboxPolicy`]
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let executor = SandboxedExecutor::new(
+///     SandboxPolicy::new()
+///         .with_root("/srv/workspace")
+///         .with_allowed_commands(["ls", "cat", "cargo"])
+///         .with_timeout(Duration::from_secs(60)),
+/// );
+/// let output = executor.execute("cargo", &["test".to_string()], None).await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct SandboxedExecutor {
+    policy: SandboxPolicy,
+    root: PathBuf,
+}
+
+impl SandboxedExecutor {
+    /// Jail commands under the policy root, or the current directory when it is unset
+    pub fn new(policy: SandboxPolicy) -> Self {
+        let root = policy
+            .root
+            .clone()
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_else(|| PathBuf::from("."));
+        let root = root.canonicalize().unwrap_or(root);
+        Self { policy, root }
+    }
+
+    pub fn policy(&self) -> &SandboxPolicy {
+        &self.policy
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Check a command against the policy and resolve its working directory
+    ///
+    /// `cwd` is relative to the root.
+    pub fn check(
+        &self,
+        program: &str,
+        args: &[String],
+        cwd: Option<&str>,
+    ) -> Result<PathBuf, SandboxError> {
+        let name = Path::new(program)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| SandboxError::InvalidCommand(program.to_string()))?;
+        let unversioned = name.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+        if self.policy.denied_commands.contains(name)
+            || self.policy.denied_commands.contains(unversioned)
+            || !self.policy.allowed_commands.contains(name)
+        {
+            return Err(SandboxError::CommandNotAllowed(name.to_string()));
+        }
+        // A relative path to a binary runs something from the workspace
+        if program.contains('/') && !Path::new(program).is_absolute() {
+            self.resolve(&self.root, program)?;
+        }
+
+        let cwd = match cwd {
+            Some(cwd) => self.resolve(&self.root, cwd.trim_start_matches('/'))?,
+            None => self.root.clone(),
+        };
+        for arg in args {
+            if looks_like_path(arg) {
+                self.resolve(&cwd, arg)?;
+            }
+        }
+        Ok(cwd)
+    }
+
+    /// Run a command without a shell
+    ///
+    /// Policy violations are errors; a failing command is a successful [`CommandOutput`]
+    /// with a non-zero exit code.
+    pub async fn execute(
+        &self,
+        program: &str,
+        args: &[String],
+        cwd: Option<&str>,
+    ) -> Result<CommandOutput, SandboxError> {
+        let cwd = self.check(program, args, cwd)?;
+
+        let mut command = tokio::process::Command::new(program);
+        command
+            .args(args)
+            .current_dir(&cwd)
+            .env_clear()
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        for name in &self.policy.inherited_env {
+            if let Ok(value) = std::env::var(name) {
+                command.env(name, value);
+            }
+        }
+        command.env("HOME", &self.root);
+        command.envs(&self.policy.env);
+        self.apply_limits(&mut command)?;
+
+        let mut child = command.spawn()?;
+        let pid = child.id();
+        let cap = self.policy.max_output_bytes;
+        let stdout = child
+            .stdout
+            .take()
+            .map(|out| tokio::spawn(read_capped(out, cap)));
+        let stderr = child
+            .stderr
+            .take()
+            .map(|err| tokio::spawn(read_capped(err, cap)));
+
+        let exited = tokio::time::timeout(self.policy.timeout, wait_for_exit(&mut child)).await;
+        // The command is not reaped yet, so its process group id cannot have been reused.
+        // Also kills background processes the command left behind.
+        kill_process_group(pid, &mut child);
+        let status = child.wait().await;
+        let (exit_code, timed_out) = match exited {
+            Ok(exited) => {
+                exited?;
+                (status?.code(), false)
+            }
+            Err(_) => (None, true),
+        };
+
+        let (stdout, stdout_truncated) = collect_output(stdout).await;
+        let (stderr, stderr_truncated) = collect_output(stderr).await;
+        Ok(CommandOutput {
+            exit_code,
+            stdout,
+            stderr,
+            timed_out,
+            truncated: stdout_truncated || stderr_truncated,
+        })
+    }
+
+    /// Resolve `path` against `base` and make sure it stays under the root
+    fn resolve(&self, base: &Path, path: &str) -> Result<PathBuf, SandboxError> {
+        let joined = normalize(&base.join(path));
+        // Follow symlinks of existing paths, so links cannot point out of the root
+        let resolved = joined.canonicalize().unwrap_or(joined);
+        if resolved.starts_with(&self.root) {
+            Ok(resolved)
+        } else {
+            Err(SandboxError::PathEscapesRoot(path.to_string()))
+        }
+    }
+
+    #[cfg(unix)]
+    fn apply_limits(&self, command: &mut tokio::process::Command) -> Result<(), SandboxError> {
+        if self.policy.isolation == Isolation::Required && !cfg!(target_os = "linux") {
+            return Err(SandboxError::IsolationUnavailable(
+                "namespaces require Linux".to_string(),
+            ));
+        }
+        let cpu_seconds = self
+            .policy
+            .cpu_time_limit
+            .map(|limit| limit.as_secs().max(1));
+        let memory = self.policy.memory_limit_bytes;
+        let isolation = self.policy.isolation;
+
+        command.process_group(0);
+        // SAFETY: the closure only makes async-signal-safe system calls
+        unsafe {
+            command.pre_exec(move || {
+                if let Some(seconds) = cpu_seconds {
+                    set_limit(libc::RLIMIT_CPU, seconds)?;
+                }
+                if let Some(bytes) = memory {
+                    set_limit(libc::RLIMIT_AS, bytes)?;
+                }
+                #[cfg(target_os = "linux")]
+                {
+                    if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                    if isolation != Isolation::None {
+                        let flags = libc::CLONE_NEWUSER
+                            | libc::CLONE_NEWNET
+                            | libc::CLONE_NEWIPC
+                            | libc::CLONE_NEWUTS;
+                        if libc::unshare(flags) != 0 && isolation == Isolation::Required {
+                            return Err(std::io::Error::last_os_error());
+                        }
+                    }
+                }
+                #[cfg(not(target_os = "linux"))]
+                let _ = isolation;
+                Ok(())
+            });
+        }
+        Ok(())
+    }
+
+    /// Resource limits and isolation need Unix; refuse policies that ask for them elsewhere
+    #[cfg(not(unix))]
+    fn apply_limits(&self, _command: &mut tokio::process::Command) -> Result<(), SandboxError> {
+        if self.policy.isolation == Isolation::Required {
+            return Err(SandboxError::IsolationUnavailable(
+                "namespaces require Linux".to_string(),
+            ));
+        }
+        if self.policy.cpu_time_limit.is_some() || self.policy.memory_limit_bytes.is_some() {
+            return Err(SandboxError::IsolationUnavailable(
+                "resource limits require Unix".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Wait until the command exits
+///
+/// On Unix the command is left unreaped, so its pid and process group id stay reserved until
+/// [`kill_process_group`] has run.
+async fn wait_for_exit(child: &mut tokio::process::Child) -> std::io::Result<()> {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        return tokio::task::spawn_blocking(move || loop {
+            // SAFETY: an all-zero siginfo_t is valid; waitid only writes into it
+            let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+            // SAFETY: WNOWAIT leaves the child waitable, so `Child::wait` still reaps it
+            let result = unsafe {
+                libc::waitid(
+                    libc::P_PID,
+                    pid as libc::id_t,
+                    &mut info,
+                    libc::WEXITED | libc::WNOWAIT,
+                )
+            };
+            if result == 0 {
+                return Ok(());
+            }
+            let error = std::io::Error::last_os_error();
+            if error.kind() != std::io::ErrorKind::Interrupted {
+                return Err(error);
+            }
+        })
+        .await
+        .map_err(std::io::Error::other)?;
+    }
+    child.wait().await.map(|_| ())
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+type RlimitResource = libc::__rlimit_resource_t;
+#[cfg(all(unix, not(all(target_os = "linux", target_env = "gnu"))))]
+type RlimitResource = libc::c_int;
+
+#[cfg(unix)]
+fn set_limit(resource: RlimitResource, value: u64) -> std::io::Result<()> {
+    let limit = libc::rlimit {
+        rlim_cur: value as libc::rlim_t,
+        rlim_max: value as libc::rlim_t,
+    };
+    // SAFETY: setrlimit only reads the given struct
+    if unsafe { libc::setrlimit(resource, &limit) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Kill the command's process group, or just the command off Unix
+///
+/// Must run before the command is reaped: afterwards its pid may belong to another process.
+fn kill_process_group(pid: Option<u32>, child: &mut tokio::process::Child) {
+    #[cfg(unix)]
+    if let Some(pid) = pid {
+        // SAFETY: signals the process group created for the child in `apply_limits`, whose
+        // leader has not been reaped yet
+        unsafe {
+            libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+        }
+        return;
+    }
+    let _ = pid;
+    let _ = child.start_kill();
+}
+
+/// Read a stream to the end, keeping at most `cap` bytes
+async fn read_capped<R: AsyncRead + Unpin>(mut reader: R, cap: usize) -> (Vec<u8>, bool) {
+    let mut output = Vec::new();
+    let mut truncated = false;
+    let mut chunk = [0u8; 8192];
+    loop {
+        match reader.read(&mut chunk).await {
+            Ok(0) | Err(_) => break,
+            Ok(read) => {
+                let room = cap.saturating_sub(output.len());
+                truncated |= read > room;
+                output.extend_from_slice(&chunk[..read.min(room)]);
+            }
+        }
+    }
+    (output, truncated)
+}
+
+async fn collect_output(
+    reader: Option<tokio::task::JoinHandle<(Vec<u8>, bool)>>,
+) -> (String, bool) {
+    let Some(reader) = reader else {
+        return (String::new(), false);
+    };
+    match tokio::time::timeout(Duration::from_secs(5), reader).await {
+        Ok(Ok((output, truncated))) => (String::from_utf8_lossy(&output).into_owned(), truncated),
+        _ => (String::new(), false),
+    }
+}
+
+/// Whether an argument is checked as a path: absolute, home-relative or going up
+fn looks_like_path(arg: &str) -> bool {
+    let value = arg.split_once('=').map_or(arg, |(_, value)| value);
+    value.starts_with('/')
+        || value.starts_with('~')
+        || Path::new(value)
+            .components()
+            .any(|component| component == Component::ParentDir)
+}
+
+/// Remove `.` and `..` components without touching the file system
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Split a command line into words like a POSIX shell, without expansions
+///
+/// Supports single and double quotes and backslash escapes. Shell operators such as `|`,
+/// `;` or `>` are rejected, since commands run without a shell.
+pub fn split_command_line(line: &str) -> Result<Vec<String>, SandboxError> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => {
+                            return Err(SandboxError::InvalidCommand(
+                                "unterminated single quote".to_string(),
+                            ))
+                        }
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => break,
+                        },
+                        Some(c) => word.push(c),
+                        None => {
+                            return Err(SandboxError::InvalidCommand(
+                                "unterminated double quote".to_string(),
+                            ))
+                        }
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                if let Some(c) = chars.next() {
+                    word.push(c);
+                }
+            }
+            '|' | '&' | ';' | '<' | '>' | '`' | '$' | '(' | ')' => {
+                return Err(SandboxError::InvalidCommand(format!(
+                    "shell operator '{}' is not supported; run one command at a time",
+                    c
+                )))
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// A policy allowing `sh`, to script the commands under test
+    fn shell_policy() -> SandboxPolicy {
+        let mut policy = SandboxPolicy::new()
+            .with_root(std::env::temp_dir())
+            .with_allowed_commands(["sh"]);
+        policy.denied_commands.remove("sh");
+        policy
+    }
+
+    #[test]
+    fn test_split_command_line() {
+        assert_eq!(
+            split_command_line(r#"grep -n "fn main" 'src dir'/a\ b"#).unwrap(),
+            vec!["grep", "-n", "fn main", "src dir/a b"]
+        );
+        assert!(split_command_line("ls | wc -l").is_err());
+        assert!(split_command_line("echo 'open").is_err());
+    }
+
+    #[test]
+    fn test_policy_checks() {
+        let root = std::env::temp_dir();
+        let executor = SandboxedExecutor::new(
+            SandboxPolicy::new()
+                .with_root(&root)
+                .with_allowed_commands(["ls", "sudo"]),
+        );
+        assert!(executor.check("ls", &[], None).is_ok());
+        assert!(matches!(
+            executor.check("cat", &[], None),
+            Err(SandboxError::CommandNotAllowed(_))
+        ));
+        // The denylist wins over the allowlist
+        assert!(executor.check("sudo", &[], None).is_err());
+        assert!(matches!(
+            executor.check("ls", &["/etc".to_string()], None),
+            Err(SandboxError::PathEscapesRoot(_))
+        ));
+        assert!(executor.check("ls", &["../..".to_string()], None).is_err());
+        assert!(executor.check("ls", &[], Some("../")).is_err());
+    }
+
+    #[test]
+    fn test_default_policy() {
+        let policy = SandboxPolicy::new();
+        #[cfg(target_os = "linux")]
+        assert_eq!(policy.isolation, Isolation::BestEffort);
+        let executor = SandboxedExecutor::new(policy.with_root(std::env::temp_dir()));
+        assert!(executor
+            .check("cat", &["notes.txt".to_string()], None)
+            .is_ok());
+        // Not on the default allowlist
+        assert!(executor.check("curl", &[], None).is_err());
+        // Shells, interpreters and exec wrappers stay denied even when allowed
+        let executor = SandboxedExecutor::new(
+            SandboxPolicy::new()
+                .with_root(std::env::temp_dir())
+                .with_allowed_commands(["sh", "bash", "env", "xargs", "python3.12", "busybox"]),
+        );
+        for program in ["sh", "/bin/bash", "env", "xargs", "python3.12", "busybox"] {
+            assert!(
+                matches!(
+                    executor.check(program, &[], None),
+                    Err(SandboxError::CommandNotAllowed(_))
+                ),
+                "{program} should be denied"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_output() {
+        let executor = SandboxedExecutor::new(shell_policy());
+        let output = executor
+            .execute(
+                "sh",
+                &[
+                    "-c".to_string(),
+                    "echo out; echo err >&2; exit 3".to_string(),
+                ],
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(output.exit_code, Some(3));
+        assert_eq!(output.stdout, "out\n");
+        assert_eq!(output.stderr, "err\n");
+        assert!(!output.timed_out);
+    }
+
+    #[tokio::test]
+    async fn test_environment_is_scrubbed() {
+        std::env::set_var("SANDBOX_TEST_SECRET", "hunter2");
+        let executor = SandboxedExecutor::new(
+            SandboxPolicy::new()
+                .with_root(std::env::temp_dir())
+                .with_env("GREETING", "hi"),
+        );
+        let output = executor.execute("printenv", &[], None).await.unwrap();
+        assert!(!output.stdout.contains("SANDBOX_TEST_SECRET"));
+        assert!(output.stdout.contains("GREETING=hi"));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_and_output_cap() {
+        let executor = SandboxedExecutor::new(
+            shell_policy()
+                .with_timeout(Duration::from_millis(200))
+                .with_max_output_bytes(4),
+        );
+        let output = executor
+            .execute(
+                "sh",
+                &["-c".to_string(), "echo 123456789; sleep 10".to_string()],
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(output.timed_out);
+        assert_eq!(output.exit_code, None);
+        assert_eq!(output.stdout, "1234");
+        assert!(output.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_background_processes_are_killed() {
+        let executor = SandboxedExecutor::new(shell_policy());
+        let started = std::time::Instant::now();
+        let output = executor
+            .execute(
+                "sh",
+                &["-c".to_string(), "sleep 30 & echo done".to_string()],
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(output.exit_code, Some(0));
+        assert_eq!(output.stdout, "done\n");
+        // The leftover sleep would otherwise hold stdout open until the read timeout
+        assert!(started.elapsed() < Duration::from_secs(3));
+    }
+}
@@ -0,0 +1,415 @@
+use std::{
+    collections::HashMap,
+    process::Stdio,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex as StdMutex,
+    },
+    time::Duration,
+};
+
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    sync::mpsc::{self, UnboundedSender},
+};
+
+use crate::tools::Tool;
+
+use super::{
+    error::McpError,
+    tool::McpTool,
+    transport::{HttpTransport, NotificationHandler, StdioTransport, Transport},
+    types::{
+        CallToolResult, GetPromptResult, Implementation, InitializeResult, JsonRpcRequest,
+        ProgressNotification, Prompt, Resource, ResourceContents, ResourceTemplate, ToolDefinition,
+        MCP_PROTOCOL_VERSION,
+    },
+};
+
+/// How long a stdio request waits for its response by default
+pub const DEFAULT_STDIO_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Progress listeners of running tool calls, by progress token
+type ProgressListeners = Arc<StdMutex<HashMap<String, UnboundedSender<ProgressNotification>>>>;
+
+/// Client for a Model Context Protocol server, over stdio or streamable HTTP
+///
+/// Connecting performs the `initialize` handshake. The client is cheap to clone; clones share
+/// the connection, which is closed (and a stdio server process killed) when the last one is
+/// dropped.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let mut command = tokio::process::Command::new("npx");
+/// command.args(["-y", "@modelcontextprotocol/server-filesystem", "/srv/docs"]);
+/// let client = McpClient::stdio(command).await?;
+///
+/// // Every server tool as an agent tool
+/// let tools = client.tools().await?;
+/// let agent = create_agent("gpt-4o-mini", &tools, None, None)?;
+/// ```
+#[derive(Clone)]
+pub struct McpClient {
+    inner: Arc<ClientInner>,
+}
+
+struct ClientInner {
+    transport: Box<dyn Transport>,
+    next_id: AtomicU64,
+    progress: ProgressListeners,
+    server: InitializeResult,
+}
+
+impl McpClient {
+    /// Spawn a server process and talk to it over its stdin and stdout
+    ///
+    /// Requests fail with [`McpError::Timeout`] after [`DEFAULT_STDIO_TIMEOUT`].
+    pub async fn stdio(command: tokio::process::Command) -> Result<Self, McpError> {
+        Self::stdio_with_timeout(command, DEFAULT_STDIO_TIMEOUT).await
+    }
+
+    /// Spawn a server process, failing requests that get no response within `timeout`
+    pub async fn stdio_with_timeout(
+        mut command: tokio::process::Command,
+        timeout: Duration,
+    ) -> Result<Self, McpError> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+        let stdin = child.stdin.take().ok_or(McpError::ConnectionClosed)?;
+        let stdout = child.stdout.take().ok_or(McpError::ConnectionClosed)?;
+
+        let progress = ProgressListeners::default();
+        let transport = StdioTransport::new(
+            stdout,
+            stdin,
+            Some(child),
+            timeout,
+            progress_handler(&progress),
+        );
+        Self::initialize(Box::new(transport), progress).await
+    }
+
+    /// Talk newline-delimited JSON-RPC over an already connected pair of streams, with
+    /// [`DEFAULT_STDIO_TIMEOUT`]
+    pub async fn from_streams<R, W>(reader: R, writer: W) -> Result<Self, McpError>
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+        W: AsyncWrite + Send + Unpin + 'static,
+    {
+        let progress = ProgressListeners::default();
+        let transport = StdioTransport::new(
+            reader,
+            writer,
+            None,
+            DEFAULT_STDIO_TIMEOUT,
+            progress_handler(&progress),
+        );
+        Self::initialize(Box::new(transport), progress).await
+    }
+
+    /// Connect to a server's streamable HTTP endpoint, e.g. `http://localhost:8000/mcp`
+    pub async fn http<S: Into<String>>(url: S) -> Result<Self, McpError> {
+        Self::http_with_client(url, reqwest::Client::new()).await
+    }
+
+    /// Connect over streamable HTTP with a custom HTTP client, e.g. with default
+    /// authentication headers
+    pub async fn http_with_client<S: Into<String>>(
+        url: S,
+        client: reqwest::Client,
+    ) -> Result<Self, McpError> {
+        let progress = ProgressListeners::default();
+        let transport = HttpTransport::new(client, url.into(), progress_handler(&progress));
+        Self::initialize(Box::new(transport), progress).await
+    }
+
+    async fn initialize(
+        transport: Box<dyn Transport>,
+        progress: ProgressListeners,
+    ) -> Result<Self, McpError> {
+        let request = JsonRpcRequest::new(
+            json!(0),
+            "initialize",
+            Some(json!({
+                "protocolVersion": MCP_PROTOCOL_VERSION,
+                "capabilities": {},
+                "clientInfo": Implementation::new("langchain-ai-rust", env!("CARGO_PKG_VERSION")),
+            })),
+        );
+        let server: InitializeResult = rpc_result(transport.request(request).await?)?;
+        transport.set_protocol_version(&server.protocol_version);
+        transport
+            .notify(JsonRpcRequest::notification(
+                "notifications/initialized",
+                None,
+            ))
+            .await?;
+
+        Ok(Self {
+            inner: Arc::new(ClientInner {
+                transport,
+                next_id: AtomicU64::new(1),
+                progress,
+                server,
+            }),
+        })
+    }
+
+    /// Server name, version, capabilities and instructions from the handshake
+    pub fn server_info(&self) -> &InitializeResult {
+        &self.inner.server
+    }
+
+    pub async fn ping(&self) -> Result<(), McpError> {
+        self.request::<Value>("ping", None).await.map(|_| ())
+    }
+
+    /// List all tools of the server
+    pub async fn list_tools(&self) -> Result<Vec<ToolDefinition>, McpError> {
+        self.list_all("tools/list", "tools").await
+    }
+
+    /// All server tools as agent tools, for `create_agent` or `create_deep_agent`
+    pub async fn tools(&self) -> Result<Vec<Arc<dyn Tool>>, McpError> {
+        Ok(self
+            .list_tools()
+            .await?
+            .into_iter()
+            .map(|definition| Arc::new(McpTool::new(self.clone(), definition)) as Arc<dyn Tool>)
+            .collect())
+    }
+
+    /// All server tools as agent tools named `<prefix>_<tool>`, to tell apart the tools of
+    /// several servers
+    pub async fn tools_with_prefix(&self, prefix: &str) -> Result<Vec<Arc<dyn Tool>>, McpError> {
+        Ok(self
+            .list_tools()
+            .await?
+            .into_iter()
+            .map(|definition| {
+                let name = format!("{}_{}", prefix, definition.name);
+                Arc::new(McpTool::new(self.clone(), definition).with_name(name)) as Arc<dyn Tool>
+            })
+            .collect())
+    }
+
+    /// The tools of several servers as one list of agent tools
+    ///
+    /// A tool name offered by more than one server is prefixed with each server's name (see
+    /// [`McpClient::tools_with_prefix`]); names that still clash, e.g. two servers with the
+    /// same name, fail with [`McpError::DuplicateTool`].
+    pub async fn merge_tools(clients: &[McpClient]) -> Result<Vec<Arc<dyn Tool>>, McpError> {
+        let mut definitions = Vec::new();
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for client in clients {
+            let tools = client.list_tools().await?;
+            for tool in &tools {
+                *counts.entry(tool.name.clone()).or_default() += 1;
+            }
+            definitions.push((client, tools));
+        }
+
+        let mut names = std::collections::HashSet::new();
+        let mut merged: Vec<Arc<dyn Tool>> = Vec::new();
+        for (client, tools) in definitions {
+            let server: String = client
+                .server_info()
+                .server_info
+                .name
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c } else { '_' })
+                .collect();
+            for definition in tools {
+                let name = if counts[&definition.name] > 1 {
+                    format!("{}_{}", server, definition.name)
+                } else {
+                    definition.name.clone()
+                };
+                if !names.insert(name.clone()) {
+                    return Err(McpError::DuplicateTool(name));
+                }
+                merged.push(Arc::new(
+                    McpTool::new(client.clone(), definition).with_name(name),
+                ));
+            }
+        }
+        Ok(merged)
+    }
+
+    /// Call a tool; a failing tool is reported through [`CallToolResult::is_error`]
+    pub async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Value,
+    ) -> Result<CallToolResult, McpError> {
+        self.request(
+            "tools/call",
+            Some(json!({ "name": name, "arguments": arguments })),
+        )
+        .await
+    }
+
+    /// Call a tool, passing each progress notification of the call to `on_progress`
+    pub async fn call_tool_with_progress<F>(
+        &self,
+        name: &str,
+        arguments: Value,
+        on_progress: F,
+    ) -> Result<CallToolResult, McpError>
+    where
+        F: Fn(&ProgressNotification) + Send + Sync,
+    {
+        let id = self.next_id();
+        let token = json!(format!("progress-{}", id));
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        self.inner
+            .progress
+            .lock()
+            .unwrap()
+            .insert(token.to_string(), sender);
+
+        let request = JsonRpcRequest::new(
+            json!(id),
+            "tools/call",
+            Some(json!({
+                "name": name,
+                "arguments": arguments,
+                "_meta": { "progressToken": token },
+            })),
+        );
+        let call = self.inner.transport.request(request);
+        futures::pin_mut!(call);
+        let response = loop {
+            tokio::select! {
+                biased;
+                Some(progress) = receiver.recv() => on_progress(&progress),
+                response = &mut call => break response,
+            }
+        };
+        self.inner
+            .progress
+            .lock()
+            .unwrap()
+            .remove(&token.to_string());
+        while let Ok(progress) = receiver.try_recv() {
+            on_progress(&progress);
+        }
+        rpc_result(response?)
+    }
+
+    /// List all resources of the server
+    pub async fn list_resources(&self) -> Result<Vec<Resource>, McpError> {
+        self.list_all("resources/list", "resources").await
+    }
+
+    /// List the resource templates of the server
+    pub async fn list_resource_templates(&self) -> Result<Vec<ResourceTemplate>, McpError> {
+        self.list_all("resources/templates/list", "resourceTemplates")
+            .await
+    }
+
+    /// Read a resource; a resource may have several contents
+    pub async fn read_resource(&self, uri: &str) -> Result<Vec<ResourceContents>, McpError> {
+        let result: Value = self
+            .request("resources/read", Some(json!({ "uri": uri })))
+            .await?;
+        Ok(serde_json::from_value(
+            result.get("contents").cloned().unwrap_or_default(),
+        )?)
+    }
+
+    /// List all prompt templates of the server
+    pub async fn list_prompts(&self) -> Result<Vec<Prompt>, McpError> {
+        self.list_all("prompts/list", "prompts").await
+    }
+
+    /// Render a prompt template; see [`GetPromptResult::to_messages`]
+    pub async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: HashMap<String, String>,
+    ) -> Result<GetPromptResult, McpError> {
+        self.request(
+            "prompts/get",
+            Some(json!({ "name": name, "arguments": arguments })),
+        )
+        .await
+    }
+
+    fn next_id(&self) -> u64 {
+        self.inner.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    async fn request<R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Option<Value>,
+    ) -> Result<R, McpError> {
+        let request = JsonRpcRequest::new(json!(self.next_id()), method, params);
+        rpc_result(self.inner.transport.request(request).await?)
+    }
+
+    /// Collect all pages of a `*/list` method
+    async fn list_all<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        key: &str,
+    ) -> Result<Vec<T>, McpError> {
+        let mut items = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let params = cursor.as_ref().map(|cursor| json!({ "cursor": cursor }));
+            let mut page: Value = self.request(method, params).await?;
+            items.extend(serde_json::from_value::<Vec<T>>(
+                page.get_mut(key).map(Value::take).unwrap_or_default(),
+            )?);
+            cursor = page
+                .get("nextCursor")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            if cursor.is_none() {
+                return Ok(items);
+            }
+        }
+    }
+}
+
+/// Route `notifications/progress` to the listener of its tool call
+fn progress_handler(progress: &ProgressListeners) -> NotificationHandler {
+    let progress = Arc::clone(progress);
+    Arc::new(move |notification: JsonRpcRequest| {
+        if notification.method != "notifications/progress" {
+            log::debug!("Ignoring MCP notification {}", notification.method);
+            return;
+        }
+        let Some(params) = notification
+            .params
+            .and_then(|params| serde_json::from_value::<ProgressNotification>(params).ok())
+        else {
+            return;
+        };
+        if let Some(listener) = progress
+            .lock()
+            .unwrap()
+            .get(&params.progress_token.to_string())
+        {
+            let _ = listener.send(params);
+        }
+    })
+}
+
+fn rpc_result<R: DeserializeOwned>(response: super::types::JsonRpcResponse) -> Result<R, McpError> {
+    if let Some(error) = response.error {
+        return Err(McpError::RpcError(error));
+    }
+    let result = response
+        .result
+        .ok_or_else(|| McpError::InvalidResponse("response has no result".to_string()))?;
+    Ok(serde_json::from_value(result)?)
+}
@@ -0,0 +1,34 @@
+use thiserror::Error;
+
+use super::types::JsonRpcError;
+
+/// Errors returned by the MCP client
+#[derive(Error, Debug)]
+pub enum McpError {
+    #[error("Request error: {0}")]
+    RequestError(#[from] reqwest::Error),
+
+    #[error("HTTP status {status}: {body}")]
+    StatusError { status: u16, body: String },
+
+    #[error("JSON-RPC error {}: {}", .0.code, .0.message)]
+    RpcError(JsonRpcError),
+
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+
+    #[error("Connection closed")]
+    ConnectionClosed,
+
+    #[error("No response within {0:?}")]
+    Timeout(std::time::Duration),
+
+    #[error("Duplicate tool name: {0}")]
+    DuplicateTool(String),
+
+    #[error("Serde json error: {0}")]
+    SerdeJsonError(#[from] serde_json::Error),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
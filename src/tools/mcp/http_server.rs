@@ -0,0 +1,108 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use async_stream::stream;
+use axum::{
+    extract::State as AppState,
+    http::{header, HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::post,
+    Json, Router,
+};
+use serde::Serialize;
+use tokio::{net::TcpListener, sync::mpsc};
+
+use super::{
+    error::McpError,
+    server::{McpServer, ServerInner},
+    types::{JsonRpcError, JsonRpcMessage, JsonRpcResponse},
+};
+
+/// Path of the streamable HTTP endpoint
+pub const MCP_ENDPOINT: &str = "/mcp";
+
+impl McpServer {
+    /// Build the axum router serving the streamable HTTP endpoint at [`MCP_ENDPOINT`]
+    ///
+    /// Requests are answered with JSON, or with an SSE stream carrying progress
+    /// notifications before the response when the client accepts `text/event-stream`.
+    /// The server keeps no session state, so no server-initiated stream is offered.
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route(
+                MCP_ENDPOINT,
+                post(handle_post)
+                    .get(|| async { StatusCode::METHOD_NOT_ALLOWED })
+                    .delete(|| async { StatusCode::OK }),
+            )
+            .with_state(Arc::clone(&self.inner))
+    }
+
+    /// Serve the router on `listener` until the process exits
+    pub async fn serve(&self, listener: TcpListener) -> Result<(), McpError> {
+        axum::serve(listener, self.router()).await?;
+        Ok(())
+    }
+}
+
+async fn handle_post(
+    AppState(server): AppState<Arc<ServerInner>>,
+    headers: HeaderMap,
+    body: String,
+) -> Response {
+    let request = match serde_json::from_str::<JsonRpcMessage>(&body) {
+        Ok(JsonRpcMessage::Request(request)) => request,
+        // Notifications and responses need no answer
+        Ok(JsonRpcMessage::Response(_)) => return StatusCode::ACCEPTED.into_response(),
+        Err(e) => {
+            let error = JsonRpcError::new(JsonRpcError::PARSE_ERROR, e.to_string());
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(JsonRpcResponse::failure(serde_json::Value::Null, error)),
+            )
+                .into_response();
+        }
+    };
+    if request.is_notification() {
+        return StatusCode::ACCEPTED.into_response();
+    }
+
+    let session_id = (request.method == "initialize").then(|| uuid::Uuid::new_v4().to_string());
+    let accepts_sse = headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.contains("text/event-stream"));
+
+    let mut response = if accepts_sse && request.method == "tools/call" {
+        let (notifications, mut pending) = mpsc::unbounded_channel();
+        let call = tokio::spawn(async move { server.handle(request, Some(notifications)).await });
+        let events = stream! {
+            while let Some(notification) = pending.recv().await {
+                yield sse_event(&notification);
+            }
+            match call.await {
+                Ok(response) => yield sse_event(&response),
+                Err(e) => log::warn!("MCP tool call crashed: {}", e),
+            }
+        };
+        Sse::new(events)
+            .keep_alive(KeepAlive::default())
+            .into_response()
+    } else {
+        Json(server.handle(request, None).await).into_response()
+    };
+
+    if let Some(session_id) = session_id.and_then(|id| id.parse().ok()) {
+        response.headers_mut().insert("Mcp-Session-Id", session_id);
+    }
+    response
+}
+
+fn sse_event<T: Serialize>(message: &T) -> Result<Event, Infallible> {
+    Ok(Event::default()
+        .event("message")
+        .data(serde_json::to_string(message).unwrap_or_default()))
+}
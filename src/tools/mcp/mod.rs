@@ -0,0 +1,54 @@
+//! Model Context Protocol (MCP) client and server
+//!
+//! [`McpClient`] connects to an MCP server over stdio (a child process) or streamable HTTP,
+//! performs the `initialize` handshake and discovers the server's tools, resources and prompts.
+//! Each server tool is adapted into an [`McpTool`], so any MCP server can be given to
+//! `create_agent` or a `DeepAgentConfig` like a native tool. Progress notifications of a tool
+//! call are written to the [`ToolRuntime`](crate::tools::ToolRuntime) stream writer.
+//!
+//! [`McpServer`] goes the other way: it publishes tools, retrievers, agents and prompt
+//! templates to IDE assistants and other agent runtimes, over stdio or, with the
+//! `mcp-server` feature, streamable HTTP.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use langchain_ai_rust::tools::mcp::{McpClient, McpServer};
+//!
+//! let github = McpClient::http("http://localhost:8000/mcp").await?;
+//! let mut command = tokio::process::Command::new("uvx");
+//! command.arg("mcp-server-time");
+//! let time = McpClient::stdio(command).await?;
+//!
+//! // Tools offered by both servers are prefixed with the server name
+//! let tools = McpClient::merge_tools(&[github, time]).await?;
+//!
+//! let agent = create_agent("gpt-4o-mini", &tools, None, None)?;
+//!
+//! // Publish our own tools
+//! McpServer::builder("internal-tools", "1.0.0")
+//!     .tools(our_tools)
+//!     .build()
+//!     .serve_stdio()
+//!     .await?;
+//! ```
+
+mod client;
+mod error;
+#[cfg(feature = "mcp-server")]
+mod http_server;
+mod server;
+mod tool;
+mod transport;
+mod types;
+
+#[cfg(test)]
+mod tests;
+
+pub use client::*;
+pub use error::*;
+#[cfg(feature = "mcp-server")]
+pub use http_server::*;
+pub use server::*;
+pub use tool::*;
+pub use types::*;
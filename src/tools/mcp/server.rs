@@ -0,0 +1,561 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex as StdMutex},
+};
+
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
+    sync::{
+        mpsc::{self, UnboundedSender},
+        Mutex,
+    },
+};
+
+use crate::{
+    agent::{AgentState, SubagentTool, UnifiedAgent},
+    prompt::{PromptArgs, PromptFromatter},
+    rag::RetrieverTool,
+    schemas::Retriever,
+    tools::{
+        collect_tool_stream, EmptyContext, InMemoryStore, StreamWriter, Tool, ToolRuntime,
+        ToolUpdate,
+    },
+};
+
+use super::{
+    error::McpError,
+    types::{
+        CallToolResult, Content, GetPromptResult, Implementation, InitializeResult, JsonRpcError,
+        JsonRpcMessage, JsonRpcRequest, JsonRpcResponse, Prompt, PromptArgument, PromptMessage,
+        PromptRole, ResourceContents, ResourceTemplate, ServerCapabilities, ToolDefinition,
+        MCP_PROTOCOL_VERSION, SUPPORTED_PROTOCOL_VERSIONS,
+    },
+};
+
+/// Scheme of the resource URIs under which retrievers are published
+const RETRIEVER_SCHEME: &str = "retriever://";
+
+/// Model Context Protocol server publishing tools, retrievers, agents and prompts
+///
+/// Each [`Tool`] is listed by `tools/list` with its [`Tool::parameters`] as input schema
+/// and run by `tools/call`; a failing tool is returned as an error result rather than a
+/// protocol error, so the calling model can see what went wrong. Retrievers are published
+/// both as a search tool and as the resource template `retriever://{name}/{query}`.
+///
+/// Serve it over stdio with [`McpServer::serve_stdio`], or over streamable HTTP with the
+/// `mcp-server` feature.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let server = McpServer::builder("docs", "1.0.0")
+///     .tools(vec![Arc::new(Wolfram::default())])
+///     .retriever("handbook", "Search the employee handbook", Arc::new(retriever))
+///     .prompt("summarize", "Summarize a text", template)
+///     .build();
+/// server.serve_stdio().await?;
+/// ```
+#[derive(Clone)]
+pub struct McpServer {
+    pub(crate) inner: Arc<ServerInner>,
+}
+
+pub(crate) struct ServerInner {
+    info: Implementation,
+    instructions: Option<String>,
+    tools: Vec<Arc<dyn Tool>>,
+    retrievers: HashMap<String, PublishedRetriever>,
+    prompts: Vec<PublishedPrompt>,
+}
+
+struct PublishedRetriever {
+    description: String,
+    retriever: Arc<dyn Retriever>,
+}
+
+struct PublishedPrompt {
+    name: String,
+    description: String,
+    template: Box<dyn PromptFromatter>,
+}
+
+/// Builder for [`McpServer`]
+pub struct McpServerBuilder {
+    info: Implementation,
+    instructions: Option<String>,
+    tools: Vec<Arc<dyn Tool>>,
+    retrievers: HashMap<String, PublishedRetriever>,
+    prompts: Vec<PublishedPrompt>,
+}
+
+impl McpServerBuilder {
+    pub fn new<N: Into<String>, V: Into<String>>(name: N, version: V) -> Self {
+        Self {
+            info: Implementation::new(name, version),
+            instructions: None,
+            tools: Vec::new(),
+            retrievers: HashMap::new(),
+            prompts: Vec::new(),
+        }
+    }
+
+    /// Usage hints sent to clients in the `initialize` handshake
+    pub fn instructions<S: Into<String>>(mut self, instructions: S) -> Self {
+        self.instructions = Some(instructions.into());
+        self
+    }
+
+    pub fn tool(mut self, tool: Arc<dyn Tool>) -> Self {
+        self.tools.push(tool);
+        self
+    }
+
+    pub fn tools(mut self, tools: Vec<Arc<dyn Tool>>) -> Self {
+        self.tools.extend(tools);
+        self
+    }
+
+    /// Publish a retriever as the search tool `name` and the resource template
+    /// `retriever://{name}/{query}`
+    pub fn retriever<N: Into<String>, D: Into<String>>(
+        mut self,
+        name: N,
+        description: D,
+        retriever: Arc<dyn Retriever>,
+    ) -> Self {
+        let name = name.into();
+        let description = description.into();
+        self.tools.push(Arc::new(RetrieverTool::new(
+            Arc::clone(&retriever),
+            name.clone(),
+            description.clone(),
+        )));
+        self.retrievers.insert(
+            name,
+            PublishedRetriever {
+                description,
+                retriever,
+            },
+        );
+        self
+    }
+
+    /// Publish an agent as the tool `name`, taking the request as its `input`
+    pub fn agent<N: Into<String>, D: Into<String>>(
+        self,
+        name: N,
+        description: D,
+        agent: Arc<UnifiedAgent>,
+    ) -> Self {
+        self.tool(Arc::new(SubagentTool::new(
+            agent,
+            name.into(),
+            description.into(),
+        )))
+    }
+
+    /// Publish a prompt template; its variables become required prompt arguments
+    pub fn prompt<N, D, P>(mut self, name: N, description: D, template: P) -> Self
+    where
+        N: Into<String>,
+        D: Into<String>,
+        P: PromptFromatter + 'static,
+    {
+        self.prompts.push(PublishedPrompt {
+            name: name.into(),
+            description: description.into(),
+            template: Box::new(template),
+        });
+        self
+    }
+
+    pub fn build(self) -> McpServer {
+        McpServer {
+            inner: Arc::new(ServerInner {
+                info: self.info,
+                instructions: self.instructions,
+                tools: self.tools,
+                retrievers: self.retrievers,
+                prompts: self.prompts,
+            }),
+        }
+    }
+}
+
+impl McpServer {
+    pub fn builder<N: Into<String>, V: Into<String>>(name: N, version: V) -> McpServerBuilder {
+        McpServerBuilder::new(name, version)
+    }
+
+    /// Serve over the process' stdin and stdout until stdin is closed
+    pub async fn serve_stdio(&self) -> Result<(), McpError> {
+        self.serve_streams(tokio::io::stdin(), tokio::io::stdout())
+            .await
+    }
+
+    /// Serve newline-delimited JSON-RPC over a pair of streams until the reader is closed
+    ///
+    /// Requests are handled concurrently, so a long tool call does not block pings or
+    /// other calls.
+    pub async fn serve_streams<R, W>(&self, reader: R, writer: W) -> Result<(), McpError>
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+        W: AsyncWrite + Send + Unpin + 'static,
+    {
+        let (sender, mut outgoing) = mpsc::unbounded_channel::<JsonRpcMessage>();
+        let write = tokio::spawn(async move {
+            let mut writer = writer;
+            while let Some(message) = outgoing.recv().await {
+                let mut line = serde_json::to_vec(&message)?;
+                line.push(b'\n');
+                writer.write_all(&line).await?;
+                writer.flush().await?;
+            }
+            Ok::<_, McpError>(())
+        });
+
+        let mut lines = BufReader::new(reader).lines();
+        while let Some(line) = lines.next_line().await? {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let request = match serde_json::from_str::<JsonRpcMessage>(line) {
+                Ok(JsonRpcMessage::Request(request)) => request,
+                // The server sends no requests, so there are no responses to wait for
+                Ok(JsonRpcMessage::Response(_)) => continue,
+                Err(e) => {
+                    let error = JsonRpcError::new(JsonRpcError::PARSE_ERROR, e.to_string());
+                    let _ = sender.send(JsonRpcMessage::Response(JsonRpcResponse::failure(
+                        Value::Null,
+                        error,
+                    )));
+                    continue;
+                }
+            };
+            if request.is_notification() {
+                continue;
+            }
+
+            let server = Arc::clone(&self.inner);
+            let sender = sender.clone();
+            tokio::spawn(async move {
+                let (notifications, mut pending) = mpsc::unbounded_channel();
+                // Ends once the request is handled and the notification sender dropped
+                let forward = async {
+                    while let Some(notification) = pending.recv().await {
+                        let _ = sender.send(JsonRpcMessage::Request(notification));
+                    }
+                };
+                let (response, _) =
+                    tokio::join!(server.handle(request, Some(notifications)), forward);
+                let _ = sender.send(JsonRpcMessage::Response(response));
+            });
+        }
+
+        drop(sender);
+        write.await.map_err(|_| McpError::ConnectionClosed)?
+    }
+}
+
+impl ServerInner {
+    /// Answer a request; notifications sent while handling it (such as progress) go to
+    /// `notifications`
+    pub(crate) async fn handle(
+        &self,
+        request: JsonRpcRequest,
+        notifications: Option<UnboundedSender<JsonRpcRequest>>,
+    ) -> JsonRpcResponse {
+        let id = request.id.clone().unwrap_or_default();
+        let params = request.params.unwrap_or_else(|| json!({}));
+        let result = match request.method.as_str() {
+            "initialize" => self.initialize(&params),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": self.tool_definitions() })),
+            "tools/call" => self.call_tool(params, notifications).await,
+            "resources/list" => Ok(json!({ "resources": [] })),
+            "resources/templates/list" => {
+                Ok(json!({ "resourceTemplates": self.resource_templates() }))
+            }
+            "resources/read" => self.read_resource(params).await,
+            "prompts/list" => Ok(json!({ "prompts": self.prompt_definitions() })),
+            "prompts/get" => self.get_prompt(params),
+            method => Err(JsonRpcError::new(
+                JsonRpcError::METHOD_NOT_FOUND,
+                format!("Method not found: {}", method),
+            )),
+        };
+        match result {
+            Ok(result) => JsonRpcResponse::success(id, result),
+            Err(error) => JsonRpcResponse::failure(id, error),
+        }
+    }
+
+    fn initialize(&self, params: &Value) -> Result<Value, JsonRpcError> {
+        // Answer with the client's version when supported, otherwise with the latest one
+        let protocol_version = params["protocolVersion"]
+            .as_str()
+            .filter(|version| SUPPORTED_PROTOCOL_VERSIONS.contains(version))
+            .unwrap_or(MCP_PROTOCOL_VERSION);
+        let present = |present: bool| present.then(|| json!({}));
+        to_result(&InitializeResult {
+            protocol_version: protocol_version.to_string(),
+            capabilities: ServerCapabilities {
+                tools: present(!self.tools.is_empty()),
+                resources: present(!self.retrievers.is_empty()),
+                prompts: present(!self.prompts.is_empty()),
+                logging: None,
+            },
+            server_info: self.info.clone(),
+            instructions: self.instructions.clone(),
+        })
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        self.tools
+            .iter()
+            .map(|tool| ToolDefinition {
+                name: tool.name(),
+                title: None,
+                description: Some(tool.description()),
+                input_schema: tool.parameters(),
+                output_schema: None,
+                annotations: None,
+            })
+            .collect()
+    }
+
+    async fn call_tool(
+        &self,
+        params: Value,
+        notifications: Option<UnboundedSender<JsonRpcRequest>>,
+    ) -> Result<Value, JsonRpcError> {
+        let name = params["name"]
+            .as_str()
+            .ok_or_else(|| invalid_params("Missing tool name"))?;
+        let tool = self
+            .tools
+            .iter()
+            .find(|tool| tool.name() == name)
+            .ok_or_else(|| invalid_params(format!("Unknown tool: {}", name)))?;
+
+        let mut runtime = ToolRuntime::new(
+            Arc::new(Mutex::new(AgentState::new())),
+            Arc::new(EmptyContext),
+            Arc::new(InMemoryStore::new()),
+            format!("mcp-{}", name),
+        );
+        let token = params["_meta"].get("progressToken").cloned();
+        if let (Some(token), Some(sender)) = (token, notifications) {
+            runtime = runtime.with_stream_writer(Arc::new(ProgressWriter {
+                token,
+                sender,
+                progress: StdMutex::new(0.0),
+            }));
+        }
+
+        // Same input handling as when an agent calls the tool
+        let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+        let input = tool.parse_input(&arguments.to_string()).await;
+        let result = match collect_tool_stream(tool.run_stream(input, &runtime), &runtime).await {
+            Ok(result) => {
+                let attachments: Vec<Content> = result
+                    .attachments()
+                    .iter()
+                    .map(Content::from_content_part)
+                    .collect();
+                let mut content = vec![Content::text(result.into_string())];
+                content.extend(attachments);
+                CallToolResult {
+                    content,
+                    ..Default::default()
+                }
+            }
+            Err(e) => CallToolResult {
+                content: vec![Content::text(e.to_string())],
+                is_error: true,
+                ..Default::default()
+            },
+        };
+        to_result(&result)
+    }
+
+    fn resource_templates(&self) -> Vec<ResourceTemplate> {
+        let mut templates: Vec<_> = self
+            .retrievers
+            .iter()
+            .map(|(name, published)| ResourceTemplate {
+                uri_template: format!("{}{}/{{query}}", RETRIEVER_SCHEME, name),
+                name: name.clone(),
+                title: None,
+                description: Some(published.description.clone()),
+                mime_type: Some("text/plain".to_string()),
+            })
+            .collect();
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+        templates
+    }
+
+    /// Run the retriever named by a `retriever://{name}/{query}` URI, one content per document
+    async fn read_resource(&self, params: Value) -> Result<Value, JsonRpcError> {
+        #[derive(serde::Deserialize)]
+        struct ReadParams {
+            uri: String,
+        }
+        let ReadParams { uri } = parse_params(params)?;
+        let (name, query) = uri
+            .strip_prefix(RETRIEVER_SCHEME)
+            .and_then(|rest| rest.split_once('/'))
+            .ok_or_else(|| resource_not_found(&uri))?;
+        let published = self
+            .retrievers
+            .get(name)
+            .ok_or_else(|| resource_not_found(&uri))?;
+        let query = urlencoding::decode(query)
+            .map_err(|e| invalid_params(e.to_string()))?
+            .into_owned();
+
+        let documents = published
+            .retriever
+            .get_relevant_documents(&query)
+            .await
+            .map_err(|e| JsonRpcError::new(JsonRpcError::INTERNAL_ERROR, e.to_string()))?;
+        let contents: Vec<ResourceContents> = documents
+            .into_iter()
+            .map(|document| ResourceContents {
+                uri: uri.clone(),
+                mime_type: Some("text/plain".to_string()),
+                text: Some(document.page_content),
+                blob: None,
+            })
+            .collect();
+        Ok(json!({ "contents": contents }))
+    }
+
+    fn prompt_definitions(&self) -> Vec<Prompt> {
+        self.prompts
+            .iter()
+            .map(|prompt| Prompt {
+                name: prompt.name.clone(),
+                title: None,
+                description: Some(prompt.description.clone()),
+                arguments: prompt
+                    .template
+                    .variables()
+                    .into_iter()
+                    .map(|name| PromptArgument {
+                        name,
+                        description: None,
+                        required: true,
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    fn get_prompt(&self, params: Value) -> Result<Value, JsonRpcError> {
+        #[derive(serde::Deserialize)]
+        struct GetParams {
+            name: String,
+            #[serde(default)]
+            arguments: HashMap<String, String>,
+        }
+        let GetParams { name, arguments } = parse_params(params)?;
+        let prompt = self
+            .prompts
+            .iter()
+            .find(|prompt| prompt.name == name)
+            .ok_or_else(|| invalid_params(format!("Unknown prompt: {}", name)))?;
+
+        let arguments: PromptArgs = arguments
+            .into_iter()
+            .map(|(key, value)| (key, Value::String(value)))
+            .collect();
+        let text = prompt
+            .template
+            .format(arguments)
+            .map_err(|e| invalid_params(e.to_string()))?;
+        to_result(&GetPromptResult {
+            description: Some(prompt.description.clone()),
+            messages: vec![PromptMessage {
+                role: PromptRole::User,
+                content: Content::text(text),
+            }],
+        })
+    }
+}
+
+/// Sends each update a tool streams as a `notifications/progress` of its call
+///
+/// Progress updates carry their own amounts; other updates count up from the last one.
+struct ProgressWriter {
+    token: Value,
+    sender: UnboundedSender<JsonRpcRequest>,
+    progress: StdMutex<f64>,
+}
+
+impl ProgressWriter {
+    fn notify(&self, progress: f64, total: Option<f64>, message: Option<String>) {
+        let mut params = json!({
+            "progressToken": self.token,
+            "progress": progress,
+        });
+        if let Some(total) = total {
+            params["total"] = json!(total);
+        }
+        if let Some(message) = message {
+            params["message"] = json!(message);
+        }
+        let _ = self.sender.send(JsonRpcRequest::notification(
+            "notifications/progress",
+            Some(params),
+        ));
+    }
+}
+
+impl StreamWriter for ProgressWriter {
+    fn write(&self, message: &str) {
+        self.write_update(&ToolUpdate::message(message));
+    }
+
+    fn write_update(&self, update: &ToolUpdate) {
+        let mut last = self.progress.lock().unwrap_or_else(|e| e.into_inner());
+        match update {
+            ToolUpdate::Progress {
+                progress,
+                total,
+                message,
+            } => {
+                *last = *progress;
+                self.notify(*progress, *total, message.clone());
+            }
+            other => {
+                *last += 1.0;
+                self.notify(*last, None, Some(other.to_string()));
+            }
+        }
+    }
+}
+
+fn invalid_params<S: Into<String>>(message: S) -> JsonRpcError {
+    JsonRpcError::new(JsonRpcError::INVALID_PARAMS, message)
+}
+
+fn resource_not_found(uri: &str) -> JsonRpcError {
+    JsonRpcError {
+        code: JsonRpcError::RESOURCE_NOT_FOUND,
+        message: "Resource not found".to_string(),
+        data: Some(json!({ "uri": uri })),
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, JsonRpcError> {
+    serde_json::from_value(params).map_err(|e| invalid_params(e.to_string()))
+}
+
+fn to_result<T: serde::Serialize>(value: &T) -> Result<Value, JsonRpcError> {
+    serde_json::to_value(value)
+        .map_err(|e| JsonRpcError::new(JsonRpcError::INTERNAL_ERROR, e.to_string()))
+}
@@ -0,0 +1,588 @@
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream},
+    sync::Mutex,
+};
+
+use crate::{
+    agent::AgentState,
+    schemas::{ContentPart, FileContent, ImageContent},
+    tools::{CollectingStreamWriter, EmptyContext, InMemoryStore, Tool, ToolRuntime},
+};
+
+use super::*;
+
+/// A minimal MCP server speaking newline-delimited JSON-RPC.
+async fn fake_server(stream: DuplexStream) {
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let request: JsonRpcRequest = serde_json::from_str(&line).unwrap();
+        let Some(id) = request.id.clone() else {
+            continue;
+        };
+        let params = request.params.clone().unwrap_or_default();
+        let mut out = Vec::new();
+        let result = match request.method.as_str() {
+            "initialize" => json!({
+                "protocolVersion": MCP_PROTOCOL_VERSION,
+                "capabilities": { "tools": {}, "resources": {}, "prompts": {} },
+                "serverInfo": { "name": "fake", "version": "1.0" },
+            }),
+            // Two pages, to exercise cursors
+            "tools/list" if params["cursor"].is_null() => json!({
+                "tools": [{
+                    "name": "echo",
+                    "description": "Echo the text",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": { "text": { "type": "string" } },
+                        "required": ["text"],
+                    },
+                    "annotations": { "readOnlyHint": true },
+                }],
+                "nextCursor": "2",
+            }),
+            "tools/list" => json!({
+                "tools": [{ "name": "fail", "inputSchema": { "type": "object" } }],
+            }),
+            "tools/call" if params["name"] == "echo" => {
+                if let Some(token) = params["_meta"].get("progressToken") {
+                    let progress = JsonRpcRequest::notification(
+                        "notifications/progress",
+                        Some(json!({
+                            "progressToken": token,
+                            "progress": 1,
+                            "total": 2,
+                            "message": "Echoing",
+                        })),
+                    );
+                    out.push(serde_json::to_string(&progress).unwrap());
+                }
+                json!({ "content": [{ "type": "text", "text": params["arguments"]["text"] }] })
+            }
+            "tools/call" => json!({
+                "content": [{ "type": "text", "text": "boom" }],
+                "isError": true,
+            }),
+            "resources/list" => json!({
+                "resources": [{ "uri": "file:///readme", "name": "readme" }],
+            }),
+            "resources/read" => json!({
+                "contents": [{ "uri": params["uri"], "text": "Hello" }],
+            }),
+            "prompts/list" => json!({
+                "prompts": [{ "name": "greet", "arguments": [{ "name": "who", "required": true }] }],
+            }),
+            "prompts/get" => json!({
+                "messages": [{
+                    "role": "user",
+                    "content": { "type": "text", "text": format!("Hi {}", params["arguments"]["who"].as_str().unwrap()) },
+                }],
+            }),
+            "ping" => json!({}),
+            _ => Value::Null,
+        };
+        let response = if result.is_null() {
+            JsonRpcResponse::failure(
+                id,
+                JsonRpcError::new(JsonRpcError::METHOD_NOT_FOUND, "Method not found"),
+            )
+        } else {
+            JsonRpcResponse::success(id, result)
+        };
+        out.push(serde_json::to_string(&response).unwrap());
+        for line in out {
+            writer.write_all(line.as_bytes()).await.unwrap();
+            writer.write_all(b"\n").await.unwrap();
+        }
+    }
+}
+
+async fn connect() -> McpClient {
+    let (client, server) = tokio::io::duplex(64 * 1024);
+    tokio::spawn(fake_server(server));
+    let (reader, writer) = tokio::io::split(client);
+    McpClient::from_streams(reader, writer).await.unwrap()
+}
+
+fn runtime(writer: &CollectingStreamWriter) -> ToolRuntime {
+    ToolRuntime::new(
+        Arc::new(Mutex::new(AgentState::new())),
+        Arc::new(EmptyContext),
+        Arc::new(InMemoryStore::new()),
+        "call_1".to_string(),
+    )
+    .with_stream_writer(Arc::new(writer.clone()))
+}
+
+#[tokio::test]
+async fn test_initialize_and_list_tools() {
+    let client = connect().await;
+    assert_eq!(client.server_info().server_info.name, "fake");
+    client.ping().await.unwrap();
+
+    let tools = client.list_tools().await.unwrap();
+    let names: Vec<_> = tools.iter().map(|tool| tool.name.as_str()).collect();
+    assert_eq!(names, ["echo", "fail"]);
+}
+
+#[tokio::test]
+async fn test_mcp_tool_adapter() {
+    let client = connect().await;
+    let tools = client.tools().await.unwrap();
+    let echo = &tools[0];
+
+    assert_eq!(echo.name(), "echo");
+    assert_eq!(echo.description(), "Echo the text");
+    assert_eq!(echo.parameters()["required"], json!(["text"]));
+    assert!(echo.is_parallel_safe());
+    assert!(!tools[1].is_parallel_safe());
+
+    assert_eq!(echo.call(r#"{"text": "hello"}"#).await.unwrap(), "hello");
+    // A bare string fills the only parameter
+    assert_eq!(echo.call("plain").await.unwrap(), "plain");
+
+    let error = tools[1].call("{}").await.unwrap_err();
+    assert!(error.to_string().contains("boom"));
+}
+
+#[tokio::test]
+async fn test_progress_is_streamed() {
+    let client = connect().await;
+    let echo = client.tools().await.unwrap().remove(0);
+    let writer = CollectingStreamWriter::new();
+
+    let result = echo
+        .run_with_runtime(json!({ "text": "hi" }), &runtime(&writer))
+        .await
+        .unwrap();
+    assert_eq!(result.as_str(), "hi");
+    assert_eq!(writer.get_messages().await, vec!["Echoing (1/2)"]);
+}
+
+#[tokio::test]
+async fn test_resources_and_prompts() {
+    let client = connect().await;
+
+    let resources = client.list_resources().await.unwrap();
+    assert_eq!(resources[0].uri, "file:///readme");
+    let contents = client.read_resource("file:///readme").await.unwrap();
+    assert_eq!(contents[0].text.as_deref(), Some("Hello"));
+
+    let prompts = client.list_prompts().await.unwrap();
+    assert!(prompts[0].arguments[0].required);
+    let prompt = client
+        .get_prompt("greet", [("who".to_string(), "Ada".to_string())].into())
+        .await
+        .unwrap();
+    assert_eq!(prompt.to_messages()[0].content, "Hi Ada");
+}
+
+#[tokio::test]
+async fn test_closed_connection() {
+    let (client, server) = tokio::io::duplex(1024);
+    drop(server);
+    let (reader, writer) = tokio::io::split(client);
+    assert!(McpClient::from_streams(reader, writer).await.is_err());
+}
+
+/// A stdio server process answering the handshake and `tools/list`, then nothing.
+#[cfg(unix)]
+fn stdio_server_command() -> tokio::process::Command {
+    let script = format!(
+        r#"read -r line
+printf '%s\n' '{{"jsonrpc":"2.0","id":0,"result":{{"protocolVersion":"{}","capabilities":{{}},"serverInfo":{{"name":"sh","version":"1.0"}}}}}}'
+read -r line
+read -r line
+printf '%s\n' '{{"jsonrpc":"2.0","id":1,"result":{{"tools":[{{"name":"date","inputSchema":{{"type":"object"}}}}]}}}}'
+cat > /dev/null"#,
+        MCP_PROTOCOL_VERSION
+    );
+    let mut command = tokio::process::Command::new("sh");
+    command.arg("-c").arg(script);
+    command
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_stdio_server_process() {
+    let client = McpClient::stdio(stdio_server_command()).await.unwrap();
+    assert_eq!(client.server_info().server_info.name, "sh");
+    let tools = client.tools().await.unwrap();
+    assert_eq!(tools[0].name(), "date");
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_stdio_request_timeout() {
+    let timeout = std::time::Duration::from_millis(200);
+    let client = McpClient::stdio_with_timeout(stdio_server_command(), timeout)
+        .await
+        .unwrap();
+    client.list_tools().await.unwrap();
+    // The server stops answering after `tools/list`
+    let error = client.ping().await.unwrap_err();
+    assert!(matches!(error, McpError::Timeout(t) if t == timeout));
+}
+
+#[tokio::test]
+async fn test_merge_tools_of_several_servers() {
+    let other = McpServer::builder("other tools", "0.1.0")
+        .tools(vec![Arc::new(ShoutTool)])
+        .build();
+    let clients = [
+        connect().await,
+        connect_to_server().await,
+        connect_to(other).await,
+    ];
+    let tools = McpClient::merge_tools(&clients).await.unwrap();
+    let names: Vec<_> = tools.iter().map(|tool| tool.name()).collect();
+    assert_eq!(
+        names,
+        [
+            "echo",
+            "fail",
+            "rust_tools_shout",
+            "handbook",
+            "other_tools_shout"
+        ]
+    );
+    // Renamed tools still call the server's tool
+    assert_eq!(tools[4].call("hi").await.unwrap(), "HI");
+
+    let clients = [connect().await, connect().await];
+    let error = McpClient::merge_tools(&clients).await.err().unwrap();
+    assert!(matches!(error, McpError::DuplicateTool(name) if name == "fake_echo"));
+}
+
+#[tokio::test]
+async fn test_http_transport_with_sse() {
+    let mut server = mockito::Server::new_async().await;
+    let init = JsonRpcResponse::success(
+        json!(0),
+        json!({
+            "protocolVersion": MCP_PROTOCOL_VERSION,
+            "serverInfo": { "name": "http", "version": "1.0" },
+        }),
+    );
+    let _init = server
+        .mock("POST", "/mcp")
+        .match_body(mockito::Matcher::PartialJson(
+            json!({ "method": "initialize" }),
+        ))
+        .with_header("content-type", "application/json")
+        .with_header("Mcp-Session-Id", "session-1")
+        .with_body(serde_json::to_string(&init).unwrap())
+        .create_async()
+        .await;
+    let _initialized = server
+        .mock("POST", "/mcp")
+        .match_header("Mcp-Session-Id", "session-1")
+        .match_body(mockito::Matcher::PartialJson(
+            json!({ "method": "notifications/initialized" }),
+        ))
+        .with_status(202)
+        .create_async()
+        .await;
+    let call = JsonRpcResponse::success(
+        json!(1),
+        json!({ "content": [{ "type": "text", "text": "done" }] }),
+    );
+    let _call = server
+        .mock("POST", "/mcp")
+        .match_header("Mcp-Session-Id", "session-1")
+        .match_body(mockito::Matcher::PartialJson(
+            json!({ "method": "tools/call" }),
+        ))
+        .with_header("content-type", "text/event-stream")
+        .with_body(format!(
+            "event: message\ndata: {}\n\n",
+            serde_json::to_string(&call).unwrap()
+        ))
+        .create_async()
+        .await;
+
+    let client = McpClient::http(format!("{}/mcp", server.url()))
+        .await
+        .unwrap();
+    assert_eq!(client.server_info().server_info.name, "http");
+    let result = client.call_tool("work", json!({})).await.unwrap();
+    assert_eq!(result.text(), "done");
+}
+
+/// Streams a progress message, then echoes its input or fails on `fail`.
+struct ShoutTool;
+
+#[async_trait::async_trait]
+impl Tool for ShoutTool {
+    fn name(&self) -> String {
+        "shout".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Shout the input".to_string()
+    }
+
+    async fn run(&self, input: Value) -> Result<String, crate::error::ToolError> {
+        let text = input.as_str().unwrap_or_default();
+        if text == "fail" {
+            return Err(crate::error::ToolError::InvalidInputError(
+                "cannot shout that".to_string(),
+            ));
+        }
+        Ok(text.to_uppercase())
+    }
+
+    async fn run_with_runtime(
+        &self,
+        input: Value,
+        runtime: &ToolRuntime,
+    ) -> Result<crate::tools::ToolResult, Box<dyn std::error::Error>> {
+        runtime.stream("Shouting");
+        Ok(crate::tools::ToolResult::Text(self.run(input).await?))
+    }
+}
+
+struct FixedRetriever;
+
+#[async_trait::async_trait]
+impl crate::schemas::Retriever for FixedRetriever {
+    async fn get_relevant_documents(
+        &self,
+        query: &str,
+    ) -> Result<Vec<crate::schemas::Document>, crate::error::RetrieverError> {
+        Ok(vec![crate::schemas::Document::new(format!(
+            "About {}",
+            query
+        ))])
+    }
+}
+
+/// Returns a chart as an image attachment
+struct ChartTool;
+
+#[async_trait::async_trait]
+impl Tool for ChartTool {
+    fn name(&self) -> String {
+        "chart".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Render a chart".to_string()
+    }
+
+    async fn run(&self, _input: Value) -> Result<String, crate::error::ToolError> {
+        Ok("Rendered the chart".to_string())
+    }
+
+    async fn run_with_runtime(
+        &self,
+        _input: Value,
+        _runtime: &ToolRuntime,
+    ) -> Result<crate::tools::ToolResult, Box<dyn std::error::Error>> {
+        Ok(crate::tools::ToolResult::content(vec![
+            ContentPart::text("Rendered the chart"),
+            ContentPart::image(ImageContent::from_base64("image/png", "iVBORw0K")),
+        ]))
+    }
+}
+
+fn test_server() -> McpServer {
+    McpServer::builder("rust-tools", "0.1.0")
+        .instructions("Use shout for emphasis")
+        .tools(vec![Arc::new(ShoutTool)])
+        .retriever("handbook", "Search the handbook", Arc::new(FixedRetriever))
+        .prompt(
+            "greet",
+            "Greet someone",
+            crate::prompt::PromptTemplate::new(
+                "Hello {name}!".to_string(),
+                vec!["name".to_string()],
+                crate::prompt::TemplateFormat::FString,
+            ),
+        )
+        .build()
+}
+
+async fn connect_to_server() -> McpClient {
+    connect_to(test_server()).await
+}
+
+async fn connect_to(mcp_server: McpServer) -> McpClient {
+    let (client, server) = tokio::io::duplex(64 * 1024);
+    tokio::spawn(async move {
+        let (reader, writer) = tokio::io::split(server);
+        mcp_server.serve_streams(reader, writer).await
+    });
+    let (reader, writer) = tokio::io::split(client);
+    McpClient::from_streams(reader, writer).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_server_tools() {
+    let client = connect_to_server().await;
+    let info = client.server_info();
+    assert_eq!(info.server_info.name, "rust-tools");
+    assert_eq!(info.instructions.as_deref(), Some("Use shout for emphasis"));
+    assert!(info.capabilities.tools.is_some());
+
+    let tools = client.list_tools().await.unwrap();
+    let names: Vec<_> = tools.iter().map(|tool| tool.name.as_str()).collect();
+    assert_eq!(names, ["shout", "handbook"]);
+    assert_eq!(tools[0].input_schema, ShoutTool.parameters());
+
+    let result = client
+        .call_tool("shout", json!({ "input": "hey" }))
+        .await
+        .unwrap();
+    assert_eq!(result.text(), "HEY");
+
+    let failed = client
+        .call_tool("shout", json!({ "input": "fail" }))
+        .await
+        .unwrap();
+    assert!(failed.is_error);
+    assert_eq!(failed.text(), "Invalid input: cannot shout that");
+
+    let unknown = client.call_tool("whisper", json!({})).await.unwrap_err();
+    assert!(matches!(unknown, McpError::RpcError(e) if e.code == JsonRpcError::INVALID_PARAMS));
+}
+
+#[tokio::test]
+async fn test_server_streams_progress() {
+    let client = connect_to_server().await;
+    let shout = client.tools().await.unwrap().remove(0);
+    let writer = CollectingStreamWriter::new();
+
+    let result = shout
+        .run_with_runtime(json!({ "input": "hey" }), &runtime(&writer))
+        .await
+        .unwrap();
+    assert_eq!(result.as_str(), "HEY");
+    assert_eq!(writer.get_messages().await, vec!["Shouting (1)"]);
+}
+
+#[tokio::test]
+async fn test_attachments_round_trip() {
+    let client = connect_to(
+        McpServer::builder("charts", "0.1.0")
+            .tools(vec![Arc::new(ChartTool)])
+            .build(),
+    )
+    .await;
+
+    let result = client.call_tool("chart", json!({})).await.unwrap();
+    assert_eq!(
+        result.content,
+        vec![
+            Content::text("Rendered the chart"),
+            Content::Image {
+                data: "iVBORw0K".to_string(),
+                mime_type: "image/png".to_string(),
+            },
+        ]
+    );
+
+    let chart = client.tools().await.unwrap().remove(0);
+    let writer = CollectingStreamWriter::new();
+    let result = chart
+        .run_with_runtime(json!({}), &runtime(&writer))
+        .await
+        .unwrap();
+    assert_eq!(result.as_str(), "Rendered the chart");
+    assert_eq!(
+        result.attachments(),
+        [ContentPart::image(ImageContent::from_base64(
+            "image/png",
+            "iVBORw0K"
+        ))]
+    );
+}
+
+#[test]
+fn test_content_parts() {
+    let result = CallToolResult {
+        content: vec![
+            Content::Resource {
+                resource: ResourceContents {
+                    uri: "file:///report.pdf".to_string(),
+                    mime_type: Some("application/pdf".to_string()),
+                    text: None,
+                    blob: Some("JVBERi0=".to_string()),
+                },
+            },
+            Content::Resource {
+                resource: ResourceContents {
+                    uri: "file:///notes.txt".to_string(),
+                    mime_type: None,
+                    text: Some("Notes".to_string()),
+                    blob: None,
+                },
+            },
+        ],
+        ..Default::default()
+    };
+    let file =
+        FileContent::from_base64("application/pdf", "JVBERi0=").with_name("file:///report.pdf");
+    assert_eq!(
+        result.content_parts(),
+        vec![ContentPart::file(file.clone()), ContentPart::text("Notes")]
+    );
+    assert!(matches!(
+        Content::from_content_part(&ContentPart::file(file)),
+        Content::Resource { resource } if resource.blob.as_deref() == Some("JVBERi0=")
+    ));
+}
+
+#[tokio::test]
+async fn test_server_retriever_and_prompt() {
+    let client = connect_to_server().await;
+
+    let templates = client.list_resource_templates().await.unwrap();
+    assert_eq!(templates[0].uri_template, "retriever://handbook/{query}");
+    let contents = client
+        .read_resource("retriever://handbook/vacation%20days")
+        .await
+        .unwrap();
+    assert_eq!(contents[0].text.as_deref(), Some("About vacation days"));
+    assert!(client.read_resource("retriever://wiki/x").await.is_err());
+
+    let result = client
+        .call_tool("handbook", json!({ "query": "pets" }))
+        .await
+        .unwrap();
+    assert!(result.text().contains("About"));
+
+    let prompts = client.list_prompts().await.unwrap();
+    assert_eq!(prompts[0].arguments[0].name, "name");
+    let prompt = client
+        .get_prompt("greet", [("name".to_string(), "Ada".to_string())].into())
+        .await
+        .unwrap();
+    assert_eq!(prompt.to_messages()[0].content, "Hello Ada!");
+}
+
+#[cfg(feature = "mcp-server")]
+#[tokio::test]
+async fn test_server_over_http() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { test_server().serve(listener).await });
+
+    let client = McpClient::http(format!("http://{}{}", address, MCP_ENDPOINT))
+        .await
+        .unwrap();
+    assert_eq!(client.server_info().server_info.name, "rust-tools");
+
+    let shout = client.tools().await.unwrap().remove(0);
+    let writer = CollectingStreamWriter::new();
+    let result = shout
+        .run_with_runtime(json!({ "input": "hey" }), &runtime(&writer))
+        .await
+        .unwrap();
+    assert_eq!(result.as_str(), "HEY");
+    assert_eq!(writer.get_messages().await, vec!["Shouting (1)"]);
+}
@@ -0,0 +1,163 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+
+use crate::{
+    error::ToolError,
+    tools::{Tool, ToolResult, ToolRuntime},
+};
+
+use super::{
+    client::McpClient,
+    types::{CallToolResult, ToolDefinition},
+};
+
+/// A tool of an MCP server, adapted to [`Tool`]
+///
+/// The parameters are the server's input schema and running it calls `tools/call`. When run by
+/// an agent, progress notifications of the call are written to the runtime's stream writer.
+/// Images and embedded files in the result are attached to the [`ToolResult`].
+pub struct McpTool {
+    client: McpClient,
+    definition: ToolDefinition,
+    /// Name shown to the model, the server's tool name unless renamed
+    name: String,
+}
+
+impl McpTool {
+    pub fn new(client: McpClient, definition: ToolDefinition) -> Self {
+        Self {
+            client,
+            name: definition.name.clone(),
+            definition,
+        }
+    }
+
+    /// Expose the tool under another name; calls still use the server's tool name
+    pub fn with_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn definition(&self) -> &ToolDefinition {
+        &self.definition
+    }
+
+    /// Tool arguments from the model's input: a JSON object, or a plain string when the
+    /// schema has a single property
+    fn arguments(&self, input: Value) -> Result<Value, ToolError> {
+        let input = match input {
+            Value::String(text) => match serde_json::from_str::<Value>(&text) {
+                Ok(value @ Value::Object(_)) => value,
+                _ => Value::String(text),
+            },
+            other => other,
+        };
+        match input {
+            Value::Object(_) => Ok(input),
+            Value::Null => Ok(Value::Object(Map::new())),
+            other => {
+                let properties = self.definition.input_schema["properties"].as_object();
+                match properties.filter(|properties| properties.len() == 1) {
+                    Some(properties) => {
+                        let name = properties.keys().next().unwrap().clone();
+                        Ok(Value::Object(Map::from_iter([(name, other)])))
+                    }
+                    None => Err(ToolError::InvalidInputError(format!(
+                        "{} expects a JSON object of arguments",
+                        self.definition.name
+                    ))),
+                }
+            }
+        }
+    }
+
+    async fn call_tool(
+        &self,
+        input: Value,
+        runtime: Option<&ToolRuntime>,
+    ) -> Result<CallToolResult, ToolError> {
+        let arguments = self.arguments(input)?;
+        let result = match runtime {
+            Some(runtime) => {
+                self.client
+                    .call_tool_with_progress(&self.definition.name, arguments, |progress| {
+                        runtime.progress(
+                            progress.progress,
+                            progress.total,
+                            progress.message.as_deref(),
+                        )
+                    })
+                    .await
+            }
+            None => {
+                self.client
+                    .call_tool(&self.definition.name, arguments)
+                    .await
+            }
+        }
+        .map_err(|e| ToolError::ExecutionError(format!("MCP error: {}", e)))?;
+
+        if result.is_error {
+            return Err(ToolError::ExecutionError(result.text()));
+        }
+        Ok(result)
+    }
+}
+
+#[async_trait]
+impl Tool for McpTool {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn description(&self) -> String {
+        self.definition
+            .description
+            .clone()
+            .or_else(|| self.definition.title.clone())
+            .unwrap_or_else(|| self.definition.name.clone())
+    }
+
+    fn parameters(&self) -> Value {
+        let mut schema = self.definition.input_schema.clone();
+        if let Value::Object(schema) = &mut schema {
+            schema
+                .entry("type")
+                .or_insert_with(|| Value::String("object".to_string()));
+        }
+        schema
+    }
+
+    async fn parse_input(&self, input: &str) -> Value {
+        serde_json::from_str::<Value>(input).unwrap_or_else(|_| Value::String(input.to_string()))
+    }
+
+    async fn run(&self, input: Value) -> Result<String, ToolError> {
+        Ok(self.call_tool(input, None).await?.text())
+    }
+
+    async fn run_with_runtime(
+        &self,
+        input: Value,
+        runtime: &ToolRuntime,
+    ) -> Result<ToolResult, Box<dyn Error>> {
+        let result = self.call_tool(input, Some(runtime)).await?;
+        // Images and embedded files reach the model as attachments
+        Ok(ToolResult::content(result.content_parts()))
+    }
+
+    fn requires_runtime(&self) -> bool {
+        true
+    }
+
+    /// Only tools the server marks read-only run alongside other tools
+    fn is_parallel_safe(&self) -> bool {
+        self.definition
+            .annotations
+            .as_ref()
+            .and_then(|annotations| annotations.read_only_hint)
+            .unwrap_or(false)
+    }
+}
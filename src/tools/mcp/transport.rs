@@ -0,0 +1,293 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex as StdMutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
+    process::Child,
+    sync::{oneshot, Mutex},
+    task::JoinHandle,
+};
+
+use super::{
+    error::McpError,
+    types::{JsonRpcError, JsonRpcMessage, JsonRpcRequest, JsonRpcResponse},
+};
+
+/// Called with each notification the server sends
+pub(crate) type NotificationHandler = Arc<dyn Fn(JsonRpcRequest) + Send + Sync>;
+
+/// A connection to an MCP server
+#[async_trait]
+pub(crate) trait Transport: Send + Sync {
+    /// Send a request and wait for its response
+    async fn request(&self, request: JsonRpcRequest) -> Result<JsonRpcResponse, McpError>;
+
+    /// Send a notification
+    async fn notify(&self, notification: JsonRpcRequest) -> Result<(), McpError>;
+
+    /// Remember the protocol version negotiated by the handshake
+    fn set_protocol_version(&self, _version: &str) {}
+}
+
+/// Answer a request the server sent to the client; only `ping` is supported
+fn reply_to_server(request: &JsonRpcRequest) -> JsonRpcResponse {
+    let id = request.id.clone().unwrap_or_default();
+    if request.method == "ping" {
+        JsonRpcResponse::success(id, serde_json::json!({}))
+    } else {
+        JsonRpcResponse::failure(
+            id,
+            JsonRpcError::new(
+                JsonRpcError::METHOD_NOT_FOUND,
+                format!("Method not supported by the client: {}", request.method),
+            ),
+        )
+    }
+}
+
+type Writer = Arc<Mutex<Box<dyn AsyncWrite + Send + Unpin>>>;
+/// Requests waiting for a response by id; `None` once the connection is closed
+type Pending = Arc<StdMutex<Option<HashMap<String, oneshot::Sender<JsonRpcResponse>>>>>;
+
+/// Newline-delimited JSON-RPC over a pair of streams, usually a child process' stdio
+pub(crate) struct StdioTransport {
+    writer: Writer,
+    pending: Pending,
+    /// How long a request waits for its response
+    timeout: Duration,
+    reader: JoinHandle<()>,
+    /// Killed when the transport is dropped
+    _child: Option<Child>,
+}
+
+impl StdioTransport {
+    pub(crate) fn new<R, W>(
+        reader: R,
+        writer: W,
+        child: Option<Child>,
+        timeout: Duration,
+        on_notification: NotificationHandler,
+    ) -> Self
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+        W: AsyncWrite + Send + Unpin + 'static,
+    {
+        let writer: Writer = Arc::new(Mutex::new(Box::new(writer)));
+        let pending: Pending = Arc::new(StdMutex::new(Some(HashMap::new())));
+
+        let reader = tokio::spawn({
+            let writer = Arc::clone(&writer);
+            let pending = Arc::clone(&pending);
+            async move {
+                let mut lines = BufReader::new(reader).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    let line = line.trim();
+                    if line.is_empty() {
+                        continue;
+                    }
+                    match serde_json::from_str::<JsonRpcMessage>(line) {
+                        Ok(JsonRpcMessage::Response(response)) => {
+                            let sender = pending
+                                .lock()
+                                .unwrap()
+                                .as_mut()
+                                .and_then(|p| p.remove(&response.id.to_string()));
+                            if let Some(sender) = sender {
+                                let _ = sender.send(response);
+                            }
+                        }
+                        Ok(JsonRpcMessage::Request(request)) if request.is_notification() => {
+                            on_notification(request)
+                        }
+                        Ok(JsonRpcMessage::Request(request)) => {
+                            let reply = reply_to_server(&request);
+                            if let Err(e) = write_line(&writer, &reply).await {
+                                log::warn!("Failed to answer MCP server request: {}", e);
+                            }
+                        }
+                        Err(e) => log::warn!("Ignoring invalid MCP message: {}", e),
+                    }
+                }
+                // Dropping the senders fails all waiting requests
+                pending.lock().unwrap().take();
+            }
+        });
+
+        Self {
+            writer,
+            pending,
+            timeout,
+            reader,
+            _child: child,
+        }
+    }
+}
+
+impl Drop for StdioTransport {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+async fn write_line<T: serde::Serialize>(writer: &Writer, message: &T) -> Result<(), McpError> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    let mut writer = writer.lock().await;
+    writer.write_all(&line).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+#[async_trait]
+impl Transport for StdioTransport {
+    async fn request(&self, request: JsonRpcRequest) -> Result<JsonRpcResponse, McpError> {
+        let id = request.id.clone().unwrap_or_default().to_string();
+        let (sender, receiver) = oneshot::channel();
+        self.pending
+            .lock()
+            .unwrap()
+            .as_mut()
+            .ok_or(McpError::ConnectionClosed)?
+            .insert(id.clone(), sender);
+
+        let result = match write_line(&self.writer, &request).await {
+            Ok(()) => match tokio::time::timeout(self.timeout, receiver).await {
+                Ok(response) => return response.map_err(|_| McpError::ConnectionClosed),
+                Err(_) => Err(McpError::Timeout(self.timeout)),
+            },
+            Err(e) => Err(e),
+        };
+        if let Some(pending) = self.pending.lock().unwrap().as_mut() {
+            pending.remove(&id);
+        }
+        result
+    }
+
+    async fn notify(&self, notification: JsonRpcRequest) -> Result<(), McpError> {
+        write_line(&self.writer, &notification).await
+    }
+}
+
+/// Streamable HTTP: every message is POSTed to one endpoint, which answers with JSON or an
+/// SSE stream carrying notifications before the response
+pub(crate) struct HttpTransport {
+    client: reqwest::Client,
+    url: String,
+    session_id: StdMutex<Option<String>>,
+    protocol_version: StdMutex<Option<String>>,
+    on_notification: NotificationHandler,
+}
+
+impl HttpTransport {
+    pub(crate) fn new(
+        client: reqwest::Client,
+        url: String,
+        on_notification: NotificationHandler,
+    ) -> Self {
+        Self {
+            client,
+            url,
+            session_id: StdMutex::new(None),
+            protocol_version: StdMutex::new(None),
+            on_notification,
+        }
+    }
+
+    async fn post<T: serde::Serialize>(&self, message: &T) -> Result<reqwest::Response, McpError> {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header(
+                reqwest::header::ACCEPT,
+                "application/json, text/event-stream",
+            )
+            .json(message);
+        if let Some(session_id) = self.session_id.lock().unwrap().clone() {
+            request = request.header("Mcp-Session-Id", session_id);
+        }
+        if let Some(version) = self.protocol_version.lock().unwrap().clone() {
+            request = request.header("MCP-Protocol-Version", version);
+        }
+        let response = request.send().await?;
+
+        if let Some(session_id) = response
+            .headers()
+            .get("Mcp-Session-Id")
+            .and_then(|value| value.to_str().ok())
+        {
+            *self.session_id.lock().unwrap() = Some(session_id.to_string());
+        }
+        if response.status().is_success() {
+            return Ok(response);
+        }
+        Err(McpError::StatusError {
+            status: response.status().as_u16(),
+            body: response.text().await.unwrap_or_default(),
+        })
+    }
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn request(&self, request: JsonRpcRequest) -> Result<JsonRpcResponse, McpError> {
+        let response = self.post(&request).await?;
+        let is_sse = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/event-stream"));
+        if !is_sse {
+            return Ok(response.json().await?);
+        }
+
+        let id = request.id.unwrap_or_default();
+        let mut bytes = response.bytes_stream();
+        let mut buffer = String::new();
+        while let Some(chunk) = bytes.next().await {
+            buffer.push_str(&String::from_utf8_lossy(&chunk?).replace("\r\n", "\n"));
+            while let Some(end) = buffer.find("\n\n") {
+                let block: String = buffer.drain(..end + 2).collect();
+                let Some(data) = sse_data(&block) else {
+                    continue;
+                };
+                match serde_json::from_str::<JsonRpcMessage>(&data)? {
+                    JsonRpcMessage::Response(response) if response.id == id => return Ok(response),
+                    JsonRpcMessage::Response(response) => {
+                        log::warn!("Ignoring MCP response to request {}", response.id)
+                    }
+                    JsonRpcMessage::Request(request) if request.is_notification() => {
+                        (self.on_notification)(request)
+                    }
+                    JsonRpcMessage::Request(request) => {
+                        self.post(&reply_to_server(&request)).await?;
+                    }
+                }
+            }
+        }
+        Err(McpError::ConnectionClosed)
+    }
+
+    async fn notify(&self, notification: JsonRpcRequest) -> Result<(), McpError> {
+        self.post(&notification).await?;
+        Ok(())
+    }
+
+    fn set_protocol_version(&self, version: &str) {
+        *self.protocol_version.lock().unwrap() = Some(version.to_string());
+    }
+}
+
+/// The `data` of an SSE event block, multi-line data joined with newlines
+fn sse_data(block: &str) -> Option<String> {
+    let lines: Vec<&str> = block
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(|data| data.strip_prefix(' ').unwrap_or(data))
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
@@ -0,0 +1,469 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::schemas::{ContentPart, FileContent, ImageContent, Message};
+
+/// Latest MCP protocol version, requested by the client and preferred by the server
+pub const MCP_PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// Protocol versions the server accepts in the `initialize` handshake
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// A JSON-RPC 2.0 request, or a notification when `id` is absent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcRequest {
+    pub jsonrpc: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    pub method: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+}
+
+impl JsonRpcRequest {
+    pub fn new<S: Into<String>>(id: Value, method: S, params: Option<Value>) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id: Some(id),
+            method: method.into(),
+            params,
+        }
+    }
+
+    pub fn notification<S: Into<String>>(method: S, params: Option<Value>) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id: None,
+            method: method.into(),
+            params,
+        }
+    }
+
+    pub fn is_notification(&self) -> bool {
+        self.id.is_none()
+    }
+}
+
+/// A JSON-RPC 2.0 response, with either a result or an error
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcResponse {
+    pub jsonrpc: String,
+    pub id: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<JsonRpcError>,
+}
+
+impl JsonRpcResponse {
+    pub fn success(id: Value, result: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    pub fn failure(id: Value, error: JsonRpcError) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(error),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl JsonRpcError {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    pub const INTERNAL_ERROR: i64 = -32603;
+    pub const RESOURCE_NOT_FOUND: i64 = -32002;
+
+    pub fn new<S: Into<String>>(code: i64, message: S) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+/// Any message on an MCP connection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum JsonRpcMessage {
+    Request(JsonRpcRequest),
+    Response(JsonRpcResponse),
+}
+
+/// Name and version of an MCP client or server
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Implementation {
+    pub name: String,
+    pub version: String,
+}
+
+impl Implementation {
+    pub fn new<N: Into<String>, V: Into<String>>(name: N, version: V) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+        }
+    }
+}
+
+/// Features a server offers; each present capability may carry options
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ServerCapabilities {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resources: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompts: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logging: Option<Value>,
+}
+
+/// Result of the `initialize` handshake
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InitializeResult {
+    pub protocol_version: String,
+    #[serde(default)]
+    pub capabilities: ServerCapabilities,
+    pub server_info: Implementation,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+}
+
+/// A tool as described by `tools/list`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolDefinition {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// JSON schema of the tool arguments
+    pub input_schema: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<ToolAnnotations>,
+}
+
+/// Hints about a tool's behavior; not guaranteed by the server
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolAnnotations {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only_hint: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destructive_hint: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotent_hint: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_world_hint: Option<bool>,
+}
+
+/// A block of content in tool results and prompt messages
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Content {
+    Text {
+        text: String,
+    },
+    Image {
+        /// Base64-encoded image data
+        data: String,
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+    },
+    Audio {
+        /// Base64-encoded audio data
+        data: String,
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+    },
+    /// A resource embedded in the result
+    Resource {
+        resource: ResourceContents,
+    },
+    /// A link to a resource the client may read
+    ResourceLink {
+        uri: String,
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        #[serde(rename = "mimeType", default, skip_serializing_if = "Option::is_none")]
+        mime_type: Option<String>,
+    },
+}
+
+impl Content {
+    pub fn text<S: Into<String>>(text: S) -> Self {
+        Self::Text { text: text.into() }
+    }
+
+    /// Text form of the content; binary content is summarized
+    pub fn to_text(&self) -> String {
+        match self {
+            Self::Text { text } => text.clone(),
+            Self::Image { mime_type, .. } => format!("[image: {}]", mime_type),
+            Self::Audio { mime_type, .. } => format!("[audio: {}]", mime_type),
+            Self::Resource { resource } => resource
+                .text
+                .clone()
+                .unwrap_or_else(|| format!("[resource: {}]", resource.uri)),
+            Self::ResourceLink { uri, .. } => format!("[resource: {}]", uri),
+        }
+    }
+
+    /// Content as part of a multimodal tool result: images stay images and embedded binary
+    /// resources become files; audio and resource links are summarized as text
+    pub fn to_content_part(&self) -> ContentPart {
+        match self {
+            Self::Image { data, mime_type } => {
+                ContentPart::image(ImageContent::from_base64(mime_type, data))
+            }
+            Self::Resource { resource } => match (&resource.text, &resource.blob) {
+                (None, Some(blob)) => {
+                    let mime_type = resource
+                        .mime_type
+                        .as_deref()
+                        .unwrap_or("application/octet-stream");
+                    ContentPart::file(
+                        FileContent::from_base64(mime_type, blob).with_name(resource.uri.clone()),
+                    )
+                }
+                _ => ContentPart::text(self.to_text()),
+            },
+            _ => ContentPart::text(self.to_text()),
+        }
+    }
+
+    /// Content for a part of a tool result: inline images and files are embedded, linked
+    /// ones become resource links
+    pub fn from_content_part(part: &ContentPart) -> Self {
+        match part {
+            ContentPart::Text { text } => Self::text(text.clone()),
+            ContentPart::Image(image) => match image.base64_data() {
+                Some((mime_type, data)) => Self::Image {
+                    data: data.to_string(),
+                    mime_type: mime_type.to_string(),
+                },
+                None => Self::ResourceLink {
+                    uri: image.image_url.clone(),
+                    name: "image".to_string(),
+                    description: None,
+                    mime_type: None,
+                },
+            },
+            ContentPart::File(file) => {
+                let name = file.name.clone().unwrap_or_else(|| "file".to_string());
+                match file.base64_data() {
+                    Some(data) => Self::Resource {
+                        resource: ResourceContents {
+                            uri: format!("attachment://{}", name),
+                            mime_type: Some(file.mime_type.clone()),
+                            text: None,
+                            blob: Some(data.to_string()),
+                        },
+                    },
+                    None => Self::ResourceLink {
+                        uri: file.uri.clone(),
+                        name,
+                        description: None,
+                        mime_type: Some(file.mime_type.clone()),
+                    },
+                }
+            }
+        }
+    }
+}
+
+/// Result of `tools/call`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CallToolResult {
+    #[serde(default)]
+    pub content: Vec<Content>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<Value>,
+    /// Whether the tool failed; the content then describes the error
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_error: bool,
+}
+
+impl CallToolResult {
+    /// All content as text, one block per line; falls back to the structured content
+    pub fn text(&self) -> String {
+        if self.content.is_empty() {
+            return self
+                .structured_content
+                .as_ref()
+                .map(Value::to_string)
+                .unwrap_or_default();
+        }
+        self.content
+            .iter()
+            .map(Content::to_text)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// All content as parts of a multimodal tool result, see [`Content::to_content_part`];
+    /// falls back to the structured content as text
+    pub fn content_parts(&self) -> Vec<ContentPart> {
+        if self.content.is_empty() {
+            return vec![ContentPart::text(self.text())];
+        }
+        self.content.iter().map(Content::to_content_part).collect()
+    }
+}
+
+/// A resource as described by `resources/list`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Resource {
+    pub uri: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+/// A parameterized resource as described by `resources/templates/list`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceTemplate {
+    /// RFC 6570 URI template, e.g. `retriever://docs/{query}`
+    pub uri_template: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+/// Contents of a resource, either text or base64 `blob`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceContents {
+    pub uri: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob: Option<String>,
+}
+
+/// A prompt template as described by `prompts/list`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Prompt {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub arguments: Vec<PromptArgument>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PromptArgument {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptRole {
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PromptMessage {
+    pub role: PromptRole,
+    pub content: Content,
+}
+
+impl PromptMessage {
+    /// Convert to a chat message: `user` becomes a human message, `assistant` an AI message
+    pub fn to_message(&self) -> Message {
+        let text = self.content.to_text();
+        match self.role {
+            PromptRole::User => Message::new_human_message(text),
+            PromptRole::Assistant => Message::new_ai_message(text),
+        }
+    }
+}
+
+/// Result of `prompts/get`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GetPromptResult {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub messages: Vec<PromptMessage>,
+}
+
+impl GetPromptResult {
+    pub fn to_messages(&self) -> Vec<Message> {
+        self.messages
+            .iter()
+            .map(PromptMessage::to_message)
+            .collect()
+    }
+}
+
+/// Params of `notifications/progress`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressNotification {
+    pub progress_token: Value,
+    pub progress: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl ProgressNotification {
+    /// Human-readable progress, e.g. `Indexing (2/5)`
+    pub fn describe(&self) -> String {
+        let amount = match self.total {
+            Some(total) => format!("{}/{}", self.progress, total),
+            None => self.progress.to_string(),
+        };
+        match &self.message {
+            Some(message) => format!("{} ({})", message, amount),
+            None => format!("Progress {}", amount),
+        }
+    }
+}
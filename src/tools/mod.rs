@@ -19,6 +19,9 @@ pub use stream::*;
 mod schema;
 pub use schema::*;
 
+/// Turn an async fn into a typed [`Tool`]; see the macro docs for details.
+pub use langchain_ai_rust_macros::tool;
+
 mod error;
 pub use error::*;
 
@@ -49,7 +52,16 @@ pub use long_term_memory::*;
 mod sequential_thinking;
 pub use sequential_thinking::*;
 
+#[cfg(feature = "rhai")]
+mod code_interpreter;
+#[cfg(feature = "rhai")]
+pub use code_interpreter::*;
+
 #[cfg(feature = "browser-use")]
 mod browser_use;
 #[cfg(feature = "browser-use")]
 pub use browser_use::*;
+
+pub mod mcp;
+
+pub mod openapi;
@@ -0,0 +1,24 @@
+use thiserror::Error;
+
+/// Errors loading an OpenAPI document
+#[derive(Error, Debug)]
+pub enum OpenApiError {
+    #[error("Serde json error: {0}")]
+    SerdeJsonError(#[from] serde_json::Error),
+
+    #[cfg(feature = "yaml")]
+    #[error("YAML error: {0}")]
+    YamlError(#[from] serde_yaml::Error),
+
+    #[error("Request error: {0}")]
+    RequestError(#[from] reqwest::Error),
+
+    #[error("Invalid OpenAPI document: {0}")]
+    InvalidSpec(String),
+
+    #[error("Unresolved reference: {0}")]
+    UnresolvedRef(String),
+
+    #[error("No base URL: the document has no absolute server URL, set one with with_base_url")]
+    MissingBaseUrl,
+}
@@ -0,0 +1,35 @@
+//! Agent tools generated from an OpenAPI 3 document
+//!
+//! [`OpenApiToolkit`] reads a JSON or, with the `yaml` feature, YAML document and creates
+//! one [`OpenApiTool`] per operation. The operation parameters and request body become the
+//! tool's JSON schema, requests go out with `reqwest`, and credentials are read from the
+//! [`ToolContext`](crate::tools::ToolContext) when the tool is called. Long responses are
+//! truncated, or summarized by an LLM.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use langchain_ai_rust::tools::openapi::OpenApiToolkit;
+//!
+//! let tools = OpenApiToolkit::from_yaml(&std::fs::read_to_string("billing.yaml")?)?
+//!     .with_base_url("https://billing.internal")
+//!     .with_bearer_auth("billing_token")
+//!     .tools()?;
+//!
+//! let context = SimpleContext::new().with_custom("billing_token".into(), token);
+//! let agent = create_agent_with_runtime(
+//!     "gpt-4o-mini", &tools, None, Some(Arc::new(context)), None, None, None, None,
+//! )?;
+//! ```
+
+mod error;
+mod spec;
+#[cfg(test)]
+mod tests;
+mod tool;
+mod toolkit;
+
+pub use error::OpenApiError;
+pub use spec::{OpenApiSpec, Operation, Parameter, ParameterLocation, RequestBody};
+pub use tool::{OpenApiAuth, OpenApiTool};
+pub use toolkit::OpenApiToolkit;
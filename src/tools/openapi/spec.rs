@@ -0,0 +1,416 @@
+use std::collections::HashSet;
+
+use reqwest::Method;
+use serde_json::{json, Map, Value};
+
+use super::error::OpenApiError;
+
+/// How deep `$ref`s are followed; recursive schemas are cut off below this
+const MAX_REF_DEPTH: usize = 8;
+
+/// Longest tool name model APIs accept
+const MAX_NAME_LENGTH: usize = 64;
+
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// An OpenAPI 3 document
+#[derive(Debug, Clone)]
+pub struct OpenApiSpec {
+    document: Value,
+    /// Where the document was loaded from; relative server URLs are resolved against it
+    source_url: Option<String>,
+}
+
+/// Where an operation parameter goes in the request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParameterLocation {
+    Path,
+    Query,
+    Header,
+    Cookie,
+}
+
+#[derive(Debug, Clone)]
+pub struct Parameter {
+    pub name: String,
+    pub location: ParameterLocation,
+    pub required: bool,
+    pub description: Option<String>,
+    /// JSON schema with references resolved
+    pub schema: Value,
+}
+
+#[derive(Debug, Clone)]
+pub struct RequestBody {
+    pub content_type: String,
+    pub required: bool,
+    pub description: Option<String>,
+    /// JSON schema with references resolved
+    pub schema: Value,
+}
+
+/// One method on one path
+#[derive(Debug, Clone)]
+pub struct Operation {
+    /// `operationId`, or one derived from the method and path
+    pub id: String,
+    pub method: Method,
+    pub path: String,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub parameters: Vec<Parameter>,
+    pub request_body: Option<RequestBody>,
+}
+
+impl OpenApiSpec {
+    pub fn from_value(document: Value) -> Result<Self, OpenApiError> {
+        let version = document
+            .get("openapi")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        if !version.starts_with("3.") {
+            return Err(OpenApiError::InvalidSpec(format!(
+                "expected an OpenAPI 3 document, got version '{}'",
+                version
+            )));
+        }
+        Ok(Self {
+            document,
+            source_url: None,
+        })
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, OpenApiError> {
+        Self::from_value(serde_json::from_str(json)?)
+    }
+
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(yaml: &str) -> Result<Self, OpenApiError> {
+        Self::from_value(serde_yaml::from_str(yaml)?)
+    }
+
+    /// Download a JSON or, with the `yaml` feature, YAML document
+    ///
+    /// Relative server URLs of the document are resolved against `url`.
+    pub async fn from_url(url: &str) -> Result<Self, OpenApiError> {
+        let text = reqwest::get(url).await?.error_for_status()?.text().await?;
+        #[cfg(feature = "yaml")]
+        let spec = match serde_json::from_str::<Value>(&text) {
+            Ok(document) => Self::from_value(document)?,
+            Err(_) => Self::from_yaml(&text)?,
+        };
+        #[cfg(not(feature = "yaml"))]
+        let spec = Self::from_json(&text)?;
+        Ok(spec.with_source_url(url))
+    }
+
+    /// Resolve relative server URLs against the URL the document was loaded from
+    pub fn with_source_url<S: Into<String>>(mut self, url: S) -> Self {
+        self.source_url = Some(url.into());
+        self
+    }
+
+    pub fn document(&self) -> &Value {
+        &self.document
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.document.pointer("/info/title").and_then(Value::as_str)
+    }
+
+    /// URL of the first server
+    ///
+    /// A relative URL such as `/api/v1` is resolved against the source URL; without one it
+    /// is `None`.
+    pub fn base_url(&self) -> Option<String> {
+        let url = self
+            .document
+            .pointer("/servers/0/url")
+            .and_then(Value::as_str)?;
+        if reqwest::Url::parse(url).is_ok() {
+            return Some(url.to_string());
+        }
+        let source = reqwest::Url::parse(self.source_url.as_deref()?).ok()?;
+        source.join(url).ok().map(String::from)
+    }
+
+    pub fn operations(&self) -> Result<Vec<Operation>, OpenApiError> {
+        let Some(paths) = self.document.get("paths").and_then(Value::as_object) else {
+            return Err(OpenApiError::InvalidSpec("missing paths".into()));
+        };
+        let mut operations = Vec::new();
+        for (path, item) in paths {
+            let item = self.resolve(item)?;
+            let shared = item
+                .get("parameters")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default();
+            for method in METHODS {
+                if let Some(operation) = item.get(method) {
+                    operations.push(self.operation(path, method, operation, &shared)?);
+                }
+            }
+        }
+        deduplicate_ids(&mut operations);
+        Ok(operations)
+    }
+
+    fn operation(
+        &self,
+        path: &str,
+        method: &str,
+        operation: &Value,
+        shared: &[Value],
+    ) -> Result<Operation, OpenApiError> {
+        let mut parameters: Vec<Parameter> = Vec::new();
+        let own = operation
+            .get("parameters")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        // Operation parameters override path item parameters with the same name and location
+        for parameter in shared.iter().chain(own.iter()) {
+            let parameter = self.parameter(parameter)?;
+            parameters.retain(|p| p.name != parameter.name || p.location != parameter.location);
+            parameters.push(parameter);
+        }
+
+        let request_body = match operation.get("requestBody") {
+            Some(body) => self.request_body(body)?,
+            None => None,
+        };
+        let id = match operation.get("operationId").and_then(Value::as_str) {
+            Some(id) => sanitize_name(id),
+            None => sanitize_name(&format!("{}_{}", method, path)),
+        };
+
+        Ok(Operation {
+            id,
+            method: method
+                .to_uppercase()
+                .parse()
+                .map_err(|_| OpenApiError::InvalidSpec(format!("invalid method {}", method)))?,
+            path: path.to_string(),
+            summary: string_field(operation, "summary"),
+            description: string_field(operation, "description"),
+            parameters,
+            request_body,
+        })
+    }
+
+    fn parameter(&self, parameter: &Value) -> Result<Parameter, OpenApiError> {
+        let parameter = self.resolve(parameter)?;
+        let name = string_field(&parameter, "name")
+            .ok_or_else(|| OpenApiError::InvalidSpec("parameter without name".into()))?;
+        let location = match parameter.get("in").and_then(Value::as_str) {
+            Some("path") => ParameterLocation::Path,
+            Some("query") => ParameterLocation::Query,
+            Some("header") => ParameterLocation::Header,
+            Some("cookie") => ParameterLocation::Cookie,
+            other => {
+                return Err(OpenApiError::InvalidSpec(format!(
+                    "parameter {} has invalid location {:?}",
+                    name, other
+                )))
+            }
+        };
+        let schema = match parameter.get("schema") {
+            Some(schema) => self.resolve_schema(schema, 0)?,
+            None => json!({ "type": "string" }),
+        };
+        Ok(Parameter {
+            required: location == ParameterLocation::Path
+                || parameter
+                    .get("required")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false),
+            description: string_field(&parameter, "description"),
+            name,
+            location,
+            schema,
+        })
+    }
+
+    fn request_body(&self, body: &Value) -> Result<Option<RequestBody>, OpenApiError> {
+        let body = self.resolve(body)?;
+        let Some(content) = body.get("content").and_then(Value::as_object) else {
+            return Ok(None);
+        };
+        let Some((content_type, media)) = content
+            .iter()
+            .find(|(content_type, _)| content_type.contains("json"))
+            .or_else(|| content.iter().next())
+        else {
+            return Ok(None);
+        };
+        let schema = match media.get("schema") {
+            Some(schema) => self.resolve_schema(schema, 0)?,
+            None => json!({}),
+        };
+        Ok(Some(RequestBody {
+            content_type: content_type.clone(),
+            required: body
+                .get("required")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            description: string_field(&body, "description"),
+            schema,
+        }))
+    }
+
+    /// Follow a top level `$ref`
+    fn resolve(&self, value: &Value) -> Result<Value, OpenApiError> {
+        let mut value = value.clone();
+        for _ in 0..MAX_REF_DEPTH {
+            let Some(reference) = value.get("$ref").and_then(Value::as_str) else {
+                return Ok(value);
+            };
+            value = self.lookup(reference)?.clone();
+        }
+        Err(OpenApiError::UnresolvedRef(
+            "reference chain too deep".into(),
+        ))
+    }
+
+    fn lookup(&self, reference: &str) -> Result<&Value, OpenApiError> {
+        reference
+            .strip_prefix('#')
+            .and_then(|pointer| self.document.pointer(pointer))
+            .ok_or_else(|| OpenApiError::UnresolvedRef(reference.to_string()))
+    }
+
+    /// Inline every `$ref` in a schema
+    fn resolve_schema(&self, schema: &Value, depth: usize) -> Result<Value, OpenApiError> {
+        match schema {
+            Value::Object(object) => {
+                if let Some(reference) = object.get("$ref").and_then(Value::as_str) {
+                    if depth >= MAX_REF_DEPTH {
+                        return Ok(json!({ "type": "object" }));
+                    }
+                    return self.resolve_schema(self.lookup(reference)?, depth + 1);
+                }
+                let mut resolved = Map::new();
+                for (key, value) in object {
+                    // Examples and extensions only cost tokens
+                    if key == "example" || key == "examples" || key.starts_with("x-") {
+                        continue;
+                    }
+                    resolved.insert(key.clone(), self.resolve_schema(value, depth)?);
+                }
+                Ok(Value::Object(resolved))
+            }
+            Value::Array(items) => items
+                .iter()
+                .map(|item| self.resolve_schema(item, depth))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::Array),
+            other => Ok(other.clone()),
+        }
+    }
+}
+
+impl Operation {
+    /// Tool parameters: one property per parameter, and `body` for the request body
+    pub fn parameters_schema(&self) -> Value {
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        for parameter in &self.parameters {
+            if parameter.location == ParameterLocation::Cookie {
+                continue;
+            }
+            let mut schema = parameter.schema.clone();
+            if let (Some(description), Value::Object(object)) =
+                (&parameter.description, &mut schema)
+            {
+                object
+                    .entry("description")
+                    .or_insert_with(|| Value::String(description.clone()));
+            }
+            properties.insert(parameter.name.clone(), schema);
+            if parameter.required {
+                required.push(Value::String(parameter.name.clone()));
+            }
+        }
+        if let Some(body) = &self.request_body {
+            let mut schema = body.schema.clone();
+            if let (Some(description), Value::Object(object)) = (&body.description, &mut schema) {
+                object
+                    .entry("description")
+                    .or_insert_with(|| Value::String(description.clone()));
+            }
+            properties.insert("body".to_string(), schema);
+            if body.required {
+                required.push(Value::String("body".to_string()));
+            }
+        }
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": required
+        })
+    }
+
+    /// Summary and description, or the method and path
+    pub fn tool_description(&self) -> String {
+        let text = [&self.summary, &self.description]
+            .into_iter()
+            .flatten()
+            .map(|text| text.trim())
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        if text.is_empty() {
+            format!("{} {}", self.method, self.path)
+        } else {
+            format!("{} {}: {}", self.method, self.path, text)
+        }
+    }
+}
+
+fn string_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+/// Number the ids that repeat, e.g. after truncation: `id`, `id_2`, `id_3`
+fn deduplicate_ids(operations: &mut [Operation]) {
+    let mut seen = HashSet::new();
+    for operation in operations {
+        if seen.insert(operation.id.clone()) {
+            continue;
+        }
+        let mut suffix = 2;
+        let id = loop {
+            let suffix_text = format!("_{}", suffix);
+            let mut id = operation.id.clone();
+            id.truncate(MAX_NAME_LENGTH - suffix_text.len());
+            id.push_str(&suffix_text);
+            if !seen.contains(&id) {
+                break id;
+            }
+            suffix += 1;
+        };
+        seen.insert(id.clone());
+        operation.id = id;
+    }
+}
+
+/// Tool names may only contain letters, digits, `_` and `-`, up to 64 characters
+fn sanitize_name(name: &str) -> String {
+    let mut sanitized = String::new();
+    for c in name.chars() {
+        let c = if c.is_ascii_alphanumeric() || c == '-' {
+            c
+        } else {
+            '_'
+        };
+        if !(c == '_' && (sanitized.is_empty() || sanitized.ends_with('_'))) {
+            sanitized.push(c);
+        }
+    }
+    let mut sanitized = sanitized.trim_end_matches('_').to_string();
+    sanitized.truncate(MAX_NAME_LENGTH);
+    sanitized
+}
@@ -0,0 +1,295 @@
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+
+use super::*;
+use crate::agent::AgentState;
+use crate::tools::{InMemoryStore, SimpleContext, Tool, ToolRuntime};
+
+fn petstore(base_url: &str) -> Value {
+    json!({
+        "openapi": "3.0.3",
+        "info": { "title": "Petstore", "version": "1.0.0" },
+        "servers": [{ "url": base_url }],
+        "paths": {
+            "/pets": {
+                "get": {
+                    "operationId": "listPets",
+                    "summary": "List pets",
+                    "parameters": [{ "$ref": "#/components/parameters/Limit" }],
+                    "responses": { "200": { "description": "ok" } }
+                },
+                "post": {
+                    "operationId": "createPet",
+                    "summary": "Create a pet",
+                    "requestBody": {
+                        "required": true,
+                        "content": {
+                            "application/json": {
+                                "schema": { "$ref": "#/components/schemas/Pet" }
+                            }
+                        }
+                    },
+                    "responses": { "201": { "description": "created" } }
+                }
+            },
+            "/pets/{petId}": {
+                "parameters": [{
+                    "name": "petId",
+                    "in": "path",
+                    "description": "Id of the pet",
+                    "schema": { "type": "integer" }
+                }],
+                "get": {
+                    "summary": "Get a pet",
+                    "parameters": [{ "name": "X-Trace", "in": "header", "schema": { "type": "string" } }],
+                    "responses": { "200": { "description": "ok" } }
+                }
+            }
+        },
+        "components": {
+            "parameters": {
+                "Limit": {
+                    "name": "limit",
+                    "in": "query",
+                    "description": "Maximum number of pets",
+                    "schema": { "type": "integer" }
+                }
+            },
+            "schemas": {
+                "Pet": {
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": {
+                        "name": { "type": "string", "example": "Rex" },
+                        "tag": { "type": "string" }
+                    }
+                }
+            }
+        }
+    })
+}
+
+fn tool(tools: &[Arc<dyn Tool>], name: &str) -> Arc<dyn Tool> {
+    Arc::clone(tools.iter().find(|tool| tool.name() == name).unwrap())
+}
+
+fn runtime(context: SimpleContext) -> ToolRuntime {
+    ToolRuntime::new(
+        Arc::new(Mutex::new(AgentState::new())),
+        Arc::new(context),
+        Arc::new(InMemoryStore::new()),
+        "call_1".to_string(),
+    )
+}
+
+#[test]
+fn test_operations_from_spec() {
+    let spec = OpenApiSpec::from_value(petstore("http://localhost")).unwrap();
+    assert_eq!(spec.title(), Some("Petstore"));
+    let operations = spec.operations().unwrap();
+    let ids: Vec<&str> = operations.iter().map(|o| o.id.as_str()).collect();
+    assert_eq!(ids, vec!["listPets", "createPet", "get_pets_petId"]);
+
+    assert_eq!(
+        operations[1].parameters_schema(),
+        json!({
+            "type": "object",
+            "properties": {
+                "body": {
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": { "name": { "type": "string" }, "tag": { "type": "string" } }
+                }
+            },
+            "required": ["body"]
+        })
+    );
+    assert_eq!(
+        operations[2].parameters_schema()["properties"]["petId"],
+        json!({ "type": "integer", "description": "Id of the pet" })
+    );
+    assert_eq!(
+        operations[2].parameters_schema()["required"],
+        json!(["petId"])
+    );
+    assert!(OpenApiSpec::from_value(json!({ "swagger": "2.0" })).is_err());
+}
+
+#[tokio::test]
+async fn test_tools_call_api() {
+    let mut server = mockito::Server::new_async().await;
+    let _list = server
+        .mock("GET", "/pets")
+        .match_query(mockito::Matcher::UrlEncoded("limit".into(), "2".into()))
+        .with_header("content-type", "application/json")
+        .with_body(r#"[ {"name": "Rex"}, {"name": "Tom"} ]"#)
+        .create_async()
+        .await;
+    let _create = server
+        .mock("POST", "/pets")
+        .match_body(mockito::Matcher::Json(json!({ "name": "Rex" })))
+        .with_status(201)
+        .with_body(r#"{"id": 7}"#)
+        .create_async()
+        .await;
+    let _get = server
+        .mock("GET", "/pets/7")
+        .match_header("X-Trace", "abc")
+        .with_body("x".repeat(100))
+        .create_async()
+        .await;
+    let _missing = server
+        .mock("GET", "/pets/8")
+        .with_status(404)
+        .with_body("not found")
+        .create_async()
+        .await;
+
+    let tools = OpenApiToolkit::new(OpenApiSpec::from_value(petstore(&server.url())).unwrap())
+        .with_max_response_bytes(50)
+        .tools()
+        .unwrap();
+    assert_eq!(tools.len(), 3);
+
+    let list = tool(&tools, "listPets");
+    assert!(list.is_parallel_safe());
+    assert_eq!(
+        list.call(r#"{"limit": 2}"#).await.unwrap(),
+        r#"[{"name":"Rex"},{"name":"Tom"}]"#
+    );
+    let create = tool(&tools, "createPet");
+    assert!(!create.is_parallel_safe());
+    assert_eq!(
+        create.call(r#"{"body": {"name": "Rex"}}"#).await.unwrap(),
+        r#"{"id":7}"#
+    );
+
+    let get = tool(&tools, "get_pets_petId");
+    let output = get.call(r#"{"petId": 7, "X-Trace": "abc"}"#).await.unwrap();
+    assert!(output.ends_with("... (truncated, 100 bytes total)"));
+    let error = get.call(r#"{"petId": 8}"#).await.unwrap_err();
+    assert!(error.to_string().contains("404"));
+    let error = get.call("{}").await.unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("missing required parameter petId"));
+}
+
+#[tokio::test]
+async fn test_auth_from_context() {
+    let mut server = mockito::Server::new_async().await;
+    let _list = server
+        .mock("GET", "/pets")
+        .match_header("authorization", "Bearer secret")
+        .match_query(mockito::Matcher::UrlEncoded("key".into(), "k1".into()))
+        .with_body("[]")
+        .create_async()
+        .await;
+
+    let tools = OpenApiToolkit::new(OpenApiSpec::from_value(petstore("http://unused")).unwrap())
+        .with_base_url(server.url())
+        .with_bearer_auth("petstore_token")
+        .with_query_auth("key", "petstore_key")
+        .with_operations(["listPets"])
+        .tools()
+        .unwrap();
+    assert_eq!(tools.len(), 1);
+    assert!(tools[0].requires_runtime());
+
+    let context = SimpleContext::new()
+        .with_custom("petstore_token".into(), "secret".into())
+        .with_custom("petstore_key".into(), "k1".into());
+    let output = tools[0]
+        .run_with_runtime(json!({}), &runtime(context))
+        .await
+        .unwrap();
+    assert_eq!(output.as_str(), "[]");
+    // Missing credentials fail before any request
+    let error = tools[0]
+        .run_with_runtime(json!({}), &runtime(SimpleContext::new()))
+        .await
+        .unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("no 'petstore_token' in the tool context"));
+    assert!(tools[0].call("{}").await.is_err());
+}
+
+#[test]
+fn test_truncated_operation_ids_are_unique() {
+    let long = "a".repeat(70);
+    let operation = json!({ "responses": { "200": { "description": "ok" } } });
+    let spec = OpenApiSpec::from_value(json!({
+        "openapi": "3.0.3",
+        "info": { "title": "Long", "version": "1" },
+        "paths": {
+            "/one": { "get": { "operationId": format!("{}_one", long), "responses": {} } },
+            "/two": { "get": { "operationId": format!("{}_two", long), "responses": {} } },
+            "/three": { "get": operation },
+        }
+    }))
+    .unwrap();
+    let ids: Vec<String> = spec
+        .operations()
+        .unwrap()
+        .into_iter()
+        .map(|o| o.id)
+        .collect();
+    assert_eq!(ids[0], "a".repeat(64));
+    assert_eq!(ids[1], format!("{}_2", "a".repeat(62)));
+    assert!(ids.iter().all(|id| id.len() <= 64));
+}
+
+#[tokio::test]
+async fn test_relative_server_url_from_spec_url() {
+    let mut server = mockito::Server::new_async().await;
+    let mut document = petstore("/api/v1");
+    document["paths"]["/pets"]["get"]["parameters"] = json!([]);
+    let _spec = server
+        .mock("GET", "/specs/petstore.json")
+        .with_body(document.to_string())
+        .create_async()
+        .await;
+    let _list = server
+        .mock("GET", "/api/v1/pets")
+        .with_body("[]")
+        .create_async()
+        .await;
+
+    let toolkit = OpenApiToolkit::from_url(&format!("{}/specs/petstore.json", server.url()))
+        .await
+        .unwrap();
+    assert_eq!(
+        toolkit.spec().base_url(),
+        Some(format!("{}/api/v1", server.url()))
+    );
+    let tools = toolkit.tools().unwrap();
+    assert_eq!(tool(&tools, "listPets").call("{}").await.unwrap(), "[]");
+
+    // Without the document URL a relative server cannot be used
+    let spec = OpenApiSpec::from_value(petstore("/api/v1")).unwrap();
+    assert!(matches!(
+        OpenApiToolkit::new(spec).tools(),
+        Err(OpenApiError::MissingBaseUrl)
+    ));
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_from_yaml() {
+    let yaml = r#"
+openapi: 3.1.0
+info: { title: Todo, version: "1" }
+servers: [{ url: "http://localhost:8080" }]
+paths:
+  /todos:
+    get:
+      operationId: list_todos
+      responses: { "200": { description: ok } }
+"#;
+    let tools = OpenApiToolkit::from_yaml(yaml).unwrap().tools().unwrap();
+    assert_eq!(tools[0].name(), "list_todos");
+    assert_eq!(tools[0].description(), "GET /todos");
+}
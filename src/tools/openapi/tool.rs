@@ -0,0 +1,271 @@
+use std::{error::Error, sync::Arc};
+
+use async_trait::async_trait;
+use reqwest::header::CONTENT_TYPE;
+use serde_json::Value;
+
+use crate::error::ToolError;
+use crate::language_models::llm::LLM;
+use crate::schemas::Message;
+use crate::telemetry;
+use crate::tools::{parse_tool_input, Tool, ToolContext, ToolResult, ToolRuntime};
+
+use super::spec::{Operation, ParameterLocation};
+
+/// Credentials added to every request, read from the [`ToolContext`] at call time
+///
+/// A call fails when the context has no value for the key. Keeping secrets in the context
+/// keeps them out of the prompt and lets each user or session call the API with their own
+/// token.
+#[derive(Debug, Clone)]
+pub enum OpenApiAuth {
+    /// `Authorization: Bearer <value>`
+    Bearer { context_key: String },
+    /// A header such as `X-API-Key: <value>`
+    Header { name: String, context_key: String },
+    /// A query parameter such as `?api_key=<value>`
+    Query { name: String, context_key: String },
+}
+
+impl OpenApiAuth {
+    fn context_key(&self) -> &str {
+        match self {
+            OpenApiAuth::Bearer { context_key }
+            | OpenApiAuth::Header { context_key, .. }
+            | OpenApiAuth::Query { context_key, .. } => context_key,
+        }
+    }
+}
+
+/// Settings shared by all tools of a toolkit
+pub(crate) struct HttpSettings {
+    pub(crate) base_url: String,
+    pub(crate) client: reqwest::Client,
+    pub(crate) auth: Vec<OpenApiAuth>,
+    pub(crate) max_response_bytes: usize,
+    pub(crate) summarizer: Option<Arc<dyn LLM>>,
+}
+
+/// Calls one OpenAPI operation
+///
+/// Arguments are the operation parameters by name plus `body` for the request body.
+/// Non-2xx responses are errors carrying the status and body.
+pub struct OpenApiTool {
+    operation: Operation,
+    settings: Arc<HttpSettings>,
+}
+
+impl OpenApiTool {
+    pub(crate) fn new(operation: Operation, settings: Arc<HttpSettings>) -> Self {
+        Self {
+            operation,
+            settings,
+        }
+    }
+
+    pub fn operation(&self) -> &Operation {
+        &self.operation
+    }
+
+    async fn call_operation(
+        &self,
+        input: Value,
+        context: Option<&dyn ToolContext>,
+    ) -> Result<String, ToolError> {
+        let arguments = match input {
+            Value::Object(arguments) => arguments,
+            Value::String(text) if text.trim().is_empty() => Default::default(),
+            Value::Null => Default::default(),
+            other => {
+                return Err(ToolError::InvalidInputError(format!(
+                    "expected an object of arguments, got {}",
+                    other
+                )))
+            }
+        };
+
+        let mut path = self.operation.path.clone();
+        let mut query = Vec::new();
+        let mut headers = Vec::new();
+        for parameter in &self.operation.parameters {
+            let Some(value) = arguments.get(&parameter.name).filter(|v| !v.is_null()) else {
+                if parameter.required {
+                    return Err(ToolError::InvalidInputError(format!(
+                        "missing required parameter {}",
+                        parameter.name
+                    )));
+                }
+                continue;
+            };
+            match parameter.location {
+                ParameterLocation::Path => {
+                    path = path.replace(
+                        &format!("{{{}}}", parameter.name),
+                        &urlencoding::encode(&value_to_string(value)),
+                    );
+                }
+                ParameterLocation::Query => match value {
+                    Value::Array(items) => query.extend(
+                        items
+                            .iter()
+                            .map(|item| (parameter.name.clone(), value_to_string(item))),
+                    ),
+                    value => query.push((parameter.name.clone(), value_to_string(value))),
+                },
+                ParameterLocation::Header => {
+                    headers.push((parameter.name.clone(), value_to_string(value)))
+                }
+                ParameterLocation::Cookie => {}
+            }
+        }
+
+        let url = format!("{}{}", self.settings.base_url.trim_end_matches('/'), path);
+        let mut request = self
+            .settings
+            .client
+            .request(self.operation.method.clone(), &url);
+        for auth in &self.settings.auth {
+            let Some(secret) = context.and_then(|context| context.get(auth.context_key())) else {
+                return Err(ToolError::ConfigurationError(format!(
+                    "{}: no '{}' in the tool context",
+                    self.operation.id,
+                    auth.context_key()
+                )));
+            };
+            request = match auth {
+                OpenApiAuth::Bearer { .. } => request.bearer_auth(secret),
+                OpenApiAuth::Header { name, .. } => request.header(name.as_str(), secret),
+                OpenApiAuth::Query { name, .. } => {
+                    query.push((name.clone(), secret.to_string()));
+                    request
+                }
+            };
+        }
+        request = request.query(&query);
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        if let (Some(body), Some(spec)) = (arguments.get("body"), &self.operation.request_body) {
+            request = match body {
+                Value::String(text) if !spec.content_type.contains("json") => request
+                    .header(CONTENT_TYPE, spec.content_type.as_str())
+                    .body(text.clone()),
+                body => request.json(body),
+            };
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("{}: {}", self.operation.id, e)))?;
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+        // Compact JSON bodies, pretty printing wastes tokens
+        let text = match serde_json::from_str::<Value>(&text) {
+            Ok(json) => json.to_string(),
+            Err(_) => text,
+        };
+
+        if !status.is_success() {
+            return Err(ToolError::ExecutionError(format!(
+                "HTTP {}: {}",
+                status,
+                truncate(&text, self.settings.max_response_bytes)
+            )));
+        }
+        if text.is_empty() {
+            return Ok(format!("HTTP {}", status));
+        }
+        self.shorten(text).await
+    }
+
+    /// Summarize or truncate a response body over the size limit
+    async fn shorten(&self, text: String) -> Result<String, ToolError> {
+        let limit = self.settings.max_response_bytes;
+        if text.len() <= limit {
+            return Ok(text);
+        }
+        let Some(llm) = &self.settings.summarizer else {
+            return Ok(truncate(&text, limit));
+        };
+        let system = format!(
+            "Summarize this response of the API operation `{}` in at most {} characters. \
+             Keep identifiers, names, numbers and anything needed to make follow-up calls.",
+            self.operation.tool_description(),
+            limit
+        );
+        let messages = [
+            Message::new_system_message(system),
+            Message::new_human_message(truncate(&text, limit * 8)),
+        ];
+        let result = telemetry::generate(llm.as_ref(), &messages)
+            .await
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+        Ok(result.generation)
+    }
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn truncate(text: &str, limit: usize) -> String {
+    if text.len() <= limit {
+        return text.to_string();
+    }
+    let mut end = limit;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!(
+        "{}\n... (truncated, {} bytes total)",
+        &text[..end],
+        text.len()
+    )
+}
+
+#[async_trait]
+impl Tool for OpenApiTool {
+    fn name(&self) -> String {
+        self.operation.id.clone()
+    }
+
+    fn description(&self) -> String {
+        self.operation.tool_description()
+    }
+
+    fn parameters(&self) -> Value {
+        self.operation.parameters_schema()
+    }
+
+    async fn parse_input(&self, input: &str) -> Value {
+        parse_tool_input(input)
+    }
+
+    async fn run(&self, input: Value) -> Result<String, ToolError> {
+        self.call_operation(input, None).await
+    }
+
+    async fn run_with_runtime(
+        &self,
+        input: Value,
+        runtime: &ToolRuntime,
+    ) -> Result<ToolResult, Box<dyn Error>> {
+        let output = self.call_operation(input, Some(runtime.context())).await?;
+        Ok(ToolResult::Text(output))
+    }
+
+    fn requires_runtime(&self) -> bool {
+        !self.settings.auth.is_empty()
+    }
+
+    fn is_parallel_safe(&self) -> bool {
+        matches!(self.operation.method.as_str(), "GET" | "HEAD" | "OPTIONS")
+    }
+}
@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use crate::language_models::llm::LLM;
+use crate::tools::Tool;
+
+use super::{
+    error::OpenApiError,
+    spec::OpenApiSpec,
+    tool::{HttpSettings, OpenApiAuth, OpenApiTool},
+};
+
+/// Turns an OpenAPI 3 document into one [`OpenApiTool`] per operation
+pub struct OpenApiToolkit {
+    spec: OpenApiSpec,
+    base_url: Option<String>,
+    client: reqwest::Client,
+    auth: Vec<OpenApiAuth>,
+    max_response_bytes: usize,
+    summarizer: Option<Arc<dyn LLM>>,
+    operations: Option<Vec<String>>,
+}
+
+impl OpenApiToolkit {
+    pub fn new(spec: OpenApiSpec) -> Self {
+        Self {
+            spec,
+            base_url: None,
+            client: reqwest::Client::new(),
+            auth: Vec::new(),
+            max_response_bytes: 8 * 1024,
+            summarizer: None,
+            operations: None,
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, OpenApiError> {
+        Ok(Self::new(OpenApiSpec::from_json(json)?))
+    }
+
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(yaml: &str) -> Result<Self, OpenApiError> {
+        Ok(Self::new(OpenApiSpec::from_yaml(yaml)?))
+    }
+
+    /// Download the document, see [`OpenApiSpec::from_url`]
+    pub async fn from_url(url: &str) -> Result<Self, OpenApiError> {
+        Ok(Self::new(OpenApiSpec::from_url(url).await?))
+    }
+
+    /// Base URL of the API; defaults to the first server of the document
+    pub fn with_base_url<S: Into<String>>(mut self, base_url: S) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Client for requests, e.g. with timeouts or default headers
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Send `Authorization: Bearer <token>` with the token from the context key
+    pub fn with_bearer_auth<S: Into<String>>(mut self, context_key: S) -> Self {
+        self.auth.push(OpenApiAuth::Bearer {
+            context_key: context_key.into(),
+        });
+        self
+    }
+
+    /// Send a header with its value from the context key
+    pub fn with_header_auth<N: Into<String>, S: Into<String>>(
+        mut self,
+        header: N,
+        context_key: S,
+    ) -> Self {
+        self.auth.push(OpenApiAuth::Header {
+            name: header.into(),
+            context_key: context_key.into(),
+        });
+        self
+    }
+
+    /// Send a query parameter with its value from the context key
+    pub fn with_query_auth<N: Into<String>, S: Into<String>>(
+        mut self,
+        parameter: N,
+        context_key: S,
+    ) -> Self {
+        self.auth.push(OpenApiAuth::Query {
+            name: parameter.into(),
+            context_key: context_key.into(),
+        });
+        self
+    }
+
+    /// Response bodies longer than this are summarized or truncated (default 8 KiB)
+    pub fn with_max_response_bytes(mut self, max_response_bytes: usize) -> Self {
+        self.max_response_bytes = max_response_bytes;
+        self
+    }
+
+    /// Summarize long response bodies with this LLM instead of truncating them
+    pub fn with_summarizer(mut self, llm: Arc<dyn LLM>) -> Self {
+        self.summarizer = Some(llm);
+        self
+    }
+
+    /// Only create tools for these operation ids
+    pub fn with_operations<I, S>(mut self, operations: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.operations = Some(operations.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn spec(&self) -> &OpenApiSpec {
+        &self.spec
+    }
+
+    pub fn tools(&self) -> Result<Vec<Arc<dyn Tool>>, OpenApiError> {
+        let base_url = self
+            .base_url
+            .clone()
+            .or_else(|| self.spec.base_url())
+            .ok_or(OpenApiError::MissingBaseUrl)?;
+        let settings = Arc::new(HttpSettings {
+            base_url,
+            client: self.client.clone(),
+            auth: self.auth.clone(),
+            max_response_bytes: self.max_response_bytes,
+            summarizer: self.summarizer.clone(),
+        });
+
+        Ok(self
+            .spec
+            .operations()?
+            .into_iter()
+            .filter(|operation| {
+                self.operations
+                    .as_ref()
+                    .is_none_or(|operations| operations.contains(&operation.id))
+            })
+            .map(|operation| {
+                Arc::new(OpenApiTool::new(operation, Arc::clone(&settings))) as Arc<dyn Tool>
+            })
+            .collect())
+    }
+}
@@ -1,12 +1,12 @@
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-use crate::agent::AgentState;
+use crate::agent::{AgentEventEmitter, AgentState};
 
 pub use super::context::ToolContext;
 pub use super::file_backend::FileBackend;
 pub use super::store::ToolStore;
-pub use super::stream::StreamWriter;
+pub use super::stream::{StreamWriter, ToolUpdate};
 
 /// Runtime information available to tools during execution.
 ///
@@ -49,6 +49,8 @@ pub struct ToolRuntime {
     pub file_backend: Option<Arc<dyn FileBackend>>,
     /// Current tool call ID
     pub tool_call_id: String,
+    /// Optional event emitter for this tool run (set when the agent run is streamed)
+    pub event_emitter: Option<AgentEventEmitter>,
 }
 
 impl ToolRuntime {
@@ -65,6 +67,7 @@ impl ToolRuntime {
             stream_writer: None,
             file_backend: None,
             tool_call_id,
+            event_emitter: None,
         }
     }
 
@@ -78,6 +81,16 @@ impl ToolRuntime {
         self
     }
 
+    pub fn with_event_emitter(mut self, emitter: AgentEventEmitter) -> Self {
+        self.event_emitter = Some(emitter);
+        self
+    }
+
+    /// Event emitter for this tool run; nested agents use it to report into the parent stream.
+    pub fn event_emitter(&self) -> Option<&AgentEventEmitter> {
+        self.event_emitter.as_ref()
+    }
+
     /// File backend for FS tools; when None, tools use workspace from context.
     pub fn file_backend(&self) -> Option<&Arc<dyn FileBackend>> {
         self.file_backend.as_ref()
@@ -119,6 +132,27 @@ impl ToolRuntime {
             writer.write(message);
         }
     }
+
+    /// Report a structured update if stream writer is available
+    pub fn update(&self, update: ToolUpdate) {
+        if let Some(writer) = &self.stream_writer {
+            writer.write_update(&update);
+        }
+    }
+
+    /// Report progress, e.g. `runtime.progress(3.0, Some(10.0), Some("Crawling"))`
+    pub fn progress(&self, progress: f64, total: Option<f64>, message: Option<&str>) {
+        self.update(ToolUpdate::progress(
+            progress,
+            total,
+            message.map(str::to_string),
+        ));
+    }
+
+    /// Report a piece of the output before the tool finishes
+    pub fn partial(&self, content: &str) {
+        self.update(ToolUpdate::partial(content));
+    }
 }
 
 #[cfg(test)]
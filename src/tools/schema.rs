@@ -1,5 +1,9 @@
+use schemars::{gen::SchemaSettings, JsonSchema};
+use serde::de::DeserializeOwned;
 use serde_json::{json, Value};
 
+use crate::error::ToolError;
+
 /// Generate a JSON schema from a Rust type name and description.
 ///
 /// This is a basic implementation. For more advanced schema generation,
@@ -102,6 +106,76 @@ pub fn extract_base_type(type_str: &str) -> (&str, bool) {
     (type_str, false)
 }
 
+/// Generate the parameters schema of a typed tool from its arguments type.
+///
+/// Subschemas are inlined and the `$schema` and `title` keys dropped, since not every
+/// provider accepts references or metadata in function parameters. Used by `#[tool]`.
+pub fn parameters_schema<T: JsonSchema>() -> Value {
+    let settings = SchemaSettings::draft07().with(|settings| {
+        settings.inline_subschemas = true;
+        settings.meta_schema = None;
+    });
+    let schema = settings.into_generator().into_root_schema_for::<T>();
+    let mut schema = serde_json::to_value(schema).unwrap_or_else(|_| json!({}));
+    if let Some(object) = schema.as_object_mut() {
+        object.remove("title");
+        object
+            .entry("type")
+            .or_insert_with(|| Value::String("object".to_string()));
+        object.entry("properties").or_insert_with(|| json!({}));
+    }
+    schema
+}
+
+/// Parse the raw input of a typed tool, keeping JSON objects intact.
+pub fn parse_tool_input(input: &str) -> Value {
+    serde_json::from_str(input).unwrap_or_else(|_| Value::String(input.to_string()))
+}
+
+/// Deserialize the arguments of a typed tool.
+///
+/// A bare value is accepted for a single-parameter schema. Invalid input is reported with
+/// every schema violation, so the model can correct its call.
+pub fn parse_arguments<T: DeserializeOwned + JsonSchema>(
+    tool: &str,
+    input: Value,
+) -> Result<T, ToolError> {
+    let schema = parameters_schema::<T>();
+    let input = match input {
+        Value::Null => json!({}),
+        Value::Object(_) => input,
+        other => match schema["properties"].as_object() {
+            Some(properties) if properties.len() == 1 => {
+                let name = properties.keys().next().cloned().unwrap_or_default();
+                json!({ name: other })
+            }
+            _ => other,
+        },
+    };
+
+    serde_json::from_value(input.clone()).map_err(|e| {
+        let violations: Vec<String> = jsonschema::JSONSchema::compile(&schema)
+            .ok()
+            .and_then(|compiled| {
+                compiled.validate(&input).err().map(|errors| {
+                    errors
+                        .map(|error| match error.instance_path.to_string() {
+                            path if path.is_empty() => error.to_string(),
+                            path => format!("{}: {}", path, error),
+                        })
+                        .collect()
+                })
+            })
+            .unwrap_or_default();
+        let reason = if violations.is_empty() {
+            e.to_string()
+        } else {
+            violations.join("; ")
+        };
+        ToolError::InvalidInputError(format!("Invalid arguments for {}: {}", tool, reason))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,4 +205,16 @@ mod tests {
         assert_eq!(extract_base_type("Option<String>"), ("String", false));
         assert_eq!(extract_base_type("Vec<String>"), ("String", true));
     }
+
+    #[test]
+    fn test_parse_arguments_bare_value() {
+        #[derive(serde::Deserialize, schemars::JsonSchema)]
+        struct Args {
+            query: String,
+        }
+
+        let args: Args = parse_arguments("search", json!("rust")).unwrap();
+        assert_eq!(args.query, "rust");
+        assert!(parse_arguments::<Args>("search", json!({ "q": 1 })).is_err());
+    }
 }
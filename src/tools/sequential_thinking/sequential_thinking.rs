@@ -10,6 +10,7 @@ use serde_json::{json, Value};
 
 use crate::language_models::llm::LLM;
 use crate::schemas::Message;
+use crate::telemetry;
 
 use crate::tools::Tool;
 
@@ -96,9 +97,7 @@ impl Tool for SequentialThinking {
         let user = Message::new_human_message(thought);
         let messages = [system, user];
 
-        let result = self
-            .llm
-            .generate(&messages)
+        let result = telemetry::generate(self.llm.as_ref(), &messages)
             .await
             .map_err(|e| crate::error::ToolError::ExecutionError(e.to_string()))?;
 
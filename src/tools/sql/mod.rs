@@ -1,5 +1,11 @@
+#[cfg(feature = "mysql")]
+pub mod mysql;
 #[cfg(feature = "postgres")]
 pub mod postgres;
 mod sql;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+mod toolkit;
 
 pub use sql::*;
+pub use toolkit::*;
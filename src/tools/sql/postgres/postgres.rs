@@ -1,18 +1,45 @@
 use async_trait::async_trait;
-use sqlx::{postgres::PgPoolOptions, Column, Pool, Postgres, Row, TypeInfo};
+use sqlx::{
+    postgres::{PgPoolOptions, PgRow},
+    Column, Executor, Pool, Postgres, Row, TypeInfo, ValueRef,
+};
 use std::error::Error;
 
-use crate::tools::{Dialect, Engine};
+use crate::tools::{ensure_read_only, Dialect, Engine, QueryResult, SqlValue, ISO_DATETIME};
 
 pub struct PostgreSQLEngine {
     pool: Pool<Postgres>,
+    read_only: bool,
 }
 
 impl PostgreSQLEngine {
     pub async fn new(dsn: &str) -> Result<Self, Box<dyn Error>> {
         let pool = PgPoolOptions::new().max_connections(5).connect(dsn).await?;
 
-        Ok(PostgreSQLEngine { pool })
+        Ok(PostgreSQLEngine {
+            pool,
+            read_only: false,
+        })
+    }
+
+    /// Connect with read-only sessions; only single read statements are accepted
+    pub async fn read_only(dsn: &str) -> Result<Self, Box<dyn Error>> {
+        let pool = PgPoolOptions::new()
+            .max_connections(5)
+            .after_connect(|conn, _| {
+                Box::pin(async move {
+                    conn.execute("SET SESSION CHARACTERISTICS AS TRANSACTION READ ONLY")
+                        .await?;
+                    Ok(())
+                })
+            })
+            .connect(dsn)
+            .await?;
+
+        Ok(PostgreSQLEngine {
+            pool,
+            read_only: true,
+        })
     }
 }
 
@@ -22,57 +49,73 @@ impl Into<Box<dyn Engine>> for PostgreSQLEngine {
     }
 }
 
+fn decode_value(row: &PgRow, index: usize) -> Result<SqlValue, sqlx::Error> {
+    if row.try_get_raw(index)?.is_null() {
+        return Ok(SqlValue::Null);
+    }
+    let value = match row.columns()[index].type_info().name() {
+        "BOOL" => SqlValue::Bool(row.try_get(index)?),
+        "INT2" => SqlValue::Int(row.try_get::<i16, _>(index)?.into()),
+        "INT4" => SqlValue::Int(row.try_get::<i32, _>(index)?.into()),
+        "INT8" => SqlValue::Int(row.try_get(index)?),
+        "FLOAT4" => SqlValue::Float(row.try_get::<f32, _>(index)?.into()),
+        "FLOAT8" => SqlValue::Float(row.try_get(index)?),
+        "BYTEA" => SqlValue::Bytes(row.try_get(index)?),
+        "JSON" | "JSONB" => SqlValue::Json(row.try_get(index)?),
+        "UUID" => SqlValue::Text(row.try_get::<uuid::Uuid, _>(index)?.to_string()),
+        // Exact decimal text; values out of `Decimal` range (or NaN) are unsupported
+        "NUMERIC" => match row.try_get::<sqlx::types::Decimal, _>(index) {
+            Ok(value) => SqlValue::Text(value.to_string()),
+            Err(_) => SqlValue::Unsupported("NUMERIC".to_string()),
+        },
+        "DATE" => SqlValue::Text(row.try_get::<chrono::NaiveDate, _>(index)?.to_string()),
+        "TIME" => SqlValue::Text(row.try_get::<chrono::NaiveTime, _>(index)?.to_string()),
+        "TIMESTAMP" => SqlValue::Text(
+            row.try_get::<chrono::NaiveDateTime, _>(index)?
+                .format(ISO_DATETIME)
+                .to_string(),
+        ),
+        "TIMESTAMPTZ" => SqlValue::Text(
+            row.try_get::<chrono::DateTime<chrono::Utc>, _>(index)?
+                .to_rfc3339(),
+        ),
+        "TEXT[]" | "VARCHAR[]" => SqlValue::Json(row.try_get::<Vec<String>, _>(index)?.into()),
+        name => match row.try_get::<String, _>(index) {
+            Ok(text) => SqlValue::Text(text),
+            Err(_) => SqlValue::Unsupported(name.to_string()),
+        },
+    };
+    Ok(value)
+}
+
 #[async_trait]
 impl Engine for PostgreSQLEngine {
     fn dialect(&self) -> Dialect {
         Dialect::PostgreSQL
     }
 
-    async fn query(&self, query: &str) -> Result<(Vec<String>, Vec<Vec<String>>), Box<dyn Error>> {
+    async fn query(&self, query: &str) -> Result<QueryResult, Box<dyn Error>> {
+        if self.read_only {
+            ensure_read_only(query, self.dialect())?;
+        }
         let rows = sqlx::query(query).fetch_all(&self.pool).await?;
 
-        let mut cols = vec![];
-        let mut results = vec![];
-
+        let mut result = QueryResult::default();
         if let Some(row) = rows.first() {
-            cols = row
+            result.columns = row
                 .columns()
                 .iter()
                 .map(|col| col.name().to_string())
                 .collect();
         }
-
         for row in rows {
-            let mut result = Vec::with_capacity(cols.len());
-            for index in 0..cols.len() {
-                let column_type = row.columns()[index].type_info().name();
-
-                let value_str = match column_type {
-                    "TEXT[]" => {
-                        // Fetch the TEXT[] column as a vector of strings
-                        match row.try_get::<Vec<String>, _>(index) {
-                            Ok(array) => format!("{:?}", array), // Format the vector as a string
-                            Err(_) => "N/A".to_string(),
-                        }
-                    }
-                    _ => {
-                        // For other types, attempt to get them as strings
-                        match row.try_get::<&str, _>(index) {
-                            Ok(str_val) => str_val.to_string(),
-                            Err(_) => {
-                                // Fallback for types that cannot be directly converted to string
-                                "N/A".to_string()
-                            }
-                        }
-                    }
-                };
-
-                result.push(value_str);
-            }
-            results.push(result);
+            let values = (0..result.columns.len())
+                .map(|index| decode_value(&row, index))
+                .collect::<Result<Vec<_>, _>>()?;
+            result.rows.push(values);
         }
 
-        Ok((cols, results))
+        Ok(result)
     }
 
     async fn table_names(&self) -> Result<Vec<String>, Box<dyn Error>> {
@@ -89,13 +132,9 @@ impl Engine for PostgreSQLEngine {
     }
 
     async fn table_info(&self, table: &str) -> Result<String, Box<dyn Error>> {
-        let query = format!(
-            "SELECT column_name, data_type FROM information_schema.columns WHERE table_name = $1"
-        );
-        let rows = sqlx::query(&query)
-            .bind(table)
-            .fetch_all(&self.pool)
-            .await?;
+        let query = "SELECT column_name, data_type FROM information_schema.columns \
+                     WHERE table_name = $1 ORDER BY ordinal_position";
+        let rows = sqlx::query(query).bind(table).fetch_all(&self.pool).await?;
 
         // Simplified representation of table info, similar to a CREATE TABLE statement
         let info = rows
@@ -1,9 +1,11 @@
-use std::{collections::HashSet, error::Error};
+use std::{collections::HashSet, error::Error, fmt};
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use thiserror::Error;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Dialect {
     #[serde(rename = "mysql")]
     MySQL,
@@ -22,12 +24,315 @@ impl ToString for Dialect {
     }
 }
 
+impl Dialect {
+    /// Quote a table or column name for this dialect
+    pub fn quote_identifier(&self, name: &str) -> String {
+        match self {
+            Dialect::MySQL => format!("`{}`", name.replace('`', "``")),
+            Dialect::SQLite | Dialect::PostgreSQL => format!("\"{}\"", name.replace('"', "\"\"")),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum SQLError {
+    #[error("Only read-only statements are allowed, got: {0}")]
+    NotReadOnly(String),
+    #[error("Only a single statement is allowed")]
+    MultipleStatements,
+    #[error("Statement type not allowed: {0}")]
+    StatementNotAllowed(String),
+}
+
+/// Format of date-time values without a time zone in query results
+pub const ISO_DATETIME: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+/// A single value of a query result
+///
+/// Decimals, dates and times are returned as text (ISO 8601 for dates and times), so no
+/// precision is lost.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum SqlValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Bytes(Vec<u8>),
+    Json(Value),
+    /// A value whose column type cannot be decoded, with the type name
+    Unsupported(String),
+}
+
+impl fmt::Display for SqlValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SqlValue::Null => write!(f, "NULL"),
+            SqlValue::Bool(value) => write!(f, "{}", value),
+            SqlValue::Int(value) => write!(f, "{}", value),
+            SqlValue::Float(value) => write!(f, "{}", value),
+            SqlValue::Text(value) => write!(f, "{}", value),
+            SqlValue::Bytes(value) => write!(f, "<{} bytes>", value.len()),
+            SqlValue::Json(value) => write!(f, "{}", value),
+            SqlValue::Unsupported(_) => write!(f, "N/A"),
+        }
+    }
+}
+
+/// Columns and typed rows returned by [`Engine::query`]
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<SqlValue>>,
+}
+
+impl QueryResult {
+    /// Rows as JSON objects keyed by column name
+    pub fn records(&self) -> Vec<Map<String, Value>> {
+        self.rows
+            .iter()
+            .map(|row| {
+                self.columns
+                    .iter()
+                    .cloned()
+                    .zip(
+                        row.iter()
+                            .map(|value| serde_json::to_value(value).unwrap_or_default()),
+                    )
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Tab separated, with a header line of column names
+impl fmt::Display for QueryResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.columns.join("\t"))?;
+        for row in &self.rows {
+            let row: Vec<String> = row.iter().map(ToString::to_string).collect();
+            writeln!(f, "{}", row.join("\t"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Statements a read-only engine may run
+const READ_ONLY_KEYWORDS: &[&str] = &[
+    "SELECT", "WITH", "EXPLAIN", "VALUES", "SHOW", "DESCRIBE", "DESC", "PRAGMA",
+];
+
+/// Pragmas whose argument names the object to inspect instead of setting a value
+const INSPECTING_PRAGMAS: &[&str] = &[
+    "TABLE_INFO",
+    "TABLE_XINFO",
+    "TABLE_LIST",
+    "INDEX_LIST",
+    "INDEX_INFO",
+    "INDEX_XINFO",
+    "FOREIGN_KEY_LIST",
+    "FOREIGN_KEY_CHECK",
+    "INTEGRITY_CHECK",
+    "QUICK_CHECK",
+];
+
+/// Keywords that write even when nested in a read statement (`WITH ... DELETE`, `SELECT ... INTO`)
+const WRITE_KEYWORDS: &[&str] = &["INSERT", "UPDATE", "DELETE", "MERGE", "INTO"];
+
+/// Reject anything but a single read statement
+///
+/// This is a guard against generated SQL; read-only engines also open the database read-only.
+/// Comments and quoting follow `dialect`, assuming the server's default settings (MySQL
+/// backslash escapes, PostgreSQL `standard_conforming_strings`).
+pub fn ensure_read_only(query: &str, dialect: Dialect) -> Result<(), SQLError> {
+    let words = sql_words(query, dialect)?;
+    let first = words.first().map(String::as_str).unwrap_or_default();
+    if !READ_ONLY_KEYWORDS.contains(&first) {
+        return Err(SQLError::NotReadOnly(first.to_string()));
+    }
+    if let Some(word) = words
+        .iter()
+        .find(|word| WRITE_KEYWORDS.contains(&word.as_str()))
+    {
+        return Err(SQLError::NotReadOnly(word.clone()));
+    }
+    // `PRAGMA name = value` and `PRAGMA name(value)` change settings
+    if first == "PRAGMA" && (query.contains('=') || !is_inspecting_pragma(query)) {
+        return Err(SQLError::NotReadOnly(first.to_string()));
+    }
+    Ok(())
+}
+
+/// Whether a PRAGMA statement has no argument, or one naming the object to inspect
+fn is_inspecting_pragma(query: &str) -> bool {
+    let Some((head, _)) = query.split_once('(') else {
+        return true;
+    };
+    // `PRAGMA schema.name(argument)`
+    let name = head
+        .trim()
+        .rsplit(['.', ' ', '\t', '\n'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_uppercase();
+    INSPECTING_PRAGMAS.contains(&name.as_str())
+}
+
+/// Upper-cased bare words of a single statement, skipping quoted text and comments
+pub(crate) fn sql_words(query: &str, dialect: Dialect) -> Result<Vec<String>, SQLError> {
+    Ok(sql_words_with_depth(query, dialect)?
+        .into_iter()
+        .map(|(word, _)| word)
+        .collect())
+}
+
+/// Like [`sql_words`], with the parenthesis depth of each word (0 at the top level)
+pub(crate) fn sql_words_with_depth(
+    query: &str,
+    dialect: Dialect,
+) -> Result<Vec<(String, usize)>, SQLError> {
+    let chars: Vec<char> = query.trim().trim_end_matches(';').chars().collect();
+    let at = |i: usize| chars.get(i).copied();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut depth = 0usize;
+    // Inside a MySQL `/*! ... */` comment, whose content runs as SQL
+    let mut in_executable_comment = false;
+    let mut i = 0;
+    while let Some(c) = at(i) {
+        i += 1;
+        if c.is_alphanumeric() || c == '_' || (c == '$' && !word.is_empty()) {
+            word.push(c.to_ascii_uppercase());
+            continue;
+        }
+        // `E'...'`, with no space before the quote
+        let escape_prefix = word == "E";
+        if !word.is_empty() {
+            words.push((std::mem::take(&mut word), depth));
+        }
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            '\'' | '"' => {
+                // MySQL strings and PostgreSQL `E'...'` strings take backslash escapes
+                let backslash = dialect == Dialect::MySQL
+                    || (dialect == Dialect::PostgreSQL && c == '\'' && escape_prefix);
+                i = skip_quoted(&chars, i, c, backslash);
+            }
+            '`' => i = skip_quoted(&chars, i, c, false),
+            '[' if dialect == Dialect::SQLite => i = skip_quoted(&chars, i, ']', false),
+            '$' if dialect == Dialect::PostgreSQL => {
+                if let Some(end) = dollar_quote_end(&chars, i) {
+                    i = end;
+                }
+            }
+            // In MySQL `--` only starts a comment before whitespace: `1 --1` is `1 - -1`
+            '-' if at(i) == Some('-')
+                && (dialect != Dialect::MySQL
+                    || at(i + 1).is_none_or(|next| next.is_whitespace() || next.is_control())) =>
+            {
+                i = skip_line(&chars, i)
+            }
+            '#' if dialect == Dialect::MySQL => i = skip_line(&chars, i),
+            '/' if at(i) == Some('*') => {
+                let executable = match (at(i + 1), at(i + 2)) {
+                    (Some('!'), _) => Some(i + 2),
+                    (Some('M'), Some('!')) => Some(i + 3),
+                    _ => None,
+                };
+                match executable {
+                    Some(body) if dialect == Dialect::MySQL => {
+                        // Skip the optional version, e.g. `/*!50001 ...`
+                        i = body;
+                        while at(i).is_some_and(|c| c.is_ascii_digit()) {
+                            i += 1;
+                        }
+                        in_executable_comment = true;
+                    }
+                    _ => i = skip_block_comment(&chars, i + 1, dialect == Dialect::PostgreSQL),
+                }
+            }
+            '*' if in_executable_comment && at(i) == Some('/') => {
+                i += 1;
+                in_executable_comment = false;
+            }
+            ';' => return Err(SQLError::MultipleStatements),
+            _ => {}
+        }
+    }
+    if !word.is_empty() {
+        words.push((word, depth));
+    }
+    Ok(words)
+}
+
+/// Index after the quoted text that opened before `i` and ends at `close`
+fn skip_quoted(chars: &[char], mut i: usize, close: char, backslash: bool) -> usize {
+    while let Some(&c) = chars.get(i) {
+        i += 1;
+        if backslash && c == '\\' {
+            i += 1;
+        } else if c == close {
+            break;
+        }
+    }
+    i
+}
+
+/// Index of the end of the line containing `i`
+fn skip_line(chars: &[char], mut i: usize) -> usize {
+    while chars.get(i).is_some_and(|&c| c != '\n') {
+        i += 1;
+    }
+    i
+}
+
+/// Index after the block comment whose `/*` ends before `i`; PostgreSQL comments nest
+fn skip_block_comment(chars: &[char], mut i: usize, nested: bool) -> usize {
+    let mut depth = 1;
+    while i < chars.len() {
+        if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+            i += 2;
+            depth -= 1;
+            if depth == 0 {
+                break;
+            }
+        } else if nested && chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            depth += 1;
+        } else {
+            i += 1;
+        }
+    }
+    i
+}
+
+/// Index after a PostgreSQL `$tag$...$tag$` string whose first `$` is before `i`, or `None`
+/// for a `$1` parameter
+fn dollar_quote_end(chars: &[char], i: usize) -> Option<usize> {
+    let tag_len = chars[i..]
+        .iter()
+        .take_while(|c| c.is_alphanumeric() || **c == '_')
+        .count();
+    if chars.get(i + tag_len) != Some(&'$') || chars.get(i).is_some_and(char::is_ascii_digit) {
+        return None;
+    }
+    let delimiter = &chars[i - 1..=i + tag_len];
+    let body = i + tag_len + 1;
+    Some(
+        (body..chars.len())
+            .find(|&start| chars[start..].starts_with(delimiter))
+            .map_or(chars.len(), |start| start + delimiter.len()),
+    )
+}
+
 #[async_trait]
 pub trait Engine: Send + Sync {
     // Dialect returns the dialect(e.g. mysql, sqlite, postgre) of the database.
     fn dialect(&self) -> Dialect;
-    // Query executes the query and returns the columns and results.
-    async fn query(&self, query: &str) -> Result<(Vec<String>, Vec<Vec<String>>), Box<dyn Error>>;
+    // Query executes the query and returns the columns and typed rows.
+    async fn query(&self, query: &str) -> Result<QueryResult, Box<dyn Error>>;
     // TableNames returns all the table names of the database.
     async fn table_names(&self) -> Result<Vec<String>, Box<dyn Error>>;
     // TableInfo returns the table information of the database.
@@ -130,14 +435,12 @@ impl SQLDatabase {
     }
 
     pub async fn query(&self, query: &str) -> Result<String, Box<dyn Error>> {
+        Ok(self.query_rows(query).await?.to_string())
+    }
+
+    pub async fn query_rows(&self, query: &str) -> Result<QueryResult, Box<dyn Error>> {
         log::debug!("Query: {}", query);
-        let (cols, results) = self.engine.query(query).await?;
-        let mut str = cols.join("\t") + "\n";
-        for row in results {
-            str += &row.join("\t");
-            str.push('\n');
-        }
-        Ok(str)
+        self.engine.query(query).await
     }
 
     pub fn close(&self) -> Result<(), Box<dyn Error>> {
@@ -145,8 +448,90 @@ impl SQLDatabase {
     }
 
     pub async fn sample_rows(&self, table: &str) -> Result<String, Box<dyn Error>> {
-        let query = format!("SELECT * FROM {} LIMIT {}", table, self.sample_rows_number);
+        let query = format!(
+            "SELECT * FROM {} LIMIT {}",
+            self.dialect().quote_identifier(table),
+            self.sample_rows_number
+        );
         log::debug!("Sample Rows Query: {}", query);
         self.query(&query).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ensure_read_only() {
+        assert!(ensure_read_only(
+            "SELECT name FROM users WHERE note = 'x; DELETE'",
+            Dialect::SQLite
+        )
+        .is_ok());
+        assert!(ensure_read_only(
+            "select replace(name, 'a', 'b') from users;",
+            Dialect::SQLite
+        )
+        .is_ok());
+        assert!(ensure_read_only(
+            "WITH t AS (SELECT 1) SELECT * FROM t -- delete",
+            Dialect::SQLite
+        )
+        .is_ok());
+        assert!(ensure_read_only("PRAGMA table_info(users)", Dialect::SQLite).is_ok());
+        assert!(ensure_read_only("PRAGMA main.index_list('users')", Dialect::SQLite).is_ok());
+        assert!(ensure_read_only("PRAGMA journal_mode", Dialect::SQLite).is_ok());
+
+        assert!(ensure_read_only("DELETE FROM users", Dialect::SQLite).is_err());
+        assert!(
+            ensure_read_only("WITH t AS (SELECT 1) DELETE FROM users", Dialect::SQLite).is_err()
+        );
+        assert!(ensure_read_only("SELECT * INTO backup FROM users", Dialect::SQLite).is_err());
+        assert!(ensure_read_only("SELECT 1; DROP TABLE users", Dialect::SQLite).is_err());
+        assert!(ensure_read_only("PRAGMA journal_mode = WAL", Dialect::SQLite).is_err());
+        assert!(ensure_read_only("PRAGMA journal_mode(WAL)", Dialect::SQLite).is_err());
+        assert!(ensure_read_only("pragma main.user_version (7)", Dialect::SQLite).is_err());
+    }
+
+    #[test]
+    fn test_ensure_read_only_mysql() {
+        let read_only = |query| ensure_read_only(query, Dialect::MySQL);
+        assert!(read_only("SELECT 'it''s', \"a\\\"b\" FROM users -- INTO").is_ok());
+        assert!(read_only("SELECT 1 # INTO OUTFILE").is_ok());
+        assert!(read_only("SELECT 1 /* INTO */").is_ok());
+
+        // `--` needs whitespace after it: this is `SELECT 1 - -1 INTO OUTFILE ...`
+        assert!(read_only("SELECT 1 --1 INTO OUTFILE '/tmp/x'").is_err());
+        // `\'` is an escaped quote, so the string ends before INTO
+        assert!(read_only(r"SELECT 'a\'', 1 INTO OUTFILE '/tmp/x'").is_err());
+        // Versioned comments run as SQL
+        assert!(read_only("SELECT 1 /*!50000 INTO OUTFILE '/tmp/x' */").is_err());
+        assert!(read_only("SELECT 1 # note\n; DELETE FROM users").is_err());
+    }
+
+    #[test]
+    fn test_ensure_read_only_postgres() {
+        let read_only = |query| ensure_read_only(query, Dialect::PostgreSQL);
+        assert!(read_only("SELECT $$ DELETE; $$, $1, a$b FROM users").is_ok());
+        assert!(read_only(r"SELECT 'a\', 1").is_ok());
+
+        // Comments nest, so the quote is still inside one
+        assert!(read_only("SELECT /* /* */ ' */ 1 INTO t FROM users --'").is_err());
+        assert!(read_only("SELECT $tag$'$tag$ INTO t FROM users --'").is_err());
+        assert!(read_only(r"SELECT E'a\'', 1 INTO t FROM users --'").is_err());
+    }
+
+    #[test]
+    fn test_query_result_display_and_records() {
+        let result = QueryResult {
+            columns: vec!["id".into(), "name".into()],
+            rows: vec![vec![SqlValue::Int(1), SqlValue::Null]],
+        };
+        assert_eq!(result.to_string(), "id\tname\n1\tNULL\n");
+        assert_eq!(
+            Value::Object(result.records()[0].clone()),
+            serde_json::json!({ "id": 1, "name": null })
+        );
+    }
+}
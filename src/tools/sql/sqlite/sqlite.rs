@@ -0,0 +1,197 @@
+use std::{error::Error, str::FromStr};
+
+use async_trait::async_trait;
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions, SqliteRow},
+    Column, Pool, Row, Sqlite, TypeInfo, ValueRef,
+};
+
+use crate::tools::{ensure_read_only, Dialect, Engine, QueryResult, SqlValue};
+
+pub struct SQLiteEngine {
+    pool: Pool<Sqlite>,
+    read_only: bool,
+}
+
+impl SQLiteEngine {
+    /// Connect to a database such as `sqlite://analytics.db` or `sqlite::memory:`
+    pub async fn new(dsn: &str) -> Result<Self, Box<dyn Error>> {
+        Self::connect(dsn, false).await
+    }
+
+    /// Open the database read-only; only single read statements are accepted
+    pub async fn read_only(dsn: &str) -> Result<Self, Box<dyn Error>> {
+        Self::connect(dsn, true).await
+    }
+
+    pub fn from_pool(pool: Pool<Sqlite>) -> Self {
+        SQLiteEngine {
+            pool,
+            read_only: false,
+        }
+    }
+
+    async fn connect(dsn: &str, read_only: bool) -> Result<Self, Box<dyn Error>> {
+        let options = SqliteConnectOptions::from_str(dsn)?.read_only(read_only);
+        // Every connection to an in-memory database opens a new, empty one
+        let pool = if dsn.contains(":memory:") {
+            SqlitePoolOptions::new()
+                .max_connections(1)
+                .idle_timeout(None)
+                .max_lifetime(None)
+        } else {
+            SqlitePoolOptions::new().max_connections(5)
+        }
+        .connect_with(options)
+        .await?;
+
+        Ok(SQLiteEngine { pool, read_only })
+    }
+}
+
+impl From<SQLiteEngine> for Box<dyn Engine> {
+    fn from(engine: SQLiteEngine) -> Self {
+        Box::new(engine)
+    }
+}
+
+fn decode_value(row: &SqliteRow, index: usize) -> Result<SqlValue, sqlx::Error> {
+    let raw = row.try_get_raw(index)?;
+    if raw.is_null() {
+        return Ok(SqlValue::Null);
+    }
+    // SQLite types are per value, so decode by storage class rather than declared type
+    let value = match raw.type_info().name() {
+        "INTEGER" => SqlValue::Int(row.try_get(index)?),
+        "REAL" => SqlValue::Float(row.try_get(index)?),
+        "BLOB" => SqlValue::Bytes(row.try_get(index)?),
+        _ => SqlValue::Text(row.try_get(index)?),
+    };
+    Ok(value)
+}
+
+#[async_trait]
+impl Engine for SQLiteEngine {
+    fn dialect(&self) -> Dialect {
+        Dialect::SQLite
+    }
+
+    async fn query(&self, query: &str) -> Result<QueryResult, Box<dyn Error>> {
+        if self.read_only {
+            ensure_read_only(query, self.dialect())?;
+        }
+        let rows = sqlx::query(query).fetch_all(&self.pool).await?;
+
+        let mut result = QueryResult::default();
+        if let Some(row) = rows.first() {
+            result.columns = row
+                .columns()
+                .iter()
+                .map(|col| col.name().to_string())
+                .collect();
+        }
+        for row in rows {
+            let values = (0..result.columns.len())
+                .map(|index| decode_value(&row, index))
+                .collect::<Result<Vec<_>, _>>()?;
+            result.rows.push(values);
+        }
+
+        Ok(result)
+    }
+
+    async fn table_names(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        let query = "SELECT name FROM sqlite_master \
+                     WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name";
+        let rows = sqlx::query(query).fetch_all(&self.pool).await?;
+
+        let table_names = rows
+            .into_iter()
+            .map(|row| row.get::<String, &str>("name"))
+            .collect();
+
+        Ok(table_names)
+    }
+
+    async fn table_info(&self, table: &str) -> Result<String, Box<dyn Error>> {
+        let query = "SELECT sql FROM sqlite_master WHERE type IN ('table', 'view') AND name = ?";
+        let create: Option<String> = sqlx::query_scalar(query)
+            .bind(table)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        create.ok_or_else(|| format!("Table not found: {}", table).into())
+    }
+
+    fn close(&self) -> Result<(), Box<dyn Error>> {
+        // sqlx Pool is automatically closed when it goes out of scope
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::SQLDatabaseBuilder;
+
+    async fn create_database(path: &std::path::Path) {
+        let engine = SQLiteEngine::new(&format!("sqlite://{}?mode=rwc", path.display()))
+            .await
+            .unwrap();
+        for statement in [
+            "DROP TABLE IF EXISTS users",
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, score REAL, avatar BLOB)",
+            "INSERT INTO users VALUES (1, 'Ana', 9.5, x'00ff'), (2, NULL, 7, NULL)",
+        ] {
+            engine.query(statement).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_engine() {
+        let path = std::env::temp_dir().join(format!(
+            "langchain_sqlite_engine_{}.db",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        create_database(&path).await;
+        let engine = SQLiteEngine::read_only(&format!("sqlite://{}", path.display()))
+            .await
+            .unwrap();
+        let database = SQLDatabaseBuilder::new(engine)
+            .custom_sample_rows_number(1)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(database.table_names(), vec!["users".to_string()]);
+
+        let rows = database
+            .query_rows("SELECT id, name, score, avatar FROM users ORDER BY id")
+            .await
+            .unwrap();
+        assert_eq!(rows.columns, vec!["id", "name", "score", "avatar"]);
+        assert_eq!(
+            rows.rows,
+            vec![
+                vec![
+                    SqlValue::Int(1),
+                    SqlValue::Text("Ana".into()),
+                    SqlValue::Float(9.5),
+                    SqlValue::Bytes(vec![0, 255]),
+                ],
+                vec![
+                    SqlValue::Int(2),
+                    SqlValue::Null,
+                    SqlValue::Float(7.0),
+                    SqlValue::Null,
+                ],
+            ]
+        );
+
+        let info = database.table_info(&[]).await.unwrap();
+        assert!(info.starts_with("CREATE TABLE users (id INTEGER PRIMARY KEY"));
+        assert!(info.contains("1\tAna\t9.5\t<2 bytes>"));
+
+        assert!(database.query("DELETE FROM users").await.is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+}
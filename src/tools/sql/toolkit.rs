@@ -0,0 +1,589 @@
+//! Tools for letting an agent explore and query a [`SQLDatabase`].
+//!
+//! Unlike [`SQLDatabaseChain`](crate::chain::SQLDatabaseChain), the schema is not put in the
+//! prompt: the agent lists tables, describes the ones it needs and runs queries that pass
+//! the [`SqlGuardrails`].
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use crate::error::ToolError;
+use crate::language_models::llm::LLM;
+use crate::schemas::Message;
+use crate::telemetry;
+use crate::tools::Tool;
+
+use super::{ensure_read_only, sql_words_with_depth, Dialect, SQLDatabase, SQLError};
+
+/// Checks and rewrites queries before [`QuerySQLDatabaseTool`] runs them
+#[derive(Debug, Clone)]
+pub struct SqlGuardrails {
+    /// Only allow single read statements
+    pub read_only: bool,
+    /// Maximum number of rows returned to the model
+    pub max_rows: usize,
+    /// Statement types (first keyword) that may run, e.g. `SELECT`
+    pub allowed_statements: Vec<String>,
+    /// Append `LIMIT max_rows` to queries without a top-level LIMIT
+    pub inject_limit: bool,
+}
+
+impl Default for SqlGuardrails {
+    fn default() -> Self {
+        Self {
+            read_only: true,
+            max_rows: 100,
+            allowed_statements: vec!["SELECT".into(), "WITH".into(), "EXPLAIN".into()],
+            inject_limit: true,
+        }
+    }
+}
+
+impl SqlGuardrails {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub fn with_max_rows(mut self, max_rows: usize) -> Self {
+        self.max_rows = max_rows;
+        self
+    }
+
+    pub fn with_allowed_statements<I, S>(mut self, statements: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_statements = statements
+            .into_iter()
+            .map(|statement| statement.into().to_uppercase())
+            .collect();
+        self
+    }
+
+    pub fn with_inject_limit(mut self, inject_limit: bool) -> Self {
+        self.inject_limit = inject_limit;
+        self
+    }
+
+    /// Validate a query for a database of `dialect` and return it as it should run
+    pub fn prepare(&self, query: &str, dialect: Dialect) -> Result<String, SQLError> {
+        let query = query.trim().trim_end_matches(';').trim();
+        let words = sql_words_with_depth(query, dialect)?;
+        let first = words
+            .first()
+            .map(|(word, _)| word.clone())
+            .unwrap_or_default();
+        if !self.allowed_statements.contains(&first) {
+            return Err(SQLError::StatementNotAllowed(first));
+        }
+        if self.read_only {
+            ensure_read_only(query, dialect)?;
+        }
+        let is_select = first == "SELECT" || first == "WITH";
+        // A LIMIT in a subquery does not limit the result
+        let has_limit = words
+            .iter()
+            .any(|(word, depth)| word == "LIMIT" && *depth == 0);
+        if self.inject_limit && is_select && !has_limit {
+            // On its own line, so a trailing `-- comment` cannot swallow it
+            return Ok(format!("{}\nLIMIT {}", query, self.max_rows));
+        }
+        Ok(query.to_string())
+    }
+}
+
+/// Builds the tools for one database
+///
+/// ```rust,ignore
+/// let database = Arc::new(SQLDatabaseBuilder::new(engine).build().await?);
+/// let tools = SQLDatabaseToolkit::new(database)
+///     .with_llm(llm)
+///     .with_guardrails(SqlGuardrails::new().with_max_rows(20))
+///     .tools();
+/// ```
+pub struct SQLDatabaseToolkit {
+    database: Arc<SQLDatabase>,
+    llm: Option<Arc<dyn LLM>>,
+    guardrails: SqlGuardrails,
+}
+
+impl SQLDatabaseToolkit {
+    pub fn new(database: Arc<SQLDatabase>) -> Self {
+        Self {
+            database,
+            llm: None,
+            guardrails: SqlGuardrails::default(),
+        }
+    }
+
+    /// LLM for the query checker tool; without one the checker is not included
+    pub fn with_llm(mut self, llm: Arc<dyn LLM>) -> Self {
+        self.llm = Some(llm);
+        self
+    }
+
+    pub fn with_guardrails(mut self, guardrails: SqlGuardrails) -> Self {
+        self.guardrails = guardrails;
+        self
+    }
+
+    pub fn database(&self) -> &Arc<SQLDatabase> {
+        &self.database
+    }
+
+    pub fn guardrails(&self) -> &SqlGuardrails {
+        &self.guardrails
+    }
+
+    pub fn tools(&self) -> Vec<Arc<dyn Tool>> {
+        let mut tools: Vec<Arc<dyn Tool>> = vec![
+            Arc::new(ListSQLDatabaseTool::new(Arc::clone(&self.database))),
+            Arc::new(InfoSQLDatabaseTool::new(Arc::clone(&self.database))),
+            Arc::new(SampleRowsSQLDatabaseTool::new(Arc::clone(&self.database))),
+            Arc::new(
+                QuerySQLDatabaseTool::new(Arc::clone(&self.database))
+                    .with_guardrails(self.guardrails.clone()),
+            ),
+        ];
+        if let Some(llm) = &self.llm {
+            tools.push(Arc::new(QuerySQLCheckerTool::new(
+                Arc::clone(&self.database),
+                Arc::clone(llm),
+            )));
+        }
+        tools
+    }
+}
+
+fn input_parameters(description: &str) -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "input": {
+                "type": "string",
+                "description": description
+            }
+        },
+        "required": ["input"]
+    })
+}
+
+fn input_str(input: &Value) -> Result<&str, ToolError> {
+    input
+        .as_str()
+        .or_else(|| input.get("input").and_then(Value::as_str))
+        .map(str::trim)
+        .ok_or_else(|| ToolError::InvalidInputError("input must be a string".into()))
+}
+
+/// Check that every table exists, so the model gets the list instead of a database error
+fn known_tables(database: &SQLDatabase, input: &str) -> Result<Vec<String>, ToolError> {
+    let tables: Vec<String> = input
+        .split(',')
+        .map(|table| table.trim().trim_matches(|c| c == '"' || c == '`'))
+        .filter(|table| !table.is_empty())
+        .map(str::to_string)
+        .collect();
+    let unknown: Vec<&str> = tables
+        .iter()
+        .filter(|table| !database.all_tables.contains(*table))
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        let mut available = database.table_names();
+        available.sort();
+        return Err(ToolError::InvalidInputError(format!(
+            "Unknown tables: {}. Available tables: {}",
+            unknown.join(", "),
+            available.join(", ")
+        )));
+    }
+    Ok(tables)
+}
+
+/// `sql_db_list_tables`: comma separated table names
+pub struct ListSQLDatabaseTool {
+    database: Arc<SQLDatabase>,
+}
+
+impl ListSQLDatabaseTool {
+    pub fn new(database: Arc<SQLDatabase>) -> Self {
+        Self { database }
+    }
+}
+
+#[async_trait]
+impl Tool for ListSQLDatabaseTool {
+    fn name(&self) -> String {
+        "sql_db_list_tables".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Lists the tables in the database as a comma separated list. Input is an empty string."
+            .to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        input_parameters("An empty string")
+    }
+
+    async fn run(&self, _input: Value) -> Result<String, ToolError> {
+        let mut tables = self.database.table_names();
+        tables.sort();
+        Ok(tables.join(", "))
+    }
+}
+
+/// `sql_db_schema`: CREATE statements and sample rows of some tables
+pub struct InfoSQLDatabaseTool {
+    database: Arc<SQLDatabase>,
+}
+
+impl InfoSQLDatabaseTool {
+    pub fn new(database: Arc<SQLDatabase>) -> Self {
+        Self { database }
+    }
+}
+
+#[async_trait]
+impl Tool for InfoSQLDatabaseTool {
+    fn name(&self) -> String {
+        "sql_db_schema".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Returns the schema and sample rows of the given tables. Input is a comma separated \
+         list of tables, e.g. `users, orders`. Call sql_db_list_tables first to be sure the \
+         tables exist."
+            .to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        input_parameters("Comma separated list of table names")
+    }
+
+    async fn run(&self, input: Value) -> Result<String, ToolError> {
+        let tables = known_tables(&self.database, input_str(&input)?)?;
+        if tables.is_empty() {
+            return Err(ToolError::InvalidInputError(
+                "Give at least one table name".into(),
+            ));
+        }
+        self.database
+            .table_info(&tables)
+            .await
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))
+    }
+}
+
+/// `sql_db_sample_rows`: the first rows of one table
+pub struct SampleRowsSQLDatabaseTool {
+    database: Arc<SQLDatabase>,
+    rows: usize,
+}
+
+impl SampleRowsSQLDatabaseTool {
+    pub fn new(database: Arc<SQLDatabase>) -> Self {
+        Self { database, rows: 3 }
+    }
+
+    pub fn with_rows(mut self, rows: usize) -> Self {
+        self.rows = rows;
+        self
+    }
+}
+
+#[async_trait]
+impl Tool for SampleRowsSQLDatabaseTool {
+    fn name(&self) -> String {
+        "sql_db_sample_rows".to_string()
+    }
+
+    fn description(&self) -> String {
+        format!(
+            "Returns the first {} rows of a table, to see what its values look like. \
+             Input is a single table name.",
+            self.rows
+        )
+    }
+
+    fn parameters(&self) -> Value {
+        input_parameters("Table name")
+    }
+
+    async fn run(&self, input: Value) -> Result<String, ToolError> {
+        let table = match known_tables(&self.database, input_str(&input)?)?.as_slice() {
+            [table] => table.clone(),
+            _ => {
+                return Err(ToolError::InvalidInputError(
+                    "Give exactly one table name".into(),
+                ))
+            }
+        };
+        let query = format!(
+            "SELECT * FROM {} LIMIT {}",
+            self.database.dialect().quote_identifier(&table),
+            self.rows
+        );
+        self.database
+            .query(&query)
+            .await
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))
+    }
+}
+
+/// `sql_db_query`: run a query that passes the guardrails
+pub struct QuerySQLDatabaseTool {
+    database: Arc<SQLDatabase>,
+    guardrails: SqlGuardrails,
+}
+
+impl QuerySQLDatabaseTool {
+    pub fn new(database: Arc<SQLDatabase>) -> Self {
+        Self {
+            database,
+            guardrails: SqlGuardrails::default(),
+        }
+    }
+
+    pub fn with_guardrails(mut self, guardrails: SqlGuardrails) -> Self {
+        self.guardrails = guardrails;
+        self
+    }
+}
+
+#[async_trait]
+impl Tool for QuerySQLDatabaseTool {
+    fn name(&self) -> String {
+        "sql_db_query".to_string()
+    }
+
+    fn description(&self) -> String {
+        format!(
+            "Runs a single {} query and returns the result as tab separated rows, at most {}. \
+             If the query fails, rewrite it and try again. Check table and column names with \
+             sql_db_schema first. Allowed statements: {}.",
+            self.database.dialect().to_string(),
+            self.guardrails.max_rows,
+            self.guardrails.allowed_statements.join(", ")
+        )
+    }
+
+    fn parameters(&self) -> Value {
+        input_parameters("A detailed and correct SQL query")
+    }
+
+    async fn run(&self, input: Value) -> Result<String, ToolError> {
+        let query = self
+            .guardrails
+            .prepare(input_str(&input)?, self.database.dialect())
+            .map_err(|e| ToolError::PermissionError(e.to_string()))?;
+        let mut result = self
+            .database
+            .query_rows(&query)
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("{}. Query: {}", e, query)))?;
+        if result.rows.is_empty() {
+            return Ok("The query returned no rows".to_string());
+        }
+        let total = result.rows.len();
+        result.rows.truncate(self.guardrails.max_rows);
+        let mut output = result.to_string();
+        if total > self.guardrails.max_rows {
+            output.push_str(&format!(
+                "({} of {} rows shown)\n",
+                self.guardrails.max_rows, total
+            ));
+        }
+        Ok(output)
+    }
+
+    fn is_parallel_safe(&self) -> bool {
+        self.guardrails.read_only
+    }
+}
+
+const QUERY_CHECKER_PROMPT: &str = "Double check the {dialect} query below for common mistakes, including:
+- Using NOT IN with NULL values
+- Using UNION when UNION ALL should have been used
+- Using BETWEEN for exclusive ranges
+- Data type mismatch in predicates
+- Properly quoting identifiers
+- Using the correct number of arguments for functions
+- Casting to the correct data type
+- Using the proper columns for joins
+
+If there are any of the above mistakes, rewrite the query. If there are no mistakes, just reproduce the original query.
+Output the final SQL query only, without explanation or code fences.";
+
+/// `sql_db_query_checker`: ask an LLM to review a query before running it
+pub struct QuerySQLCheckerTool {
+    database: Arc<SQLDatabase>,
+    llm: Arc<dyn LLM>,
+}
+
+impl QuerySQLCheckerTool {
+    pub fn new(database: Arc<SQLDatabase>, llm: Arc<dyn LLM>) -> Self {
+        Self { database, llm }
+    }
+}
+
+#[async_trait]
+impl Tool for QuerySQLCheckerTool {
+    fn name(&self) -> String {
+        "sql_db_query_checker".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Checks a query for common mistakes and returns the corrected query. Always use this \
+         before running a query with sql_db_query."
+            .to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        input_parameters("The SQL query to check")
+    }
+
+    async fn run(&self, input: Value) -> Result<String, ToolError> {
+        let query = input_str(&input)?;
+        let system =
+            QUERY_CHECKER_PROMPT.replace("{dialect}", &self.database.dialect().to_string());
+        let messages = [
+            Message::new_system_message(&system),
+            Message::new_human_message(query),
+        ];
+        let result = telemetry::generate(self.llm.as_ref(), &messages)
+            .await
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+
+        let checked = result.generation.trim();
+        let checked = checked
+            .strip_prefix("```sql")
+            .or_else(|| checked.strip_prefix("```"))
+            .and_then(|rest| rest.strip_suffix("```"))
+            .unwrap_or(checked);
+        Ok(checked.trim().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guardrails_prepare() {
+        let guardrails = SqlGuardrails::new().with_max_rows(10);
+        assert_eq!(
+            guardrails
+                .prepare("SELECT name FROM users;", Dialect::SQLite)
+                .unwrap(),
+            "SELECT name FROM users\nLIMIT 10"
+        );
+        assert_eq!(
+            guardrails
+                .prepare("SELECT name FROM users -- all of them", Dialect::SQLite)
+                .unwrap(),
+            "SELECT name FROM users -- all of them\nLIMIT 10"
+        );
+        assert_eq!(
+            guardrails
+                .prepare(
+                    "SELECT * FROM users WHERE id IN (SELECT id FROM vips LIMIT 5)",
+                    Dialect::SQLite
+                )
+                .unwrap(),
+            "SELECT * FROM users WHERE id IN (SELECT id FROM vips LIMIT 5)\nLIMIT 10"
+        );
+        assert_eq!(
+            guardrails
+                .prepare("select name from users limit 3", Dialect::SQLite)
+                .unwrap(),
+            "select name from users limit 3"
+        );
+        // A MySQL `#` comment hides the LIMIT
+        assert_eq!(
+            guardrails
+                .prepare("SELECT name FROM users # LIMIT 1", Dialect::MySQL)
+                .unwrap(),
+            "SELECT name FROM users # LIMIT 1\nLIMIT 10"
+        );
+        assert!(guardrails
+            .prepare("SELECT 1 --1 INTO OUTFILE '/tmp/x'", Dialect::MySQL)
+            .is_err());
+        assert!(matches!(
+            guardrails.prepare("PRAGMA table_info(users)", Dialect::SQLite),
+            Err(SQLError::StatementNotAllowed(_))
+        ));
+        assert!(guardrails
+            .prepare("WITH t AS (SELECT 1) DELETE FROM users", Dialect::SQLite)
+            .is_err());
+
+        let writable = SqlGuardrails::new()
+            .with_read_only(false)
+            .with_allowed_statements(["select", "insert"]);
+        assert_eq!(
+            writable
+                .prepare("INSERT INTO users VALUES (1)", Dialect::SQLite)
+                .unwrap(),
+            "INSERT INTO users VALUES (1)"
+        );
+        assert!(writable
+            .prepare("DROP TABLE users", Dialect::SQLite)
+            .is_err());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_toolkit_tools() {
+        use crate::tools::{sqlite::SQLiteEngine, SQLDatabaseBuilder};
+
+        let engine = SQLiteEngine::new("sqlite::memory:").await.unwrap();
+        let create = "CREATE TABLE orders (id INTEGER PRIMARY KEY, total REAL)";
+        crate::tools::Engine::query(&engine, create).await.unwrap();
+        for id in 1..=5 {
+            let insert = format!("INSERT INTO orders VALUES ({}, {}.5)", id, id);
+            crate::tools::Engine::query(&engine, &insert).await.unwrap();
+        }
+        let database = Arc::new(SQLDatabaseBuilder::new(engine).build().await.unwrap());
+        let tools = SQLDatabaseToolkit::new(database)
+            .with_guardrails(SqlGuardrails::new().with_max_rows(2))
+            .tools();
+        let tool = |name: &str| Arc::clone(tools.iter().find(|tool| tool.name() == name).unwrap());
+
+        let tables = tool("sql_db_list_tables").run(json!("")).await.unwrap();
+        assert_eq!(tables, "orders");
+        let schema = tool("sql_db_schema").run(json!("orders")).await.unwrap();
+        assert!(schema.contains("CREATE TABLE orders"));
+        let missing = tool("sql_db_schema").run(json!("order")).await;
+        assert!(missing
+            .unwrap_err()
+            .to_string()
+            .contains("Available tables: orders"));
+        let sample = tool("sql_db_sample_rows")
+            .run(json!("orders"))
+            .await
+            .unwrap();
+        assert_eq!(sample.lines().count(), 4);
+
+        let query = tool("sql_db_query");
+        let rows = query
+            .run(json!("SELECT id, total FROM orders ORDER BY id"))
+            .await
+            .unwrap();
+        assert_eq!(rows, "id\ttotal\n1\t1.5\n2\t2.5\n");
+        let rows = query
+            .run(json!("SELECT id FROM orders LIMIT 4"))
+            .await
+            .unwrap();
+        assert!(rows.ends_with("(2 of 4 rows shown)\n"));
+        assert!(query.run(json!("DELETE FROM orders")).await.is_err());
+    }
+}
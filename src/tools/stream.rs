@@ -1,3 +1,74 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// An update a tool reports while it runs.
+///
+/// Updates reach the [`StreamWriter`] of the tool's runtime, agent event streams
+/// (as `tool_progress` events) and LangGraph `custom` stream mode.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ToolUpdate {
+    /// A free-form status message
+    Message { message: String },
+    /// Progress towards completion, e.g. 3 of 10 pages crawled
+    Progress {
+        progress: f64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        total: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
+    },
+    /// A piece of the output, available before the tool finishes
+    Partial { content: String },
+}
+
+impl ToolUpdate {
+    pub fn message(message: impl Into<String>) -> Self {
+        Self::Message {
+            message: message.into(),
+        }
+    }
+
+    pub fn progress(progress: f64, total: Option<f64>, message: Option<String>) -> Self {
+        Self::Progress {
+            progress,
+            total,
+            message,
+        }
+    }
+
+    pub fn partial(content: impl Into<String>) -> Self {
+        Self::Partial {
+            content: content.into(),
+        }
+    }
+}
+
+/// Human-readable update, e.g. `Crawling (3/10)`
+impl fmt::Display for ToolUpdate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Message { message } => f.write_str(message),
+            Self::Progress {
+                progress,
+                total,
+                message,
+            } => {
+                let amount = match total {
+                    Some(total) => format!("{}/{}", progress, total),
+                    None => progress.to_string(),
+                };
+                match message {
+                    Some(message) => write!(f, "{} ({})", message, amount),
+                    None => write!(f, "Progress {}", amount),
+                }
+            }
+            Self::Partial { content } => f.write_str(content),
+        }
+    }
+}
+
 /// Stream writer for real-time updates from tools.
 ///
 /// Tools can use the stream writer to provide progress updates
@@ -5,6 +76,11 @@
 pub trait StreamWriter: Send + Sync {
     /// Write a message to the stream
     fn write(&self, message: &str);
+
+    /// Write a structured update; by default its text is written with [`write`](Self::write)
+    fn write_update(&self, update: &ToolUpdate) {
+        self.write(&update.to_string());
+    }
 }
 
 /// Simple stream writer that collects messages.
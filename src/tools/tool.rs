@@ -1,13 +1,17 @@
 use std::error::Error;
+use std::pin::Pin;
 use std::string::String;
 
 use async_trait::async_trait;
+use futures::{stream, Stream, StreamExt};
 use serde_json::{json, Value};
 
 use crate::agent::Command;
 use crate::error::ToolError;
+use crate::schemas::ContentPart;
 
 use super::runtime::ToolRuntime;
+use super::stream::ToolUpdate;
 
 #[async_trait]
 pub trait Tool: Send + Sync {
@@ -96,6 +100,32 @@ pub trait Tool: Send + Sync {
         Ok(ToolResult::Text(result))
     }
 
+    /// Executes the tool as a stream of updates ending with its output.
+    ///
+    /// The agent executor consumes this stream: updates are forwarded to the runtime's
+    /// stream writer, agent event streams and LangGraph `custom` stream mode, and the
+    /// final [`ToolStreamItem::Output`] becomes the observation. The default yields the
+    /// result of `run_with_runtime`.
+    ///
+    /// Long-running tools can override it to report progress and partial output:
+    /// ```rust,ignore
+    /// fn run_stream<'a>(&'a self, input: Value, _runtime: &'a ToolRuntime) -> ToolStream<'a> {
+    ///     Box::pin(async_stream::stream! {
+    ///         for page in 1..=10 {
+    ///             crawl(page).await;
+    ///             yield ToolStreamItem::Update(ToolUpdate::progress(page as f64, Some(10.0), None));
+    ///         }
+    ///         yield ToolStreamItem::Output(Ok(ToolResult::Text("done".into())));
+    ///     })
+    /// }
+    /// ```
+    fn run_stream<'a>(&'a self, input: Value, runtime: &'a ToolRuntime) -> ToolStream<'a> {
+        Box::pin(stream::once(async move {
+            let result = self.run_with_runtime(input, runtime).await;
+            ToolStreamItem::Output(result.map_err(|e| e.to_string().into()))
+        }))
+    }
+
     /// Check if this tool requires runtime access.
     ///
     /// Returns `true` if the tool needs access to runtime information
@@ -104,6 +134,16 @@ pub trait Tool: Send + Sync {
         false
     }
 
+    /// Check if this tool can run concurrently with other tool calls.
+    ///
+    /// When a model requests several tools in one turn, the agent executor runs
+    /// parallel-safe tools concurrently. Tools with side effects that depend on
+    /// ordering (writing files, running shell commands, updating agent state)
+    /// should return `false`; they then run alone, in call order. Default is `true`.
+    fn is_parallel_safe(&self) -> bool {
+        true
+    }
+
     /// Parses the input string, which could be a JSON value or a raw string, depending on the LLM model.
     ///
     /// Implement this function to extract the parameters needed for your tool. If a simple
@@ -123,8 +163,40 @@ pub trait Tool: Send + Sync {
     }
 }
 
-/// Result type for tool execution that can return either text or a command.
+/// An item of a [`ToolStream`]
+pub enum ToolStreamItem {
+    /// Progress or partial output reported while the tool runs
+    Update(ToolUpdate),
+    /// The final result; nothing after it is read
+    Output(Result<ToolResult, Box<dyn Error + Send + Sync>>),
+}
+
+/// Stream returned by [`Tool::run_stream`]
+pub type ToolStream<'a> = Pin<Box<dyn Stream<Item = ToolStreamItem> + Send + 'a>>;
+
+/// Drive a [`ToolStream`], reporting its updates through `runtime`, and return its output.
+///
+/// Streaming tools can implement `run_with_runtime` with it:
+/// `collect_tool_stream(self.run_stream(input, runtime), runtime).await.map_err(|e| e as _)`.
+pub async fn collect_tool_stream(
+    mut stream: ToolStream<'_>,
+    runtime: &ToolRuntime,
+) -> Result<ToolResult, Box<dyn Error + Send + Sync>> {
+    while let Some(item) = stream.next().await {
+        match item {
+            ToolStreamItem::Update(update) => runtime.update(update),
+            ToolStreamItem::Output(output) => return output,
+        }
+    }
+    Err("Tool stream ended without an output".into())
+}
+
+/// Result type for tool execution that can return text, multimodal content or a command.
+///
+/// Build results with [ToolResult::text], [ToolResult::with_command] or [ToolResult::content];
+/// more variants may be added.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum ToolResult {
     /// Simple text result (backward compatible)
     Text(String),
@@ -133,6 +205,11 @@ pub enum ToolResult {
         text: String,
         command: Option<Command>,
     },
+    /// Text plus images and files shown to the model, e.g. a browser screenshot
+    WithContent {
+        text: String,
+        attachments: Vec<ContentPart>,
+    },
 }
 
 impl ToolResult {
@@ -147,14 +224,64 @@ impl ToolResult {
         }
     }
 
+    /// Multimodal result; text parts are joined into the text, images and files attached.
+    ///
+    /// ```rust,ignore
+    /// ToolResult::content(vec![
+    ///     ContentPart::text("Rendered the chart"),
+    ///     ContentPart::image(ImageContent::from_base64("image/png", png_base64)),
+    /// ])
+    /// ```
+    pub fn content(parts: Vec<ContentPart>) -> Self {
+        let mut texts = Vec::new();
+        let mut attachments = Vec::new();
+        for part in parts {
+            match part {
+                ContentPart::Text { text } => texts.push(text),
+                other => attachments.push(other),
+            }
+        }
+        Self::WithContent {
+            text: texts.join("\n"),
+            attachments,
+        }
+    }
+
     pub fn into_string(self) -> String {
+        self.into_parts().0
+    }
+
+    pub fn as_str(&self) -> &str {
         match self {
             Self::Text(s) => s,
-            Self::WithCommand { text, .. } => text,
+            Self::WithCommand { text, .. } | Self::WithContent { text, .. } => text,
+        }
+    }
+
+    /// Images and files to show the model alongside the text
+    pub fn attachments(&self) -> &[ContentPart] {
+        match self {
+            Self::WithContent { attachments, .. } => attachments,
+            _ => &[],
+        }
+    }
+
+    /// Split into the text and the command, if any.
+    pub fn into_parts(self) -> (String, Option<Command>) {
+        match self {
+            Self::Text(s) => (s, None),
+            Self::WithCommand { text, command } => (text, command),
+            Self::WithContent { text, .. } => (text, None),
         }
     }
 }
 
+impl AsRef<str> for ToolResult {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
 impl From<String> for ToolResult {
     fn from(s: String) -> Self {
         Self::Text(s)
@@ -190,11 +190,13 @@ mod tests {
                 page_content: "text1".to_string(),
                 metadata: Default::default(),
                 score: Some(0.8),
+                id: None,
             },
             Document {
                 page_content: "text2".to_string(),
                 metadata: Default::default(),
                 score: Some(0.3),
+                id: None,
             },
         ];
         let filtered = VectorStoreHelpers::apply_score_threshold(docs, Some(0.5));
@@ -209,11 +211,13 @@ mod tests {
                 page_content: "text1".to_string(),
                 metadata: Default::default(),
                 score: Some(0.3),
+                id: None,
             },
             Document {
                 page_content: "text2".to_string(),
                 metadata: Default::default(),
                 score: Some(0.8),
+                id: None,
             },
         ];
         let sorted = VectorStoreHelpers::sort_by_score(docs);
@@ -2,13 +2,15 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use chromadb::collection::{ChromaCollection, CollectionEntries, QueryOptions, QueryResult};
+use chromadb::collection::{
+    ChromaCollection, CollectionEntries, GetOptions, GetResult, QueryOptions, QueryResult,
+};
 use serde_json::{Map, Value};
 
 use crate::{
     embedding::embedder_trait::Embedder,
     schemas::Document,
-    vectorstore::{VecStoreOptions, VectorStore, VectorStoreError},
+    vectorstore::{OperatorDialect, VecStoreOptions, VectorStore, VectorStoreError},
 };
 
 pub struct Store {
@@ -18,6 +20,100 @@ pub struct Store {
 
 pub type ChromaOptions = VecStoreOptions<Value>;
 
+/// Chroma metadata is flat and its `where` filters have no `$not` or `$exists`
+///
+/// Unlike [`MetadataFilter`](crate::vectorstore::MetadataFilter), Chroma's `$ne` and `$nin`
+/// skip documents missing the field, so `Ne` and negated `Eq` or `In` only match documents
+/// that have it. This is deliberate: there is no `$exists` to make up for it.
+const FILTER_DIALECT: OperatorDialect = OperatorDialect {
+    field_prefix: "",
+    nested_paths: false,
+    exists: false,
+    nor: false,
+    string_ranges: false,
+};
+
+impl Store {
+    /// Query the collection with an already embedded query, also returning the stored
+    /// embeddings when asked
+    async fn search(
+        &self,
+        query_vector: &[f64],
+        limit: usize,
+        opt: &ChromaOptions,
+        with_embeddings: bool,
+    ) -> Result<Vec<(Document, Vec<f64>)>, VectorStoreError> {
+        let qv_f32: Vec<f32> = query_vector.iter().map(|&x| x as f32).collect();
+        let metadata_filter = match &opt.metadata_filter {
+            Some(metadata_filter) => Some(metadata_filter.to_operator_filter(&FILTER_DIALECT)?)
+                .filter(|filter| filter.as_object().is_none_or(|m| !m.is_empty())),
+            None => None,
+        };
+        let where_metadata = match (opt.filters.clone(), metadata_filter) {
+            (Some(filters), Some(metadata_filter)) => {
+                Some(serde_json::json!({ "$and": [filters, metadata_filter] }))
+            }
+            (filters, metadata_filter) => filters.or(metadata_filter),
+        };
+        let mut include = vec!["documents", "metadatas", "distances"];
+        if with_embeddings {
+            include.push("embeddings");
+        }
+        let query_opts = QueryOptions {
+            query_embeddings: Some(vec![qv_f32]),
+            query_texts: None,
+            n_results: Some(limit),
+            where_metadata,
+            where_document: None,
+            include: Some(include),
+        };
+        let result: QueryResult = self
+            .collection
+            .query(query_opts, None)
+            .await
+            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+        let ids = result.ids.into_iter().next().unwrap_or_default();
+        let documents = result.documents.and_then(|d| d.into_iter().next());
+        let metadatas = result.metadatas.and_then(|m| m.into_iter().next());
+        let distances = result.distances.and_then(|d| d.into_iter().next());
+        let mut embeddings = result
+            .embeddings
+            .and_then(|e| e.into_iter().next())
+            .unwrap_or_default()
+            .into_iter();
+        let docs = match (documents, metadatas, distances) {
+            (Some(docs), meta, dist) => {
+                let meta = meta.unwrap_or_else(|| (0..docs.len()).map(|_| None).collect());
+                let dist = dist.unwrap_or_else(|| (0..docs.len()).map(|_| 0.0_f32).collect());
+                docs.into_iter()
+                    .zip(meta.into_iter().zip(dist.into_iter()))
+                    .enumerate()
+                    .map(|(i, (page_content, (metadata, d)))| {
+                        let metadata: HashMap<String, Value> = metadata
+                            .map(|m| m.into_iter().collect())
+                            .unwrap_or_default();
+                        // Chroma returns distance (lower = more similar). Use 1 - normalized as score.
+                        let score = 1.0 - (d as f64).min(1.0).max(0.0);
+                        let embedding = embeddings
+                            .next()
+                            .map(|e| e.into_iter().map(f64::from).collect())
+                            .unwrap_or_default();
+                        let document = Document {
+                            page_content,
+                            metadata,
+                            score,
+                            id: ids.get(i).cloned(),
+                        };
+                        (document, embedding)
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+        Ok(docs)
+    }
+}
+
 #[async_trait]
 impl VectorStore for Store {
     type Options = ChromaOptions;
@@ -37,7 +133,10 @@ impl VectorStore for Store {
         }
         let ids: Vec<String> = docs
             .iter()
-            .map(|_| uuid::Uuid::new_v4().to_string())
+            .map(|d| {
+                d.id.clone()
+                    .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+            })
             .collect();
         let embeddings_f32: Vec<Vec<f32>> = vectors
             .into_iter()
@@ -71,48 +170,9 @@ impl VectorStore for Store {
         limit: usize,
         opt: &ChromaOptions,
     ) -> Result<Vec<Document>, VectorStoreError> {
-        let embedder = opt.embedder.as_ref().unwrap_or(&self.embedder);
-        let qv = embedder.embed_query(query).await?;
-        let qv_f32: Vec<f32> = qv.into_iter().map(|x| x as f32).collect();
-        let query_opts = QueryOptions {
-            query_embeddings: Some(vec![qv_f32]),
-            query_texts: None,
-            n_results: Some(limit),
-            where_metadata: opt.filters.clone(),
-            where_document: None,
-            include: Some(vec!["documents", "metadatas", "distances"]),
-        };
-        let result: QueryResult = self
-            .collection
-            .query(query_opts, None)
-            .await
-            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
-        let documents = result.documents.and_then(|d| d.into_iter().next());
-        let metadatas = result.metadatas.and_then(|m| m.into_iter().next());
-        let distances = result.distances.and_then(|d| d.into_iter().next());
-        let docs = match (documents, metadatas, distances) {
-            (Some(docs), meta, dist) => {
-                let meta = meta.unwrap_or_else(|| (0..docs.len()).map(|_| None).collect());
-                let dist = dist.unwrap_or_else(|| (0..docs.len()).map(|_| 0.0_f32).collect());
-                docs.into_iter()
-                    .zip(meta.into_iter().zip(dist.into_iter()))
-                    .map(|(page_content, (metadata, d))| {
-                        let metadata: HashMap<String, Value> = metadata
-                            .map(|m| m.into_iter().collect())
-                            .unwrap_or_default();
-                        // Chroma returns distance (lower = more similar). Use 1 - normalized as score.
-                        let score = 1.0 - (d as f64).min(1.0).max(0.0);
-                        Document {
-                            page_content,
-                            metadata,
-                            score,
-                        }
-                    })
-                    .collect()
-            }
-            _ => Vec::new(),
-        };
-        Ok(docs)
+        let query_vector = self.embed_query(query, opt).await?;
+        let results = self.search(&query_vector, limit, opt, false).await?;
+        Ok(results.into_iter().map(|(doc, _)| doc).collect())
     }
 
     async fn delete(&self, ids: &[String], _opt: &ChromaOptions) -> Result<(), VectorStoreError> {
@@ -126,4 +186,155 @@ impl VectorStore for Store {
             .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
         Ok(())
     }
+
+    async fn similarity_search_by_vector(
+        &self,
+        embedding: &[f64],
+        limit: usize,
+        opt: &ChromaOptions,
+    ) -> Result<Vec<Document>, VectorStoreError> {
+        let results = self.search(embedding, limit, opt, false).await?;
+        Ok(results.into_iter().map(|(doc, _)| doc).collect())
+    }
+
+    async fn get_by_ids(
+        &self,
+        ids: &[String],
+        _opt: &ChromaOptions,
+    ) -> Result<Vec<Document>, VectorStoreError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let get_opts = GetOptions {
+            ids: ids.to_vec(),
+            where_metadata: None,
+            limit: None,
+            offset: None,
+            where_document: None,
+            include: Some(vec!["documents".into(), "metadatas".into()]),
+        };
+        let result: GetResult = self
+            .collection
+            .get(get_opts)
+            .await
+            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+        let documents = result.documents.unwrap_or_default();
+        let metadatas = result.metadatas.unwrap_or_default();
+        let docs = result
+            .ids
+            .into_iter()
+            .enumerate()
+            .map(|(i, id)| {
+                let page_content = documents.get(i).cloned().flatten().unwrap_or_default();
+                let metadata: HashMap<String, Value> = metadatas
+                    .get(i)
+                    .cloned()
+                    .flatten()
+                    .map(|m| m.into_iter().collect())
+                    .unwrap_or_default();
+                Document::new(page_content)
+                    .with_metadata(metadata)
+                    .with_id(id)
+            })
+            .collect();
+        Ok(docs)
+    }
+
+    async fn embed_query(
+        &self,
+        query: &str,
+        opt: &ChromaOptions,
+    ) -> Result<Vec<f64>, VectorStoreError> {
+        let embedder = opt.embedder.as_ref().unwrap_or(&self.embedder);
+        Ok(embedder.embed_query(query).await?)
+    }
+
+    async fn similarity_search_by_vector_with_embeddings(
+        &self,
+        embedding: &[f64],
+        limit: usize,
+        opt: &ChromaOptions,
+    ) -> Result<Vec<(Document, Vec<f64>)>, VectorStoreError> {
+        self.search(embedding, limit, opt, true).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chromadb::client::{ChromaClient, ChromaClientOptions};
+    use testcontainers::{
+        core::IntoContainerPort, runners::AsyncRunner, ContainerAsync, GenericImage,
+    };
+
+    use crate::vectorstore::{chroma::StoreBuilder, conformance, MetadataFilter};
+
+    use super::*;
+
+    /// Start Chroma and connect once the server answers its heartbeat
+    async fn store() -> (ContainerAsync<GenericImage>, Store) {
+        let container = GenericImage::new("chromadb/chroma", "latest")
+            .with_exposed_port(8000.tcp())
+            .start()
+            .await
+            .unwrap();
+        let port = container.get_host_port_ipv4(8000).await.unwrap();
+        let options = || ChromaClientOptions {
+            url: Some(format!("http://localhost:{}", port)),
+            ..Default::default()
+        };
+        let mut client = None;
+        for _ in 0..60 {
+            if let Ok(connected) = ChromaClient::new(options()).await {
+                if connected.heartbeat().await.is_ok() {
+                    client = Some(connected);
+                    break;
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+        let store = StoreBuilder::new()
+            .client(client.expect("Chroma did not start"))
+            .embedder(conformance::ConstantEmbedder)
+            .collection_name("conformance")
+            .build()
+            .await
+            .unwrap();
+        (container, store)
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Docker"]
+    async fn test_metadata_filter_conformance() {
+        let (_container, store) = store().await;
+        conformance::run_flat(&store, |filter: Option<MetadataFilter>| {
+            let options = ChromaOptions::default();
+            match filter {
+                Some(filter) => options.with_metadata_filter(filter),
+                None => options,
+            }
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Docker"]
+    async fn test_search_returns_stored_embeddings() {
+        let (_container, store) = store().await;
+        let options = ChromaOptions::default();
+        store
+            .add_documents(&conformance::flat_documents(), &options)
+            .await
+            .unwrap();
+
+        let found = store
+            .similarity_search_by_vector_with_embeddings(&[1.0, 0.5, 0.25], 2, &options)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 2);
+        assert!(found
+            .iter()
+            .all(|(_, embedding)| embedding == &[1.0, 0.5, 0.25]));
+    }
 }
@@ -6,6 +6,9 @@ pub enum VectorStoreError {
     #[error("This vector store does not support delete")]
     DeleteNotSupported,
 
+    #[error("This vector store does not support {0}")]
+    OperationNotSupported(String),
+
     #[error("Connection failed: {0}")]
     ConnectionError(String),
 
@@ -59,6 +62,9 @@ pub enum VectorStoreError {
     #[error("Missing required parameter: {0}")]
     MissingParameter(String),
 
+    #[error("Unsupported filter: {0}")]
+    UnsupportedFilter(String),
+
     // ============ 内部错误 ============
     #[error("Internal error: {0}")]
     InternalError(String),
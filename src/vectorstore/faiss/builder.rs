@@ -60,6 +60,7 @@ impl StoreBuilder {
         Ok(Store {
             hnsw: std::sync::RwLock::new(hnsw),
             docstore: std::sync::RwLock::new(Vec::new()),
+            embeddings: std::sync::RwLock::new(Vec::new()),
             ids: std::sync::RwLock::new(Vec::new()),
             embedder,
             dim,
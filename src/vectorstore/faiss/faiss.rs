@@ -14,13 +14,85 @@ use crate::{
 pub struct Store {
     pub(crate) hnsw: RwLock<Hnsw<'static, f32, DistL2>>,
     pub(crate) docstore: RwLock<Vec<Document>>,
-    pub(crate) ids: RwLock<Vec<String>>,
+    pub(crate) embeddings: RwLock<Vec<Vec<f64>>>,
+    /// Id of each indexed point, `None` once it was deleted or a later add with the same id
+    /// replaced it
+    pub(crate) ids: RwLock<Vec<Option<String>>>,
     pub(crate) embedder: Arc<dyn Embedder>,
     pub(crate) dim: usize,
 }
 
 pub type FaissOptions = VecStoreOptions<Value>;
 
+impl Store {
+    /// Search the index, returning the stored embeddings with the documents
+    fn search(
+        &self,
+        query_vector: &[f64],
+        limit: usize,
+        opt: &FaissOptions,
+    ) -> Result<Vec<(Document, Vec<f64>)>, VectorStoreError> {
+        let qv_f32: Vec<f32> = query_vector.iter().map(|x| *x as f32).collect();
+        let hnsw = self
+            .hnsw
+            .read()
+            .map_err(|e| VectorStoreError::InternalError(e.to_string()))?;
+        let docstore = self
+            .docstore
+            .read()
+            .map_err(|e| VectorStoreError::InternalError(e.to_string()))?;
+        let embeddings = self
+            .embeddings
+            .read()
+            .map_err(|e| VectorStoreError::InternalError(e.to_string()))?;
+        let ids = self
+            .ids
+            .read()
+            .map_err(|e| VectorStoreError::InternalError(e.to_string()))?;
+        // Metadata filters run on the results, so search every document when one is set.
+        // Otherwise fetch enough to make up for replaced and deleted points.
+        let knbn = match opt.metadata_filter {
+            Some(_) => docstore.len().max(limit),
+            None => limit + ids.iter().filter(|id| id.is_none()).count(),
+        };
+        let ef = (knbn * 2).max(32);
+        let neighbours = hnsw.search(qv_f32.as_slice(), knbn, ef);
+        let score_threshold = opt
+            .score_threshold
+            .map(f64::from)
+            .unwrap_or(f64::NEG_INFINITY);
+        let mut result: Vec<(Document, Vec<f64>)> = neighbours
+            .into_iter()
+            .filter_map(|n| {
+                let idx = n.d_id;
+                let dist = n.distance as f64;
+                // L2: lower is more similar. Use -dist as score (higher = more similar).
+                let score = -dist;
+                if score < score_threshold {
+                    return None;
+                }
+                let id = ids.get(idx).cloned().flatten()?;
+                let mut d = docstore.get(idx).cloned()?;
+                d.score = score;
+                d.id = Some(id);
+                Some((d, embeddings.get(idx).cloned().unwrap_or_default()))
+            })
+            .filter(|(d, _)| {
+                opt.metadata_filter
+                    .as_ref()
+                    .is_none_or(|f| f.matches(&d.metadata))
+            })
+            .collect();
+        result.sort_by(|(a, _), (b, _)| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        result.truncate(limit);
+        Ok(result)
+    }
+}
+
 #[async_trait]
 impl VectorStore for Store {
     type Options = FaissOptions;
@@ -47,18 +119,31 @@ impl VectorStore for Store {
             .docstore
             .write()
             .map_err(|e| VectorStoreError::InternalError(e.to_string()))?;
+        let mut embeddings = self
+            .embeddings
+            .write()
+            .map_err(|e| VectorStoreError::InternalError(e.to_string()))?;
         let mut id_vec = self
             .ids
             .write()
             .map_err(|e| VectorStoreError::InternalError(e.to_string()))?;
         for (doc, vector) in docs.iter().zip(vectors.iter()) {
-            let id = uuid::Uuid::new_v4().to_string();
+            let id = doc
+                .id
+                .clone()
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
             ids.push(id.clone());
+            // HNSW points can't be removed, so a replaced point is only unlinked from its id
+            if let Some(replaced) = id_vec.iter_mut().find(|i| i.as_ref() == Some(&id)) {
+                *replaced = None;
+            }
             let idx = docstore.len();
             let mut d = doc.clone();
             d.score = 0.0;
+            d.id = Some(id.clone());
             docstore.push(d);
-            id_vec.push(id);
+            embeddings.push(vector.clone());
+            id_vec.push(Some(id));
             let v_f32: Vec<f32> = vector.iter().map(|x| *x as f32).collect();
             hnsw.insert((v_f32.as_slice(), idx));
         }
@@ -71,48 +156,109 @@ impl VectorStore for Store {
         limit: usize,
         opt: &FaissOptions,
     ) -> Result<Vec<Document>, VectorStoreError> {
-        let embedder = opt.embedder.as_ref().unwrap_or(&self.embedder);
-        let qv = embedder.embed_query(query).await?;
-        let qv_f32: Vec<f32> = qv.into_iter().map(|x| x as f32).collect();
-        let ef = (limit * 2).max(32);
-        let hnsw = self
-            .hnsw
-            .read()
+        let query_vector = self.embed_query(query, opt).await?;
+        let results = self.search(&query_vector, limit, opt)?;
+        Ok(results.into_iter().map(|(doc, _)| doc).collect())
+    }
+
+    async fn delete(&self, ids: &[String], _opt: &FaissOptions) -> Result<(), VectorStoreError> {
+        let mut id_vec = self
+            .ids
+            .write()
             .map_err(|e| VectorStoreError::InternalError(e.to_string()))?;
+        // Like a replaced point, a deleted one stays in the index unlinked from its id
+        for id in id_vec.iter_mut() {
+            if id.as_ref().is_some_and(|id| ids.contains(id)) {
+                *id = None;
+            }
+        }
+        Ok(())
+    }
+
+    async fn similarity_search_by_vector(
+        &self,
+        embedding: &[f64],
+        limit: usize,
+        opt: &FaissOptions,
+    ) -> Result<Vec<Document>, VectorStoreError> {
+        let results = self.search(embedding, limit, opt)?;
+        Ok(results.into_iter().map(|(doc, _)| doc).collect())
+    }
+
+    async fn get_by_ids(
+        &self,
+        ids: &[String],
+        _opt: &FaissOptions,
+    ) -> Result<Vec<Document>, VectorStoreError> {
         let docstore = self
             .docstore
             .read()
             .map_err(|e| VectorStoreError::InternalError(e.to_string()))?;
-        let neighbours = hnsw.search(qv_f32.as_slice(), limit, ef);
-        let score_threshold = opt
-            .score_threshold
-            .map(f64::from)
-            .unwrap_or(f64::NEG_INFINITY);
-        let mut result: Vec<Document> = neighbours
-            .into_iter()
-            .filter_map(|n| {
-                let idx = n.d_id;
-                let dist = n.distance as f64;
-                // L2: lower is more similar. Use -dist as score (higher = more similar).
-                let score = -dist;
-                if score < score_threshold {
-                    return None;
-                }
-                docstore.get(idx).cloned().map(|mut d| {
-                    d.score = score;
-                    d
-                })
-            })
-            .collect();
-        result.sort_by(|a, b| {
-            b.score
-                .partial_cmp(&a.score)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-        Ok(result)
+        let id_vec = self
+            .ids
+            .read()
+            .map_err(|e| VectorStoreError::InternalError(e.to_string()))?;
+        Ok(ids
+            .iter()
+            .filter_map(|id| id_vec.iter().position(|i| i.as_ref() == Some(id)))
+            .filter_map(|idx| docstore.get(idx).cloned())
+            .collect())
+    }
+
+    async fn embed_query(
+        &self,
+        query: &str,
+        opt: &FaissOptions,
+    ) -> Result<Vec<f64>, VectorStoreError> {
+        let embedder = opt.embedder.as_ref().unwrap_or(&self.embedder);
+        Ok(embedder.embed_query(query).await?)
+    }
+
+    async fn similarity_search_by_vector_with_embeddings(
+        &self,
+        embedding: &[f64],
+        limit: usize,
+        opt: &FaissOptions,
+    ) -> Result<Vec<(Document, Vec<f64>)>, VectorStoreError> {
+        self.search(embedding, limit, opt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vectorstore::{conformance, faiss::StoreBuilder, MetadataFilter};
+
+    use super::*;
+
+    async fn store() -> Store {
+        StoreBuilder::new()
+            .embedder(conformance::ConstantEmbedder)
+            .dim(3)
+            .build()
+            .await
+            .unwrap()
+    }
+
+    fn options(filter: Option<MetadataFilter>) -> FaissOptions {
+        let options = FaissOptions::default();
+        match filter {
+            Some(filter) => options.with_metadata_filter(filter),
+            None => options,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metadata_filter_conformance() {
+        conformance::run(&store().await, options).await;
+    }
+
+    #[tokio::test]
+    async fn test_filter_beyond_top_k() {
+        conformance::run_filter_beyond_top_k(&store().await, options).await;
     }
 
-    async fn delete(&self, _ids: &[String], _opt: &FaissOptions) -> Result<(), VectorStoreError> {
-        Err(VectorStoreError::DeleteNotSupported)
+    #[tokio::test]
+    async fn test_upsert_conformance() {
+        conformance::run_upsert(&store().await, FaissOptions::default).await;
     }
 }
@@ -0,0 +1,1122 @@
+//! Backend-agnostic metadata filters
+//!
+//! A [`MetadataFilter`] is set on [`VecStoreOptions`](super::VecStoreOptions) with
+//! `with_metadata_filter` and every store translates it to its native query language, so
+//! the same filter works whichever store is behind a retriever.
+//!
+//! ```rust,ignore
+//! let filter = MetadataFilter::eq("genre", "scifi")
+//!     .and(MetadataFilter::gte("year", 2000))
+//!     .and(!MetadataFilter::exists("author.retired"));
+//! let options = VecStoreOptions::new().with_metadata_filter(filter);
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+use serde_json::{json, Map, Value};
+
+use super::VectorStoreError;
+
+/// Path to a metadata value, one segment per level of nesting
+///
+/// Built from a dotted string: `"author.name"` reads `metadata["author"]["name"]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MetadataPath(Vec<String>);
+
+impl MetadataPath {
+    pub fn new(segments: Vec<String>) -> Self {
+        Self(segments)
+    }
+
+    pub fn segments(&self) -> &[String] {
+        &self.0
+    }
+
+    /// Whether the path reaches into a nested object
+    pub fn is_nested(&self) -> bool {
+        self.0.len() > 1
+    }
+
+    /// The value at this path, if any
+    pub fn get<'a>(&self, metadata: &'a HashMap<String, Value>) -> Option<&'a Value> {
+        let (first, rest) = self.0.split_first()?;
+        rest.iter()
+            .try_fold(metadata.get(first)?, |value, segment| value.get(segment))
+    }
+}
+
+impl fmt::Display for MetadataPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.join("."))
+    }
+}
+
+impl From<&str> for MetadataPath {
+    fn from(path: &str) -> Self {
+        Self(path.split('.').map(String::from).collect())
+    }
+}
+
+impl From<String> for MetadataPath {
+    fn from(path: String) -> Self {
+        Self::from(path.as_str())
+    }
+}
+
+impl From<Vec<String>> for MetadataPath {
+    fn from(segments: Vec<String>) -> Self {
+        Self(segments)
+    }
+}
+
+/// Scalar a metadata value is compared with
+///
+/// Integers and floats compare numerically, so `Int(7)` equals a stored `7.0`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+impl FilterValue {
+    pub fn to_json(&self) -> Value {
+        match self {
+            FilterValue::Bool(b) => Value::Bool(*b),
+            FilterValue::Int(i) => json!(i),
+            FilterValue::Float(f) => json!(f),
+            FilterValue::String(s) => Value::String(s.clone()),
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            FilterValue::Int(i) => Some(*i as f64),
+            FilterValue::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+
+    pub fn is_number(&self) -> bool {
+        matches!(self, FilterValue::Int(_) | FilterValue::Float(_))
+    }
+
+    /// How a stored value orders against this one, `None` when their types differ
+    fn compare(&self, value: &Value) -> Option<Ordering> {
+        match (self, value) {
+            (FilterValue::Bool(b), Value::Bool(v)) => Some(v.cmp(b)),
+            (FilterValue::String(s), Value::String(v)) => Some(v.as_str().cmp(s)),
+            (FilterValue::Int(i), Value::Number(v)) if v.is_i64() || v.is_u64() => {
+                v.as_i64().map(|v| v.cmp(i))
+            }
+            (FilterValue::Int(_) | FilterValue::Float(_), Value::Number(v)) => {
+                v.as_f64()?.partial_cmp(&self.as_f64()?)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for FilterValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_json())
+    }
+}
+
+impl From<bool> for FilterValue {
+    fn from(value: bool) -> Self {
+        FilterValue::Bool(value)
+    }
+}
+
+impl From<i32> for FilterValue {
+    fn from(value: i32) -> Self {
+        FilterValue::Int(value.into())
+    }
+}
+
+impl From<u32> for FilterValue {
+    fn from(value: u32) -> Self {
+        FilterValue::Int(value.into())
+    }
+}
+
+impl From<i64> for FilterValue {
+    fn from(value: i64) -> Self {
+        FilterValue::Int(value)
+    }
+}
+
+impl From<f32> for FilterValue {
+    fn from(value: f32) -> Self {
+        FilterValue::Float(value.into())
+    }
+}
+
+impl From<f64> for FilterValue {
+    fn from(value: f64) -> Self {
+        FilterValue::Float(value)
+    }
+}
+
+impl From<&str> for FilterValue {
+    fn from(value: &str) -> Self {
+        FilterValue::String(value.to_string())
+    }
+}
+
+impl From<String> for FilterValue {
+    fn from(value: String) -> Self {
+        FilterValue::String(value)
+    }
+}
+
+/// Bounds of a [`MetadataFilter::Range`], all optional
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FilterRange {
+    pub gt: Option<FilterValue>,
+    pub gte: Option<FilterValue>,
+    pub lt: Option<FilterValue>,
+    pub lte: Option<FilterValue>,
+}
+
+impl FilterRange {
+    /// The bounds with their operator names (`gt`, `gte`, `lt`, `lte`)
+    pub fn bounds(&self) -> impl Iterator<Item = (&'static str, &FilterValue)> {
+        [
+            ("gt", &self.gt),
+            ("gte", &self.gte),
+            ("lt", &self.lt),
+            ("lte", &self.lte),
+        ]
+        .into_iter()
+        .filter_map(|(op, bound)| bound.as_ref().map(|bound| (op, bound)))
+    }
+
+    /// Whether every bound is a number
+    pub fn is_numeric(&self) -> bool {
+        self.bounds().all(|(_, bound)| bound.is_number())
+    }
+
+    fn contains(&self, value: &Value) -> bool {
+        self.bounds().all(|(op, bound)| {
+            matches!(
+                (op, bound.compare(value)),
+                ("gt", Some(Ordering::Greater))
+                    | ("gte", Some(Ordering::Greater | Ordering::Equal))
+                    | ("lt", Some(Ordering::Less))
+                    | ("lte", Some(Ordering::Less | Ordering::Equal))
+            )
+        })
+    }
+}
+
+/// Filter on document metadata, translated natively by every vector store
+///
+/// Comparisons only match values of the same type. A missing field never matches `Eq`,
+/// `In`, `Range` or `Exists`, so it matches their negation, including `Ne`. `Exists`
+/// treats a `null` value as missing.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetadataFilter {
+    Eq(MetadataPath, FilterValue),
+    Ne(MetadataPath, FilterValue),
+    In(MetadataPath, Vec<FilterValue>),
+    Range(MetadataPath, FilterRange),
+    Exists(MetadataPath),
+    And(Vec<MetadataFilter>),
+    Or(Vec<MetadataFilter>),
+    Not(Box<MetadataFilter>),
+}
+
+impl MetadataFilter {
+    pub fn eq<P: Into<MetadataPath>, V: Into<FilterValue>>(path: P, value: V) -> Self {
+        MetadataFilter::Eq(path.into(), value.into())
+    }
+
+    pub fn ne<P: Into<MetadataPath>, V: Into<FilterValue>>(path: P, value: V) -> Self {
+        MetadataFilter::Ne(path.into(), value.into())
+    }
+
+    pub fn is_in<P, V, I>(path: P, values: I) -> Self
+    where
+        P: Into<MetadataPath>,
+        V: Into<FilterValue>,
+        I: IntoIterator<Item = V>,
+    {
+        MetadataFilter::In(path.into(), values.into_iter().map(Into::into).collect())
+    }
+
+    pub fn gt<P: Into<MetadataPath>, V: Into<FilterValue>>(path: P, value: V) -> Self {
+        Self::range(
+            path,
+            FilterRange {
+                gt: Some(value.into()),
+                ..Default::default()
+            },
+        )
+    }
+
+    pub fn gte<P: Into<MetadataPath>, V: Into<FilterValue>>(path: P, value: V) -> Self {
+        Self::range(
+            path,
+            FilterRange {
+                gte: Some(value.into()),
+                ..Default::default()
+            },
+        )
+    }
+
+    pub fn lt<P: Into<MetadataPath>, V: Into<FilterValue>>(path: P, value: V) -> Self {
+        Self::range(
+            path,
+            FilterRange {
+                lt: Some(value.into()),
+                ..Default::default()
+            },
+        )
+    }
+
+    pub fn lte<P: Into<MetadataPath>, V: Into<FilterValue>>(path: P, value: V) -> Self {
+        Self::range(
+            path,
+            FilterRange {
+                lte: Some(value.into()),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Values between `low` and `high`, both inclusive
+    pub fn between<P: Into<MetadataPath>, V: Into<FilterValue>>(path: P, low: V, high: V) -> Self {
+        Self::range(
+            path,
+            FilterRange {
+                gte: Some(low.into()),
+                lte: Some(high.into()),
+                ..Default::default()
+            },
+        )
+    }
+
+    pub fn range<P: Into<MetadataPath>>(path: P, range: FilterRange) -> Self {
+        MetadataFilter::Range(path.into(), range)
+    }
+
+    pub fn exists<P: Into<MetadataPath>>(path: P) -> Self {
+        MetadataFilter::Exists(path.into())
+    }
+
+    /// Match documents matching every filter, all documents when empty
+    pub fn all<I: IntoIterator<Item = MetadataFilter>>(filters: I) -> Self {
+        MetadataFilter::And(filters.into_iter().collect())
+    }
+
+    /// Match documents matching at least one filter, none when empty
+    pub fn any<I: IntoIterator<Item = MetadataFilter>>(filters: I) -> Self {
+        MetadataFilter::Or(filters.into_iter().collect())
+    }
+
+    pub fn and(self, other: MetadataFilter) -> Self {
+        match self {
+            MetadataFilter::And(mut filters) => {
+                filters.push(other);
+                MetadataFilter::And(filters)
+            }
+            filter => MetadataFilter::And(vec![filter, other]),
+        }
+    }
+
+    pub fn or(self, other: MetadataFilter) -> Self {
+        match self {
+            MetadataFilter::Or(mut filters) => {
+                filters.push(other);
+                MetadataFilter::Or(filters)
+            }
+            filter => MetadataFilter::Or(vec![filter, other]),
+        }
+    }
+
+    /// Evaluate the filter against a document's metadata
+    ///
+    /// This is the reference semantics every store translation follows.
+    pub fn matches(&self, metadata: &HashMap<String, Value>) -> bool {
+        let value_at = |path: &MetadataPath| path.get(metadata).filter(|value| !value.is_null());
+        match self {
+            MetadataFilter::Eq(path, expected) => {
+                value_at(path).is_some_and(|value| expected.compare(value) == Some(Ordering::Equal))
+            }
+            MetadataFilter::Ne(path, expected) => {
+                !MetadataFilter::Eq(path.clone(), expected.clone()).matches(metadata)
+            }
+            MetadataFilter::In(path, values) => value_at(path).is_some_and(|value| {
+                values
+                    .iter()
+                    .any(|expected| expected.compare(value) == Some(Ordering::Equal))
+            }),
+            MetadataFilter::Range(path, range) => {
+                value_at(path).is_some_and(|value| range.contains(value))
+            }
+            MetadataFilter::Exists(path) => value_at(path).is_some(),
+            MetadataFilter::And(filters) => filters.iter().all(|f| f.matches(metadata)),
+            MetadataFilter::Or(filters) => filters.iter().any(|f| f.matches(metadata)),
+            MetadataFilter::Not(filter) => !filter.matches(metadata),
+        }
+    }
+
+    /// Translate to a MongoDB-style operator document (`$eq`, `$in`, `$and`…)
+    ///
+    /// Dialects without `$nor` get negations pushed down to the leaves; a negated range
+    /// then only matches documents that have the field.
+    #[cfg_attr(
+        not(any(feature = "mongodb", feature = "chroma", feature = "pinecone")),
+        allow(dead_code)
+    )]
+    pub(crate) fn to_operator_filter(
+        &self,
+        dialect: &OperatorDialect,
+    ) -> Result<Value, VectorStoreError> {
+        self.operator_filter(dialect, false)
+    }
+
+    fn operator_filter(
+        &self,
+        dialect: &OperatorDialect,
+        negated: bool,
+    ) -> Result<Value, VectorStoreError> {
+        let field = |path: &MetadataPath| -> Result<String, VectorStoreError> {
+            if path.is_nested() && !dialect.nested_paths {
+                return Err(VectorStoreError::UnsupportedFilter(format!(
+                    "nested metadata path `{}`",
+                    path
+                )));
+            }
+            Ok(format!("{}{}", dialect.field_prefix, path))
+        };
+        let values = |values: &[FilterValue]| -> Vec<Value> {
+            values.iter().map(FilterValue::to_json).collect()
+        };
+        let combine = |op: &str, filters: &[MetadataFilter], negated: bool| {
+            let mut translated = filters
+                .iter()
+                .map(|f| f.operator_filter(dialect, negated))
+                .collect::<Result<Vec<_>, _>>()?;
+            match translated.len() {
+                1 => Ok(translated.remove(0)),
+                0 if op == "$and" => Ok(json!({})),
+                0 => Err(VectorStoreError::UnsupportedFilter(
+                    "empty `or` filter".to_string(),
+                )),
+                _ => Ok(json!({ op: translated })),
+            }
+        };
+
+        if negated && dialect.nor {
+            return Ok(json!({ "$nor": [self.operator_filter(dialect, false)?] }));
+        }
+        match (self, negated) {
+            (MetadataFilter::Eq(path, value), false) | (MetadataFilter::Ne(path, value), true) => {
+                Ok(json!({ field(path)?: { "$eq": value.to_json() } }))
+            }
+            (MetadataFilter::Ne(path, value), false) | (MetadataFilter::Eq(path, value), true) => {
+                Ok(json!({ field(path)?: { "$ne": value.to_json() } }))
+            }
+            (MetadataFilter::In(path, list), false) => {
+                Ok(json!({ field(path)?: { "$in": values(list) } }))
+            }
+            (MetadataFilter::In(path, list), true) => {
+                Ok(json!({ field(path)?: { "$nin": values(list) } }))
+            }
+            (MetadataFilter::Range(path, range), _) => {
+                if !dialect.string_ranges && !range.is_numeric() {
+                    return Err(VectorStoreError::UnsupportedFilter(format!(
+                        "non-numeric range on `{}`",
+                        path
+                    )));
+                }
+                let field = field(path)?;
+                if negated {
+                    let complement = |op| match op {
+                        "gt" => "$lte",
+                        "gte" => "$lt",
+                        "lt" => "$gte",
+                        _ => "$gt",
+                    };
+                    let mut outside = range
+                        .bounds()
+                        .map(|(op, bound)| {
+                            json!({ field.as_str(): { complement(op): bound.to_json() } })
+                        })
+                        .collect::<Vec<_>>();
+                    return match outside.len() {
+                        1 => Ok(outside.remove(0)),
+                        _ => Ok(json!({ "$or": outside })),
+                    };
+                }
+                let bounds: Map<String, Value> = range
+                    .bounds()
+                    .map(|(op, bound)| (format!("${}", op), bound.to_json()))
+                    .collect();
+                Ok(json!({ field: bounds }))
+            }
+            (MetadataFilter::Exists(path), _) => {
+                if !dialect.exists {
+                    return Err(VectorStoreError::UnsupportedFilter(format!(
+                        "exists on `{}`",
+                        path
+                    )));
+                }
+                if negated {
+                    Ok(json!({ field(path)?: { "$exists": false } }))
+                } else if dialect.nor {
+                    Ok(json!({ field(path)?: { "$exists": true, "$ne": null } }))
+                } else {
+                    Ok(json!({ field(path)?: { "$exists": true } }))
+                }
+            }
+            (MetadataFilter::And(filters), false) => combine("$and", filters, false),
+            (MetadataFilter::Or(filters), true) => combine("$and", filters, true),
+            (MetadataFilter::Or(filters), false) => combine("$or", filters, false),
+            (MetadataFilter::And(filters), true) => combine("$or", filters, true),
+            (MetadataFilter::Not(filter), negated) => filter.operator_filter(dialect, !negated),
+        }
+    }
+
+    /// Translate to a SQLite condition over the JSON text in `column`
+    ///
+    /// The condition is never `NULL`, so negating it keeps documents missing the field.
+    #[cfg_attr(
+        not(any(feature = "sqlite-vec", feature = "sqlite-vss")),
+        allow(dead_code)
+    )]
+    pub(crate) fn to_sqlite_condition(&self, column: &str) -> Result<String, VectorStoreError> {
+        let json_path = |path: &MetadataPath| -> Result<String, VectorStoreError> {
+            let mut json_path = String::from("$");
+            for segment in path.segments() {
+                if segment.contains('"') {
+                    return Err(VectorStoreError::UnsupportedFilter(format!(
+                        "metadata key with a double quote: {}",
+                        segment
+                    )));
+                }
+                json_path.push_str(&format!(".\"{}\"", segment));
+            }
+            Ok(sql_string(&json_path))
+        };
+        let compare = |path: &MetadataPath, op: &str, value: &FilterValue| {
+            let path = json_path(path)?;
+            let (types, literal) = match value {
+                FilterValue::Bool(b) if op == "=" => {
+                    return Ok(format!("json_type({}, {}) = '{}'", column, path, b));
+                }
+                FilterValue::Bool(_) => {
+                    return Err(VectorStoreError::UnsupportedFilter(
+                        "range on a boolean".to_string(),
+                    ))
+                }
+                FilterValue::String(s) => ("'text'", sql_string(s)),
+                number => ("'integer', 'real'", sql_number(number)?),
+            };
+            Ok(format!(
+                "coalesce(json_type({column}, {path}) IN ({types}) AND json_extract({column}, {path}) {op} {literal}, FALSE)"
+            ))
+        };
+
+        match self {
+            MetadataFilter::Eq(path, value) => compare(path, "=", value),
+            MetadataFilter::Ne(path, value) => Ok(format!("NOT {}", compare(path, "=", value)?)),
+            MetadataFilter::In(path, values) => sql_any(
+                values
+                    .iter()
+                    .map(|value| compare(path, "=", value))
+                    .collect::<Result<_, _>>()?,
+            ),
+            MetadataFilter::Range(path, range) => sql_all(
+                range
+                    .bounds()
+                    .map(|(op, bound)| compare(path, sql_operator(op), bound))
+                    .collect::<Result<_, _>>()?,
+            ),
+            MetadataFilter::Exists(path) => Ok(format!(
+                "coalesce(json_type({}, {}), 'null') <> 'null'",
+                column,
+                json_path(path)?
+            )),
+            MetadataFilter::And(filters) => sql_all(
+                filters
+                    .iter()
+                    .map(|f| f.to_sqlite_condition(column))
+                    .collect::<Result<_, _>>()?,
+            ),
+            MetadataFilter::Or(filters) => sql_any(
+                filters
+                    .iter()
+                    .map(|f| f.to_sqlite_condition(column))
+                    .collect::<Result<_, _>>()?,
+            ),
+            MetadataFilter::Not(filter) => {
+                Ok(format!("NOT ({})", filter.to_sqlite_condition(column)?))
+            }
+        }
+    }
+
+    /// Translate to a PostgreSQL condition over the JSON in `column`
+    ///
+    /// The condition is never `NULL`, so negating it keeps documents missing the field.
+    #[cfg_attr(not(feature = "postgres"), allow(dead_code))]
+    pub(crate) fn to_postgres_condition(&self, column: &str) -> Result<String, VectorStoreError> {
+        let value_at = |path: &MetadataPath| {
+            let segments = path
+                .segments()
+                .iter()
+                .map(|segment| sql_string(segment))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("({}::jsonb #> ARRAY[{}]::text[])", column, segments)
+        };
+        let jsonb = |value: &FilterValue| -> Result<String, VectorStoreError> {
+            if let FilterValue::Float(_) = value {
+                sql_number(value)?;
+            }
+            Ok(format!("{}::jsonb", sql_string(&value.to_string())))
+        };
+        let compare = |path: &MetadataPath, op: &str, value: &FilterValue| {
+            let json_type = match value {
+                FilterValue::Bool(_) => "boolean",
+                FilterValue::String(_) => "string",
+                _ => "number",
+            };
+            let value_at = value_at(path);
+            Ok::<_, VectorStoreError>(format!(
+                "coalesce(jsonb_typeof({value_at}) = '{json_type}' AND {value_at} {op} {}, FALSE)",
+                jsonb(value)?
+            ))
+        };
+
+        match self {
+            MetadataFilter::Eq(path, value) => compare(path, "=", value),
+            MetadataFilter::Ne(path, value) => Ok(format!("NOT {}", compare(path, "=", value)?)),
+            MetadataFilter::In(path, values) => sql_any(
+                values
+                    .iter()
+                    .map(|value| compare(path, "=", value))
+                    .collect::<Result<_, _>>()?,
+            ),
+            MetadataFilter::Range(path, range) => sql_all(
+                range
+                    .bounds()
+                    .map(|(op, bound)| compare(path, sql_operator(op), bound))
+                    .collect::<Result<_, _>>()?,
+            ),
+            MetadataFilter::Exists(path) => Ok(format!(
+                "coalesce(jsonb_typeof({}) <> 'null', FALSE)",
+                value_at(path)
+            )),
+            MetadataFilter::And(filters) => sql_all(
+                filters
+                    .iter()
+                    .map(|f| f.to_postgres_condition(column))
+                    .collect::<Result<_, _>>()?,
+            ),
+            MetadataFilter::Or(filters) => sql_any(
+                filters
+                    .iter()
+                    .map(|f| f.to_postgres_condition(column))
+                    .collect::<Result<_, _>>()?,
+            ),
+            MetadataFilter::Not(filter) => {
+                Ok(format!("NOT ({})", filter.to_postgres_condition(column)?))
+            }
+        }
+    }
+}
+
+impl std::ops::Not for MetadataFilter {
+    type Output = MetadataFilter;
+
+    fn not(self) -> Self::Output {
+        MetadataFilter::Not(Box::new(self))
+    }
+}
+
+/// Capabilities of a MongoDB-style filter language
+#[cfg_attr(
+    not(any(feature = "mongodb", feature = "chroma", feature = "pinecone")),
+    allow(dead_code)
+)]
+pub(crate) struct OperatorDialect {
+    /// Prepended to every field, e.g. `metadata.`
+    pub field_prefix: &'static str,
+    pub nested_paths: bool,
+    pub exists: bool,
+    pub nor: bool,
+    pub string_ranges: bool,
+}
+
+/// Quote a SQL string literal
+fn sql_string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+fn sql_number(value: &FilterValue) -> Result<String, VectorStoreError> {
+    match value {
+        FilterValue::Float(f) if !f.is_finite() => Err(VectorStoreError::UnsupportedFilter(
+            format!("non-finite number {}", f),
+        )),
+        value => Ok(value.to_string()),
+    }
+}
+
+fn sql_operator(op: &str) -> &'static str {
+    match op {
+        "gt" => ">",
+        "gte" => ">=",
+        "lt" => "<",
+        _ => "<=",
+    }
+}
+
+fn sql_all(conditions: Vec<String>) -> Result<String, VectorStoreError> {
+    if conditions.is_empty() {
+        return Ok("TRUE".to_string());
+    }
+    Ok(format!("({})", conditions.join(" AND ")))
+}
+
+fn sql_any(conditions: Vec<String>) -> Result<String, VectorStoreError> {
+    if conditions.is_empty() {
+        return Ok("FALSE".to_string());
+    }
+    Ok(format!("({})", conditions.join(" OR ")))
+}
+
+#[cfg(test)]
+pub(crate) mod conformance {
+    //! Shared checks that every store behaves alike: its translation of [`MetadataFilter`]
+    //! matches [`MetadataFilter::matches`], and id-aware adds upsert
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::embedding::{embedder_trait::Embedder, EmbedderError};
+    use crate::schemas::Document;
+    use crate::vectorstore::{VecStoreOptions, VectorStore};
+
+    /// Embeds every text to the same vector so that filters alone decide the results
+    pub(crate) struct ConstantEmbedder;
+
+    #[async_trait]
+    impl Embedder for ConstantEmbedder {
+        async fn embed_documents(
+            &self,
+            documents: &[String],
+        ) -> Result<Vec<Vec<f64>>, EmbedderError> {
+            Ok(documents.iter().map(|_| vec![1.0, 0.5, 0.25]).collect())
+        }
+
+        async fn embed_query(&self, _text: &str) -> Result<Vec<f64>, EmbedderError> {
+            Ok(vec![1.0, 0.5, 0.25])
+        }
+    }
+
+    pub(crate) fn documents() -> Vec<Document> {
+        let doc = |id: &str, metadata: Value| {
+            let Value::Object(metadata) = metadata else {
+                unreachable!()
+            };
+            Document::new(format!("document {}", id)).with_metadata(metadata.into_iter().collect())
+        };
+        vec![
+            doc(
+                "a",
+                json!({"id": "a", "genre": "scifi", "year": 1999, "rating": 8.5, "published": true,
+                       "author": {"name": "Jane", "country": "US"}}),
+            ),
+            doc(
+                "b",
+                json!({"id": "b", "genre": "drama", "year": 2005, "rating": 7.0, "published": false,
+                       "author": {"name": "Bob", "country": "UK"}}),
+            ),
+            doc(
+                "c",
+                json!({"id": "c", "genre": "scifi", "year": 2012, "rating": 6.2, "published": true}),
+            ),
+            doc(
+                "d",
+                json!({"id": "d", "genre": "comedy", "year": 2020, "published": false,
+                       "author": {"name": "Jane", "country": "FR"}}),
+            ),
+        ]
+    }
+
+    pub(crate) fn cases() -> Vec<(MetadataFilter, &'static [&'static str])> {
+        type F = MetadataFilter;
+        vec![
+            (F::eq("genre", "scifi"), &["a", "c"]),
+            (F::eq("published", true), &["a", "c"]),
+            (F::eq("year", 2005), &["b"]),
+            (F::eq("rating", 7), &["b"]),
+            (F::ne("genre", "scifi"), &["b", "d"]),
+            (F::is_in("genre", ["drama", "comedy"]), &["b", "d"]),
+            (F::is_in("year", [1999, 2020]), &["a", "d"]),
+            (F::gt("year", 2005), &["c", "d"]),
+            (F::gte("year", 2005), &["b", "c", "d"]),
+            (F::between("year", 2000, 2015), &["b", "c"]),
+            (F::lt("rating", 7.0), &["c"]),
+            (F::gte("genre", "d"), &["a", "b", "c"]),
+            (F::exists("rating"), &["a", "b", "c"]),
+            (F::exists("author.name"), &["a", "b", "d"]),
+            (F::eq("author.name", "Jane"), &["a", "d"]),
+            (F::ne("author.country", "US"), &["b", "c", "d"]),
+            (F::eq("genre", "scifi").and(F::gt("year", 2000)), &["c"]),
+            (
+                F::eq("genre", "drama").or(F::eq("author.country", "FR")),
+                &["b", "d"],
+            ),
+            (!F::eq("genre", "scifi"), &["b", "d"]),
+            (!F::exists("author"), &["c"]),
+            (!F::gt("year", 2005), &["a", "b"]),
+            (
+                !F::all([F::eq("genre", "scifi"), F::eq("published", true)]),
+                &["b", "d"],
+            ),
+            (F::all([]), &["a", "b", "c", "d"]),
+        ]
+    }
+
+    /// The fixture documents without their nested fields, for stores with flat metadata
+    pub(crate) fn flat_documents() -> Vec<Document> {
+        documents()
+            .into_iter()
+            .map(|mut doc| {
+                doc.metadata.retain(|_, value| !value.is_object());
+                doc
+            })
+            .collect()
+    }
+
+    /// Add the fixture documents, then check every case against the store
+    pub(crate) async fn run<S, F>(
+        store: &S,
+        options: impl Fn(Option<MetadataFilter>) -> VecStoreOptions<F>,
+    ) where
+        S: VectorStore<Options = VecStoreOptions<F>>,
+    {
+        add_and_check(store, options, documents()).await;
+    }
+
+    /// [`run`] for stores whose metadata is flat, using [`flat_documents`]
+    pub(crate) async fn run_flat<S, F>(
+        store: &S,
+        options: impl Fn(Option<MetadataFilter>) -> VecStoreOptions<F>,
+    ) where
+        S: VectorStore<Options = VecStoreOptions<F>>,
+    {
+        add_and_check(store, options, flat_documents()).await;
+    }
+
+    async fn add_and_check<S, F>(
+        store: &S,
+        options: impl Fn(Option<MetadataFilter>) -> VecStoreOptions<F>,
+        docs: Vec<Document>,
+    ) where
+        S: VectorStore<Options = VecStoreOptions<F>>,
+    {
+        store
+            .add_documents(&docs, &options(None))
+            .await
+            .expect("add fixture documents");
+        check(store, options, &docs).await;
+    }
+
+    /// Check every case against a store already holding `docs`
+    ///
+    /// The expected results are those of [`MetadataFilter::matches`] on `docs`. Cases the
+    /// store reports as [`VectorStoreError::UnsupportedFilter`] are skipped.
+    pub(crate) async fn check<S, F>(
+        store: &S,
+        options: impl Fn(Option<MetadataFilter>) -> VecStoreOptions<F>,
+        docs: &[Document],
+    ) where
+        S: VectorStore<Options = VecStoreOptions<F>>,
+    {
+        for (filter, _) in cases() {
+            let mut expected: Vec<&str> = docs
+                .iter()
+                .filter(|doc| filter.matches(&doc.metadata))
+                .filter_map(|doc| doc.metadata.get("id")?.as_str())
+                .collect();
+            expected.sort();
+            let result = store
+                .similarity_search("query", 10, &options(Some(filter.clone())))
+                .await;
+            let found = match result {
+                Err(VectorStoreError::UnsupportedFilter(_)) => continue,
+                result => result.unwrap_or_else(|e| panic!("{:?} failed: {}", filter, e)),
+            };
+            let mut ids: Vec<String> = found
+                .iter()
+                .filter_map(|doc| doc.metadata.get("id")?.as_str().map(String::from))
+                .collect();
+            ids.sort();
+            assert_eq!(ids, expected, "{:?}", filter);
+        }
+    }
+
+    /// Embeds texts starting with "near" close to every query, and the rest far from it
+    struct NearFarEmbedder;
+
+    #[async_trait]
+    impl Embedder for NearFarEmbedder {
+        async fn embed_documents(
+            &self,
+            documents: &[String],
+        ) -> Result<Vec<Vec<f64>>, EmbedderError> {
+            Ok(documents
+                .iter()
+                .map(|text| match text.starts_with("near") {
+                    true => vec![1.0, 0.0, 0.0],
+                    false => vec![0.0, 1.0, 0.0],
+                })
+                .collect())
+        }
+
+        async fn embed_query(&self, _text: &str) -> Result<Vec<f64>, EmbedderError> {
+            Ok(vec![1.0, 0.0, 0.0])
+        }
+    }
+
+    /// Check that a filter matching only documents outside the nearest `limit` still finds
+    /// them, i.e. that a store filtering after its k-NN search fetches more than `limit`
+    pub(crate) async fn run_filter_beyond_top_k<S, F>(
+        store: &S,
+        options: impl Fn(Option<MetadataFilter>) -> VecStoreOptions<F>,
+    ) where
+        S: VectorStore<Options = VecStoreOptions<F>>,
+    {
+        let options = |filter| options(filter).with_embedder(NearFarEmbedder);
+        let doc = |text: String, genre: &str| {
+            Document::new(text.clone()).with_metadata(HashMap::from([
+                ("id".to_string(), json!(text)),
+                ("genre".to_string(), json!(genre)),
+            ]))
+        };
+        let mut docs: Vec<Document> = (0..20)
+            .map(|i| doc(format!("near {}", i), "drama"))
+            .collect();
+        docs.push(doc("far".to_string(), "scifi"));
+        store
+            .add_documents(&docs, &options(None))
+            .await
+            .expect("add documents");
+
+        let found = store
+            .similarity_search(
+                "query",
+                2,
+                &options(Some(MetadataFilter::eq("genre", "scifi"))),
+            )
+            .await
+            .expect("search beyond the nearest documents");
+        let ids: Vec<&str> = found.iter().map(|doc| doc.page_content.as_str()).collect();
+        assert_eq!(ids, vec!["far"]);
+
+        let found = store
+            .similarity_search(
+                "query",
+                5,
+                &options(Some(MetadataFilter::eq("genre", "drama"))),
+            )
+            .await
+            .expect("search near documents");
+        assert_eq!(found.len(), 5);
+    }
+
+    /// The id [`run_upsert`] stores a fixture document under, a UUID so every backend takes it
+    pub(crate) fn document_id(doc: &Document) -> String {
+        let id = doc.metadata["id"].as_str().unwrap_or_default();
+        format!("00000000-0000-4000-8000-00000000000{}", id)
+    }
+
+    /// Add the fixture documents with ids twice, then check they were replaced rather than
+    /// duplicated, can be fetched back by id and can be deleted
+    pub(crate) async fn run_upsert<S, F>(store: &S, options: impl Fn() -> VecStoreOptions<F>)
+    where
+        S: VectorStore<Options = VecStoreOptions<F>>,
+    {
+        let docs: Vec<Document> = documents()
+            .into_iter()
+            .map(|doc| {
+                let id = document_id(&doc);
+                doc.with_id(id)
+            })
+            .collect();
+        let ids: Vec<String> = docs.iter().filter_map(|doc| doc.id.clone()).collect();
+        let added = store.add_documents(&docs, &options()).await;
+        assert_eq!(added.expect("add documents"), ids);
+
+        let mut revised = docs.clone();
+        revised[1].page_content = "document b, revised".to_string();
+        let upserted = store.add_documents(&revised, &options()).await;
+        assert_eq!(upserted.expect("upsert documents"), ids);
+
+        let missing = "00000000-0000-4000-8000-0000000000ff".to_string();
+        let fetched = store
+            .get_by_ids(&[ids[1].clone(), missing], &options())
+            .await
+            .expect("get documents by id");
+        assert_eq!(fetched.len(), 1);
+        assert_eq!(fetched[0].id.as_ref(), Some(&ids[1]));
+        assert_eq!(fetched[0].page_content, "document b, revised");
+        assert_eq!(fetched[0].metadata, revised[1].metadata);
+
+        let embedding = store.embed_query("query", &options()).await.unwrap();
+        let by_vector = store
+            .similarity_search_by_vector(&embedding, 10, &options())
+            .await
+            .expect("search by vector");
+        let mut found: Vec<String> = by_vector.iter().filter_map(|doc| doc.id.clone()).collect();
+        found.sort();
+        assert_eq!(found, ids, "upserts must not duplicate documents");
+
+        let with_score = store
+            .similarity_search_with_score("query", 10, &options())
+            .await
+            .expect("search with score");
+        assert_eq!(with_score.len(), ids.len());
+        assert!(with_score.iter().all(|(doc, score)| doc.score == *score));
+
+        store
+            .delete(&[], &options())
+            .await
+            .expect("delete no documents");
+        store
+            .delete(&ids[..2], &options())
+            .await
+            .expect("delete documents");
+        let fetched = store
+            .get_by_ids(&ids, &options())
+            .await
+            .expect("get documents by id");
+        assert_eq!(fetched.len(), ids.len() - 2);
+        let by_vector = store
+            .similarity_search_by_vector(&embedding, 10, &options())
+            .await
+            .expect("search by vector");
+        let mut found: Vec<String> = by_vector.iter().filter_map(|doc| doc.id.clone()).collect();
+        found.sort();
+        assert_eq!(found, ids[2..], "deleted documents must not be found");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_semantics() {
+        for (filter, expected) in conformance::cases() {
+            let ids: Vec<String> = conformance::documents()
+                .into_iter()
+                .filter(|doc| filter.matches(&doc.metadata))
+                .filter_map(|doc| doc.metadata.get("id")?.as_str().map(String::from))
+                .collect();
+            assert_eq!(ids, expected, "{:?}", filter);
+        }
+    }
+
+    #[test]
+    fn test_operator_filter() {
+        let mongo = OperatorDialect {
+            field_prefix: "metadata.",
+            nested_paths: true,
+            exists: true,
+            nor: true,
+            string_ranges: true,
+        };
+        let filter =
+            MetadataFilter::eq("genre", "scifi").and(!MetadataFilter::between("year", 2000, 2010));
+        assert_eq!(
+            filter.to_operator_filter(&mongo).unwrap(),
+            json!({"$and": [
+                {"metadata.genre": {"$eq": "scifi"}},
+                {"$nor": [{"metadata.year": {"$gte": 2000, "$lte": 2010}}]}
+            ]})
+        );
+
+        let flat = OperatorDialect {
+            field_prefix: "",
+            nested_paths: false,
+            exists: false,
+            nor: false,
+            string_ranges: false,
+        };
+        assert_eq!(
+            filter.to_operator_filter(&flat).unwrap(),
+            json!({"$and": [
+                {"genre": {"$eq": "scifi"}},
+                {"$or": [{"year": {"$lt": 2000}}, {"year": {"$gt": 2010}}]}
+            ]})
+        );
+        assert_eq!(
+            (!MetadataFilter::is_in("genre", ["a"]).or(MetadataFilter::ne("year", 1)))
+                .to_operator_filter(&flat)
+                .unwrap(),
+            json!({"$and": [{"genre": {"$nin": ["a"]}}, {"year": {"$eq": 1}}]})
+        );
+        assert!(matches!(
+            MetadataFilter::eq("author.name", "Jane").to_operator_filter(&flat),
+            Err(VectorStoreError::UnsupportedFilter(_))
+        ));
+        assert!(matches!(
+            MetadataFilter::exists("rating").to_operator_filter(&flat),
+            Err(VectorStoreError::UnsupportedFilter(_))
+        ));
+    }
+
+    #[cfg(any(feature = "sqlite-vec", feature = "sqlite-vss"))]
+    #[tokio::test]
+    async fn test_sqlite_condition_conformance() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE docs (metadata BLOB)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for doc in conformance::documents() {
+            sqlx::query("INSERT INTO docs (metadata) VALUES (?)")
+                .bind(json!(&doc.metadata))
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        for (filter, expected) in conformance::cases() {
+            let condition = filter.to_sqlite_condition("metadata").unwrap();
+            let ids: Vec<String> = sqlx::query_scalar(&format!(
+                "SELECT json_extract(metadata, '$.id') AS id FROM docs WHERE {} ORDER BY id",
+                condition
+            ))
+            .fetch_all(&pool)
+            .await
+            .unwrap_or_else(|e| panic!("{}: {}", condition, e));
+            assert_eq!(ids, expected, "{}", condition);
+        }
+    }
+
+    #[test]
+    fn test_postgres_condition() {
+        let filter =
+            MetadataFilter::eq("author.name", "O'Brien").and(!MetadataFilter::exists("year"));
+        assert_eq!(
+            filter.to_postgres_condition("cmetadata").unwrap(),
+            "(coalesce(jsonb_typeof((cmetadata::jsonb #> ARRAY['author', 'name']::text[])) = 'string' \
+AND (cmetadata::jsonb #> ARRAY['author', 'name']::text[]) = '\"O''Brien\"'::jsonb, FALSE) \
+AND NOT (coalesce(jsonb_typeof((cmetadata::jsonb #> ARRAY['year']::text[])) <> 'null', FALSE)))"
+        );
+    }
+}
@@ -85,6 +85,49 @@ fn metadata_matches(
 
 pub type InMemoryOptions = VecStoreOptions<Value>;
 
+impl Store {
+    /// Entries matching the options, most similar first, with their embeddings
+    fn search(
+        &self,
+        query_vector: &[f64],
+        limit: usize,
+        opt: &InMemoryOptions,
+    ) -> Result<Vec<(Document, Vec<f64>)>, VectorStoreError> {
+        let data = self.data.read().map_err(|e| e.to_string())?;
+        let namespace_filter = opt.name_space.as_deref();
+        let score_threshold = opt
+            .score_threshold
+            .map(f64::from)
+            .unwrap_or(f64::NEG_INFINITY);
+        let filter_map = opt.filters.as_ref().and_then(|v| v.as_object());
+
+        let mut scored: Vec<(f64, &Document, &Vec<f64>)> = data
+            .iter()
+            .filter(|(_, _, _, ns)| match (namespace_filter, ns) {
+                (None, _) => true,
+                (Some(n), Some(s)) => n == s,
+                (Some(_), None) => false,
+            })
+            .filter(|(_, doc, _, _)| filter_map.is_none_or(|m| metadata_matches(&doc.metadata, m)))
+            .filter(|(_, doc, _, _)| {
+                opt.metadata_filter
+                    .as_ref()
+                    .is_none_or(|f| f.matches(&doc.metadata))
+            })
+            .map(|(_, doc, emb, _)| (cosine_similarity(query_vector, emb), doc, emb))
+            .filter(|(s, _, _)| *s >= score_threshold)
+            .collect();
+
+        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        let result = scored
+            .into_iter()
+            .take(limit)
+            .map(|(score, doc, emb)| (doc.clone().with_score(score), emb.clone()))
+            .collect();
+        Ok(result)
+    }
+}
+
 #[async_trait]
 impl VectorStore for Store {
     type Options = InMemoryOptions;
@@ -104,11 +147,19 @@ impl VectorStore for Store {
         let mut data = self.data.write().map_err(|e| e.to_string())?;
         let mut ids = Vec::with_capacity(docs.len());
         for (doc, vector) in docs.iter().zip(vectors.iter()) {
-            let id = next_id();
+            let id = doc.id.clone().unwrap_or_else(next_id);
             ids.push(id.clone());
             let mut doc = doc.clone();
             doc.score = 0.0;
-            data.push((id, doc, vector.clone(), namespace.clone()));
+            doc.id = Some(id.clone());
+            let entry = (id, doc, vector.clone(), namespace.clone());
+            match data
+                .iter_mut()
+                .find(|(existing, _, _, _)| *existing == entry.0)
+            {
+                Some(existing) => *existing = entry,
+                None => data.push(entry),
+            }
         }
         Ok(ids)
     }
@@ -119,45 +170,43 @@ impl VectorStore for Store {
         limit: usize,
         opt: &InMemoryOptions,
     ) -> Result<Vec<Document>, VectorStoreError> {
-        let embedder = opt.embedder.as_ref().unwrap_or(&self.embedder);
-        let query_vector = embedder.embed_query(query).await?;
-        let data = self.data.read().map_err(|e| e.to_string())?;
-        let namespace_filter = opt.name_space.as_deref();
-        let score_threshold = opt
-            .score_threshold
-            .map(f64::from)
-            .unwrap_or(f64::NEG_INFINITY);
-        let filter_map = opt.filters.as_ref().and_then(|v| v.as_object());
-
-        let mut scored: Vec<(f64, Document)> = data
-            .iter()
-            .filter(|(_, _, _, ns)| match (namespace_filter, ns) {
-                (None, _) => true,
-                (Some(n), Some(s)) => n == s,
-                (Some(_), None) => false,
-            })
-            .filter(|(_, doc, _, _)| {
-                filter_map.map_or(true, |m| metadata_matches(&doc.metadata, m))
-            })
-            .map(|(_, doc, emb, _)| {
-                let score = cosine_similarity(&query_vector, emb);
-                (score, doc.clone())
-            })
-            .filter(|(s, _)| *s >= score_threshold)
+        let query_vector = self.embed_query(query, opt).await?;
+        let result = self
+            .search(&query_vector, limit, opt)?
+            .into_iter()
+            .map(|(doc, _)| doc)
             .collect();
+        Ok(result)
+    }
 
-        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
-        let result: Vec<Document> = scored
+    async fn similarity_search_by_vector(
+        &self,
+        embedding: &[f64],
+        limit: usize,
+        opt: &InMemoryOptions,
+    ) -> Result<Vec<Document>, VectorStoreError> {
+        let result = self
+            .search(embedding, limit, opt)?
             .into_iter()
-            .take(limit)
-            .map(|(score, mut doc)| {
-                doc.score = score;
-                doc
-            })
+            .map(|(doc, _)| doc)
             .collect();
         Ok(result)
     }
 
+    async fn get_by_ids(
+        &self,
+        ids: &[String],
+        _opt: &InMemoryOptions,
+    ) -> Result<Vec<Document>, VectorStoreError> {
+        let data = self.data.read().map_err(|e| e.to_string())?;
+        let docs = ids
+            .iter()
+            .filter_map(|id| data.iter().find(|(existing, _, _, _)| existing == id))
+            .map(|(_, doc, _, _)| doc.clone())
+            .collect();
+        Ok(docs)
+    }
+
     async fn delete(&self, ids: &[String], _opt: &InMemoryOptions) -> Result<(), VectorStoreError> {
         if ids.is_empty() {
             return Ok(());
@@ -167,4 +216,158 @@ impl VectorStore for Store {
         data.retain(|(id, _, _, _)| !ids_set.contains(id));
         Ok(())
     }
+
+    async fn embed_query(
+        &self,
+        query: &str,
+        opt: &InMemoryOptions,
+    ) -> Result<Vec<f64>, VectorStoreError> {
+        let embedder = opt.embedder.as_ref().unwrap_or(&self.embedder);
+        Ok(embedder.embed_query(query).await?)
+    }
+
+    async fn similarity_search_by_vector_with_embeddings(
+        &self,
+        embedding: &[f64],
+        limit: usize,
+        opt: &InMemoryOptions,
+    ) -> Result<Vec<(Document, Vec<f64>)>, VectorStoreError> {
+        self.search(embedding, limit, opt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedding::EmbedderError;
+    use crate::schemas::Retriever as _;
+    use crate::vectorstore::{conformance, MetadataFilter, Retriever};
+
+    /// Embeds the fixture texts of the MMR tests to fixed vectors
+    struct FixtureEmbedder;
+
+    #[async_trait]
+    impl Embedder for FixtureEmbedder {
+        async fn embed_documents(
+            &self,
+            documents: &[String],
+        ) -> Result<Vec<Vec<f64>>, EmbedderError> {
+            let mut vectors = Vec::new();
+            for document in documents {
+                vectors.push(self.embed_query(document).await?);
+            }
+            Ok(vectors)
+        }
+
+        async fn embed_query(&self, text: &str) -> Result<Vec<f64>, EmbedderError> {
+            Ok(match text {
+                "apple pie" => vec![1.0, 0.2],
+                "apple pie recipe" => vec![1.0, 0.21],
+                "banana bread" => vec![0.5, 0.8],
+                "cherry jam" => vec![0.0, 1.0],
+                _ => vec![1.0, 0.3],
+            })
+        }
+    }
+
+    async fn fixture_store() -> Store {
+        let store = StoreBuilder::new()
+            .embedder(FixtureEmbedder)
+            .build()
+            .unwrap();
+        let docs: Vec<Document> = [
+            "apple pie",
+            "apple pie recipe",
+            "banana bread",
+            "cherry jam",
+        ]
+        .into_iter()
+        .map(Document::new)
+        .collect();
+        store
+            .add_documents(&docs, &InMemoryOptions::default())
+            .await
+            .unwrap();
+        store
+    }
+
+    fn contents(docs: &[Document]) -> Vec<&str> {
+        docs.iter().map(|doc| doc.page_content.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_max_marginal_relevance_search() {
+        let store = fixture_store().await;
+        let options = InMemoryOptions::default();
+        let similar = store
+            .similarity_search("dessert", 2, &options)
+            .await
+            .unwrap();
+        assert_eq!(contents(&similar), vec!["apple pie recipe", "apple pie"]);
+
+        let diverse = store
+            .max_marginal_relevance_search("dessert", 2, 4, 0.5, &options)
+            .await
+            .unwrap();
+        assert_eq!(contents(&diverse), vec!["apple pie recipe", "cherry jam"]);
+        assert!(diverse[0].score > 0.9);
+
+        let retriever = Retriever::new(fixture_store().await, 2).with_mmr(4, 0.5);
+        let retrieved = retriever.get_relevant_documents("dessert").await.unwrap();
+        assert_eq!(contents(&retrieved), vec!["apple pie recipe", "cherry jam"]);
+
+        for lambda in [-0.1, 1.5, f64::NAN] {
+            let err = store
+                .max_marginal_relevance_search("dessert", 2, 4, lambda, &options)
+                .await
+                .unwrap_err();
+            assert!(matches!(err, VectorStoreError::InvalidParameter(_)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metadata_filter_conformance() {
+        let store = StoreBuilder::new()
+            .embedder(conformance::ConstantEmbedder)
+            .build()
+            .unwrap();
+        conformance::run(&store, |filter| {
+            let options = InMemoryOptions::default();
+            match filter {
+                Some(filter) => options.with_metadata_filter(filter),
+                None => options,
+            }
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_upsert_conformance() {
+        let store = StoreBuilder::new()
+            .embedder(conformance::ConstantEmbedder)
+            .build()
+            .unwrap();
+        conformance::run_upsert(&store, InMemoryOptions::default).await;
+    }
+
+    #[tokio::test]
+    async fn test_metadata_filter_with_json_filters() {
+        let store = StoreBuilder::new()
+            .embedder(conformance::ConstantEmbedder)
+            .build()
+            .unwrap();
+        store
+            .add_documents(&conformance::documents(), &InMemoryOptions::default())
+            .await
+            .unwrap();
+        let options = InMemoryOptions::default()
+            .with_filters(serde_json::json!({"genre": "scifi"}))
+            .with_metadata_filter(MetadataFilter::gt("year", 2000));
+        let docs = store
+            .similarity_search("query", 10, &options)
+            .await
+            .unwrap();
+        assert_eq!(docs.len(), 1);
+        assert_eq!(docs[0].metadata["id"], "c");
+    }
 }
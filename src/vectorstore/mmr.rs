@@ -0,0 +1,88 @@
+use crate::{error::VectorStoreError, utils::cosine_similarity_f64};
+
+/// Check that `lambda` is a weight in `0..=1`, rejecting NaN
+pub(crate) fn check_lambda(lambda: f64) -> Result<(), VectorStoreError> {
+    if !(0.0..=1.0).contains(&lambda) {
+        return Err(VectorStoreError::InvalidParameter(format!(
+            "MMR lambda must be between 0 and 1, got {}",
+            lambda
+        )));
+    }
+    Ok(())
+}
+
+/// Pick up to `k` of `candidates` by maximal marginal relevance to `query`
+///
+/// Each step takes the candidate maximising
+/// `lambda * sim(query, c) - (1 - lambda) * max(sim(c, selected))`, so `lambda` of 1 ranks
+/// by relevance alone and 0 by diversity alone. Returns indices into `candidates` in
+/// selection order.
+pub fn maximal_marginal_relevance<E: AsRef<[f64]>>(
+    query: &[f64],
+    candidates: &[E],
+    k: usize,
+    lambda: f64,
+) -> Vec<usize> {
+    let relevance: Vec<f64> = candidates
+        .iter()
+        .map(|candidate| cosine_similarity_f64(query, candidate.as_ref()))
+        .collect();
+    // Highest similarity of each candidate to the ones selected so far
+    let mut redundancy = vec![f64::NEG_INFINITY; candidates.len()];
+    let mut selected: Vec<usize> = Vec::with_capacity(k.min(candidates.len()));
+
+    while selected.len() < k.min(candidates.len()) {
+        let best = (0..candidates.len())
+            .filter(|i| !selected.contains(i))
+            .map(|i| {
+                let score = if selected.is_empty() {
+                    relevance[i]
+                } else {
+                    lambda * relevance[i] - (1.0 - lambda) * redundancy[i]
+                };
+                (i, score)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)))
+            .map(|(i, _)| i);
+        let Some(best) = best else {
+            break;
+        };
+        for (i, candidate) in candidates.iter().enumerate() {
+            let similarity = cosine_similarity_f64(candidates[best].as_ref(), candidate.as_ref());
+            redundancy[i] = redundancy[i].max(similarity);
+        }
+        selected.push(best);
+    }
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_maximal_marginal_relevance() {
+        let query = [1.0, 0.3];
+        let candidates = vec![
+            vec![1.0, 0.2],
+            vec![1.0, 0.21],
+            vec![0.5, 0.8],
+            vec![0.0, 1.0],
+        ];
+        // Pure relevance keeps the near-duplicate
+        assert_eq!(
+            maximal_marginal_relevance(&query, &candidates, 2, 1.0),
+            vec![1, 0]
+        );
+        // Balancing relevance and diversity skips it
+        assert_eq!(
+            maximal_marginal_relevance(&query, &candidates, 2, 0.5),
+            vec![1, 3]
+        );
+        assert_eq!(
+            maximal_marginal_relevance(&query, &candidates, 10, 0.5).len(),
+            4
+        );
+        assert!(maximal_marginal_relevance(&query, &candidates, 0, 0.5).is_empty());
+    }
+}
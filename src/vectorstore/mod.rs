@@ -1,4 +1,6 @@
 mod error;
+mod filter;
+mod mmr;
 mod options;
 
 #[cfg(feature = "postgres")]
@@ -44,5 +46,10 @@ pub use base::{
     VectorStoreBaseConfig, VectorStoreBatch, VectorStoreHelpers, VectorStoreInitializable,
 };
 pub use error::*;
+pub use filter::*;
+pub use mmr::*;
 pub use options::*;
 pub use vectorstore::*;
+
+#[cfg(test)]
+pub(crate) use filter::conformance;
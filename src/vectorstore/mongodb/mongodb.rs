@@ -3,12 +3,13 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use mongodb::bson::{doc, Bson, Document};
+use mongodb::options::ReplaceOptions;
 use serde_json::Value;
 
 use crate::{
     embedding::embedder_trait::Embedder,
     schemas::Document as LangchainDocument,
-    vectorstore::{VecStoreOptions, VectorStore, VectorStoreError},
+    vectorstore::{OperatorDialect, VecStoreOptions, VectorStore, VectorStoreError},
 };
 
 pub struct Store {
@@ -21,6 +22,15 @@ pub struct Store {
 
 pub type MongoOptions = VecStoreOptions<Value>;
 
+/// `$vectorSearch` pre-filters; the metadata fields must be indexed as `filter` fields
+const FILTER_DIALECT: OperatorDialect = OperatorDialect {
+    field_prefix: "metadata.",
+    nested_paths: true,
+    exists: true,
+    nor: true,
+    string_ranges: true,
+};
+
 fn bson_to_value(b: &Bson) -> Value {
     match b {
         Bson::Null => Value::Null,
@@ -64,54 +74,16 @@ fn value_to_bson(v: &Value) -> Bson {
     }
 }
 
-#[async_trait]
-impl VectorStore for Store {
-    type Options = MongoOptions;
-
-    async fn add_documents(
-        &self,
-        docs: &[LangchainDocument],
-        opt: &MongoOptions,
-    ) -> Result<Vec<String>, VectorStoreError> {
-        let _ = opt;
-        let embedder = opt.embedder.as_ref().unwrap_or(&self.embedder);
-        let texts: Vec<String> = docs.iter().map(|d| d.page_content.clone()).collect();
-        let vectors = embedder.embed_documents(&texts).await?;
-        if vectors.len() != docs.len() {
-            return Err(VectorStoreError::InternalError(
-                "Number of vectors and documents do not match".to_string(),
-            ));
-        }
-        let mut ids = Vec::with_capacity(docs.len());
-        for (doc, vector) in docs.iter().zip(vectors.iter()) {
-            let id = uuid::Uuid::new_v4();
-            let mut bson_doc = doc! {
-                "_id": id.to_string(),
-                &self.content_field: doc.page_content.clone(),
-                &self.vector_field: vector.iter().map(|x| Bson::Double(*x)).collect::<Vec<_>>(),
-                "metadata": doc.metadata.iter().map(|(k,v)| (k.clone(), value_to_bson(v))).collect::<Document>()
-            };
-            if let Some(ref ns) = opt.name_space {
-                bson_doc.insert("namespace", ns.as_str());
-            }
-            self.collection
-                .insert_one(bson_doc, None)
-                .await
-                .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
-            ids.push(id.to_string());
-        }
-        Ok(ids)
-    }
-
-    async fn similarity_search(
+impl Store {
+    /// Run a `$vectorSearch` aggregation, optionally projecting the stored vectors
+    async fn search(
         &self,
-        query: &str,
+        query_vector: &[f64],
         limit: usize,
         opt: &MongoOptions,
-    ) -> Result<Vec<LangchainDocument>, VectorStoreError> {
-        let embedder = opt.embedder.as_ref().unwrap_or(&self.embedder);
-        let qv = embedder.embed_query(query).await?;
-        let qv_bson: Vec<Bson> = qv.iter().map(|x| Bson::Double(*x)).collect();
+        with_embeddings: bool,
+    ) -> Result<Vec<(LangchainDocument, Vec<f64>)>, VectorStoreError> {
+        let qv_bson: Vec<Bson> = query_vector.iter().map(|x| Bson::Double(*x)).collect();
 
         let mut filter_doc = Document::new();
         if let Some(ref f) = opt.filters {
@@ -124,6 +96,14 @@ impl VectorStore for Store {
         if let Some(ref ns) = opt.name_space {
             filter_doc.insert("namespace", ns.as_str());
         }
+        if let Some(ref metadata_filter) = opt.metadata_filter {
+            let translated = value_to_bson(&metadata_filter.to_operator_filter(&FILTER_DIALECT)?);
+            filter_doc = if filter_doc.is_empty() {
+                translated.as_document().cloned().unwrap_or_default()
+            } else {
+                doc! { "$and": [filter_doc, translated] }
+            };
+        }
 
         let mut vs_doc = doc! {
             "index": &self.index_name,
@@ -144,6 +124,9 @@ impl VectorStore for Store {
             Bson::String(format!("${}", self.content_field)),
         );
         project_doc.insert("metadata", doc! { "$ifNull": [ "$metadata", {} ] });
+        if with_embeddings {
+            project_doc.insert("embedding", Bson::String(format!("${}", self.vector_field)));
+        }
 
         let pipeline = vec![vector_search_stage, doc! { "$project": project_doc }];
 
@@ -171,14 +154,81 @@ impl VectorStore for Store {
                 })
                 .unwrap_or_default();
             let score = d.get_f64("score").unwrap_or(0.0);
-            result.push(LangchainDocument {
-                page_content,
-                metadata,
-                score,
-            });
+            let id = d.get_str("_id").ok().map(String::from);
+            let embedding: Vec<f64> = d
+                .get_array("embedding")
+                .map(|v| v.iter().filter_map(Bson::as_f64).collect())
+                .unwrap_or_default();
+            result.push((
+                LangchainDocument {
+                    page_content,
+                    metadata,
+                    score,
+                    id,
+                },
+                embedding,
+            ));
         }
         Ok(result)
     }
+}
+
+#[async_trait]
+impl VectorStore for Store {
+    type Options = MongoOptions;
+
+    async fn add_documents(
+        &self,
+        docs: &[LangchainDocument],
+        opt: &MongoOptions,
+    ) -> Result<Vec<String>, VectorStoreError> {
+        let _ = opt;
+        let embedder = opt.embedder.as_ref().unwrap_or(&self.embedder);
+        let texts: Vec<String> = docs.iter().map(|d| d.page_content.clone()).collect();
+        let vectors = embedder.embed_documents(&texts).await?;
+        if vectors.len() != docs.len() {
+            return Err(VectorStoreError::InternalError(
+                "Number of vectors and documents do not match".to_string(),
+            ));
+        }
+        let mut ids = Vec::with_capacity(docs.len());
+        for (doc, vector) in docs.iter().zip(vectors.iter()) {
+            let id = doc
+                .id
+                .clone()
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+            let mut bson_doc = doc! {
+                "_id": id.clone(),
+                &self.content_field: doc.page_content.clone(),
+                &self.vector_field: vector.iter().map(|x| Bson::Double(*x)).collect::<Vec<_>>(),
+                "metadata": doc.metadata.iter().map(|(k,v)| (k.clone(), value_to_bson(v))).collect::<Document>()
+            };
+            if let Some(ref ns) = opt.name_space {
+                bson_doc.insert("namespace", ns.as_str());
+            }
+            self.collection
+                .replace_one(
+                    doc! { "_id": id.clone() },
+                    bson_doc,
+                    ReplaceOptions::builder().upsert(true).build(),
+                )
+                .await
+                .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+            ids.push(id);
+        }
+        Ok(ids)
+    }
+
+    async fn similarity_search(
+        &self,
+        query: &str,
+        limit: usize,
+        opt: &MongoOptions,
+    ) -> Result<Vec<LangchainDocument>, VectorStoreError> {
+        let query_vector = self.embed_query(query, opt).await?;
+        let results = self.search(&query_vector, limit, opt, false).await?;
+        Ok(results.into_iter().map(|(doc, _)| doc).collect())
+    }
 
     async fn delete(&self, ids: &[String], _opt: &MongoOptions) -> Result<(), VectorStoreError> {
         if ids.is_empty() {
@@ -190,4 +240,165 @@ impl VectorStore for Store {
             .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
         Ok(())
     }
+
+    async fn similarity_search_by_vector(
+        &self,
+        embedding: &[f64],
+        limit: usize,
+        opt: &MongoOptions,
+    ) -> Result<Vec<LangchainDocument>, VectorStoreError> {
+        let results = self.search(embedding, limit, opt, false).await?;
+        Ok(results.into_iter().map(|(doc, _)| doc).collect())
+    }
+
+    async fn get_by_ids(
+        &self,
+        ids: &[String],
+        opt: &MongoOptions,
+    ) -> Result<Vec<LangchainDocument>, VectorStoreError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut filter_doc = doc! { "_id": { "$in": ids } };
+        if let Some(ref ns) = opt.name_space {
+            filter_doc.insert("namespace", ns.as_str());
+        }
+        let mut cursor = self
+            .collection
+            .find(filter_doc, None)
+            .await
+            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+        let mut result = Vec::new();
+        while cursor
+            .advance()
+            .await
+            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?
+        {
+            let d = cursor
+                .deserialize_current()
+                .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+            let page_content = d.get_str(&self.content_field).unwrap_or("").to_string();
+            let metadata: HashMap<String, Value> = d
+                .get_document("metadata")
+                .map(|m| {
+                    m.iter()
+                        .map(|(k, v)| (k.clone(), bson_to_value(v)))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let mut document = LangchainDocument::new(page_content).with_metadata(metadata);
+            document.id = d.get_str("_id").ok().map(String::from);
+            result.push(document);
+        }
+        Ok(result)
+    }
+
+    async fn embed_query(
+        &self,
+        query: &str,
+        opt: &MongoOptions,
+    ) -> Result<Vec<f64>, VectorStoreError> {
+        let embedder = opt.embedder.as_ref().unwrap_or(&self.embedder);
+        Ok(embedder.embed_query(query).await?)
+    }
+
+    async fn similarity_search_by_vector_with_embeddings(
+        &self,
+        embedding: &[f64],
+        limit: usize,
+        opt: &MongoOptions,
+    ) -> Result<Vec<(LangchainDocument, Vec<f64>)>, VectorStoreError> {
+        self.search(embedding, limit, opt, true).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use mongodb::Client;
+    use testcontainers::{
+        core::{IntoContainerPort, WaitFor},
+        runners::AsyncRunner,
+        GenericImage,
+    };
+
+    use crate::vectorstore::{conformance, mongodb::StoreBuilder, MetadataFilter};
+
+    use super::*;
+
+    #[tokio::test]
+    #[ignore = "requires Docker"]
+    async fn test_metadata_filter_conformance() {
+        let container = GenericImage::new("mongodb/mongodb-atlas-local", "latest")
+            .with_exposed_port(27017.tcp())
+            .with_wait_for(WaitFor::healthcheck())
+            .start()
+            .await
+            .unwrap();
+        let port = container.get_host_port_ipv4(27017).await.unwrap();
+        let client = Client::with_uri_str(format!(
+            "mongodb://localhost:{}/?directConnection=true",
+            port
+        ))
+        .await
+        .unwrap();
+        let db = client.database("test");
+        db.create_collection("conformance", None).await.unwrap();
+
+        // Every metadata path the cases filter on must be indexed as a `filter` field
+        let mut fields = vec![doc! {
+            "type": "vector", "path": "embedding", "numDimensions": 3, "similarity": "cosine"
+        }];
+        for path in [
+            "id",
+            "genre",
+            "year",
+            "rating",
+            "published",
+            "author.name",
+            "author.country",
+        ] {
+            fields.push(doc! { "type": "filter", "path": format!("metadata.{}", path) });
+        }
+        db.run_command(
+            doc! {
+                "createSearchIndexes": "conformance",
+                "indexes": [{
+                    "name": "vector_index",
+                    "type": "vectorSearch",
+                    "definition": { "fields": fields },
+                }],
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+        let store = StoreBuilder::new()
+            .collection(db.collection("conformance"))
+            .embedder(conformance::ConstantEmbedder)
+            .index_name("vector_index")
+            .build()
+            .unwrap();
+        let options = |filter: Option<MetadataFilter>| {
+            let options = MongoOptions::default();
+            match filter {
+                Some(filter) => options.with_metadata_filter(filter),
+                None => options,
+            }
+        };
+        let docs = conformance::documents();
+        store.add_documents(&docs, &options(None)).await.unwrap();
+
+        // The search index builds and syncs in the background
+        for _ in 0..120 {
+            let found = store.similarity_search("query", 10, &options(None)).await;
+            if found.is_ok_and(|found| found.len() == docs.len()) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+        conformance::check(&store, options, &docs).await;
+    }
 }
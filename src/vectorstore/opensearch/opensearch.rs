@@ -2,7 +2,7 @@ use async_trait::async_trait;
 use opensearch::http::request::JsonBody;
 use opensearch::http::response::Response;
 use opensearch::indices::{IndicesCreateParts, IndicesDeleteParts};
-use opensearch::{BulkParts, SearchParts};
+use opensearch::{BulkParts, MgetParts, SearchParts};
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::error::Error;
@@ -16,7 +16,7 @@ pub use opensearch::OpenSearch;
 use crate::{
     embedding::embedder_trait::Embedder,
     schemas::Document,
-    vectorstore::{VecStoreOptions, VectorStore, VectorStoreError},
+    vectorstore::{MetadataFilter, VecStoreOptions, VectorStore, VectorStoreError},
 };
 
 pub struct Store {
@@ -33,6 +33,78 @@ pub struct Store {
 // https://opensearch.org/docs/latest/clients/rust/
 
 impl Store {
+    /// Run a k-NN search, returning the stored embeddings with the documents
+    async fn search(
+        &self,
+        query_vector: &[f64],
+        limit: usize,
+        opt: &VecStoreOptions<Value>,
+    ) -> Result<Vec<(Document, Vec<f64>)>, VectorStoreError> {
+        let filter = match (opt.filters.clone(), &opt.metadata_filter) {
+            (filters, None) => filters,
+            (None, Some(metadata_filter)) => Some(translate_filter(metadata_filter)),
+            (Some(filters), Some(metadata_filter)) => Some(json!({
+                "bool": { "filter": [filters, translate_filter(metadata_filter)] }
+            })),
+        };
+        let query = build_similarity_search_query(
+            query_vector.to_vec(),
+            &self.vector_field,
+            limit,
+            self.k,
+            filter,
+        );
+
+        let response = self
+            .client
+            .search(SearchParts::Index(&[&self.index]))
+            .body(query)
+            .send()
+            .await
+            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+
+        let response_body = response
+            .json::<Value>()
+            .await
+            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+
+        let aoss_documents = response_body["hits"]["hits"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|raw_value| {
+                serde_json::from_value::<HashMap<String, Value>>(raw_value.clone()).unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let documents = aoss_documents
+            .into_iter()
+            .map(|item| {
+                let page_content =
+                    serde_json::from_value::<String>(item["_source"][&self.content_field].clone())
+                        .unwrap();
+                let metadata = serde_json::from_value::<HashMap<String, Value>>(
+                    item["_source"]["metadata"].clone(),
+                )
+                .unwrap();
+                let score = serde_json::from_value::<f64>(item["_score"].clone()).unwrap();
+                let id = item["_id"].as_str().map(String::from);
+                let embedding =
+                    serde_json::from_value::<Vec<f64>>(item["_source"][&self.vector_field].clone())
+                        .unwrap_or_default();
+                let document = Document {
+                    page_content,
+                    metadata,
+                    score,
+                    id,
+                };
+                (document, embedding)
+            })
+            .collect();
+
+        Ok(documents)
+    }
+
     pub async fn delete_index(&self) -> Result<Response, Box<dyn Error>> {
         let response = self
             .client
@@ -119,7 +191,11 @@ impl VectorStore for Store {
         let mut body: Vec<JsonBody<_>> = Vec::with_capacity(docs.len() * 2);
 
         for (doc, vector) in docs.iter().zip(vectors.iter()) {
-            let operation = json!({"index": {}});
+            // Indexing under an existing id replaces that document
+            let operation = match &doc.id {
+                Some(id) => json!({"index": {"_id": id}}),
+                None => json!({"index": {}}),
+            };
             body.push(operation.into());
 
             let document = json!({
@@ -161,23 +237,60 @@ impl VectorStore for Store {
         limit: usize,
         opt: &Self::Options,
     ) -> Result<Vec<Document>, VectorStoreError> {
-        let query_vector = self.embedder.embed_query(query).await?;
-        let query = build_similarity_search_query(
-            query_vector,
-            &self.vector_field,
-            limit,
-            self.k,
-            opt.filters.clone(),
-        );
+        let query_vector = self.embed_query(query, opt).await?;
+        let results = self.search(&query_vector, limit, opt).await?;
+        Ok(results.into_iter().map(|(doc, _)| doc).collect())
+    }
+
+    async fn delete(
+        &self,
+        ids: &[String],
+        _opt: &VecStoreOptions<Value>,
+    ) -> Result<(), VectorStoreError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let body: Vec<JsonBody<_>> = ids
+            .iter()
+            .map(|id| json!({"delete": {"_id": id}}).into())
+            .collect();
+        self.client
+            .bulk(BulkParts::Index(&self.index))
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?
+            .error_for_status_code()
+            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn similarity_search_by_vector(
+        &self,
+        embedding: &[f64],
+        limit: usize,
+        opt: &Self::Options,
+    ) -> Result<Vec<Document>, VectorStoreError> {
+        let results = self.search(embedding, limit, opt).await?;
+        Ok(results.into_iter().map(|(doc, _)| doc).collect())
+    }
 
+    async fn get_by_ids(
+        &self,
+        ids: &[String],
+        _opt: &Self::Options,
+    ) -> Result<Vec<Document>, VectorStoreError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
         let response = self
             .client
-            .search(SearchParts::Index(&[&self.index]))
-            .from(0)
-            .size(3)
-            .body(query)
+            .mget(MgetParts::Index(&self.index))
+            .body(json!({ "ids": ids }))
             .send()
             .await
+            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?
+            .error_for_status_code()
             .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
 
         let response_body = response
@@ -185,58 +298,82 @@ impl VectorStore for Store {
             .await
             .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
 
-        let aoss_documents = response_body["hits"]["hits"]
+        let documents = response_body["docs"]
             .as_array()
-            .unwrap()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
             .iter()
-            .map(|raw_value| {
-                serde_json::from_value::<HashMap<String, Value>>(raw_value.clone()).unwrap()
-            })
-            .collect::<Vec<_>>();
-
-        let documents = aoss_documents
-            .into_iter()
+            .filter(|item| item["found"].as_bool().unwrap_or(false))
             .map(|item| {
-                let page_content =
-                    serde_json::from_value::<String>(item["_source"][&self.content_field].clone())
-                        .unwrap();
+                let page_content = item["_source"][&self.content_field]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string();
                 let metadata = serde_json::from_value::<HashMap<String, Value>>(
                     item["_source"]["metadata"].clone(),
                 )
-                .unwrap();
-                let score = serde_json::from_value::<f64>(item["_score"].clone()).unwrap();
-                Document {
-                    page_content,
-                    metadata,
-                    score,
-                }
+                .unwrap_or_default();
+                let mut document = Document::new(page_content).with_metadata(metadata);
+                document.id = item["_id"].as_str().map(String::from);
+                document
             })
             .collect();
 
         Ok(documents)
     }
 
-    async fn delete(
+    async fn embed_query(
         &self,
-        ids: &[String],
-        _opt: &VecStoreOptions<Value>,
-    ) -> Result<(), VectorStoreError> {
-        if ids.is_empty() {
-            return Ok(());
+        query: &str,
+        opt: &Self::Options,
+    ) -> Result<Vec<f64>, VectorStoreError> {
+        let embedder = opt.embedder.as_ref().unwrap_or(&self.embedder);
+        Ok(embedder.embed_query(query).await?)
+    }
+
+    async fn similarity_search_by_vector_with_embeddings(
+        &self,
+        embedding: &[f64],
+        limit: usize,
+        opt: &Self::Options,
+    ) -> Result<Vec<(Document, Vec<f64>)>, VectorStoreError> {
+        self.search(embedding, limit, opt).await
+    }
+}
+
+/// Translate a metadata filter to an OpenSearch query on the `metadata` object
+///
+/// `term` queries compare exact values, so string fields should be mapped as `keyword`.
+fn translate_filter(filter: &MetadataFilter) -> Value {
+    let field = |path| format!("metadata.{}", path);
+    match filter {
+        MetadataFilter::Eq(path, value) => json!({ "term": { field(path): value.to_json() } }),
+        MetadataFilter::Ne(path, value) => json!({
+            "bool": { "must_not": [{ "term": { field(path): value.to_json() } }] }
+        }),
+        MetadataFilter::In(path, values) => json!({
+            "terms": { field(path): values.iter().map(|v| v.to_json()).collect::<Vec<_>>() }
+        }),
+        MetadataFilter::Range(path, range) => {
+            let bounds: serde_json::Map<String, Value> = range
+                .bounds()
+                .map(|(op, bound)| (op.to_string(), bound.to_json()))
+                .collect();
+            json!({ "range": { field(path): bounds } })
         }
-        let body: Vec<JsonBody<_>> = ids
-            .iter()
-            .map(|id| json!({"delete": {"_id": id}}).into())
-            .collect();
-        self.client
-            .bulk(BulkParts::Index(&self.index))
-            .body(body)
-            .send()
-            .await
-            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?
-            .error_for_status_code()
-            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
-        Ok(())
+        MetadataFilter::Exists(path) => json!({ "exists": { "field": field(path) } }),
+        MetadataFilter::And(filters) => json!({
+            "bool": { "filter": filters.iter().map(translate_filter).collect::<Vec<_>>() }
+        }),
+        MetadataFilter::Or(filters) => json!({
+            "bool": {
+                "should": filters.iter().map(translate_filter).collect::<Vec<_>>(),
+                "minimum_should_match": 1
+            }
+        }),
+        MetadataFilter::Not(filter) => json!({
+            "bool": { "must_not": [translate_filter(filter)] }
+        }),
     }
 }
 
@@ -277,3 +414,95 @@ fn build_similarity_search_query(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use opensearch::{http::Url, indices::IndicesRefreshParts};
+    use testcontainers::{core::IntoContainerPort, runners::AsyncRunner, GenericImage, ImageExt};
+
+    use crate::vectorstore::{conformance, opensearch::StoreBuilder};
+
+    use super::*;
+
+    #[tokio::test]
+    #[ignore = "requires Docker"]
+    async fn test_metadata_filter_conformance() {
+        let container = GenericImage::new("opensearchproject/opensearch", "2.11.1")
+            .with_exposed_port(9200.tcp())
+            .with_env_var("discovery.type", "single-node")
+            .with_env_var("DISABLE_SECURITY_PLUGIN", "true")
+            .start()
+            .await
+            .unwrap();
+        let port = container.get_host_port_ipv4(9200).await.unwrap();
+        let url = Url::parse(&format!("http://localhost:{}", port)).unwrap();
+        let transport = TransportBuilder::new(SingleNodeConnectionPool::new(url))
+            .build()
+            .unwrap();
+        let client = OpenSearch::new(transport);
+        for _ in 0..120 {
+            if client
+                .ping()
+                .send()
+                .await
+                .is_ok_and(|r| r.status_code().is_success())
+            {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+
+        // `create_index` maps 1536 dimensions; the fixture needs 3 and keyword strings
+        client
+            .indices()
+            .create(IndicesCreateParts::Index("conformance"))
+            .body(json!({
+                "settings": { "index.knn": true },
+                "mappings": {
+                    "dynamic_templates": [{
+                        "strings": {
+                            "match_mapping_type": "string",
+                            "mapping": { "type": "keyword" }
+                        }
+                    }],
+                    "properties": {
+                        "vector_field": { "type": "knn_vector", "dimension": 3 },
+                        "page_content": { "type": "text" }
+                    }
+                }
+            }))
+            .send()
+            .await
+            .unwrap()
+            .error_for_status_code()
+            .unwrap();
+        let store = StoreBuilder::new()
+            .client(client)
+            .embedder(conformance::ConstantEmbedder)
+            .index("conformance")
+            .k(10)
+            .build()
+            .await
+            .unwrap();
+
+        let options = |filter: Option<MetadataFilter>| {
+            let options = VecStoreOptions::default();
+            match filter {
+                Some(filter) => options.with_metadata_filter(filter),
+                None => options,
+            }
+        };
+        let docs = conformance::documents();
+        store.add_documents(&docs, &options(None)).await.unwrap();
+        store
+            .client
+            .indices()
+            .refresh(IndicesRefreshParts::Index(&["conformance"]))
+            .send()
+            .await
+            .unwrap();
+        conformance::check(&store, options, &docs).await;
+    }
+}
@@ -4,9 +4,14 @@ use serde_json::Value;
 
 use crate::embedding::embedder_trait::Embedder;
 
+use super::MetadataFilter;
+
 /// The `VecStoreOptions` struct is responsible for determining options when
 /// interacting with a Vector Store. The options include `name_space`, `score_threshold`,
-/// `filters`, and `embedder`.
+/// `filters`, `metadata_filter`, and `embedder`.
+///
+/// `filters` takes the store's native filter type, while `metadata_filter` is a
+/// [`MetadataFilter`] every store understands. When both are set a document must match both.
 ///
 /// # Usage
 /// ```rust,ignore
@@ -14,12 +19,14 @@ use crate::embedding::embedder_trait::Embedder;
 ///     .with_name_space("my_custom_namespace")
 ///     .with_score_threshold(0.5)
 ///     .with_filters(json!({"genre": "Sci-Fi"}))
+///     .with_metadata_filter(MetadataFilter::gte("year", 2000))
 ///     .with_embedder(my_embedder);
 /// ```
 pub struct VecStoreOptions<F> {
     pub name_space: Option<String>,
     pub score_threshold: Option<f32>,
     pub filters: Option<F>,
+    pub metadata_filter: Option<MetadataFilter>,
     pub embedder: Option<Arc<dyn Embedder>>,
 }
 
@@ -35,6 +42,7 @@ impl<F> VecStoreOptions<F> {
             name_space: None,
             score_threshold: None,
             filters: None,
+            metadata_filter: None,
             embedder: None,
         }
     }
@@ -54,6 +62,11 @@ impl<F> VecStoreOptions<F> {
         self
     }
 
+    pub fn with_metadata_filter(mut self, filter: MetadataFilter) -> Self {
+        self.metadata_filter = Some(filter);
+        self
+    }
+
     pub fn with_embedder<E: Embedder + 'static>(mut self, embedder: E) -> Self {
         self.embedder = Some(Arc::new(embedder));
         self
@@ -1,14 +1,14 @@
 use std::collections::BTreeMap;
 
 use async_trait::async_trait;
-use pinecone_rs::models::{Match, QueryRequest, QueryResponse, Vector};
+use pinecone_rs::models::{FetchRequest, Match, QueryRequest, QueryResponse, Vector};
 use pinecone_rs::Index;
 use serde_json::Value;
 
 use crate::{
     embedding::embedder_trait::Embedder,
     schemas::Document,
-    vectorstore::{VecStoreOptions, VectorStore, VectorStoreError},
+    vectorstore::{OperatorDialect, VecStoreOptions, VectorStore, VectorStoreError},
 };
 
 pub struct Store {
@@ -18,6 +18,22 @@ pub struct Store {
 
 pub type PineconeOptions = VecStoreOptions<Value>;
 
+/// Ids per fetch request
+const FETCH_BATCH_SIZE: usize = 100;
+
+/// Pinecone metadata is flat and its filters have no `$not`
+///
+/// Unlike [`MetadataFilter`](crate::vectorstore::MetadataFilter), Pinecone's `$ne` and `$nin`
+/// skip records missing the field, so `Ne` and negated `Eq` or `In` only match records that
+/// have it. This is deliberate, to keep each leaf a single Pinecone operator.
+const FILTER_DIALECT: OperatorDialect = OperatorDialect {
+    field_prefix: "",
+    nested_paths: false,
+    exists: true,
+    nor: false,
+    string_ranges: false,
+};
+
 fn metadata_to_btreemap(doc: &Document) -> BTreeMap<String, Value> {
     let mut m: BTreeMap<String, Value> = doc
         .metadata
@@ -31,6 +47,33 @@ fn metadata_to_btreemap(doc: &Document) -> BTreeMap<String, Value> {
     m
 }
 
+/// Split stored metadata back into the document content and its metadata
+fn document_from_metadata(
+    id: String,
+    metadata: Option<BTreeMap<String, Value>>,
+    score: f64,
+) -> Document {
+    let (page_content, metadata) = match metadata {
+        Some(meta) => {
+            let pc = meta
+                .get("content")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+            let md: std::collections::HashMap<String, Value> =
+                meta.into_iter().filter(|(k, _)| k != "content").collect();
+            (pc, md)
+        }
+        None => (String::new(), std::collections::HashMap::new()),
+    };
+    Document {
+        page_content,
+        metadata,
+        score,
+        id: Some(id),
+    }
+}
+
 fn filters_to_btreemap(f: &Option<Value>) -> Option<BTreeMap<String, Value>> {
     f.as_ref().and_then(|v| {
         v.as_object()
@@ -38,6 +81,74 @@ fn filters_to_btreemap(f: &Option<Value>) -> Option<BTreeMap<String, Value>> {
     })
 }
 
+/// Combine the native filters with the translated metadata filter
+fn build_filter(
+    opt: &PineconeOptions,
+) -> Result<Option<BTreeMap<String, Value>>, VectorStoreError> {
+    let metadata_filter = match &opt.metadata_filter {
+        Some(metadata_filter) => Some(metadata_filter.to_operator_filter(&FILTER_DIALECT)?),
+        None => None,
+    };
+    let filter = match (opt.filters.clone(), metadata_filter) {
+        (Some(filters), Some(metadata_filter)) => {
+            Some(serde_json::json!({ "$and": [filters, metadata_filter] }))
+        }
+        (filters, metadata_filter) => filters.or(metadata_filter),
+    };
+    Ok(filters_to_btreemap(&filter))
+}
+
+impl Store {
+    /// Query the index with an already embedded query, also returning the stored values
+    /// when asked
+    async fn search(
+        &self,
+        query_vector: &[f64],
+        limit: usize,
+        opt: &PineconeOptions,
+        with_embeddings: bool,
+    ) -> Result<Vec<(Document, Vec<f64>)>, VectorStoreError> {
+        let qv_f32: Vec<f32> = query_vector.iter().map(|&x| x as f32).collect();
+        let namespace = opt.name_space.clone();
+        let filter = build_filter(opt)?;
+        let request = QueryRequest {
+            namespace,
+            top_k: limit,
+            filter,
+            include_values: with_embeddings,
+            include_metadata: true,
+            vector: Some(qv_f32),
+            sparse_vector: None,
+            id: None,
+        };
+        let resp: QueryResponse = self
+            .index
+            .query(request)
+            .await
+            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+        let score_threshold = opt
+            .score_threshold
+            .map(f64::from)
+            .unwrap_or(f64::NEG_INFINITY);
+        let docs: Vec<(Document, Vec<f64>)> = resp
+            .matches
+            .into_iter()
+            .filter_map(|m: Match| {
+                let score = m.score.map(f64::from).unwrap_or(0.0);
+                if score < score_threshold {
+                    return None;
+                }
+                let embedding = m
+                    .values
+                    .map(|values| values.into_iter().map(f64::from).collect())
+                    .unwrap_or_default();
+                Some((document_from_metadata(m.id, m.metadata, score), embedding))
+            })
+            .collect();
+        Ok(docs)
+    }
+}
+
 #[async_trait]
 impl VectorStore for Store {
     type Options = PineconeOptions;
@@ -55,9 +166,13 @@ impl VectorStore for Store {
                 "Number of vectors and documents do not match".to_string(),
             ));
         }
+        // Upserting under an existing id replaces that vector
         let ids: Vec<String> = docs
             .iter()
-            .map(|_| uuid::Uuid::new_v4().to_string())
+            .map(|d| {
+                d.id.clone()
+                    .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+            })
             .collect();
         let namespace = opt.name_space.as_deref().unwrap_or("").to_string();
         let vectors: Vec<Vector> = ids
@@ -88,58 +203,41 @@ impl VectorStore for Store {
         limit: usize,
         opt: &PineconeOptions,
     ) -> Result<Vec<Document>, VectorStoreError> {
-        let embedder = opt.embedder.as_ref().unwrap_or(&self.embedder);
-        let qv = embedder.embed_query(query).await?;
-        let qv_f32: Vec<f32> = qv.into_iter().map(|x| x as f32).collect();
-        let namespace = opt.name_space.clone();
-        let filter = filters_to_btreemap(&opt.filters);
-        let request = QueryRequest {
-            namespace,
-            top_k: limit,
-            filter,
-            include_values: false,
-            include_metadata: true,
-            vector: Some(qv_f32),
-            sparse_vector: None,
-            id: None,
-        };
-        let resp: QueryResponse = self
-            .index
-            .query(request)
-            .await
-            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
-        let score_threshold = opt
-            .score_threshold
-            .map(f64::from)
-            .unwrap_or(f64::NEG_INFINITY);
-        let docs: Vec<Document> = resp
-            .matches
-            .into_iter()
-            .filter_map(|m: Match| {
-                let score = m.score.map(f64::from).unwrap_or(0.0);
-                if score < score_threshold {
-                    return None;
-                }
-                let (page_content, metadata) = match m.metadata {
-                    Some(meta) => {
-                        let pc = meta
-                            .get("content")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("")
-                            .to_string();
-                        let md: std::collections::HashMap<String, Value> =
-                            meta.into_iter().filter(|(k, _)| k != "content").collect();
-                        (pc, md)
-                    }
-                    None => (String::new(), std::collections::HashMap::new()),
-                };
-                Some(Document {
-                    page_content,
-                    metadata,
-                    score,
-                })
-            })
-            .collect();
+        let query_vector = self.embed_query(query, opt).await?;
+        let results = self.search(&query_vector, limit, opt, false).await?;
+        Ok(results.into_iter().map(|(doc, _)| doc).collect())
+    }
+
+    async fn get_by_ids(
+        &self,
+        ids: &[String],
+        opt: &PineconeOptions,
+    ) -> Result<Vec<Document>, VectorStoreError> {
+        let mut docs = Vec::new();
+        // The ids go in the query string, so fetch in batches
+        for batch in ids.chunks(FETCH_BATCH_SIZE) {
+            let request = FetchRequest {
+                ids: batch
+                    .iter()
+                    .map(|id| urlencoding::encode(id).into_owned())
+                    .collect(),
+                namespace: opt
+                    .name_space
+                    .as_deref()
+                    .map(|namespace| urlencoding::encode(namespace).into_owned()),
+            };
+            let response = self
+                .index
+                .fetch(request)
+                .await
+                .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+            docs.extend(
+                response
+                    .vectors
+                    .into_values()
+                    .map(|vector| document_from_metadata(vector.id, vector.metadata, 0.0)),
+            );
+        }
         Ok(docs)
     }
 
@@ -150,4 +248,51 @@ impl VectorStore for Store {
     ) -> Result<(), VectorStoreError> {
         Err(VectorStoreError::DeleteNotSupported)
     }
+
+    async fn similarity_search_by_vector(
+        &self,
+        embedding: &[f64],
+        limit: usize,
+        opt: &PineconeOptions,
+    ) -> Result<Vec<Document>, VectorStoreError> {
+        let results = self.search(embedding, limit, opt, false).await?;
+        Ok(results.into_iter().map(|(doc, _)| doc).collect())
+    }
+
+    async fn embed_query(
+        &self,
+        query: &str,
+        opt: &PineconeOptions,
+    ) -> Result<Vec<f64>, VectorStoreError> {
+        let embedder = opt.embedder.as_ref().unwrap_or(&self.embedder);
+        Ok(embedder.embed_query(query).await?)
+    }
+
+    async fn similarity_search_by_vector_with_embeddings(
+        &self,
+        embedding: &[f64],
+        limit: usize,
+        opt: &PineconeOptions,
+    ) -> Result<Vec<(Document, Vec<f64>)>, VectorStoreError> {
+        self.search(embedding, limit, opt, true).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_round_trips_through_metadata() {
+        let doc = Document::new("hello").with_metadata(
+            [("lang".to_string(), Value::from("en"))]
+                .into_iter()
+                .collect(),
+        );
+        let restored =
+            document_from_metadata("id-1".to_string(), Some(metadata_to_btreemap(&doc)), 0.0);
+        assert_eq!(restored.page_content, "hello");
+        assert_eq!(restored.metadata, doc.metadata);
+        assert_eq!(restored.id.as_deref(), Some("id-1"));
+    }
 }
@@ -1,7 +1,8 @@
 use async_trait::async_trait;
 use qdrant_client::client::Payload;
 use qdrant_client::qdrant::{
-    DeletePointsBuilder, Filter, PointStruct, PointsIdsList, SearchPointsBuilder,
+    point_id::PointIdOptions, vectors_output::VectorsOptions, Condition, DeletePointsBuilder,
+    Filter, GetPointsBuilder, PointId, PointStruct, PointsIdsList, Range, SearchPointsBuilder,
     UpsertPointsBuilder,
 };
 use serde_json::{json, Value};
@@ -16,7 +17,9 @@ pub use qdrant_client::Qdrant as QdrantClient;
 use crate::{
     embedding::embedder_trait::Embedder,
     schemas::Document,
-    vectorstore::{VecStoreOptions, VectorStore, VectorStoreError},
+    vectorstore::{
+        FilterValue, MetadataFilter, MetadataPath, VecStoreOptions, VectorStore, VectorStoreError,
+    },
 };
 use uuid::Uuid;
 
@@ -31,12 +34,171 @@ pub struct Store {
 
 type QdrantOptions = VecStoreOptions<Value>;
 
+fn point_id_to_string(id: PointId) -> Option<String> {
+    match id.point_id_options? {
+        PointIdOptions::Uuid(uuid) => Some(uuid),
+        PointIdOptions::Num(num) => Some(num.to_string()),
+    }
+}
+
+impl Store {
+    /// Translate a metadata filter to a Qdrant condition on the metadata payload field
+    ///
+    /// Numbers are matched with ranges so that integers and floats compare equal.
+    fn translate_filter(&self, filter: &MetadataFilter) -> Result<Condition, VectorStoreError> {
+        let key = |path: &MetadataPath| format!("{}.{}", self.metadata_field, path);
+        let equals = |path: &MetadataPath, value: &FilterValue| match value {
+            FilterValue::Bool(b) => Condition::matches(key(path), *b),
+            FilterValue::String(s) => Condition::matches(key(path), s.clone()),
+            number => {
+                let number = number.as_f64();
+                Condition::range(
+                    key(path),
+                    Range {
+                        gte: number,
+                        lte: number,
+                        ..Default::default()
+                    },
+                )
+            }
+        };
+        let translate_all = |filters: &[MetadataFilter]| {
+            filters
+                .iter()
+                .map(|f| self.translate_filter(f))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        match filter {
+            MetadataFilter::Eq(path, value) => Ok(equals(path, value)),
+            MetadataFilter::Ne(path, value) => Ok(Filter::must_not([equals(path, value)]).into()),
+            MetadataFilter::In(_, values) if values.is_empty() => Err(
+                VectorStoreError::UnsupportedFilter("empty `in` filter".to_string()),
+            ),
+            MetadataFilter::In(path, values) => {
+                Ok(Filter::should(values.iter().map(|value| equals(path, value))).into())
+            }
+            MetadataFilter::Range(path, range) => {
+                if !range.is_numeric() {
+                    return Err(VectorStoreError::UnsupportedFilter(format!(
+                        "non-numeric range on `{}`",
+                        path
+                    )));
+                }
+                let bound =
+                    |bound: &Option<FilterValue>| bound.as_ref().and_then(FilterValue::as_f64);
+                Ok(Condition::range(
+                    key(path),
+                    Range {
+                        gt: bound(&range.gt),
+                        gte: bound(&range.gte),
+                        lt: bound(&range.lt),
+                        lte: bound(&range.lte),
+                    },
+                ))
+            }
+            MetadataFilter::Exists(path) => Ok(Filter::must_not([
+                Condition::is_empty(key(path)),
+                Condition::is_null(key(path)),
+            ])
+            .into()),
+            MetadataFilter::And(filters) => Ok(Filter::must(translate_all(filters)?).into()),
+            MetadataFilter::Or(filters) if filters.is_empty() => Err(
+                VectorStoreError::UnsupportedFilter("empty `or` filter".to_string()),
+            ),
+            MetadataFilter::Or(filters) => Ok(Filter::should(translate_all(filters)?).into()),
+            MetadataFilter::Not(filter) => {
+                Ok(Filter::must_not([self.translate_filter(filter)?]).into())
+            }
+        }
+    }
+
+    /// Search the collection, optionally returning the stored vectors with the documents
+    async fn search(
+        &self,
+        query_vector: &[f64],
+        limit: usize,
+        opt: &QdrantOptions,
+        with_vectors: bool,
+    ) -> Result<Vec<(Document, Vec<f64>)>, VectorStoreError> {
+        if opt.name_space.is_some() {
+            return Err(VectorStoreError::InvalidParameter(
+                "Qdrant doesn't support namespaces".to_string(),
+            ));
+        }
+
+        if opt.filters.is_some() {
+            return Err(VectorStoreError::InvalidParameter(
+                "'qdrant_client' doesn't support 'serde_json::Value' filters. 
+            Use `with_metadata_filter`, or `search_filter` when constructing VectorStore instead"
+                    .to_string(),
+            ));
+        }
+
+        let query_vector: Vec<f32> = query_vector.iter().map(|&f| f as f32).collect();
+
+        let mut operation =
+            SearchPointsBuilder::new(&self.collection_name, query_vector, limit as u64)
+                .with_payload(true)
+                .with_vectors(with_vectors);
+        if let Some(score_threshold) = opt.score_threshold {
+            operation = operation.score_threshold(score_threshold);
+        }
+        let mut conditions: Vec<Condition> = Vec::new();
+        if let Some(filter) = &self.search_filter {
+            conditions.push(filter.clone().into());
+        }
+        if let Some(metadata_filter) = &opt.metadata_filter {
+            conditions.push(self.translate_filter(metadata_filter)?);
+        }
+        if !conditions.is_empty() {
+            operation = operation.filter(Filter::must(conditions));
+        }
+        let results = self
+            .client
+            .search_points(operation)
+            .await
+            .map_err(|e| VectorStoreError::from(e.to_string()))?;
+
+        let documents = results
+            .result
+            .into_iter()
+            .map(|scored_point| {
+                let payload = scored_point.payload;
+
+                let page_content = payload[&self.content_field].to_string();
+                let metadata =
+                    serde_json::from_value(payload[&self.metadata_field].clone().into_json())
+                        .unwrap();
+                let score = scored_point.score as f64;
+                let id = scored_point.id.and_then(point_id_to_string);
+                let embedding = match scored_point.vectors.and_then(|v| v.vectors_options) {
+                    Some(VectorsOptions::Vector(vector)) => {
+                        vector.data.into_iter().map(|f| f as f64).collect()
+                    }
+                    _ => Vec::new(),
+                };
+                let document = Document {
+                    page_content,
+                    metadata,
+                    score,
+                    id,
+                };
+                (document, embedding)
+            })
+            .collect();
+
+        Ok(documents)
+    }
+}
+
 #[async_trait]
 impl VectorStore for Store {
     type Options = QdrantOptions;
 
     /// Add documents to the store.
     /// Returns a list of document IDs added to the Qdrant collection.
+    /// Document ids must be UUIDs, which Qdrant requires for string point ids.
     async fn add_documents(
         &self,
         docs: &[Document],
@@ -45,7 +207,10 @@ impl VectorStore for Store {
         let embedder = opt.embedder.as_ref().unwrap_or(&self.embedder);
         let texts: Vec<String> = docs.iter().map(|d| d.page_content.clone()).collect();
 
-        let ids = docs.iter().map(|_| Uuid::new_v4().to_string());
+        let ids: Vec<String> = docs
+            .iter()
+            .map(|d| d.id.clone().unwrap_or_else(|| Uuid::new_v4().to_string()))
+            .collect();
         let vectors = embedder.embed_documents(&texts).await?.into_iter();
         let payloads = docs.iter().map(|d| {
             let mut base = json!({
@@ -66,9 +231,9 @@ impl VectorStore for Store {
 
         let mut points: Vec<PointStruct> = Vec::with_capacity(docs.len());
 
-        for (id, (vector, payload)) in ids.clone().zip(vectors.zip(payloads)) {
+        for (id, (vector, payload)) in ids.iter().zip(vectors.zip(payloads)) {
             let vector: Vec<f32> = vector.into_iter().map(|f| f as f32).collect();
-            let point = PointStruct::new(id, vector, Payload::try_from(payload).unwrap());
+            let point = PointStruct::new(id.clone(), vector, Payload::try_from(payload).unwrap());
             points.push(point);
         }
 
@@ -77,7 +242,7 @@ impl VectorStore for Store {
             .await
             .map_err(|e| VectorStoreError::from(e.to_string()))?;
 
-        Ok(ids.collect())
+        Ok(ids)
     }
 
     /// Perform a similarity search on the store.
@@ -88,81 +253,155 @@ impl VectorStore for Store {
         limit: usize,
         opt: &QdrantOptions,
     ) -> Result<Vec<Document>, VectorStoreError> {
-        if opt.name_space.is_some() {
-            return Err(VectorStoreError::InvalidParameter(
-                "Qdrant doesn't support namespaces".to_string(),
-            ));
-        }
+        let query_vector = self.embed_query(query, opt).await?;
+        let results = self.search(&query_vector, limit, opt, false).await?;
+        Ok(results.into_iter().map(|(doc, _)| doc).collect())
+    }
 
-        if opt.filters.is_some() {
-            return Err(VectorStoreError::InvalidParameter(
-                "'qdrant_client' doesn't support 'serde_json::Value' filters. 
-            Use `search_filter` when constructing VectorStore instead"
-                    .to_string(),
-            ));
+    async fn delete(&self, ids: &[String], _opt: &QdrantOptions) -> Result<(), VectorStoreError> {
+        if ids.is_empty() {
+            return Ok(());
         }
-
-        let embedder = opt.embedder.as_ref().unwrap_or(&self.embedder);
-        let query_vector: Vec<f32> = embedder
-            .embed_query(query)
-            .await?
-            .into_iter()
-            .map(|f| f as f32)
+        let point_ids: Vec<qdrant_client::qdrant::PointId> = ids
+            .iter()
+            .map(|s| qdrant_client::qdrant::PointId::from(s.as_str()))
             .collect();
+        self.client
+            .delete_points(
+                DeletePointsBuilder::new(&self.collection_name)
+                    .points(PointsIdsList { ids: point_ids })
+                    .wait(true),
+            )
+            .await
+            .map_err(|e| VectorStoreError::from(e.to_string()))?;
+        Ok(())
+    }
 
-        let mut operation =
-            SearchPointsBuilder::new(&self.collection_name, query_vector, limit as u64)
-                .with_payload(true);
-        if let Some(score_threshold) = opt.score_threshold {
-            operation = operation.score_threshold(score_threshold);
-        }
-        if let Some(filter) = &self.search_filter {
-            operation = operation.filter(filter.clone());
+    async fn similarity_search_by_vector(
+        &self,
+        embedding: &[f64],
+        limit: usize,
+        opt: &QdrantOptions,
+    ) -> Result<Vec<Document>, VectorStoreError> {
+        let results = self.search(embedding, limit, opt, false).await?;
+        Ok(results.into_iter().map(|(doc, _)| doc).collect())
+    }
+
+    async fn get_by_ids(
+        &self,
+        ids: &[String],
+        _opt: &QdrantOptions,
+    ) -> Result<Vec<Document>, VectorStoreError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
         }
-        let results = self
+        let point_ids: Vec<PointId> = ids.iter().map(|s| PointId::from(s.as_str())).collect();
+        let response = self
             .client
-            .search_points(operation)
+            .get_points(GetPointsBuilder::new(&self.collection_name, point_ids).with_payload(true))
             .await
             .map_err(|e| VectorStoreError::from(e.to_string()))?;
 
-        let documents = results
+        let documents = response
             .result
             .into_iter()
-            .map(|scored_point| {
-                let payload = scored_point.payload;
+            .map(|point| {
+                let payload = point.payload;
 
                 let page_content = payload[&self.content_field].to_string();
                 let metadata =
                     serde_json::from_value(payload[&self.metadata_field].clone().into_json())
                         .unwrap();
-                let score = scored_point.score as f64;
-                Document {
-                    page_content,
-                    metadata,
-                    score,
-                }
+                let mut document = Document::new(page_content).with_metadata(metadata);
+                document.id = point.id.and_then(point_id_to_string);
+                document
             })
             .collect();
 
         Ok(documents)
     }
 
-    async fn delete(&self, ids: &[String], _opt: &QdrantOptions) -> Result<(), VectorStoreError> {
-        if ids.is_empty() {
-            return Ok(());
-        }
-        let point_ids: Vec<qdrant_client::qdrant::PointId> = ids
-            .iter()
-            .map(|s| qdrant_client::qdrant::PointId::from(s.as_str()))
-            .collect();
-        self.client
-            .delete_points(
-                DeletePointsBuilder::new(&self.collection_name)
-                    .points(PointsIdsList { ids: point_ids })
-                    .wait(true),
-            )
+    async fn embed_query(
+        &self,
+        query: &str,
+        opt: &QdrantOptions,
+    ) -> Result<Vec<f64>, VectorStoreError> {
+        let embedder = opt.embedder.as_ref().unwrap_or(&self.embedder);
+        Ok(embedder.embed_query(query).await?)
+    }
+
+    async fn similarity_search_by_vector_with_embeddings(
+        &self,
+        embedding: &[f64],
+        limit: usize,
+        opt: &QdrantOptions,
+    ) -> Result<Vec<(Document, Vec<f64>)>, VectorStoreError> {
+        self.search(embedding, limit, opt, true).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use testcontainers::{
+        core::{IntoContainerPort, WaitFor},
+        runners::AsyncRunner,
+        GenericImage,
+    };
+
+    use crate::vectorstore::{conformance, qdrant::StoreBuilder};
+
+    use super::*;
+
+    #[tokio::test]
+    #[ignore = "requires Docker"]
+    async fn test_metadata_filter_conformance() {
+        let container = GenericImage::new("qdrant/qdrant", "latest")
+            .with_exposed_port(6334.tcp())
+            .with_wait_for(WaitFor::message_on_stdout("Qdrant gRPC listening"))
+            .start()
             .await
-            .map_err(|e| VectorStoreError::from(e.to_string()))?;
-        Ok(())
+            .unwrap();
+        let port = container.get_host_port_ipv4(6334).await.unwrap();
+        let client = Qdrant::from_url(&format!("http://localhost:{}", port))
+            .build()
+            .unwrap();
+        let store = StoreBuilder::new()
+            .client(client)
+            .embedder(conformance::ConstantEmbedder)
+            .collection_name("conformance")
+            .build()
+            .await
+            .unwrap();
+        conformance::run(&store, |filter| {
+            let options = QdrantOptions::new();
+            match filter {
+                Some(filter) => options.with_metadata_filter(filter),
+                None => options,
+            }
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Docker"]
+    async fn test_upsert_conformance() {
+        let container = GenericImage::new("qdrant/qdrant", "latest")
+            .with_exposed_port(6334.tcp())
+            .with_wait_for(WaitFor::message_on_stdout("Qdrant gRPC listening"))
+            .start()
+            .await
+            .unwrap();
+        let port = container.get_host_port_ipv4(6334).await.unwrap();
+        let client = Qdrant::from_url(&format!("http://localhost:{}", port))
+            .build()
+            .unwrap();
+        let store = StoreBuilder::new()
+            .client(client)
+            .embedder(conformance::ConstantEmbedder)
+            .collection_name("conformance")
+            .build()
+            .await
+            .unwrap();
+        conformance::run_upsert(&store, QdrantOptions::new).await;
     }
 }
@@ -19,6 +19,9 @@ pub struct Store {
 
 pub type SqliteVssOptions = VecStoreOptions<Value>;
 
+/// Growth of k while a filtered k-NN search finds fewer matching rows than the limit
+const FILTER_FETCH_FACTOR: usize = 4;
+
 impl Store {
     pub async fn initialize(&self) -> Result<(), Box<dyn Error>> {
         self.create_table_if_not_exists().await?;
@@ -33,6 +36,7 @@ impl Store {
                 CREATE TABLE IF NOT EXISTS {table}
                 (
                   rowid INTEGER PRIMARY KEY AUTOINCREMENT,
+                  id TEXT,
                   text TEXT,
                   metadata BLOB,
                   text_embedding BLOB
@@ -43,6 +47,26 @@ impl Store {
         .execute(&self.pool)
         .await?;
 
+        // Tables created before documents had ids keep using their rowid as the id
+        let id_columns: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM pragma_table_info('{table}') WHERE name = 'id'"
+        ))
+        .fetch_one(&self.pool)
+        .await?;
+        if id_columns == 0 {
+            sqlx::query(&format!("ALTER TABLE {table} ADD COLUMN id TEXT"))
+                .execute(&self.pool)
+                .await?;
+            sqlx::query(&format!("UPDATE {table} SET id = CAST(rowid AS TEXT)"))
+                .execute(&self.pool)
+                .await?;
+        }
+        sqlx::query(&format!(
+            "CREATE UNIQUE INDEX IF NOT EXISTS {table}_id ON {table}(id)"
+        ))
+        .execute(&self.pool)
+        .await?;
+
         let dimensions = self.vector_dimensions;
         sqlx::query(&format!(
             r#"
@@ -69,8 +93,121 @@ impl Store {
         .execute(&self.pool)
         .await?;
 
+        sqlx::query(&format!(
+            r#"
+                CREATE TRIGGER IF NOT EXISTS delete_text_{table}
+                AFTER DELETE ON {table}
+                BEGIN
+                    DELETE FROM vss_{table} WHERE rowid = old.rowid
+                    ;
+                END;
+                "#
+        ))
+        .execute(&self.pool)
+        .await?;
+
         Ok(())
     }
+
+    /// Run a similarity search, returning the stored embeddings with the documents
+    async fn search(
+        &self,
+        query_vector: &[f64],
+        limit: usize,
+        opt: &SqliteVssOptions,
+    ) -> Result<Vec<(Document, Vec<f64>)>, VectorStoreError> {
+        let table = &self.table;
+
+        let query_vector = json!(query_vector);
+
+        let metadata_query = match &opt.metadata_filter {
+            Some(metadata_filter) => metadata_filter.to_sqlite_condition("e.metadata")?,
+            None => "TRUE".to_string(),
+        };
+
+        // The k-NN search runs before the filters, so fetch more rows until enough match
+        let filtered = opt.metadata_filter.is_some();
+        let mut k = match filtered {
+            true => limit * FILTER_FETCH_FACTOR,
+            false => limit,
+        };
+        let rows = loop {
+            let rows = sqlx::query(&format!(
+                r#"SELECT
+                        e.id,
+                        text,
+                        metadata,
+                        e.text_embedding,
+                        distance
+                    FROM {table} e
+                    INNER JOIN vss_{table} v on v.rowid = e.rowid
+                    WHERE vss_search(
+                      v.text_embedding,
+                      vss_search_params('{query_vector}', ?)
+                    ) AND {metadata_query}
+                    LIMIT ?"#
+            ))
+            .bind(k as i32)
+            .bind(limit as i32)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+            if !filtered || rows.len() >= limit {
+                break rows;
+            }
+            let count: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table}"))
+                .fetch_one(&self.pool)
+                .await
+                .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+            if k as i64 >= count {
+                break rows;
+            }
+            k = (k * FILTER_FETCH_FACTOR).min(count as usize);
+        };
+
+        let docs = rows
+            .into_iter()
+            .map(|row| {
+                let page_content: String = row
+                    .try_get("text")
+                    .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+                let metadata_json: Value = row
+                    .try_get("metadata")
+                    .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+                let distance: f64 = row
+                    .try_get("distance")
+                    .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+                // L2: lower is more similar. Use -distance as score (higher = more similar).
+                let score = -distance;
+                let id: Option<String> = row
+                    .try_get("id")
+                    .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+
+                let metadata = if let Value::Object(obj) = metadata_json {
+                    obj.into_iter().collect()
+                } else {
+                    HashMap::new()
+                };
+
+                let text_embedding: String = row
+                    .try_get("text_embedding")
+                    .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+                let embedding: Vec<f64> = serde_json::from_str(&text_embedding)?;
+
+                Ok((
+                    Document {
+                        page_content,
+                        metadata,
+                        score,
+                        id,
+                    },
+                    embedding,
+                ))
+            })
+            .collect::<Result<Vec<_>, VectorStoreError>>()?;
+
+        Ok(docs)
+    }
 }
 
 #[async_trait]
@@ -105,22 +242,32 @@ impl VectorStore for Store {
 
         for (doc, vector) in docs.iter().zip(vectors.iter()) {
             let text_embedding = json!(&vector);
-            let id = sqlx::query(&format!(
+            if let Some(id) = &doc.id {
+                sqlx::query(&format!("DELETE FROM {table} WHERE id = ?"))
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+            }
+            let id = doc
+                .id
+                .clone()
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+            sqlx::query(&format!(
                 r#"
                     INSERT INTO {table}
-                        (text, metadata, text_embedding)
+                        (id, text, metadata, text_embedding)
                     VALUES
-                        (?,?,?)"#
+                        (?,?,?,?)"#
             ))
+            .bind(&id)
             .bind(&doc.page_content)
             .bind(json!(&doc.metadata))
             .bind(text_embedding.to_string())
             .execute(&mut *tx)
             .await
-            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?
-            .last_insert_rowid();
-
-            ids.push(id.to_string());
+            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+            ids.push(id);
         }
 
         tx.commit()
@@ -134,43 +281,82 @@ impl VectorStore for Store {
         &self,
         query: &str,
         limit: usize,
-        _opt: &Self::Options,
+        opt: &Self::Options,
     ) -> Result<Vec<Document>, VectorStoreError> {
-        let table = &self.table;
+        let query_vector = self.embed_query(query, opt).await?;
+        let results = self.search(&query_vector, limit, opt).await?;
+        Ok(results.into_iter().map(|(doc, _)| doc).collect())
+    }
 
-        let query_vector = json!(self.embedder.embed_query(query).await?);
-
-        let rows = sqlx::query(&format!(
-            r#"SELECT
-                    text,
-                    metadata,
-                    distance
-                FROM {table} e
-                INNER JOIN vss_{table} v on v.rowid = e.rowid
-                WHERE vss_search(
-                  v.text_embedding,
-                  vss_search_params('{query_vector}', ?)
-                )
-                LIMIT ?"#
-        ))
-        .bind(limit as i32)
-        .bind(limit as i32)
-        .fetch_all(&self.pool)
-        .await
-        .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+    async fn delete(
+        &self,
+        ids: &[String],
+        _opt: &SqliteVssOptions,
+    ) -> Result<(), VectorStoreError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let placeholders: Vec<String> = (0..ids.len()).map(|_| "?".to_string()).collect();
+        let sql = format!(
+            "DELETE FROM {} WHERE id IN ({})",
+            self.table,
+            placeholders.join(", ")
+        );
+        let mut query = sqlx::query(&sql);
+        for id in ids {
+            query = query.bind(id);
+        }
+        query
+            .execute(&self.pool)
+            .await
+            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+        Ok(())
+    }
 
-        let docs = rows
-            .into_iter()
+    async fn similarity_search_by_vector(
+        &self,
+        embedding: &[f64],
+        limit: usize,
+        opt: &SqliteVssOptions,
+    ) -> Result<Vec<Document>, VectorStoreError> {
+        let results = self.search(embedding, limit, opt).await?;
+        Ok(results.into_iter().map(|(doc, _)| doc).collect())
+    }
+
+    async fn get_by_ids(
+        &self,
+        ids: &[String],
+        _opt: &SqliteVssOptions,
+    ) -> Result<Vec<Document>, VectorStoreError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders: Vec<String> = (0..ids.len()).map(|_| "?".to_string()).collect();
+        let sql = format!(
+            "SELECT id, text, metadata FROM {} WHERE id IN ({})",
+            self.table,
+            placeholders.join(", ")
+        );
+        let mut query = sqlx::query(&sql);
+        for id in ids {
+            query = query.bind(id);
+        }
+        let rows = query
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+
+        rows.into_iter()
             .map(|row| {
+                let id: String = row
+                    .try_get("id")
+                    .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
                 let page_content: String = row
                     .try_get("text")
                     .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
                 let metadata_json: Value = row
                     .try_get("metadata")
                     .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
-                let score: f64 = row
-                    .try_get("distance")
-                    .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
 
                 let metadata = if let Value::Object(obj) = metadata_json {
                     obj.into_iter().collect()
@@ -178,46 +364,88 @@ impl VectorStore for Store {
                     HashMap::new()
                 };
 
-                Ok(Document {
-                    page_content,
-                    metadata,
-                    score,
-                })
+                Ok(Document::new(page_content)
+                    .with_metadata(metadata)
+                    .with_id(id))
             })
-            .collect::<Result<Vec<Document>, VectorStoreError>>()?;
+            .collect()
+    }
 
-        Ok(docs)
+    async fn embed_query(
+        &self,
+        query: &str,
+        opt: &SqliteVssOptions,
+    ) -> Result<Vec<f64>, VectorStoreError> {
+        let embedder = opt.embedder.as_ref().unwrap_or(&self.embedder);
+        Ok(embedder.embed_query(query).await?)
     }
 
-    async fn delete(
+    async fn similarity_search_by_vector_with_embeddings(
         &self,
-        ids: &[String],
-        _opt: &SqliteVssOptions,
-    ) -> Result<(), VectorStoreError> {
-        if ids.is_empty() {
-            return Ok(());
-        }
-        let rowids: Vec<i64> = ids
-            .iter()
-            .map(|s| s.parse::<i64>())
-            .collect::<Result<_, _>>()
-            .map_err(|e: std::num::ParseIntError| {
-                VectorStoreError::InvalidParameter(e.to_string())
-            })?;
-        let placeholders: Vec<String> = (0..rowids.len()).map(|_| "?".to_string()).collect();
-        let sql = format!(
-            "DELETE FROM {} WHERE rowid IN ({})",
-            self.table,
-            placeholders.join(", ")
-        );
-        let mut query = sqlx::query(&sql);
-        for rid in &rowids {
-            query = query.bind(rid);
-        }
-        query
-            .execute(&self.pool)
+        embedding: &[f64],
+        limit: usize,
+        opt: &SqliteVssOptions,
+    ) -> Result<Vec<(Document, Vec<f64>)>, VectorStoreError> {
+        self.search(embedding, limit, opt).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+
+    use crate::vectorstore::{conformance, sqlite_vss::StoreBuilder, MetadataFilter};
+
+    use super::*;
+
+    /// Store on its own in-memory database, loading the extensions from the library path
+    async fn store() -> Store {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(
+                SqliteConnectOptions::from_str("sqlite::memory:")
+                    .unwrap()
+                    .extension("vector0")
+                    .extension("vss0"),
+            )
             .await
-            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
-        Ok(())
+            .unwrap();
+        let store = StoreBuilder::new()
+            .pool(pool)
+            .vector_dimensions(3)
+            .embedder(conformance::ConstantEmbedder)
+            .build()
+            .await
+            .unwrap();
+        store.initialize().await.unwrap();
+        store
+    }
+
+    fn options(filter: Option<MetadataFilter>) -> SqliteVssOptions {
+        let options = SqliteVssOptions::default();
+        match filter {
+            Some(filter) => options.with_metadata_filter(filter),
+            None => options,
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires the vector0 and vss0 SQLite extensions"]
+    async fn test_metadata_filter_conformance() {
+        conformance::run(&store().await, options).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "requires the vector0 and vss0 SQLite extensions"]
+    async fn test_filter_beyond_top_k() {
+        conformance::run_filter_beyond_top_k(&store().await, options).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "requires the vector0 and vss0 SQLite extensions"]
+    async fn test_upsert_conformance() {
+        conformance::run_upsert(&store().await, SqliteVssOptions::default).await;
     }
 }
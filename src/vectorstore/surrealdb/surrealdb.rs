@@ -8,7 +8,9 @@ use surrealdb::{Connection, Surreal};
 use crate::{
     embedding::embedder_trait::Embedder,
     schemas::Document,
-    vectorstore::{VecStoreOptions, VectorStore, VectorStoreError},
+    vectorstore::{
+        FilterValue, MetadataFilter, MetadataPath, VecStoreOptions, VectorStore, VectorStoreError,
+    },
 };
 
 // INSERT INTO documents {
@@ -42,6 +44,159 @@ impl<C: Connection> Store<C> {
             .unwrap_or_else(|| "collection".to_string())
     }
 
+    /// Translate a metadata filter to a SurrealQL condition, collecting its parameters
+    fn translate_filter(
+        filter: &MetadataFilter,
+        params: &mut Vec<(String, Value)>,
+    ) -> Result<String, VectorStoreError> {
+        let field = |path: &MetadataPath| -> Result<String, VectorStoreError> {
+            let mut field = String::from("metadata");
+            for segment in path.segments() {
+                if segment.contains('`') {
+                    return Err(VectorStoreError::UnsupportedFilter(format!(
+                        "metadata key with a backtick: {}",
+                        segment
+                    )));
+                }
+                field.push_str(&format!(".`{}`", segment));
+            }
+            Ok(field)
+        };
+        let mut compare = |path: &MetadataPath, op: &str, value: &FilterValue| {
+            let kind = match value {
+                FilterValue::Bool(_) => "bool",
+                FilterValue::String(_) => "string",
+                _ => "number",
+            };
+            let param = format!("filter_{}", params.len());
+            params.push((param.clone(), value.to_json()));
+            let field = field(path)?;
+            Ok::<_, VectorStoreError>(format!(
+                "(type::is::{kind}({field}) AND {field} {op} ${param})"
+            ))
+        };
+
+        match filter {
+            MetadataFilter::Eq(path, value) => compare(path, "=", value),
+            MetadataFilter::Ne(path, value) => Ok(format!("!{}", compare(path, "=", value)?)),
+            MetadataFilter::In(path, values) => {
+                let conditions = values
+                    .iter()
+                    .map(|value| compare(path, "=", value))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(join_conditions(conditions, "OR", "false"))
+            }
+            MetadataFilter::Range(path, range) => {
+                let conditions = range
+                    .bounds()
+                    .map(|(op, bound)| {
+                        let op = match op {
+                            "gt" => ">",
+                            "gte" => ">=",
+                            "lt" => "<",
+                            _ => "<=",
+                        };
+                        compare(path, op, bound)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(join_conditions(conditions, "AND", "true"))
+            }
+            MetadataFilter::Exists(path) => {
+                let field = field(path)?;
+                Ok(format!("({field} != NONE AND {field} != NULL)"))
+            }
+            MetadataFilter::And(filters) => {
+                let conditions = filters
+                    .iter()
+                    .map(|f| Self::translate_filter(f, params))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(join_conditions(conditions, "AND", "true"))
+            }
+            MetadataFilter::Or(filters) => {
+                let conditions = filters
+                    .iter()
+                    .map(|f| Self::translate_filter(f, params))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(join_conditions(conditions, "OR", "false"))
+            }
+            MetadataFilter::Not(filter) => {
+                Ok(format!("!({})", Self::translate_filter(filter, params)?))
+            }
+        }
+    }
+
+    /// Run a similarity search, also returning the stored embeddings when asked
+    async fn search(
+        &self,
+        query_vector: &[f64],
+        limit: usize,
+        opt: &VecStoreOptions<Value>,
+        with_embeddings: bool,
+    ) -> Result<Vec<(Document, Vec<f64>)>, VectorStoreError> {
+        let collection_name = &self.collection_name;
+        let collection_table_name = self.get_collection_table_name();
+
+        let embedding_field = if with_embeddings { ", embedding" } else { "" };
+
+        let collection_predicate = match &self.collection_table_name {
+            Some(_) => " AND metadata[$collection_metadata_key] = $collection_name ",
+            None => "",
+        };
+
+        let mut params = Vec::new();
+        let metadata_predicate = match &opt.metadata_filter {
+            Some(metadata_filter) => format!(
+                " AND {} ",
+                Self::translate_filter(metadata_filter, &mut params)?
+            ),
+            None => String::new(),
+        };
+
+        let mut db_query = self
+            .db
+            .query(format!(
+                r#"
+        SELECT record::id(id) as id, text, metadata{embedding_field},
+        vector::similarity::cosine(embedding, $embedding) as similarity
+        FROM {collection_table_name}
+        WHERE vector::similarity::cosine(embedding, $embedding) >= $score_threshold {collection_predicate}{metadata_predicate}
+        ORDER BY similarity DESC LIMIT $k
+            "#
+            ))
+            .bind(("collection_name", collection_name.to_owned()))
+            .bind(("collection_metadata_key", self.get_collection_metdata_key().to_owned()))
+            .bind(("score_threshold", opt.score_threshold.unwrap_or(0.0)))
+            .bind(("k", limit))
+            .bind(("embedding", query_vector.to_owned()));
+        for param in params {
+            db_query = db_query.bind(param);
+        }
+        let mut result = db_query
+            .await
+            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?
+            .check()
+            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+
+        let query_result: Vec<Row> = result
+            .take(0)
+            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+
+        let documents = query_result
+            .into_iter()
+            .map(|row| {
+                let document = Document {
+                    page_content: row.text,
+                    metadata: row.metadata,
+                    score: row.similarity,
+                    id: Some(row.id),
+                };
+                (document, row.embedding)
+            })
+            .collect();
+
+        Ok(documents)
+    }
+
     pub async fn initialize(&self) -> Result<(), Box<dyn Error>> {
         self.create_collection_table_if_not_exists().await?;
         Ok(())
@@ -112,63 +267,44 @@ impl<C: Connection> VectorStore for Store<C> {
         let mut ids = Vec::with_capacity(docs.len());
 
         for (doc, vector) in docs.iter().zip(vectors.iter()) {
-            match &self.collection_table_name {
-                Some(collection_table_name) => {
-                    let mut metadata: HashMap<String, Value> = doc.metadata.clone();
-                    metadata.insert(
-                        self.get_collection_metdata_key(),
-                        Value::String(self.collection_name.to_owned()),
-                    );
-
-                    let mut result = self
-                        .db
-                        .query(format!(
-                            r#"CREATE {collection_table_name} CONTENT {{
-                                text: $text,
-                                embedding: $embedding,
-                                metadata: $metadata,
-                            }}
-                            RETURN record::id(id) as id"#
-                        ))
-                        .bind(("text", doc.page_content.to_owned()))
-                        .bind(("embedding", vector.to_owned()))
-                        .bind(("metadata", metadata.to_owned()))
-                        .await
-                        .map_err(|e| VectorStoreError::Unknown(e.to_string()))?
-                        .check()
-                        .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
-
-                    let id: Option<String> = result
-                        .take("id")
-                        .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
-                    ids.push(id.unwrap());
-                }
-                None => {
-                    let collection_table_name = &self.collection_name;
-                    let mut result = self
-                        .db
-                        .query(format!(
-                            r#"CREATE {collection_table_name} CONTENT {{
-                                text: $text,
-                                embedding: $embedding,
-                                metadata: $metadata,
-                            }}
-                            RETURN record::id(id) as id"#
-                        ))
-                        .bind(("text", doc.page_content.to_owned()))
-                        .bind(("embedding", vector.to_owned()))
-                        .bind(("metadata", doc.metadata.to_owned()))
-                        .await
-                        .map_err(|e| VectorStoreError::Unknown(e.to_string()))?
-                        .check()
-                        .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
-
-                    let id: Option<String> = result
-                        .take("id")
-                        .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
-                    ids.push(id.unwrap());
-                }
+            let collection_table_name = self.get_collection_table_name();
+            let mut metadata: HashMap<String, Value> = doc.metadata.clone();
+            if self.collection_table_name.is_some() {
+                metadata.insert(
+                    self.get_collection_metdata_key(),
+                    Value::String(self.collection_name.to_owned()),
+                );
             }
+
+            // Documents with an id replace the record with that id
+            let target = match &doc.id {
+                Some(_) => format!("UPSERT type::thing('{collection_table_name}', $id)"),
+                None => format!("CREATE {collection_table_name}"),
+            };
+
+            let mut result = self
+                .db
+                .query(format!(
+                    r#"{target} CONTENT {{
+                        text: $text,
+                        embedding: $embedding,
+                        metadata: $metadata,
+                    }}
+                    RETURN record::id(id) as id"#
+                ))
+                .bind(("id", doc.id.to_owned()))
+                .bind(("text", doc.page_content.to_owned()))
+                .bind(("embedding", vector.to_owned()))
+                .bind(("metadata", metadata))
+                .await
+                .map_err(|e| VectorStoreError::Unknown(e.to_string()))?
+                .check()
+                .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+
+            let id: Option<String> = result
+                .take("id")
+                .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+            ids.push(id.unwrap());
         }
 
         Ok(ids)
@@ -180,72 +316,101 @@ impl<C: Connection> VectorStore for Store<C> {
         limit: usize,
         opt: &Self::Options,
     ) -> Result<Vec<Document>, VectorStoreError> {
-        let collection_name = &self.collection_name;
-        let collection_table_name = self.get_collection_table_name();
+        let query_vector = self.embed_query(query, opt).await?;
+        let results = self.search(&query_vector, limit, opt, false).await?;
+        Ok(results.into_iter().map(|(doc, _)| doc).collect())
+    }
 
-        let query_vector = self.embedder.embed_query(query).await?;
+    async fn delete(
+        &self,
+        ids: &[String],
+        _opt: &VecStoreOptions<Value>,
+    ) -> Result<(), VectorStoreError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let table = self.get_collection_table_name();
+        let ids_vec: Vec<String> = ids.to_vec();
+        self.db
+            .query(format!(
+                "DELETE FROM {table} WHERE record::id(id) INSIDE $ids"
+            ))
+            .bind(("ids", ids_vec))
+            .await
+            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?
+            .check()
+            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+        Ok(())
+    }
 
-        let collection_predicate = match &self.collection_table_name {
-            Some(_) => " AND metadata[$collection_metadata_key] = $collection_name ",
-            None => "",
-        };
+    async fn similarity_search_by_vector(
+        &self,
+        embedding: &[f64],
+        limit: usize,
+        opt: &Self::Options,
+    ) -> Result<Vec<Document>, VectorStoreError> {
+        let results = self.search(embedding, limit, opt, false).await?;
+        Ok(results.into_iter().map(|(doc, _)| doc).collect())
+    }
 
+    async fn get_by_ids(
+        &self,
+        ids: &[String],
+        _opt: &Self::Options,
+    ) -> Result<Vec<Document>, VectorStoreError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let table = self.get_collection_table_name();
         let mut result = self
             .db
             .query(format!(
-                r#"
-        SELECT record::id(id) as id, text, metadata,
-        vector::similarity::cosine(embedding, $embedding) as similarity
-        FROM {collection_table_name}
-        WHERE vector::similarity::cosine(embedding, $embedding) >= $score_threshold {collection_predicate}
-        ORDER BY similarity DESC LIMIT $k
-            "#
+                "SELECT record::id(id) as id, text, metadata FROM {table} WHERE record::id(id) INSIDE $ids"
             ))
-            .bind(("collection_name", collection_name.to_owned()))
-            .bind(("collection_metadata_key", self.get_collection_metdata_key().to_owned()))
-            .bind(("score_threshold", opt.score_threshold.unwrap_or(0.0)))
-            .bind(("k", limit))
-            .bind(("embedding", query_vector.to_owned()))
+            .bind(("ids", ids.to_vec()))
             .await
             .map_err(|e| VectorStoreError::Unknown(e.to_string()))?
             .check()
             .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
 
-        let query_result: Vec<Row> = result
+        let rows: Vec<Row> = result
             .take(0)
             .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
 
-        let documents = query_result
+        Ok(rows
             .into_iter()
-            .map(|row| Document {
-                page_content: row.text,
-                metadata: row.metadata,
-                score: row.similarity,
+            .map(|row| {
+                Document::new(row.text)
+                    .with_metadata(row.metadata)
+                    .with_id(row.id)
             })
-            .collect();
+            .collect())
+    }
 
-        Ok(documents)
+    async fn embed_query(
+        &self,
+        query: &str,
+        opt: &Self::Options,
+    ) -> Result<Vec<f64>, VectorStoreError> {
+        let embedder = opt.embedder.as_ref().unwrap_or(&self.embedder);
+        Ok(embedder.embed_query(query).await?)
     }
 
-    async fn delete(
+    async fn similarity_search_by_vector_with_embeddings(
         &self,
-        ids: &[String],
-        _opt: &VecStoreOptions<Value>,
-    ) -> Result<(), VectorStoreError> {
-        if ids.is_empty() {
-            return Ok(());
-        }
-        let table = self.get_collection_table_name();
-        let ids_vec: Vec<String> = ids.to_vec();
-        self.db
-            .query(format!("DELETE FROM {table} WHERE id INSIDE $ids"))
-            .bind(("ids", ids_vec))
-            .await
-            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?
-            .check()
-            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
-        Ok(())
+        embedding: &[f64],
+        limit: usize,
+        opt: &Self::Options,
+    ) -> Result<Vec<(Document, Vec<f64>)>, VectorStoreError> {
+        self.search(embedding, limit, opt, true).await
+    }
+}
+
+fn join_conditions(conditions: Vec<String>, operator: &str, empty: &str) -> String {
+    if conditions.is_empty() {
+        return empty.to_string();
     }
+    format!("({})", conditions.join(&format!(" {} ", operator)))
 }
 
 #[derive(Deserialize, Debug)]
@@ -253,5 +418,45 @@ struct Row {
     id: String,
     text: String,
     metadata: HashMap<String, Value>,
+    #[serde(default)]
     similarity: f64,
+    #[serde(default)]
+    embedding: Vec<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use surrealdb::engine::local::{Db, Mem};
+
+    use crate::vectorstore::{conformance, surrealdb::StoreBuilder};
+
+    use super::*;
+
+    /// Store on an in-memory database
+    async fn store() -> Store<Db> {
+        let db = Surreal::new::<Mem>(()).await.unwrap();
+        db.use_ns("test").use_db("test").await.unwrap();
+        let store = StoreBuilder::new()
+            .db(db)
+            .vector_dimensions(3)
+            .embedder(conformance::ConstantEmbedder)
+            .build()
+            .await
+            .unwrap();
+        store.initialize().await.unwrap();
+        store
+    }
+
+    fn options(filter: Option<MetadataFilter>) -> VecStoreOptions<Value> {
+        let options = VecStoreOptions::default();
+        match filter {
+            Some(filter) => options.with_metadata_filter(filter),
+            None => options,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metadata_filter_conformance() {
+        conformance::run(&store().await, options).await;
+    }
 }
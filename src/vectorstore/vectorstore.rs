@@ -3,7 +3,7 @@ use async_trait::async_trait;
 use crate::error::{RetrieverError, VectorStoreError};
 use crate::schemas::{self, Document};
 
-use super::VecStoreOptions;
+use super::{check_lambda, maximal_marginal_relevance, MetadataFilter, VecStoreOptions};
 
 // VectorStore is the trait for saving and querying documents in the
 // form of vector embeddings.
@@ -11,12 +11,20 @@ use super::VecStoreOptions;
 pub trait VectorStore: Send + Sync {
     type Options: Send + Sync;
 
+    /// Add documents, returning their ids in order
+    ///
+    /// A document with an [`id`](Document::id) replaces any stored document with the same id,
+    /// so adding the same documents again is idempotent. Documents without one get a new id.
     async fn add_documents(
         &self,
         docs: &[Document],
         opt: &Self::Options,
     ) -> Result<Vec<String>, VectorStoreError>;
 
+    /// Search for the documents most similar to `query`
+    ///
+    /// Each document's `score` is a similarity, so higher is more similar; its scale depends
+    /// on the store's distance metric.
     async fn similarity_search(
         &self,
         query: &str,
@@ -24,9 +32,120 @@ pub trait VectorStore: Send + Sync {
         opt: &Self::Options,
     ) -> Result<Vec<Document>, VectorStoreError>;
 
+    /// [`similarity_search`](VectorStore::similarity_search) returning each document with its
+    /// score
+    async fn similarity_search_with_score(
+        &self,
+        query: &str,
+        limit: usize,
+        opt: &Self::Options,
+    ) -> Result<Vec<(Document, f64)>, VectorStoreError> {
+        let docs = self.similarity_search(query, limit, opt).await?;
+        Ok(docs
+            .into_iter()
+            .map(|doc| {
+                let score = doc.score;
+                (doc, score)
+            })
+            .collect())
+    }
+
+    /// Search with a precomputed embedding
+    async fn similarity_search_by_vector(
+        &self,
+        _embedding: &[f64],
+        _limit: usize,
+        _opt: &Self::Options,
+    ) -> Result<Vec<Document>, VectorStoreError> {
+        Err(VectorStoreError::OperationNotSupported(
+            "search by vector".to_string(),
+        ))
+    }
+
+    /// Fetch stored documents by id, skipping ids that are not found
+    ///
+    /// The documents come back with their `id` set, in no particular order.
+    async fn get_by_ids(
+        &self,
+        _ids: &[String],
+        _opt: &Self::Options,
+    ) -> Result<Vec<Document>, VectorStoreError> {
+        Err(VectorStoreError::OperationNotSupported(
+            "getting documents by id".to_string(),
+        ))
+    }
+
     /// Delete documents by IDs. Returns `VectorStoreError::DeleteNotSupported`
-    /// for stores that do not support deletion.
+    /// for stores that do not support deletion, even when `ids` is empty; otherwise
+    /// deleting no ids does nothing.
     async fn delete(&self, ids: &[String], _opt: &Self::Options) -> Result<(), VectorStoreError>;
+
+    /// Embed a query with the embedder `similarity_search` would use
+    async fn embed_query(
+        &self,
+        _query: &str,
+        _opt: &Self::Options,
+    ) -> Result<Vec<f64>, VectorStoreError> {
+        Err(VectorStoreError::OperationNotSupported(
+            "embedding queries".to_string(),
+        ))
+    }
+
+    /// Search with a precomputed embedding, returning each document with its stored embedding
+    async fn similarity_search_by_vector_with_embeddings(
+        &self,
+        _embedding: &[f64],
+        _limit: usize,
+        _opt: &Self::Options,
+    ) -> Result<Vec<(Document, Vec<f64>)>, VectorStoreError> {
+        Err(VectorStoreError::OperationNotSupported(
+            "search returning embeddings".to_string(),
+        ))
+    }
+
+    /// Fetch `fetch_k` candidates and return `k` of them by maximal marginal relevance
+    ///
+    /// `lambda` of 1 ranks by relevance alone and 0 by diversity alone; 0.5 is a common
+    /// default. Any other value outside `0..=1` is an
+    /// [`InvalidParameter`](VectorStoreError::InvalidParameter). See
+    /// [`maximal_marginal_relevance`].
+    async fn max_marginal_relevance_search(
+        &self,
+        query: &str,
+        k: usize,
+        fetch_k: usize,
+        lambda: f64,
+        opt: &Self::Options,
+    ) -> Result<Vec<Document>, VectorStoreError> {
+        check_lambda(lambda)?;
+        let embedding = self.embed_query(query, opt).await?;
+        self.max_marginal_relevance_search_by_vector(&embedding, k, fetch_k, lambda, opt)
+            .await
+    }
+
+    /// [`max_marginal_relevance_search`](VectorStore::max_marginal_relevance_search) with a
+    /// precomputed query embedding
+    async fn max_marginal_relevance_search_by_vector(
+        &self,
+        embedding: &[f64],
+        k: usize,
+        fetch_k: usize,
+        lambda: f64,
+        opt: &Self::Options,
+    ) -> Result<Vec<Document>, VectorStoreError> {
+        check_lambda(lambda)?;
+        let candidates = self
+            .similarity_search_by_vector_with_embeddings(embedding, fetch_k.max(k), opt)
+            .await?;
+        let embeddings: Vec<&[f64]> = candidates.iter().map(|(_, e)| e.as_slice()).collect();
+        let selected = maximal_marginal_relevance(embedding, &embeddings, k, lambda);
+        let mut candidates: Vec<Option<Document>> =
+            candidates.into_iter().map(|(doc, _)| Some(doc)).collect();
+        Ok(selected
+            .into_iter()
+            .filter_map(|i| candidates[i].take())
+            .collect())
+    }
 }
 
 impl<VS, F> From<VS> for Box<dyn VectorStore<Options = F>>
@@ -62,11 +181,24 @@ macro_rules! similarity_search {
     };
 }
 
+/// How [`Retriever`] queries its vector store
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchType {
+    Similarity,
+    /// Maximal marginal relevance over `fetch_k` candidates, see
+    /// [`VectorStore::max_marginal_relevance_search`]
+    Mmr {
+        fetch_k: usize,
+        lambda: f64,
+    },
+}
+
 // Retriever is a retriever for vector stores.
 pub struct Retriever<F> {
     vstore: Box<dyn VectorStore<Options = VecStoreOptions<F>>>,
     num_docs: usize,
     options: VecStoreOptions<F>,
+    search_type: SearchType,
 }
 impl<F> Retriever<F> {
     pub fn new<V: Into<Box<dyn VectorStore<Options = VecStoreOptions<F>>>>>(
@@ -77,6 +209,7 @@ impl<F> Retriever<F> {
             vstore: vstore.into(),
             num_docs,
             options: VecStoreOptions::<F>::new(),
+            search_type: SearchType::Similarity,
         }
     }
 
@@ -84,14 +217,44 @@ impl<F> Retriever<F> {
         self.options = options;
         self
     }
+
+    pub fn with_metadata_filter(mut self, filter: MetadataFilter) -> Self {
+        self.options.metadata_filter = Some(filter);
+        self
+    }
+
+    pub fn with_search_type(mut self, search_type: SearchType) -> Self {
+        self.search_type = search_type;
+        self
+    }
+
+    /// Search by maximal marginal relevance over `fetch_k` candidates
+    pub fn with_mmr(self, fetch_k: usize, lambda: f64) -> Self {
+        self.with_search_type(SearchType::Mmr { fetch_k, lambda })
+    }
 }
 
 #[async_trait]
 impl<O: Sync + Send> schemas::Retriever for Retriever<O> {
     async fn get_relevant_documents(&self, query: &str) -> Result<Vec<Document>, RetrieverError> {
-        self.vstore
-            .similarity_search(query, self.num_docs, &self.options)
-            .await
-            .map_err(|e| e.into())
+        match self.search_type {
+            SearchType::Similarity => {
+                self.vstore
+                    .similarity_search(query, self.num_docs, &self.options)
+                    .await
+            }
+            SearchType::Mmr { fetch_k, lambda } => {
+                self.vstore
+                    .max_marginal_relevance_search(
+                        query,
+                        self.num_docs,
+                        fetch_k,
+                        lambda,
+                        &self.options,
+                    )
+                    .await
+            }
+        }
+        .map_err(|e| e.into())
     }
 }
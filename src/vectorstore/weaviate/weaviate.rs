@@ -21,6 +21,80 @@ pub struct Store {
 
 pub type WeaviateOptions = VecStoreOptions<Value>;
 
+/// Growth of the fetch limit while a metadata filter, run on the results, matches too few
+const METADATA_FILTER_FETCH_FACTOR: usize = 10;
+
+/// Weaviate's default cap on the objects a single query returns
+const QUERY_MAXIMUM_RESULTS: usize = 10_000;
+
+impl Store {
+    /// Run a `nearVector` query, optionally asking for the stored vectors
+    async fn search(
+        &self,
+        query_vector: &[f64],
+        limit: usize,
+        opt: &WeaviateOptions,
+        with_embeddings: bool,
+    ) -> Result<Vec<(Document, Vec<f64>)>, VectorStoreError> {
+        let near_vector_str =
+            serde_json::to_string(&serde_json::json!({ "vector": query_vector }))?;
+        let score_threshold = opt
+            .score_threshold
+            .map(f64::from)
+            .unwrap_or(f64::NEG_INFINITY);
+        // Metadata is stored as a JSON string, so metadata filters run on the results,
+        // fetching more objects until enough match
+        let mut fetch_limit = match opt.metadata_filter {
+            Some(_) => limit * METADATA_FILTER_FETCH_FACTOR,
+            None => limit,
+        };
+        loop {
+            let mut get = GetBuilder::new(&self.class_name, vec!["content", "metadata"])
+                .with_limit(fetch_limit as u32)
+                .with_near_vector(&near_vector_str)
+                .with_additional(if with_embeddings {
+                    vec!["id", "distance", "certainty", "vector"]
+                } else {
+                    vec!["id", "distance", "certainty"]
+                });
+            if let Some(ref w) = opt.filters {
+                if let Some(s) = w.as_str() {
+                    get = get.with_where(s);
+                }
+            }
+            let query_result = get.build();
+            let raw = self
+                .client
+                .query
+                .get(query_result)
+                .await
+                .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+            let results = parse_get_response(&raw, &self.class_name)?;
+            // Results come nearest first, so a short page or one below the threshold is the last
+            let exhausted = results.len() < fetch_limit
+                || fetch_limit >= QUERY_MAXIMUM_RESULTS
+                || results
+                    .last()
+                    .is_some_and(|(doc, _)| doc.score < score_threshold);
+            let docs: Vec<(Document, Vec<f64>)> = results
+                .into_iter()
+                .filter(|(doc, _)| {
+                    doc.score >= score_threshold
+                        && opt
+                            .metadata_filter
+                            .as_ref()
+                            .is_none_or(|f| f.matches(&doc.metadata))
+                })
+                .take(limit)
+                .collect();
+            if opt.metadata_filter.is_none() || docs.len() >= limit || exhausted {
+                return Ok(docs);
+            }
+            fetch_limit = (fetch_limit * METADATA_FILTER_FETCH_FACTOR).min(QUERY_MAXIMUM_RESULTS);
+        }
+    }
+}
+
 #[async_trait]
 impl VectorStore for Store {
     type Options = WeaviateOptions;
@@ -39,9 +113,21 @@ impl VectorStore for Store {
                 "Number of vectors and documents do not match".to_string(),
             ));
         }
+        // Objects can't be created over an existing id, so replaced ones are deleted first
+        let given_ids: Vec<String> = docs.iter().filter_map(|doc| doc.id.clone()).collect();
+        let existing: Vec<String> = self
+            .get_by_ids(&given_ids, opt)
+            .await?
+            .into_iter()
+            .filter_map(|doc| doc.id)
+            .collect();
+        self.delete(&existing, opt).await?;
         let mut ids = Vec::with_capacity(docs.len());
         for (doc, vec_f64) in docs.iter().zip(vectors.into_iter()) {
-            let id = Uuid::new_v4();
+            let id = match &doc.id {
+                Some(id) => parse_uuid(id)?,
+                None => Uuid::new_v4(),
+            };
             ids.push(id.to_string());
             let metadata_json =
                 serde_json::to_string(&doc.metadata).unwrap_or_else(|_| "{}".to_string());
@@ -68,31 +154,9 @@ impl VectorStore for Store {
         limit: usize,
         opt: &WeaviateOptions,
     ) -> Result<Vec<Document>, VectorStoreError> {
-        let embedder = opt.embedder.as_ref().unwrap_or(&self.embedder);
-        let qv = embedder.embed_query(query).await?;
-        let near_vector_str = serde_json::to_string(&serde_json::json!({ "vector": qv }))?;
-        let mut get = GetBuilder::new(&self.class_name, vec!["content", "metadata"])
-            .with_limit(limit as u32)
-            .with_near_vector(&near_vector_str)
-            .with_additional(vec!["distance", "certainty"]);
-        if let Some(ref w) = opt.filters {
-            if let Some(s) = w.as_str() {
-                get = get.with_where(s);
-            }
-        }
-        let query_result = get.build();
-        let raw = self
-            .client
-            .query
-            .get(query_result)
-            .await
-            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
-        let score_threshold = opt
-            .score_threshold
-            .map(f64::from)
-            .unwrap_or(f64::NEG_INFINITY);
-        let docs = parse_get_response(&raw, &self.class_name, score_threshold)?;
-        Ok(docs)
+        let query_vector = self.embed_query(query, opt).await?;
+        let results = self.search(&query_vector, limit, opt, false).await?;
+        Ok(results.into_iter().map(|(doc, _)| doc).collect())
     }
 
     async fn delete(&self, ids: &[String], _opt: &WeaviateOptions) -> Result<(), VectorStoreError> {
@@ -100,9 +164,7 @@ impl VectorStore for Store {
             return Ok(());
         }
         for id in ids {
-            let uuid = Uuid::parse_str(id).map_err(|e| {
-                VectorStoreError::InvalidParameter(format!("invalid uuid {}: {}", id, e))
-            })?;
+            let uuid = parse_uuid(id)?;
             self.client
                 .objects
                 .delete(&self.class_name, &uuid, None, None)
@@ -111,13 +173,82 @@ impl VectorStore for Store {
         }
         Ok(())
     }
+
+    async fn similarity_search_by_vector(
+        &self,
+        embedding: &[f64],
+        limit: usize,
+        opt: &WeaviateOptions,
+    ) -> Result<Vec<Document>, VectorStoreError> {
+        let results = self.search(embedding, limit, opt, false).await?;
+        Ok(results.into_iter().map(|(doc, _)| doc).collect())
+    }
+
+    async fn get_by_ids(
+        &self,
+        ids: &[String],
+        _opt: &WeaviateOptions,
+    ) -> Result<Vec<Document>, VectorStoreError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let operands = ids
+            .iter()
+            .map(|id| {
+                let uuid = parse_uuid(id)?;
+                Ok(format!(
+                    r#"{{path: ["id"], operator: Equal, valueText: "{}"}}"#,
+                    uuid
+                ))
+            })
+            .collect::<Result<Vec<_>, VectorStoreError>>()?;
+        let where_filter = format!("{{operator: Or, operands: [{}]}}", operands.join(", "));
+        let query_result = GetBuilder::new(&self.class_name, vec!["content", "metadata"])
+            .with_limit(ids.len() as u32)
+            .with_where(&where_filter)
+            .with_additional(vec!["id"])
+            .build();
+        let raw = self
+            .client
+            .query
+            .get(query_result)
+            .await
+            .map_err(|e| VectorStoreError::Unknown(e.to_string()))?;
+        let docs = parse_get_response(&raw, &self.class_name)?
+            .into_iter()
+            .map(|(doc, _)| doc)
+            .collect();
+        Ok(docs)
+    }
+
+    async fn embed_query(
+        &self,
+        query: &str,
+        opt: &WeaviateOptions,
+    ) -> Result<Vec<f64>, VectorStoreError> {
+        let embedder = opt.embedder.as_ref().unwrap_or(&self.embedder);
+        Ok(embedder.embed_query(query).await?)
+    }
+
+    async fn similarity_search_by_vector_with_embeddings(
+        &self,
+        embedding: &[f64],
+        limit: usize,
+        opt: &WeaviateOptions,
+    ) -> Result<Vec<(Document, Vec<f64>)>, VectorStoreError> {
+        self.search(embedding, limit, opt, true).await
+    }
+}
+
+fn parse_uuid(id: &str) -> Result<Uuid, VectorStoreError> {
+    Uuid::parse_str(id)
+        .map_err(|e| VectorStoreError::InvalidParameter(format!("invalid uuid {}: {}", id, e)))
 }
 
 fn parse_get_response(
     raw: &Value,
     class_name: &str,
-    score_threshold: f64,
-) -> Result<Vec<Document>, VectorStoreError> {
+) -> Result<Vec<(Document, Vec<f64>)>, VectorStoreError> {
     let get = raw
         .get("data")
         .and_then(|d| d.get("Get"))
@@ -148,13 +279,98 @@ fn parse_get_response(
                     .or_else(|| a.get("distance").and_then(|d| d.as_f64()).map(|d| 1.0 - d))
             })
             .unwrap_or(0.0);
-        if score >= score_threshold {
-            out.push(Document {
+        let id = obj
+            .get("_additional")
+            .and_then(|a| a.get("id"))
+            .and_then(|id| id.as_str())
+            .map(String::from);
+        let embedding: Vec<f64> = obj
+            .get("_additional")
+            .and_then(|a| a.get("vector"))
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+        out.push((
+            Document {
                 page_content: content,
                 metadata,
                 score,
-            });
-        }
+                id,
+            },
+            embedding,
+        ));
     }
     Ok(out)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use testcontainers::{
+        core::IntoContainerPort, runners::AsyncRunner, ContainerAsync, GenericImage, ImageExt,
+    };
+    use weaviate_community::collections::schema::{Class, Properties, Property};
+
+    use crate::vectorstore::{conformance, weaviate::StoreBuilder, MetadataFilter};
+
+    use super::*;
+
+    /// Start Weaviate and create `class_name` as [StoreBuilder] expects it
+    async fn store(class_name: &str) -> (ContainerAsync<GenericImage>, Store) {
+        let container = GenericImage::new("semitechnologies/weaviate", "1.24.10")
+            .with_exposed_port(8080.tcp())
+            .with_env_var("AUTHENTICATION_ANONYMOUS_ACCESS_ENABLED", "true")
+            .with_env_var("PERSISTENCE_DATA_PATH", "/var/lib/weaviate")
+            .with_env_var("DEFAULT_VECTORIZER_MODULE", "none")
+            .start()
+            .await
+            .unwrap();
+        let port = container.get_host_port_ipv4(8080).await.unwrap();
+        let client = WeaviateClient::builder(&format!("http://localhost:{}", port))
+            .build()
+            .unwrap();
+        for _ in 0..120 {
+            if client.is_ready().await.unwrap_or(false) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+        let class = Class::builder(class_name)
+            .with_vectorizer("none")
+            .with_properties(Properties::new(vec![
+                Property::builder("content", vec!["text"]).build(),
+                Property::builder("metadata", vec!["text"]).build(),
+            ]))
+            .build();
+        client.schema.create_class(&class).await.unwrap();
+        let store = StoreBuilder::new()
+            .client(client)
+            .class_name(class_name)
+            .embedder(conformance::ConstantEmbedder)
+            .build()
+            .unwrap();
+        (container, store)
+    }
+
+    fn options(filter: Option<MetadataFilter>) -> WeaviateOptions {
+        let options = WeaviateOptions::default();
+        match filter {
+            Some(filter) => options.with_metadata_filter(filter),
+            None => options,
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Docker"]
+    async fn test_metadata_filter_conformance() {
+        let (_container, store) = store("Conformance").await;
+        conformance::run(&store, options).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Docker"]
+    async fn test_filter_beyond_top_k() {
+        let (_container, store) = store("BeyondTopK").await;
+        conformance::run_filter_beyond_top_k(&store, options).await;
+    }
+}
@@ -0,0 +1,133 @@
+//! Tests for typed tools generated with `#[tool]`.
+
+use std::sync::Arc;
+
+use langchain_ai_rust::{
+    agent::AgentState,
+    error::ToolError,
+    tools::{tool, CollectingStreamWriter, EmptyContext, InMemoryStore, Tool, ToolRuntime},
+};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::Mutex;
+
+#[derive(Deserialize, JsonSchema)]
+struct WeatherArgs {
+    /// City to get the weather for
+    city: String,
+    /// Number of days to forecast
+    days: Option<u32>,
+}
+
+/// Get the weather forecast for a city.
+#[tool]
+async fn get_weather(args: WeatherArgs) -> Result<String, ToolError> {
+    Ok(format!(
+        "{}: sunny for {} days",
+        args.city,
+        args.days.unwrap_or(1)
+    ))
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct NoteArgs {
+    /// Text of the note
+    text: String,
+}
+
+/// Save a note, reporting progress.
+#[tool(name = "save_note", parallel_safe = false)]
+async fn save(args: NoteArgs, runtime: &ToolRuntime) -> Result<String, ToolError> {
+    runtime.stream("saving");
+    Ok(format!("saved {}", args.text))
+}
+
+/// Current time, as a fixed string.
+#[tool]
+async fn now() -> Result<&'static str, ToolError> {
+    Ok("noon")
+}
+
+fn runtime(writer: &CollectingStreamWriter) -> ToolRuntime {
+    ToolRuntime::new(
+        Arc::new(Mutex::new(AgentState::new())),
+        Arc::new(EmptyContext),
+        Arc::new(InMemoryStore::new()),
+        "call_1".to_string(),
+    )
+    .with_stream_writer(Arc::new(writer.clone()))
+}
+
+#[tokio::test]
+async fn test_tool_metadata() {
+    assert_eq!(GetWeather.name(), "get_weather");
+    assert_eq!(
+        GetWeather.description(),
+        "Get the weather forecast for a city."
+    );
+    assert!(GetWeather.is_parallel_safe());
+    assert!(!GetWeather.requires_runtime());
+
+    let parameters = GetWeather.parameters();
+    assert_eq!(parameters["type"], "object");
+    assert_eq!(parameters["required"], json!(["city"]));
+    assert_eq!(
+        parameters["properties"]["city"]["description"],
+        "City to get the weather for"
+    );
+    assert!(parameters.get("$schema").is_none());
+
+    assert_eq!(Save.name(), "save_note");
+    assert!(!Save.is_parallel_safe());
+    assert!(Save.requires_runtime());
+}
+
+#[tokio::test]
+async fn test_typed_arguments() {
+    let tool: Arc<dyn Tool> = Arc::new(GetWeather);
+    assert_eq!(
+        tool.call(r#"{"city": "Oslo", "days": 3}"#).await.unwrap(),
+        "Oslo: sunny for 3 days"
+    );
+    // The fn itself is still callable
+    let direct = get_weather(WeatherArgs {
+        city: "Rome".to_string(),
+        days: None,
+    })
+    .await
+    .unwrap();
+    assert_eq!(direct, "Rome: sunny for 1 days");
+}
+
+#[tokio::test]
+async fn test_invalid_arguments_are_explained() {
+    let error = GetWeather.call(r#"{"days": "three"}"#).await.unwrap_err();
+    let ToolError::InvalidInputError(message) = error else {
+        panic!("expected an invalid input error, got {:?}", error);
+    };
+    assert!(message.starts_with("Invalid arguments for get_weather"));
+    assert!(message.contains("\"city\" is a required property"));
+    assert!(message.contains("/days"));
+}
+
+#[tokio::test]
+async fn test_runtime_injection() {
+    let writer = CollectingStreamWriter::new();
+    let input = Save.parse_input(r#"{"text": "milk"}"#).await;
+    let result = Save
+        .run_with_runtime(input, &runtime(&writer))
+        .await
+        .unwrap();
+    assert_eq!(result.as_str(), "saved milk");
+    assert_eq!(writer.get_messages().await, vec!["saving"]);
+}
+
+#[tokio::test]
+async fn test_tool_without_arguments() {
+    assert_eq!(
+        Now.parameters(),
+        json!({ "type": "object", "properties": {} })
+    );
+    assert_eq!(Now.call("").await.unwrap(), "noon");
+}